@@ -45,6 +45,7 @@ fn main() -> Result<()> {
         leaf_fri_params: FriParameters::standard_with_100_bits_conjectured_security(agg_log_blowup)
             .into(),
         compiler_options: CompilerOptions::default().with_cycle_tracker(),
+        security: None,
     };
 
     run_with_metric_collection("OUTPUT_PATH", || -> Result<()> {
@@ -12,7 +12,7 @@ use openvm_rv32im_transpiler::{
 };
 use openvm_sdk::{
     commit::commit_app_exe,
-    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
+    config::{AggConfig, AggStarkConfig, AppConfig, FinalStage, Halo2Config},
     prover::ContinuationProver,
     Sdk, StdIn,
 };
@@ -56,19 +56,23 @@ async fn main() -> Result<()> {
         ),
         leaf_fri_params: leaf_fri_params.into(),
         compiler_options,
+        security: None,
     };
     let agg_config = AggConfig {
         agg_stark_config: AggStarkConfig {
             max_num_user_public_values: NUM_PUBLIC_VALUES,
             leaf_fri_params,
+            additional_leaf_fri_params: vec![],
             internal_fri_params,
             root_fri_params,
             compiler_options,
+            security: None,
         },
         halo2_config: Halo2Config {
             verifier_k: 24,
             wrapper_k: None,
         },
+        final_stage: FinalStage::Halo2,
     };
 
     let halo2_params_reader = CacheHalo2ParamsReader::new_with_default_params_dir();
@@ -55,6 +55,7 @@ fn main() -> Result<()> {
         app_vm_config: Rv32ImConfig::default(),
         leaf_fri_params: leaf_fri_params.into(),
         compiler_options,
+        security: None,
     };
 
     let elf = build_bench_program("fibonacci")?;
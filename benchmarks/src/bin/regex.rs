@@ -48,6 +48,7 @@ fn main() -> Result<()> {
             enable_cycle_tracker: true,
             ..Default::default()
         },
+        security: None,
     };
     run_with_metric_collection("OUTPUT_PATH", || -> Result<()> {
         info_span!("Regex Program").in_scope(|| {
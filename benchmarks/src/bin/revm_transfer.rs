@@ -42,6 +42,7 @@ fn main() -> Result<()> {
         app_vm_config: Keccak256Rv32Config::default(),
         leaf_fri_params: FriParameters::standard_with_100_bits_conjectured_security(1).into(),
         compiler_options: CompilerOptions::default().with_cycle_tracker(),
+        security: None,
     };
     run_with_metric_collection("OUTPUT_PATH", || -> Result<()> {
         info_span!("revm 100 transfers").in_scope(|| {
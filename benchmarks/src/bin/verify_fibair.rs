@@ -52,6 +52,7 @@ fn main() -> Result<()> {
             app_vm_config,
             leaf_fri_params: leaf_fri_params.into(),
             compiler_options,
+            security: None,
         };
         info_span!("Verify Fibonacci AIR").in_scope(|| {
             let (program, input_stream) = build_verification_program(vdata, compiler_options);
@@ -5,9 +5,12 @@ use clap::Parser;
 use eyre::Result;
 use openvm_circuit::arch::{instructions::exe::VmExe, VirtualMachine, VmConfig};
 use openvm_keccak256_circuit::Keccak256Rv32Config;
-use openvm_sdk::fs::read_exe_from_file;
+use openvm_sdk::{
+    config::{log_blowup_for_constraint_degree, FriPreference, SecurityModel, SecurityTarget},
+    fs::read_exe_from_file,
+};
 use openvm_stark_sdk::{
-    config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, setup_tracing, FriParameters},
+    config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, setup_tracing},
     engine::StarkFriEngine,
     openvm_stark_backend::{
         config::{StarkGenericConfig, Val},
@@ -34,6 +37,19 @@ pub struct BenchCmd {
     #[clap(long, action)]
     verbose: bool,
 
+    /// Target bits of security of the proof.
+    #[clap(long, default_value_t = 100)]
+    security_bits: usize,
+
+    /// Estimate the security of the queries up to the Johnson bound instead of the conjectured
+    /// one. The commit-phase and list-decoding errors are not accounted for.
+    #[clap(long, action)]
+    johnson_bound: bool,
+
+    /// Choose FRI parameters for smaller proofs instead of faster proving.
+    #[clap(long, action)]
+    small_proof: bool,
+
     #[clap(flatten)]
     build_args: BuildArgs,
 }
@@ -47,12 +63,23 @@ impl BenchCmd {
         let exe_path = classical_exe_path(&elf_path);
         let exe = read_exe_from_file(&exe_path)?;
 
-        // TODO: read from openvm.toml
-        let app_log_blowup = 2;
-        let engine = BabyBearPoseidon2Engine::new(
-            FriParameters::standard_with_100_bits_conjectured_security(app_log_blowup),
-        );
         let config = Keccak256Rv32Config::default();
+        let security = SecurityTarget {
+            bits: self.security_bits,
+            model: if self.johnson_bound {
+                SecurityModel::JohnsonBoundQueries
+            } else {
+                SecurityModel::Conjectured
+            },
+            preference: if self.small_proof {
+                FriPreference::ProofSize
+            } else {
+                FriPreference::ProvingTime
+            },
+        };
+        let engine = BabyBearPoseidon2Engine::new(security.fri_params(
+            log_blowup_for_constraint_degree(config.system().max_constraint_degree),
+        ));
 
         let total_proving_time_ms = bench_from_exe(engine, config, exe, vec![])?;
 
@@ -95,8 +95,10 @@ pub(crate) fn build(build_args: &BuildArgs) -> Result<Option<PathBuf>> {
                 kind: "example".to_string(),
             })
     };
+    let app_config = read_config_toml_or_default(&build_args.config)?;
     let guest_options = GuestOptions {
         features: build_args.features.clone(),
+        target_features: app_config.app_vm_config.target_features(),
         ..Default::default()
     };
 
@@ -118,12 +120,14 @@ pub(crate) fn build(build_args: &BuildArgs) -> Result<Option<PathBuf>> {
         let elf_path = elf_path?;
         println!("[openvm] Transpiling the package...");
         let output_path = &build_args.exe_output;
-        let app_config = read_config_toml_or_default(&build_args.config)?;
         let transpiler = app_config.app_vm_config.transpiler();
 
         let data = read(elf_path.clone())?;
         let elf = Elf::decode(&data, MEM_SIZE as u32)?;
         let exe = Sdk.transpile(elf, transpiler)?;
+        for incompatibility in Sdk.check_exe_compatible(&exe, &app_config.app_vm_config)? {
+            println!("[openvm] Warning: {}", incompatibility);
+        }
         write_exe_to_file(exe, output_path)?;
 
         println!(
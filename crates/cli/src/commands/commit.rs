@@ -0,0 +1,116 @@
+use std::{fs::write, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{
+    commit::AppExecutionCommit,
+    config::SdkVmConfig,
+    fs::{read_app_pk_from_file, read_exe_from_file},
+    keygen::AppProvingKey,
+    Sdk,
+};
+use openvm_stark_sdk::{
+    openvm_stark_backend::p3_field::{PrimeField, PrimeField32},
+    p3_baby_bear::BabyBear,
+    p3_bn254_fr::Bn254Fr,
+};
+use serde::Serialize;
+
+use crate::{
+    default::{DEFAULT_APP_CONFIG_PATH, DEFAULT_APP_EXE_PATH, DEFAULT_APP_PK_PATH},
+    util::read_config_toml_or_default,
+};
+
+#[derive(Parser)]
+#[command(
+    name = "commit",
+    about = "Print the exe and app VM commitments an EVM proof is checked against"
+)]
+pub struct CommitCmd {
+    #[clap(long, action, help = "Path to app proving key", default_value = DEFAULT_APP_PK_PATH)]
+    app_pk: PathBuf,
+
+    #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+    exe: PathBuf,
+
+    #[clap(long, action, help = "Path to app config TOML file", default_value = DEFAULT_APP_CONFIG_PATH)]
+    config: PathBuf,
+
+    #[clap(long, action, help = "Print the commitments as JSON")]
+    json: bool,
+
+    #[clap(
+        long,
+        action,
+        help = "Path to write the commitments to as JSON, in addition to printing them"
+    )]
+    output: Option<PathBuf>,
+}
+
+/// A commitment in the form used by the STARK proofs and in the form exposed by EVM proofs.
+#[derive(Serialize)]
+struct CommitOutput {
+    babybear: Vec<u32>,
+    bn254: String,
+}
+
+#[derive(Serialize)]
+struct CommitsOutput {
+    exe_commit: CommitOutput,
+    leaf_vm_verifier_commit: CommitOutput,
+}
+
+impl CommitCmd {
+    pub fn run(&self) -> Result<()> {
+        let app_config = read_config_toml_or_default(&self.config)?;
+        let app_pk: AppProvingKey<SdkVmConfig> = read_app_pk_from_file(&self.app_pk)?;
+        if serde_json::to_value(&app_config.app_vm_config)?
+            != serde_json::to_value(&app_pk.app_vm_pk.vm_config)?
+        {
+            return Err(eyre::eyre!(
+                "{} does not match the VM config of the app proving key {}",
+                self.config.display(),
+                self.app_pk.display()
+            ));
+        }
+        let app_exe = read_exe_from_file(&self.exe)?;
+        let committed_exe = Sdk.commit_app_exe(app_pk.app_fri_params(), app_exe)?;
+        let commits = AppExecutionCommit::compute(
+            &app_config.app_vm_config,
+            &committed_exe,
+            &app_pk.leaf_committed_exe,
+        );
+
+        let output = CommitsOutput {
+            exe_commit: CommitOutput::new(&commits.exe_commit, commits.exe_commit_to_bn254()),
+            leaf_vm_verifier_commit: CommitOutput::new(
+                &commits.leaf_vm_verifier_commit,
+                commits.app_config_commit_to_bn254(),
+            ),
+        };
+        let json = serde_json::to_string_pretty(&output)?;
+        if self.json {
+            println!("{json}");
+        } else {
+            println!("exe_commit:");
+            println!("  babybear: {:?}", output.exe_commit.babybear);
+            println!("  bn254: {}", output.exe_commit.bn254);
+            println!("leaf_vm_verifier_commit:");
+            println!("  babybear: {:?}", output.leaf_vm_verifier_commit.babybear);
+            println!("  bn254: {}", output.leaf_vm_verifier_commit.bn254);
+        }
+        if let Some(path) = &self.output {
+            write(path, json)?;
+        }
+        Ok(())
+    }
+}
+
+impl CommitOutput {
+    fn new(babybear: &[BabyBear], bn254: Bn254Fr) -> Self {
+        Self {
+            babybear: babybear.iter().map(|x| x.as_canonical_u32()).collect(),
+            bn254: format!("0x{:064x}", bn254.as_canonical_biguint()),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::{fs::write, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_sdk::{disasm::Disassembler, fs::read_exe_from_file};
+
+use crate::default::DEFAULT_APP_EXE_PATH;
+
+#[derive(Parser)]
+#[command(
+    name = "disasm",
+    about = "Print the instructions and initial memory of an OpenVM executable"
+)]
+pub struct DisasmCmd {
+    #[clap(long, action, help = "Path to OpenVM executable", default_value = DEFAULT_APP_EXE_PATH)]
+    exe: PathBuf,
+
+    #[clap(
+        long,
+        action,
+        help = "Path to write the listing to, printed to stdout if not set"
+    )]
+    output: Option<PathBuf>,
+}
+
+impl DisasmCmd {
+    pub fn run(&self) -> Result<()> {
+        let exe = read_exe_from_file(&self.exe)?;
+        let listing = Disassembler::default().disassemble(&exe);
+        if let Some(output) = &self.output {
+            write(output, listing)?;
+        } else {
+            print!("{listing}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fs::write, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
+use openvm_sdk::{config::AggConfig, Sdk};
+
+use crate::{default::DEFAULT_PARAMS_DIR, util::read_to_struct_toml};
+
+#[derive(Parser)]
+#[command(
+    name = "halo2-stats",
+    about = "Report the size of the static verifier circuit and its wrapper without a full keygen"
+)]
+pub struct Halo2StatsCmd {
+    #[clap(
+        long,
+        action,
+        help = "Path to an aggregation config TOML file, the default aggregation config is used if not specified"
+    )]
+    agg_config: Option<PathBuf>,
+
+    #[clap(long, action, help = "Directory of the KZG params", default_value = DEFAULT_PARAMS_DIR)]
+    params_dir: PathBuf,
+
+    #[clap(long, action, help = "Print the statistics as JSON")]
+    json: bool,
+
+    #[clap(
+        long,
+        action,
+        help = "Path to write the statistics to as JSON, in addition to printing them"
+    )]
+    output: Option<PathBuf>,
+}
+
+impl Halo2StatsCmd {
+    pub fn run(&self) -> Result<()> {
+        let agg_config: AggConfig = match &self.agg_config {
+            Some(path) => read_to_struct_toml(path)?,
+            None => AggConfig::default(),
+        };
+        let params_reader = CacheHalo2ParamsReader::new(&self.params_dir);
+        let stats = Sdk.static_verifier_stats(agg_config, &params_reader)?;
+
+        let json = serde_json::to_string_pretty(&stats)?;
+        if self.json {
+            println!("{json}");
+        } else {
+            let params = &stats.verifier.config_params;
+            let total = &stats.verifier.total;
+            println!("static verifier:");
+            println!("  k: {}", stats.verifier_k);
+            println!("  advice columns: {:?}", params.num_advice_per_phase);
+            println!(
+                "  lookup advice columns: {:?}",
+                params.num_lookup_advice_per_phase
+            );
+            println!("  fixed columns: {}", params.num_fixed);
+            println!("  advice cells: {}", total.total_gate_cell);
+            println!("  lookup cells: {}", total.total_lookup_cell);
+            println!("  fixed cells: {}", total.total_fixed);
+            println!("wrapper:");
+            println!("  k: {}", stats.wrapper_k);
+            println!("operations:");
+            let mut operations: Vec<_> = stats.verifier.operations.iter().collect();
+            operations.sort_by_key(|(_, op)| std::cmp::Reverse(op.cells.total_gate_cell));
+            for (name, op) in operations {
+                println!(
+                    "  {name}: count {}, advice {}, lookup {}, fixed {}",
+                    op.num_operations,
+                    op.cells.total_gate_cell,
+                    op.cells.total_lookup_cell,
+                    op.cells.total_fixed
+                );
+            }
+        }
+        if let Some(path) = &self.output {
+            write(path, json)?;
+        }
+        Ok(())
+    }
+}
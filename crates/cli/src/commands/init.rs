@@ -0,0 +1,359 @@
+use std::{
+    collections::BTreeSet,
+    fs::{create_dir_all, write},
+    path::PathBuf,
+};
+
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use crate::default::DEFAULT_MANIFEST_DIR;
+
+const OPENVM_GIT_URL: &str = "https://github.com/openvm-org/openvm.git";
+
+#[derive(Parser)]
+#[command(
+    name = "init",
+    about = "Create a new OpenVM guest crate together with a host-side test harness"
+)]
+pub struct InitCmd {
+    #[clap(
+        default_value = DEFAULT_MANIFEST_DIR,
+        help = "Directory to create the project in"
+    )]
+    pub path: PathBuf,
+
+    #[clap(
+        long,
+        help = "Name of the guest crate, defaults to the name of the project directory"
+    )]
+    pub name: Option<String>,
+
+    #[clap(
+        long,
+        action,
+        help = "Generate a guest that links the Rust standard library instead of a no_std guest"
+    )]
+    pub std: bool,
+
+    #[clap(
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "rv32m,io",
+        help = "VM extensions to enable in openvm.toml in addition to rv32i and the extensions they depend on"
+    )]
+    pub extensions: Vec<InitExtension>,
+
+    #[clap(
+        long,
+        help = "Path to a local checkout of the OpenVM repository to depend on instead of the git repository"
+    )]
+    pub openvm_path: Option<PathBuf>,
+}
+
+/// The optional fields of [SdkVmConfig](openvm_sdk::config::SdkVmConfig) that `init` can
+/// enable. `rv32i` is always enabled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum InitExtension {
+    Rv32m,
+    Io,
+    Keccak,
+    Native,
+    Zbb,
+    Rv32a,
+    Bigint,
+    Modular,
+    Fp2,
+    Pairing,
+    Ecc,
+}
+
+/// Decimal modulus of the base field of BN254, which the pairing template enables.
+const BN254_MODULUS: &str =
+    "21888242871839275222246405745257275088696311157297823662689037894645226208583";
+
+impl InitExtension {
+    /// Extensions whose chips this extension needs in the same VM config.
+    fn dependencies(self) -> &'static [InitExtension] {
+        match self {
+            Self::Fp2 | Self::Ecc => &[Self::Modular],
+            Self::Pairing => &[Self::Modular, Self::Fp2],
+            _ => &[],
+        }
+    }
+
+    /// The section of `openvm.toml` enabling this extension. The pairing template enables
+    /// BN254, so its base field is added to the moduli of the algebra extensions.
+    fn config_section(self, extensions: &BTreeSet<InitExtension>) -> String {
+        let moduli = if extensions.contains(&Self::Pairing) {
+            format!("[{BN254_MODULUS:?}]")
+        } else {
+            "[]".to_string()
+        };
+        match self {
+            Self::Rv32m => "[app_vm_config.rv32m]\n".to_string(),
+            Self::Io => "[app_vm_config.io]\n".to_string(),
+            Self::Keccak => "[app_vm_config.keccak]\n".to_string(),
+            Self::Native => "[app_vm_config.native]\n".to_string(),
+            Self::Zbb => "[app_vm_config.zbb]\n".to_string(),
+            Self::Rv32a => "[app_vm_config.rv32a]\n".to_string(),
+            Self::Bigint => "[app_vm_config.bigint]\n".to_string(),
+            Self::Modular => format!(
+                "[app_vm_config.modular]\n\
+                 # Decimal moduli, in the order they are declared in `moduli_init!`\n\
+                 supported_modulus = {moduli}\n"
+            ),
+            Self::Fp2 => format!(
+                "[app_vm_config.fp2]\n\
+                 # Decimal moduli of the base fields, each also listed in `app_vm_config.modular`\n\
+                 supported_modulus = {moduli}\n"
+            ),
+            Self::Pairing => concat!(
+                "[app_vm_config.pairing]\n",
+                "# Any of \"Bn254\", \"Bls12_381\", with their base fields listed in\n",
+                "# `app_vm_config.modular` and `app_vm_config.fp2`\n",
+                "supported_curves = [\"Bn254\"]\n",
+            )
+            .to_string(),
+            Self::Ecc => concat!(
+                "[app_vm_config.ecc]\n",
+                "# One inline table per curve, in the order they are declared in `sw_init!`, e.g.\n",
+                "# { modulus = \"<decimal>\", scalar = \"<decimal>\", a = \"<decimal>\", b = \"<decimal>\" },\n",
+                "# with `modulus` and `scalar` also listed in `app_vm_config.modular`\n",
+                "supported_curves = [\n]\n",
+            )
+            .to_string(),
+        }
+    }
+
+    /// Guest libraries, as (crate name, path in the OpenVM repository), that expose the
+    /// intrinsics of this extension.
+    fn guest_crates(self) -> &'static [(&'static str, &'static str)] {
+        const ALGEBRA: (&str, &str) = ("openvm-algebra-guest", "extensions/algebra/guest");
+        const ECC: (&str, &str) = ("openvm-ecc-guest", "extensions/ecc/guest");
+        match self {
+            Self::Rv32m | Self::Io | Self::Native | Self::Zbb | Self::Rv32a => &[],
+            Self::Keccak => &[("openvm-keccak256-guest", "extensions/keccak256/guest")],
+            Self::Bigint => &[("openvm-bigint-guest", "extensions/bigint/guest")],
+            Self::Modular | Self::Fp2 => &[ALGEBRA],
+            Self::Ecc => &[ALGEBRA, ECC],
+            Self::Pairing => &[
+                ALGEBRA,
+                ECC,
+                ("openvm-pairing-guest", "extensions/pairing/guest"),
+            ],
+        }
+    }
+}
+
+impl InitCmd {
+    pub fn run(&self) -> Result<()> {
+        let path = &self.path;
+        let name = match &self.name {
+            Some(name) => name.clone(),
+            None => path
+                .canonicalize()
+                .unwrap_or_else(|_| path.clone())
+                .file_name()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| eyre::eyre!("Cannot infer crate name from {}", path.display()))?
+                .to_string(),
+        };
+        let mut extensions: BTreeSet<_> = self.extensions.iter().copied().collect();
+        for ext in self.extensions.iter() {
+            extensions.extend(ext.dependencies());
+        }
+
+        let guest_dir = path.join("guest");
+        let host_dir = path.join("host");
+        for file in [
+            guest_dir.join("Cargo.toml"),
+            guest_dir.join("openvm.toml"),
+            host_dir.join("Cargo.toml"),
+        ] {
+            if file.exists() {
+                return Err(eyre::eyre!("{} already exists", file.display()));
+            }
+        }
+
+        let has_io = extensions.contains(&InitExtension::Io);
+        write_file(
+            guest_dir.join("Cargo.toml"),
+            self.guest_manifest(&name, &extensions),
+        )?;
+        write_file(guest_dir.join("openvm.toml"), app_config_toml(&extensions))?;
+        write_file(guest_dir.join("src/main.rs"), guest_main(self.std, has_io))?;
+        write_file(host_dir.join("Cargo.toml"), self.host_manifest(&name))?;
+        write_file(host_dir.join("src/lib.rs"), host_lib(has_io))?;
+
+        println!(
+            "[openvm] Created guest crate {} in {}",
+            name,
+            guest_dir.display()
+        );
+        println!(
+            "[openvm] Run `cargo test --release` in {} to build, execute and prove it",
+            host_dir.display()
+        );
+        Ok(())
+    }
+
+    fn dependency(&self, krate: &str, repo_path: &str, features: &[&str]) -> String {
+        let source = match &self.openvm_path {
+            Some(root) => format!("path = {:?}", root.join(repo_path).display().to_string()),
+            None => format!("git = {:?}", OPENVM_GIT_URL),
+        };
+        if features.is_empty() {
+            format!("{krate} = {{ {source} }}\n")
+        } else {
+            format!("{krate} = {{ {source}, features = {features:?} }}\n")
+        }
+    }
+
+    fn guest_manifest(&self, name: &str, extensions: &BTreeSet<InitExtension>) -> String {
+        let guest_crates: BTreeSet<_> = extensions
+            .iter()
+            .flat_map(|ext| ext.guest_crates())
+            .collect();
+        let std_features: &[&str] = if self.std { &["std"] } else { &[] };
+
+        let mut manifest = format!(
+            "[package]\nname = {name:?}\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[workspace]\nmembers = []\n\n[dependencies]\n"
+        );
+        manifest += &self.dependency("openvm", "crates/toolchain/openvm", std_features);
+        for (krate, repo_path) in &guest_crates {
+            manifest += &self.dependency(krate, repo_path, std_features);
+        }
+        if !self.std {
+            // Lets the guest also be compiled and run natively, e.g. for debugging.
+            let std_features = ["openvm"]
+                .into_iter()
+                .chain(guest_crates.iter().map(|(krate, _)| *krate))
+                .map(|krate| format!("\"{krate}/std\""))
+                .collect::<Vec<_>>()
+                .join(", ");
+            manifest += &format!("\n[features]\ndefault = []\nstd = [{std_features}]\n");
+        }
+        manifest
+    }
+
+    fn host_manifest(&self, name: &str) -> String {
+        let mut manifest = format!(
+            "[package]\nname = \"{name}-host\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n[workspace]\nmembers = []\n\n[dependencies]\n"
+        );
+        manifest += &self.dependency("openvm-build", "crates/toolchain/build", &[]);
+        manifest += &self.dependency("openvm-sdk", "crates/sdk", &[]);
+        manifest += "eyre = \"0.6\"\ntoml = \"0.8\"\n";
+        manifest
+    }
+}
+
+fn write_file(path: PathBuf, contents: impl AsRef<[u8]>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    write(&path, contents)?;
+    Ok(())
+}
+
+fn app_config_toml(extensions: &BTreeSet<InitExtension>) -> String {
+    let mut config = String::from("[app_vm_config.rv32i]\n");
+    for ext in extensions {
+        config += &ext.config_section(extensions);
+    }
+    config
+}
+
+fn guest_main(std: bool, has_io: bool) -> String {
+    let mut main = String::new();
+    if !std {
+        main += "#![cfg_attr(not(feature = \"std\"), no_main)]\n";
+        main += "#![cfg_attr(not(feature = \"std\"), no_std)]\n\n";
+    }
+    if has_io {
+        main += "use openvm::io::{read, reveal};\n\n";
+    }
+    main += "openvm::entry!(main);\n\npub fn main() {\n";
+    if has_io {
+        main += "    let n: u32 = read();\n";
+    } else {
+        main += "    let n: u32 = core::hint::black_box(10);\n";
+    }
+    main += concat!(
+        "    let mut a: u32 = 0;\n",
+        "    let mut b: u32 = 1;\n",
+        "    for _ in 0..n {\n",
+        "        let c = a.wrapping_add(b);\n",
+        "        a = b;\n",
+        "        b = c;\n",
+        "    }\n",
+    );
+    if has_io {
+        main += "    reveal(a, 0);\n";
+    } else {
+        main += "    core::hint::black_box(a);\n";
+    }
+    main += "}\n";
+    main
+}
+
+fn host_lib(has_io: bool) -> String {
+    let stdin = if has_io {
+        "let mut stdin = StdIn::default();\n        stdin.write(&10u32);"
+    } else {
+        "let stdin = StdIn::default();"
+    };
+    format!(
+        r#"use std::{{fs, path::PathBuf}};
+
+use eyre::Result;
+use openvm_sdk::config::{{AppConfig, SdkVmConfig}};
+
+/// Directory of the guest crate, which also holds its `openvm.toml`.
+pub fn guest_dir() -> PathBuf {{
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../guest")
+}}
+
+/// Reads the app config of the guest from its `openvm.toml`.
+pub fn read_app_config() -> Result<AppConfig<SdkVmConfig>> {{
+    let config = fs::read_to_string(guest_dir().join("openvm.toml"))?;
+    let mut app_config: AppConfig<SdkVmConfig> = toml::from_str(&config)?;
+    // Same as `cargo openvm`: a security target overrides the FRI parameters.
+    app_config.apply_security_target();
+    Ok(app_config)
+}}
+
+#[cfg(test)]
+mod tests {{
+    use std::sync::Arc;
+
+    use openvm_build::GuestOptions;
+    use openvm_sdk::{{Sdk, StdIn}};
+
+    use super::*;
+
+    #[test]
+    fn test_guest() -> Result<()> {{
+        let sdk = Sdk;
+        let app_config = read_app_config()?;
+        let vm_config = app_config.app_vm_config.clone();
+
+        let guest_opts = GuestOptions::default().with_target_features(vm_config.target_features());
+        let elf = sdk.build(guest_opts, guest_dir(), &Default::default())?;
+        let exe = sdk.transpile(elf, vm_config.transpiler())?;
+
+        {stdin}
+        let public_values = sdk.execute(exe.clone(), vm_config, stdin.clone())?;
+        println!("public values: {{:?}}", public_values);
+
+        let committed_exe = sdk.commit_app_exe(app_config.app_fri_params.fri_params, exe)?;
+        let app_pk = Arc::new(sdk.app_keygen(app_config)?);
+        let proof = sdk.generate_app_proof(app_pk.clone(), committed_exe, stdin)?;
+        sdk.verify_app_proof(&app_pk.get_vk(), &proof)?;
+        Ok(())
+    }}
+}}
+"#
+    )
+}
@@ -4,6 +4,18 @@ pub use bench::*;
 mod build;
 pub use build::*;
 
+mod commit;
+pub use commit::*;
+
+mod disasm;
+pub use disasm::*;
+
+mod halo2_stats;
+pub use halo2_stats::*;
+
+mod init;
+pub use init::*;
+
 mod keygen;
 pub use keygen::*;
 
@@ -7,8 +7,8 @@ use openvm_sdk::{
     commit::AppExecutionCommit,
     config::SdkVmConfig,
     fs::{
-        read_agg_pk_from_file, read_app_pk_from_file, read_exe_from_file, write_app_proof_to_file,
-        write_evm_proof_to_file,
+        agg_config_digest, app_vk_digest, read_agg_pk_from_file, read_app_pk_from_file,
+        read_exe_from_file, write_app_proof_to_file, write_evm_proof_to_file,
     },
     keygen::AppProvingKey,
     NonRootCommittedExe, Sdk, StdIn,
@@ -69,8 +69,9 @@ impl ProveCmd {
                 output,
             } => {
                 let (app_pk, committed_exe, input) = Self::prepare_execution(app_pk, exe, input)?;
+                let app_vk_digest = app_vk_digest(&app_pk.get_vk())?;
                 let app_proof = Sdk.generate_app_proof(app_pk, committed_exe, input)?;
-                write_app_proof_to_file(app_proof, output)?;
+                write_app_proof_to_file(app_proof, app_vk_digest, output)?;
             }
             ProveSubCommand::Evm {
                 app_pk,
@@ -84,9 +85,10 @@ impl ProveCmd {
                 let agg_pk = read_agg_pk_from_file(DEFAULT_AGG_PK_PATH).map_err(|e| {
                     eyre::eyre!("Failed to read aggregation proving key: {}\nPlease run 'cargo openvm setup' first", e)
                 })?;
+                let agg_config_digest = agg_config_digest(&agg_pk.agg_stark_pk)?;
                 let evm_proof =
                     Sdk.generate_evm_proof(&params_reader, app_pk, committed_exe, agg_pk, input)?;
-                write_evm_proof_to_file(evm_proof, output)?;
+                write_evm_proof_to_file(evm_proof, agg_config_digest, output)?;
             }
         }
         Ok(())
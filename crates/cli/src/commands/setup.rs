@@ -1,16 +1,21 @@
 use std::{
-    fs::{create_dir_all, write},
-    path::PathBuf,
+    fs::{copy, create_dir_all, remove_file, write},
+    path::{Path, PathBuf},
 };
 
 use aws_config::{defaults, BehaviorVersion, Region};
 use aws_sdk_s3::Client;
 use clap::Parser;
 use eyre::{eyre, Result};
-use openvm_native_recursion::halo2::utils::CacheHalo2ParamsReader;
+use openvm_native_recursion::halo2::{
+    ppot::read_ppot_challenge_params,
+    utils::{
+        gen_insecure_kzg_params, kzg_params_file_name, write_kzg_params, CacheHalo2ParamsReader,
+    },
+};
 use openvm_sdk::{
     config::AggConfig,
-    fs::{write_agg_pk_to_file, write_evm_verifier_to_file},
+    fs::{agg_config_digest, write_agg_pk_to_file, write_evm_verifier_to_file},
     Sdk,
 };
 
@@ -21,7 +26,35 @@ use crate::default::{DEFAULT_AGG_PK_PATH, DEFAULT_PARAMS_DIR, DEFAULT_VERIFIER_P
     name = "evm-proving-setup",
     about = "Set up for generating EVM proofs. ATTENTION: this requires large amounts of computation and memory. "
 )]
-pub struct EvmProvingSetupCmd {}
+pub struct EvmProvingSetupCmd {
+    #[clap(
+        long,
+        action,
+        help = "INSECURE, for testing only: generate deterministic KZG params locally instead of downloading them. Anyone can forge proofs against them",
+        conflicts_with_all = ["params_dir", "ppot"]
+    )]
+    insecure_test_params: bool,
+
+    #[clap(
+        long,
+        action,
+        help = "Import the KZG params kzg_bn254_{k}.srs from a local directory instead of downloading them",
+        conflicts_with = "ppot"
+    )]
+    params_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        action,
+        help = "Import the KZG params from a Perpetual Powers of Tau challenge file instead of downloading them"
+    )]
+    ppot: Option<PathBuf>,
+}
+
+const MIN_PARAMS_K: usize = 10;
+const MAX_PARAMS_K: usize = 24;
+/// Marks a params directory containing params generated with `--insecure-test-params`.
+const INSECURE_PARAMS_MARKER: &str = "INSECURE_TEST_PARAMS";
 
 impl EvmProvingSetupCmd {
     pub async fn run(&self) -> Result<()> {
@@ -36,8 +69,38 @@ impl EvmProvingSetupCmd {
             ));
         }
 
-        Self::download_params(10, 24).await?;
+        create_dir_all(DEFAULT_PARAMS_DIR)?;
+        let insecure_marker = Path::new(DEFAULT_PARAMS_DIR).join(INSECURE_PARAMS_MARKER);
+        if self.insecure_test_params {
+            Self::generate_insecure_params(MIN_PARAMS_K, MAX_PARAMS_K)?;
+            write(
+                &insecure_marker,
+                "The KZG params in this directory were generated from a public seed.\n",
+            )?;
+        } else {
+            if let Some(params_dir) = &self.params_dir {
+                Self::import_params(params_dir, MIN_PARAMS_K, MAX_PARAMS_K)?;
+            } else if let Some(ppot) = &self.ppot {
+                Self::import_ppot_params(ppot, MIN_PARAMS_K, MAX_PARAMS_K)?;
+            } else if insecure_marker.exists() {
+                // Downloading keeps existing params files.
+                return Err(eyre!(
+                    "{} contains INSECURE test params, remove it to download the real params",
+                    DEFAULT_PARAMS_DIR
+                ));
+            } else {
+                Self::download_params(MIN_PARAMS_K, MAX_PARAMS_K).await?;
+            }
+            if insecure_marker.exists() {
+                remove_file(&insecure_marker)?;
+            }
+        }
         let params_reader = CacheHalo2ParamsReader::new(DEFAULT_PARAMS_DIR);
+        for k in MIN_PARAMS_K..=MAX_PARAMS_K {
+            params_reader
+                .try_read_params(k)
+                .map_err(|e| eyre!("Invalid KZG params for k = {}: {}", k, e))?;
+        }
         let agg_config = AggConfig::default();
 
         println!("Generating proving key...");
@@ -47,10 +110,11 @@ impl EvmProvingSetupCmd {
         let verifier = Sdk.generate_snark_verifier_contract(&params_reader, &agg_pk)?;
 
         println!("Writing proving key to file...");
+        let agg_config_digest = agg_config_digest(&agg_pk.agg_stark_pk)?;
         write_agg_pk_to_file(agg_pk, DEFAULT_AGG_PK_PATH)?;
 
         println!("Writing verifier contract to file...");
-        write_evm_verifier_to_file(verifier, DEFAULT_VERIFIER_PATH)?;
+        write_evm_verifier_to_file(verifier, agg_config_digest, DEFAULT_VERIFIER_PATH)?;
 
         Ok(())
     }
@@ -62,8 +126,39 @@ impl EvmProvingSetupCmd {
             .is_ok()
     }
 
-    async fn download_params(min_k: u32, max_k: u32) -> Result<()> {
-        create_dir_all(DEFAULT_PARAMS_DIR)?;
+    fn generate_insecure_params(min_k: usize, max_k: usize) -> Result<()> {
+        println!("WARNING: generating INSECURE KZG params, only use them for testing");
+        let mut params = gen_insecure_kzg_params(max_k as u32);
+        for k in (min_k..=max_k).rev() {
+            params.downsize(k as u32);
+            write_kzg_params(DEFAULT_PARAMS_DIR, &params)?;
+        }
+        Ok(())
+    }
+
+    fn import_params(params_dir: &Path, min_k: usize, max_k: usize) -> Result<()> {
+        for k in min_k..=max_k {
+            let file_name = kzg_params_file_name(k);
+            println!("Importing {}", file_name);
+            copy(
+                params_dir.join(&file_name),
+                PathBuf::from(DEFAULT_PARAMS_DIR).join(&file_name),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn import_ppot_params(ppot: &Path, min_k: usize, max_k: usize) -> Result<()> {
+        println!("Importing params from {}", ppot.display());
+        let mut params = read_ppot_challenge_params(ppot, max_k)?;
+        for k in (min_k..=max_k).rev() {
+            params.downsize(k as u32);
+            write_kzg_params(DEFAULT_PARAMS_DIR, &params)?;
+        }
+        Ok(())
+    }
+
+    async fn download_params(min_k: usize, max_k: usize) -> Result<()> {
         let config = defaults(BehaviorVersion::latest())
             .region(Region::new("us-east-1"))
             .no_credentials()
@@ -72,7 +167,7 @@ impl EvmProvingSetupCmd {
         let client = Client::new(&config);
 
         for k in min_k..=max_k {
-            let file_name = format!("kzg_bn254_{}.srs", k);
+            let file_name = kzg_params_file_name(k);
             let local_file_path = PathBuf::from(DEFAULT_PARAMS_DIR).join(&file_name);
             if !local_file_path.exists() {
                 println!("Downloading {}", file_name);
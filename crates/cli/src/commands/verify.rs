@@ -3,9 +3,11 @@ use std::path::PathBuf;
 use clap::Parser;
 use eyre::{eyre, Result};
 use openvm_sdk::{
+    commit::{commit_app_exe, compute_app_exe_commit},
     fs::{
-        read_app_proof_from_file, read_app_vk_from_file, read_evm_proof_from_file,
-        read_evm_verifier_from_file,
+        app_vk_digest, check_artifact_config_digest, read_app_proof_from_file,
+        read_app_vk_from_file, read_artifact_config_digest, read_evm_proof_from_file,
+        read_evm_verifier_from_file, read_exe_from_file,
     },
     Sdk,
 };
@@ -29,6 +31,13 @@ enum VerifySubCommand {
 
         #[clap(long, action, help = "Path to app proof", default_value = DEFAULT_APP_PROOF_PATH)]
         proof: PathBuf,
+
+        #[clap(
+            long,
+            action,
+            help = "Path to OpenVM executable, if specified the proof must be an execution of it"
+        )]
+        exe: Option<PathBuf>,
     },
     Evm {
         #[clap(long, action, help = "Path to EVM proof", default_value = DEFAULT_EVM_PROOF_PATH)]
@@ -39,15 +48,34 @@ enum VerifySubCommand {
 impl VerifyCmd {
     pub fn run(&self) -> Result<()> {
         match &self.command {
-            VerifySubCommand::App { app_vk, proof } => {
+            VerifySubCommand::App { app_vk, proof, exe } => {
                 let app_vk = read_app_vk_from_file(app_vk)?;
+                check_artifact_config_digest(proof, app_vk_digest(&app_vk)?)
+                    .map_err(|e| eyre!("{}\nThe proof was not generated with this app key", e))?;
                 let app_proof = read_app_proof_from_file(proof)?;
-                Sdk.verify_app_proof(&app_vk, &app_proof)?;
+                let public_values = if let Some(exe) = exe {
+                    let app_exe = commit_app_exe(app_vk.fri_params, read_exe_from_file(exe)?);
+                    let exe_commit = compute_app_exe_commit(app_vk.memory_dimensions, &app_exe);
+                    Sdk.verify_app_proof_for_exe(&app_vk, &app_proof, &exe_commit)?
+                } else {
+                    Sdk.verify_app_proof(&app_vk, &app_proof)?
+                        .user_public_values
+                };
+                println!("Public values: {:?}", public_values);
             }
             VerifySubCommand::Evm { proof } => {
                 let evm_verifier = read_evm_verifier_from_file(DEFAULT_VERIFIER_PATH).map_err(|e| {
                     eyre::eyre!("Failed to read EVM verifier: {}\nPlease run 'cargo openvm evm-proving-setup' first", e)
                 })?;
+                if let Some(agg_config_digest) = read_artifact_config_digest(DEFAULT_VERIFIER_PATH)?
+                {
+                    check_artifact_config_digest(proof, agg_config_digest).map_err(|e| {
+                        eyre!(
+                            "{}\nThe proof was not generated with the aggregation key of the verifier",
+                            e
+                        )
+                    })?;
+                }
                 let evm_proof = read_evm_proof_from_file(proof)?;
                 if !Sdk.verify_evm_proof(&evm_verifier, &evm_proof) {
                     return Err(eyre!("EVM proof verification failed"));
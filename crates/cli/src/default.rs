@@ -25,5 +25,6 @@ pub fn default_app_config() -> AppConfig<SdkVmConfig> {
             .build(),
         leaf_fri_params: FriParameters::standard_with_100_bits_conjectured_security(2).into(),
         compiler_options: Default::default(),
+        security: None,
     }
 }
@@ -84,7 +84,9 @@ pub(crate) fn read_to_stdin(input: &Option<Input>) -> Result<StdIn> {
 
 pub(crate) fn read_config_toml_or_default(config: &PathBuf) -> Result<AppConfig<SdkVmConfig>> {
     if config.exists() {
-        read_to_struct_toml(config)
+        let mut app_config: AppConfig<SdkVmConfig> = read_to_struct_toml(config)?;
+        app_config.apply_security_target();
+        Ok(app_config)
     } else {
         println!(
             "{:?} not found, using default application configuration",
@@ -1,4 +1,4 @@
-use std::{env, process::Command};
+use std::{env, path::Path, process::Command};
 
 use eyre::Result;
 use tempfile::tempdir;
@@ -94,8 +94,91 @@ fn test_cli_app_e2e_default_paths() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_init_build() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let project_dir = temp_dir.path().join("init-example");
+    let guest_dir = project_dir.join("guest");
+    let openvm_path = env::current_dir()?.join("../..").canonicalize()?;
+    run_cmd("cargo", &["install", "--path", ".", "--force"])?;
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "init",
+            project_dir.to_str().unwrap(),
+            "--extensions",
+            "rv32m,io,keccak",
+            "--openvm-path",
+            openvm_path.to_str().unwrap(),
+        ],
+    )?;
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "build",
+            "--manifest-dir",
+            guest_dir.to_str().unwrap(),
+            "--config",
+            guest_dir.join("openvm.toml").to_str().unwrap(),
+            "--exe-output",
+            temp_dir.path().join("init.vmexe").to_str().unwrap(),
+        ],
+    )?;
+
+    // The generated harness builds, executes, proves and verifies the guest.
+    run_cmd_in(&project_dir.join("host"), "cargo", &["test", "--release"])?;
+
+    Ok(())
+}
+
+#[test]
+fn test_cli_init_pairing_config() -> Result<()> {
+    let temp_dir = tempdir()?;
+    let project_dir = temp_dir.path().join("init-pairing");
+    let guest_dir = project_dir.join("guest");
+    let openvm_path = env::current_dir()?.join("../..").canonicalize()?;
+    run_cmd("cargo", &["install", "--path", ".", "--force"])?;
+
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "init",
+            project_dir.to_str().unwrap(),
+            "--extensions",
+            "rv32m,io,ecc,pairing",
+            "--openvm-path",
+            openvm_path.to_str().unwrap(),
+        ],
+    )?;
+
+    // The generated config must parse and enable everything the pairing extension needs.
+    run_cmd(
+        "cargo",
+        &[
+            "openvm",
+            "keygen",
+            "--config",
+            guest_dir.join("openvm.toml").to_str().unwrap(),
+            "--output",
+            temp_dir.path().join("init.pk").to_str().unwrap(),
+            "--vk-output",
+            temp_dir.path().join("init.vk").to_str().unwrap(),
+        ],
+    )?;
+
+    Ok(())
+}
+
 fn run_cmd(program: &str, args: &[&str]) -> Result<()> {
-    let package_dir = env::current_dir()?;
+    run_cmd_in(&env::current_dir()?, program, args)
+}
+
+fn run_cmd_in(package_dir: &Path, program: &str, args: &[&str]) -> Result<()> {
     let prefix = "[test cli e2e]";
     println!(
         "{prefix} Running command: {} {} {} ...",
@@ -0,0 +1,18 @@
+//! Defers the verification of another execution: the guest reads the claim of the execution and
+//! reveals it at the start of its public values. The proof of this guest must then be aggregated
+//! by a deferral verifier generated for a claim at byte offset 0, together with a proof of the
+//! claimed execution.
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use openvm::{
+    deferral::{verify_stark, StarkClaim},
+    io::read,
+};
+
+openvm::entry!(main);
+
+pub fn main() {
+    let claim: StarkClaim = read();
+    verify_stark(&claim, 0);
+}
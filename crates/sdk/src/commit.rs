@@ -7,7 +7,7 @@ use openvm_circuit::{
         VmConfig,
     },
     system::{
-        memory::{memory_image_to_equipartition, tree::MemoryNode},
+        memory::{dimensions::MemoryDimensions, memory_image_to_equipartition, tree::MemoryNode},
         program::trace::VmCommittedExe,
     },
 };
@@ -26,6 +26,7 @@ use crate::{
 };
 
 /// `AppExecutionCommit` has all the commitments users should check against the final proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AppExecutionCommit<T> {
     /// Commitment of the leaf VM verifier program which commits the VmConfig of App VM.
     /// Internal verifier will verify `leaf_vm_verifier_commit`.
@@ -53,33 +54,17 @@ impl AppExecutionCommit<F> {
         assert!(
             app_exe.exe.program.max_num_public_values <= app_vm_config.system().num_public_values
         );
-        let hasher = vm_poseidon2_hasher();
         let memory_dimensions = app_vm_config.system().memory_config.memory_dimensions();
-        let app_program_commit: [F; DIGEST_SIZE] =
-            app_exe.committed_program.prover_data.commit.into();
         let leaf_verifier_program_commit: [F; DIGEST_SIZE] = leaf_vm_verifier_exe
             .committed_program
             .prover_data
             .commit
             .into();
-
-        let init_memory_commit = MemoryNode::tree_from_memory(
-            memory_dimensions,
-            &memory_image_to_equipartition(app_exe.exe.init_memory.clone()),
-            &hasher,
-        )
-        .hash();
-        let mut padded_pc_start = [F::ZERO; DIGEST_SIZE];
-        padded_pc_start[0] = F::from_canonical_u32(app_exe.exe.pc_start);
-        let app_hash = hasher.hash(&app_program_commit);
-        let init_memory_hash = hasher.hash(&init_memory_commit);
-        let pc_start_hash = hasher.hash(&padded_pc_start);
-        let compress_1 = hasher.compress(&app_hash, &init_memory_hash);
-        let user_commit = hasher.compress(&compress_1, &pc_start_hash);
+        let exe_commit = compute_app_exe_commit(memory_dimensions, app_exe);
 
         Self {
             leaf_vm_verifier_commit: leaf_verifier_program_commit,
-            exe_commit: user_commit,
+            exe_commit,
         }
     }
 
@@ -92,6 +77,43 @@ impl AppExecutionCommit<F> {
     }
 }
 
+/// Computes the exe commit of `app_exe` in an app VM with `memory_dimensions`. The memory
+/// dimensions of an app VM are in its `AppVerifyingKey`.
+pub fn compute_app_exe_commit(
+    memory_dimensions: MemoryDimensions,
+    app_exe: &NonRootCommittedExe,
+) -> [F; DIGEST_SIZE] {
+    let hasher = vm_poseidon2_hasher();
+    let app_program_commit: [F; DIGEST_SIZE] = app_exe.committed_program.prover_data.commit.into();
+    let init_memory_commit = MemoryNode::tree_from_memory(
+        memory_dimensions,
+        &memory_image_to_equipartition(app_exe.exe.init_memory.clone()),
+        &hasher,
+    )
+    .hash();
+    compute_exe_commit(
+        &app_program_commit,
+        &init_memory_commit,
+        F::from_canonical_u32(app_exe.exe.pc_start),
+    )
+}
+
+/// Computes the exe commit from the commitments of the app program and the initial memory.
+pub(crate) fn compute_exe_commit<T: PrimeField32>(
+    app_program_commit: &[T; DIGEST_SIZE],
+    init_memory_commit: &[T; DIGEST_SIZE],
+    pc_start: T,
+) -> [T; DIGEST_SIZE] {
+    let hasher = vm_poseidon2_hasher::<T>();
+    let mut padded_pc_start = [T::ZERO; DIGEST_SIZE];
+    padded_pc_start[0] = pc_start;
+    let app_hash = hasher.hash(app_program_commit);
+    let init_memory_hash = hasher.hash(init_memory_commit);
+    let pc_start_hash = hasher.hash(&padded_pc_start);
+    let compress_1 = hasher.compress(&app_hash, &init_memory_hash);
+    hasher.compress(&compress_1, &pc_start_hash)
+}
+
 pub(crate) fn babybear_digest_to_bn254(digest: &[F; DIGEST_SIZE]) -> Bn254Fr {
     let mut ret = Bn254Fr::ZERO;
     let order = Bn254Fr::from_canonical_u32(BabyBear::ORDER_U32);
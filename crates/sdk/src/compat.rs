@@ -0,0 +1,294 @@
+//! Static checks that an executable can run on a given VM config.
+//!
+//! Without these, an instruction that no chip handles only surfaces at runtime as
+//! [ExecutionError::DisabledOperation](openvm_circuit::arch::ExecutionError::DisabledOperation).
+
+use std::{collections::BTreeMap, fmt};
+
+use eyre::Result;
+use openvm_algebra_transpiler::{Fp2Opcode, Rv32ModularArithmeticOpcode};
+use openvm_circuit::arch::{
+    instructions::{exe::VmExe, SystemOpcode, UsizeOpcode, VmOpcode},
+    VmConfig,
+};
+use openvm_ecc_transpiler::Rv32WeierstrassOpcode;
+use openvm_pairing_transpiler::{Fp12Opcode, PairingOpcode};
+use strum::EnumCount;
+
+use crate::{config::SdkVmConfig, disasm::Disassembler, F};
+
+/// A reason why an executable cannot run to completion on a VM config.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExeIncompatibility {
+    /// No chip in the config handles `opcode`. It occurs `count` times, first at `first_pc`.
+    UnsupportedOpcode {
+        opcode: VmOpcode,
+        name: Option<String>,
+        first_pc: u32,
+        count: usize,
+    },
+    /// A modular arithmetic or complex extension field setup instruction for a modulus index
+    /// that is not listed in the `ModularExtension` or `Fp2Extension` of the config.
+    UnsupportedModulus {
+        extension: &'static str,
+        pc: u32,
+        modulus_idx: usize,
+        num_supported: usize,
+    },
+    /// A short Weierstrass curve setup instruction for a curve index that is not listed in
+    /// the `WeierstrassExtension` of the config.
+    UnsupportedCurve {
+        pc: u32,
+        curve_idx: usize,
+        num_supported: usize,
+    },
+}
+
+impl fmt::Display for ExeIncompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedOpcode {
+                opcode,
+                name,
+                first_pc,
+                count,
+            } => write!(
+                f,
+                "opcode {} ({}) is not supported by the VM config; used {count} time(s), first at pc {first_pc:#x}",
+                opcode.as_usize(),
+                name.as_deref().unwrap_or("unknown"),
+            ),
+            Self::UnsupportedModulus {
+                extension,
+                pc,
+                modulus_idx,
+                num_supported,
+            } => write!(
+                f,
+                "at pc {pc:#x}, setup for modulus #{modulus_idx} but the {extension} extension of the VM config supports {num_supported} moduli",
+            ),
+            Self::UnsupportedCurve {
+                pc,
+                curve_idx,
+                num_supported,
+            } => write!(
+                f,
+                "at pc {pc:#x}, setup for curve #{curve_idx} but the ecc extension of the VM config supports {num_supported} curves",
+            ),
+        }
+    }
+}
+
+/// Scans the program of `exe` for instructions that `vm_config` cannot execute.
+/// Returns an empty list if the executable is compatible.
+pub fn check_exe_compatible(
+    exe: &VmExe<F>,
+    vm_config: &SdkVmConfig,
+) -> Result<Vec<ExeIncompatibility>> {
+    let chip_complex = VmConfig::<F>::create_chip_complex(vm_config)?;
+    let disassembler = Disassembler::default();
+    let terminate = VmOpcode::with_default_offset(SystemOpcode::TERMINATE);
+
+    let setup_checks = [
+        SetupCheck {
+            kind: SetupKind::Modulus {
+                extension: "modular",
+            },
+            offset: Rv32ModularArithmeticOpcode::default_offset(),
+            end: Rv32WeierstrassOpcode::default_offset(),
+            count: Rv32ModularArithmeticOpcode::COUNT,
+            setup_opcodes: &[
+                Rv32ModularArithmeticOpcode::SETUP_ADDSUB as usize,
+                Rv32ModularArithmeticOpcode::SETUP_MULDIV as usize,
+                Rv32ModularArithmeticOpcode::SETUP_ISEQ as usize,
+            ],
+            num_supported: vm_config
+                .modular
+                .as_ref()
+                .map_or(0, |ext| ext.supported_modulus.len()),
+        },
+        SetupCheck {
+            kind: SetupKind::Modulus { extension: "fp2" },
+            offset: Fp2Opcode::default_offset(),
+            end: PairingOpcode::default_offset(),
+            count: Fp2Opcode::COUNT,
+            setup_opcodes: &[
+                Fp2Opcode::SETUP_ADDSUB as usize,
+                Fp2Opcode::SETUP_MULDIV as usize,
+            ],
+            num_supported: vm_config
+                .fp2
+                .as_ref()
+                .map_or(0, |ext| ext.supported_modulus.len()),
+        },
+        SetupCheck {
+            kind: SetupKind::Curve,
+            offset: Rv32WeierstrassOpcode::default_offset(),
+            end: Fp12Opcode::default_offset(),
+            count: Rv32WeierstrassOpcode::COUNT,
+            setup_opcodes: &[
+                Rv32WeierstrassOpcode::SETUP_EC_ADD_NE as usize,
+                Rv32WeierstrassOpcode::SETUP_EC_DOUBLE as usize,
+            ],
+            num_supported: vm_config
+                .ecc
+                .as_ref()
+                .map_or(0, |ext| ext.supported_curves.len()),
+        },
+    ];
+
+    let mut incompatibilities = Vec::new();
+    // opcode -> (first pc, count)
+    let mut unsupported: BTreeMap<usize, (u32, usize)> = BTreeMap::new();
+    for (pc, instruction, _) in exe.program.enumerate_by_pc() {
+        let opcode = instruction.opcode;
+        if opcode == terminate {
+            // Handled by the execution segment rather than a chip.
+            continue;
+        }
+        if let Some(incompatibility) = setup_checks
+            .iter()
+            .find_map(|check| check.check(pc, opcode))
+        {
+            incompatibilities.push(incompatibility);
+            continue;
+        }
+        if chip_complex.inventory.get_executor(opcode).is_none() {
+            unsupported
+                .entry(opcode.as_usize())
+                .and_modify(|(_, count)| *count += 1)
+                .or_insert((pc, 1));
+        }
+    }
+    incompatibilities.extend(unsupported.into_iter().map(|(opcode, (first_pc, count))| {
+        let opcode = VmOpcode::from_usize(opcode);
+        ExeIncompatibility::UnsupportedOpcode {
+            opcode,
+            name: disassembler.opcode_name(opcode),
+            first_pc,
+            count,
+        }
+    }));
+    Ok(incompatibilities)
+}
+
+/// What the index of a repeated block of setup instructions refers to.
+enum SetupKind {
+    Modulus { extension: &'static str },
+    Curve,
+}
+
+/// Setup instructions of an extension whose opcodes are repeated once per modulus or curve,
+/// from `offset` up to where the opcodes of the next extension begin at `end`.
+struct SetupCheck {
+    kind: SetupKind,
+    offset: usize,
+    end: usize,
+    count: usize,
+    setup_opcodes: &'static [usize],
+    num_supported: usize,
+}
+
+impl SetupCheck {
+    fn check(&self, pc: u32, opcode: VmOpcode) -> Option<ExeIncompatibility> {
+        if opcode.as_usize() >= self.end {
+            return None;
+        }
+        let local_opcode = opcode.as_usize().checked_sub(self.offset)?;
+        let idx = local_opcode / self.count;
+        if !self.setup_opcodes.contains(&(local_opcode % self.count)) || idx < self.num_supported {
+            return None;
+        }
+        Some(match self.kind {
+            SetupKind::Modulus { extension } => ExeIncompatibility::UnsupportedModulus {
+                extension,
+                pc,
+                modulus_idx: idx,
+                num_supported: self.num_supported,
+            },
+            SetupKind::Curve => ExeIncompatibility::UnsupportedCurve {
+                pc,
+                curve_idx: idx,
+                num_supported: self.num_supported,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_algebra_circuit::ModularExtension;
+    use openvm_circuit::arch::instructions::{instruction::Instruction, program::Program};
+    use openvm_ecc_circuit::SECP256K1_CONFIG;
+    use openvm_rv32im_transpiler::BaseAluOpcode;
+
+    use super::*;
+
+    #[test]
+    fn test_check_exe_compatible() {
+        let modular_opcode = |idx: usize, opcode: Rv32ModularArithmeticOpcode| {
+            VmOpcode::from_usize(
+                Rv32ModularArithmeticOpcode::default_offset()
+                    + idx * Rv32ModularArithmeticOpcode::COUNT
+                    + opcode as usize,
+            )
+        };
+        let instructions = vec![
+            Instruction::<F>::from_usize(VmOpcode::with_default_offset(BaseAluOpcode::ADD), []),
+            Instruction::from_usize(
+                modular_opcode(0, Rv32ModularArithmeticOpcode::SETUP_ADDSUB),
+                [],
+            ),
+            Instruction::from_usize(
+                modular_opcode(1, Rv32ModularArithmeticOpcode::SETUP_MULDIV),
+                [],
+            ),
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(Rv32WeierstrassOpcode::SETUP_EC_DOUBLE),
+                [],
+            ),
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(Rv32WeierstrassOpcode::EC_DOUBLE),
+                [],
+            ),
+            Instruction::from_usize(
+                VmOpcode::with_default_offset(Rv32WeierstrassOpcode::EC_DOUBLE),
+                [],
+            ),
+            Instruction::from_usize(VmOpcode::with_default_offset(SystemOpcode::TERMINATE), []),
+        ];
+        let exe = VmExe::new(Program::from_instructions(&instructions));
+        let vm_config = SdkVmConfig::builder()
+            .system(Default::default())
+            .rv32i(Default::default())
+            .modular(ModularExtension::new(vec![SECP256K1_CONFIG
+                .modulus
+                .clone()]))
+            .build();
+
+        let incompatibilities = check_exe_compatible(&exe, &vm_config).unwrap();
+        let ec_double = VmOpcode::with_default_offset(Rv32WeierstrassOpcode::EC_DOUBLE);
+        assert_eq!(
+            incompatibilities,
+            vec![
+                ExeIncompatibility::UnsupportedModulus {
+                    extension: "modular",
+                    pc: 8,
+                    modulus_idx: 1,
+                    num_supported: 1,
+                },
+                ExeIncompatibility::UnsupportedCurve {
+                    pc: 12,
+                    curve_idx: 0,
+                    num_supported: 0,
+                },
+                ExeIncompatibility::UnsupportedOpcode {
+                    opcode: ec_double,
+                    name: Disassembler::default().opcode_name(ec_double),
+                    first_pc: 16,
+                    count: 2,
+                },
+            ]
+        );
+    }
+}
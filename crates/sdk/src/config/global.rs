@@ -26,11 +26,13 @@ use openvm_pairing_circuit::{
 };
 use openvm_pairing_transpiler::PairingTranspilerExtension;
 use openvm_rv32im_circuit::{
-    Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io, Rv32IoExecutor, Rv32IoPeriphery, Rv32M,
-    Rv32MExecutor, Rv32MPeriphery,
+    Rv32A, Rv32AExecutor, Rv32APeriphery, Rv32I, Rv32IExecutor, Rv32IPeriphery, Rv32Io,
+    Rv32IoExecutor, Rv32IoPeriphery, Rv32M, Rv32MExecutor, Rv32MPeriphery, Rv32Zbb,
+    Rv32ZbbExecutor, Rv32ZbbPeriphery,
 };
 use openvm_rv32im_transpiler::{
-    Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+    Rv32ATranspilerExtension, Rv32ITranspilerExtension, Rv32IoTranspilerExtension,
+    Rv32MTranspilerExtension, Rv32ZbbTranspilerExtension,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::transpiler::Transpiler;
@@ -47,6 +49,12 @@ pub struct SdkVmConfig {
     pub io: Option<UnitStruct>,
     pub keccak: Option<UnitStruct>,
     pub native: Option<UnitStruct>,
+    /// Zbb and Zba bit-manipulation instructions. Guests are compiled with the matching
+    /// target features when this is enabled.
+    pub zbb: Option<UnitStruct>,
+    /// Word-sized atomic instructions. Guests are compiled with the `a` target feature when
+    /// this is enabled.
+    pub rv32a: Option<UnitStruct>,
 
     pub rv32m: Option<Rv32M>,
     pub bigint: Option<Int256>,
@@ -69,6 +77,10 @@ pub enum SdkVmConfigExecutor<F: PrimeField32> {
     #[any_enum]
     Native(NativeExecutor<F>),
     #[any_enum]
+    Zbb(Rv32ZbbExecutor<F>),
+    #[any_enum]
+    Rv32a(Rv32AExecutor<F>),
+    #[any_enum]
     Rv32m(Rv32MExecutor<F>),
     #[any_enum]
     BigInt(Int256Executor<F>),
@@ -95,6 +107,10 @@ pub enum SdkVmConfigPeriphery<F: PrimeField32> {
     #[any_enum]
     Native(NativePeriphery<F>),
     #[any_enum]
+    Zbb(Rv32ZbbPeriphery<F>),
+    #[any_enum]
+    Rv32a(Rv32APeriphery<F>),
+    #[any_enum]
     Rv32m(Rv32MPeriphery<F>),
     #[any_enum]
     BigInt(Int256Periphery<F>),
@@ -120,6 +136,12 @@ impl SdkVmConfig {
         if self.keccak.is_some() {
             transpiler = transpiler.with_extension(Keccak256TranspilerExtension);
         }
+        if self.zbb.is_some() {
+            transpiler = transpiler.with_extension(Rv32ZbbTranspilerExtension);
+        }
+        if self.rv32a.is_some() {
+            transpiler = transpiler.with_extension(Rv32ATranspilerExtension);
+        }
         if self.rv32m.is_some() {
             transpiler = transpiler.with_extension(Rv32MTranspilerExtension);
         }
@@ -140,6 +162,19 @@ impl SdkVmConfig {
         }
         transpiler
     }
+
+    /// Target features the guest must be compiled with for the transpiler to recognize
+    /// all instructions supported by this config.
+    pub fn target_features(&self) -> Vec<String> {
+        let mut features = Vec::new();
+        if self.zbb.is_some() {
+            features.extend(["+zbb".to_string(), "+zba".to_string()]);
+        }
+        if self.rv32a.is_some() {
+            features.push("+a".to_string());
+        }
+        features
+    }
 }
 
 impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
@@ -171,6 +206,12 @@ impl<F: PrimeField32> VmConfig<F> for SdkVmConfig {
         if self.native.is_some() {
             complex = complex.extend(&Native)?;
         }
+        if self.zbb.is_some() {
+            complex = complex.extend(&Rv32Zbb)?;
+        }
+        if self.rv32a.is_some() {
+            complex = complex.extend(&Rv32A)?;
+        }
 
         if let Some(rv32m) = self.rv32m {
             let mut rv32m = rv32m;
@@ -256,3 +297,15 @@ impl From<Native> for UnitStruct {
         UnitStruct {}
     }
 }
+
+impl From<Rv32Zbb> for UnitStruct {
+    fn from(_: Rv32Zbb) -> Self {
+        UnitStruct {}
+    }
+}
+
+impl From<Rv32A> for UnitStruct {
+    fn from(_: Rv32A) -> Self {
+        UnitStruct {}
+    }
+}
@@ -1,10 +1,14 @@
-use openvm_circuit::arch::instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES;
+use openvm_circuit::arch::{instructions::program::DEFAULT_MAX_NUM_PUBLIC_VALUES, VmConfig};
 use openvm_native_compiler::conversion::CompilerOptions;
 use openvm_stark_sdk::config::FriParameters;
 use serde::{Deserialize, Serialize};
 
+use crate::F;
+
 mod global;
+mod security;
 pub use global::*;
+pub use security::*;
 
 const DEFAULT_APP_BLOWUP: usize = 2;
 const DEFAULT_LEAF_BLOWUP: usize = 2;
@@ -21,6 +25,10 @@ pub struct AppConfig<VC> {
     /// Only for AggVM debugging. App VM users should not need this in regular flow.
     #[serde(default)]
     pub compiler_options: CompilerOptions,
+    /// If set, `app_fri_params` and `leaf_fri_params` are chosen to reach this target by
+    /// [AppConfig::apply_security_target].
+    #[serde(default)]
+    pub security: Option<SecurityTarget>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -29,16 +37,38 @@ pub struct AggConfig {
     pub agg_stark_config: AggStarkConfig,
     /// STARK-to-SNARK and SNARK-to-SNARK aggregation config
     pub halo2_config: Halo2Config,
+    /// Which proof system wraps the root verifier for on-chain verification.
+    #[serde(default)]
+    pub final_stage: FinalStage,
+}
+
+/// The last stage of the aggregation pipeline, i.e. what is verified on-chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FinalStage {
+    /// The root STARK is verified by a Halo2 circuit and the EVM verifies a SNARK.
+    #[default]
+    Halo2,
+    /// The root STARK uses a Keccak transcript and Merkle hashing and is verified directly by a
+    /// generated Solidity FRI verifier.
+    KeccakStark,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AggStarkConfig {
     pub max_num_user_public_values: usize,
     pub leaf_fri_params: FriParameters,
+    /// FRI parameters of leaf verifier proofs accepted by the internal verifier besides
+    /// `leaf_fri_params`, so apps with different leaf FRI parameters share the aggregation keys.
+    #[serde(default)]
+    pub additional_leaf_fri_params: Vec<FriParameters>,
     pub internal_fri_params: FriParameters,
     pub root_fri_params: FriParameters,
     /// Only for AggVM debugging.
     pub compiler_options: CompilerOptions,
+    /// If set, the FRI parameters of every layer are chosen to reach this target by
+    /// [AggStarkConfig::apply_security_target].
+    #[serde(default)]
+    pub security: Option<SecurityTarget>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -56,6 +86,7 @@ impl<VC> AppConfig<VC> {
             app_vm_config,
             leaf_fri_params: Default::default(),
             compiler_options: Default::default(),
+            security: None,
         }
     }
 
@@ -69,6 +100,96 @@ impl<VC> AppConfig<VC> {
             app_vm_config,
             leaf_fri_params: LeafFriParams::from(leaf_fri_params),
             compiler_options: Default::default(),
+            security: None,
+        }
+    }
+
+    /// Model in which the security of the app proofs is claimed.
+    pub fn security_model(&self) -> SecurityModel {
+        self.security
+            .map(|security| security.model)
+            .unwrap_or_default()
+    }
+}
+
+impl<VC: VmConfig<F>> AppConfig<VC> {
+    /// Creates a config whose FRI parameters are chosen to reach `security`.
+    pub fn with_security_target(security: SecurityTarget, app_vm_config: VC) -> Self {
+        let mut config = Self {
+            security: Some(security),
+            ..Self::new(AppFriParams::default().fri_params, app_vm_config)
+        };
+        config.apply_security_target();
+        config
+    }
+
+    /// Overwrites the FRI parameters of the app and leaf layers with the ones reaching
+    /// `self.security`, if set. The app log blowup is the smallest one supporting the constraint
+    /// degree of the app VM.
+    pub fn apply_security_target(&mut self) {
+        if let Some(security) = self.security {
+            let app_log_blowup =
+                log_blowup_for_constraint_degree(self.app_vm_config.system().max_constraint_degree);
+            self.app_fri_params = security.fri_params(app_log_blowup).into();
+            self.leaf_fri_params = security.fri_params(DEFAULT_LEAF_BLOWUP).into();
+        }
+    }
+}
+
+impl AggStarkConfig {
+    /// Creates a config whose FRI parameters are chosen to reach `security`.
+    pub fn with_security_target(security: SecurityTarget) -> Self {
+        let mut config = Self {
+            security: Some(security),
+            ..Default::default()
+        };
+        config.apply_security_target();
+        config
+    }
+
+    /// Overwrites the FRI parameters of the leaf, internal and root layers with the ones reaching
+    /// `self.security`, if set.
+    pub fn apply_security_target(&mut self) {
+        if let Some(security) = self.security {
+            self.leaf_fri_params = security.fri_params(DEFAULT_LEAF_BLOWUP);
+            for params in &mut self.additional_leaf_fri_params {
+                *params = security.fri_params(params.log_blowup);
+            }
+            self.internal_fri_params = security.fri_params(DEFAULT_INTERNAL_BLOWUP);
+            self.root_fri_params = security.fri_params(DEFAULT_ROOT_BLOWUP);
+        }
+    }
+
+    /// FRI parameters of the leaf verifier proofs accepted by the internal verifier. The index
+    /// of the parameters of a proof is part of the internal and root verifier inputs.
+    pub fn allowed_leaf_fri_params(&self) -> Vec<FriParameters> {
+        let mut allowed = vec![self.leaf_fri_params];
+        allowed.extend(self.additional_leaf_fri_params.iter().copied());
+        allowed
+    }
+
+    /// Model in which the security of the aggregation proofs is claimed.
+    pub fn security_model(&self) -> SecurityModel {
+        self.security
+            .map(|security| security.model)
+            .unwrap_or_default()
+    }
+
+    /// FRI parameters and claimed security of the leaf, internal and root layers.
+    pub fn security_levels(&self) -> AggSecurityLevels {
+        let model = self.security_model();
+        let layer = |fri_params: FriParameters| LayerSecurity {
+            fri_params,
+            security: model.security_level(&fri_params),
+        };
+        AggSecurityLevels {
+            leaf: self
+                .allowed_leaf_fri_params()
+                .into_iter()
+                .map(layer)
+                .collect(),
+            internal: layer(self.internal_fri_params),
+            root: layer(self.root_fri_params),
         }
     }
 }
@@ -80,6 +201,7 @@ impl Default for AggStarkConfig {
             leaf_fri_params: FriParameters::standard_with_100_bits_conjectured_security(
                 DEFAULT_LEAF_BLOWUP,
             ),
+            additional_leaf_fri_params: vec![],
             internal_fri_params: FriParameters::standard_with_100_bits_conjectured_security(
                 DEFAULT_INTERNAL_BLOWUP,
             ),
@@ -87,6 +209,7 @@ impl Default for AggStarkConfig {
                 DEFAULT_ROOT_BLOWUP,
             ),
             compiler_options: Default::default(),
+            security: None,
         }
     }
 }
@@ -99,6 +222,7 @@ impl Default for AggConfig {
                 verifier_k: 24,
                 wrapper_k: None,
             },
+            final_stage: FinalStage::default(),
         }
     }
 }
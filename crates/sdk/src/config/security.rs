@@ -0,0 +1,242 @@
+use openvm_stark_sdk::config::FriParameters;
+use serde::{Deserialize, Serialize};
+
+/// Proof of work bits of the FRI parameters chosen for a [SecurityTarget].
+const PROOF_OF_WORK_BITS: usize = 16;
+/// Smallest log blowup chosen when [FriPreference::ProofSize] is preferred.
+const PROOF_SIZE_LOG_BLOWUP: usize = 4;
+/// Bits of the challenge field, the degree 4 extension of BabyBear, rounded down. No choice of
+/// FRI parameters gives more security than this.
+pub const CHALLENGE_FIELD_BITS: usize = 123;
+
+/// How the soundness of FRI is estimated from its parameters. Neither model is a proven bound on
+/// the soundness error.
+///
+/// In both models the security is capped at [CHALLENGE_FIELD_BITS].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SecurityModel {
+    /// The ethSTARK conjecture: every query contributes `log_blowup` bits.
+    #[default]
+    Conjectured,
+    /// Every query contributes `log_blowup / 2` bits, the query error of FRI up to the Johnson
+    /// bound. This only accounts for the queries: the commit-phase and list-decoding errors, which
+    /// depend on the field size and the trace heights, are ignored, so it is a more conservative
+    /// estimate than [SecurityModel::Conjectured] rather than a proven security level.
+    JohnsonBoundQueries,
+}
+
+/// What to optimize for when choosing the FRI parameters of a [SecurityTarget].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FriPreference {
+    /// The smallest blowup the constraints allow, with more queries.
+    #[default]
+    ProvingTime,
+    /// A larger blowup, with fewer queries and therefore smaller proofs.
+    ProofSize,
+}
+
+/// Target security of the proofs of a layer, from which the SDK chooses its FRI parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityTarget {
+    pub bits: usize,
+    #[serde(default)]
+    pub model: SecurityModel,
+    #[serde(default)]
+    pub preference: FriPreference,
+}
+
+/// Security claimed for the proofs of a layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityLevel {
+    pub bits: usize,
+    pub model: SecurityModel,
+}
+
+/// FRI parameters of a layer and the security claimed for its proofs.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LayerSecurity {
+    pub fri_params: FriParameters,
+    pub security: SecurityLevel,
+}
+
+/// Security of the layers of the aggregation, recorded in the aggregation keys.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AggSecurityLevels {
+    /// One entry per allowed leaf FRI parameters, in the order of their index.
+    pub leaf: Vec<LayerSecurity>,
+    pub internal: LayerSecurity,
+    pub root: LayerSecurity,
+}
+
+impl Default for SecurityTarget {
+    fn default() -> Self {
+        Self {
+            bits: 100,
+            model: SecurityModel::Conjectured,
+            preference: FriPreference::ProvingTime,
+        }
+    }
+}
+
+impl SecurityTarget {
+    /// FRI parameters reaching the target with a log blowup of at least `min_log_blowup`.
+    ///
+    /// Panics if the target exceeds [CHALLENGE_FIELD_BITS].
+    pub fn fri_params(&self, min_log_blowup: usize) -> FriParameters {
+        assert!(min_log_blowup > 0, "log blowup must be positive");
+        assert!(
+            self.bits <= CHALLENGE_FIELD_BITS,
+            "target of {} bits exceeds the {} bits of the challenge field",
+            self.bits,
+            CHALLENGE_FIELD_BITS
+        );
+        let log_blowup = match self.preference {
+            FriPreference::ProvingTime => min_log_blowup,
+            FriPreference::ProofSize => min_log_blowup.max(PROOF_SIZE_LOG_BLOWUP),
+        };
+        let proof_of_work_bits = PROOF_OF_WORK_BITS.min(self.bits);
+        let num_queries = (2 * (self.bits - proof_of_work_bits))
+            .div_ceil(self.model.doubled_bits_per_query(log_blowup))
+            .max(1);
+        FriParameters {
+            log_blowup,
+            num_queries,
+            proof_of_work_bits,
+        }
+    }
+}
+
+impl SecurityModel {
+    /// Security of proofs with `fri_params` in this model.
+    pub fn security_level(self, fri_params: &FriParameters) -> SecurityLevel {
+        let query_bits =
+            fri_params.num_queries * self.doubled_bits_per_query(fri_params.log_blowup) / 2;
+        SecurityLevel {
+            bits: (query_bits + fri_params.proof_of_work_bits).min(CHALLENGE_FIELD_BITS),
+            model: self,
+        }
+    }
+
+    /// Bits of security of a single query, doubled to stay integral.
+    fn doubled_bits_per_query(self, log_blowup: usize) -> usize {
+        match self {
+            SecurityModel::Conjectured => 2 * log_blowup,
+            SecurityModel::JohnsonBoundQueries => log_blowup,
+        }
+    }
+}
+
+/// Smallest log blowup whose FRI parameters support constraints of degree `max_constraint_degree`.
+pub fn log_blowup_for_constraint_degree(max_constraint_degree: usize) -> usize {
+    // See `FriParameters::max_constraint_degree`.
+    let mut log_blowup = 1;
+    while (1 << log_blowup) + 1 < max_constraint_degree {
+        log_blowup += 1;
+    }
+    log_blowup
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fri_params_reach_target() {
+        for (model, preference, min_log_blowup, bits) in [
+            (
+                SecurityModel::Conjectured,
+                FriPreference::ProvingTime,
+                2,
+                100,
+            ),
+            (SecurityModel::Conjectured, FriPreference::ProofSize, 2, 120),
+            (
+                SecurityModel::JohnsonBoundQueries,
+                FriPreference::ProvingTime,
+                1,
+                100,
+            ),
+            (
+                SecurityModel::JohnsonBoundQueries,
+                FriPreference::ProofSize,
+                3,
+                80,
+            ),
+        ] {
+            let target = SecurityTarget {
+                bits,
+                model,
+                preference,
+            };
+            let fri_params = target.fri_params(min_log_blowup);
+            assert!(fri_params.log_blowup >= min_log_blowup);
+            assert!(model.security_level(&fri_params).bits >= bits);
+
+            // One query less misses the target.
+            let fewer_queries = FriParameters {
+                num_queries: fri_params.num_queries - 1,
+                ..fri_params
+            };
+            assert!(model.security_level(&fewer_queries).bits < bits);
+        }
+    }
+
+    #[test]
+    fn test_challenge_field_cap() {
+        let fri_params = FriParameters {
+            log_blowup: 4,
+            num_queries: 100,
+            proof_of_work_bits: 16,
+        };
+        let security = SecurityModel::Conjectured.security_level(&fri_params);
+        assert_eq!(security.bits, CHALLENGE_FIELD_BITS);
+
+        let target = SecurityTarget {
+            bits: CHALLENGE_FIELD_BITS,
+            ..Default::default()
+        };
+        let fri_params = target.fri_params(2);
+        assert_eq!(
+            SecurityModel::Conjectured.security_level(&fri_params).bits,
+            CHALLENGE_FIELD_BITS
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "challenge field")]
+    fn test_target_above_challenge_field() {
+        SecurityTarget {
+            bits: CHALLENGE_FIELD_BITS + 1,
+            ..Default::default()
+        }
+        .fri_params(2);
+    }
+
+    #[test]
+    fn test_proof_size_preference() {
+        let fri_params = |preference| {
+            SecurityTarget {
+                preference,
+                ..Default::default()
+            }
+            .fri_params(2)
+        };
+        let fast = fri_params(FriPreference::ProvingTime);
+        let small = fri_params(FriPreference::ProofSize);
+        assert!(small.log_blowup > fast.log_blowup);
+        assert!(small.num_queries < fast.num_queries);
+    }
+
+    #[test]
+    fn test_log_blowup_for_constraint_degree() {
+        assert_eq!(log_blowup_for_constraint_degree(2), 1);
+        assert_eq!(log_blowup_for_constraint_degree(3), 1);
+        assert_eq!(log_blowup_for_constraint_degree(5), 2);
+        assert_eq!(log_blowup_for_constraint_degree(7), 3);
+        for degree in 2..20 {
+            let log_blowup = log_blowup_for_constraint_degree(degree);
+            let fri_params = SecurityTarget::default().fri_params(log_blowup);
+            assert!(fri_params.max_constraint_degree() >= degree);
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Human-readable listing of a [VmExe].
+//!
+//! Opcode names are resolved through the opcode enums of every extension known to the SDK, so
+//! the listing does not depend on the VM config the executable was built for.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Write},
+};
+
+use openvm_algebra_transpiler::{Fp2Opcode, Rv32ModularArithmeticOpcode};
+use openvm_bigint_transpiler::{
+    Rv32BaseAlu256Opcode, Rv32BranchEqual256Opcode, Rv32BranchLessThan256Opcode,
+    Rv32LessThan256Opcode, Rv32Mul256Opcode, Rv32Shift256Opcode,
+};
+use openvm_circuit::arch::instructions::{
+    exe::VmExe, instruction::Instruction, PhantomDiscriminant, Poseidon2Opcode, PublishOpcode,
+    SysPhantom, SystemOpcode, UsizeOpcode, VmOpcode,
+};
+use openvm_ecc_transpiler::{EccPhantom, Rv32WeierstrassOpcode};
+use openvm_keccak256_transpiler::Rv32KeccakOpcode;
+use openvm_native_compiler::{
+    CastfOpcode, FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
+    NativeJalOpcode, NativeJalrOpcode, NativeLoadStoreOpcode, NativePhantom,
+};
+use openvm_pairing_transpiler::{Fp12Opcode, PairingOpcode, PairingPhantom};
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, BitwiseNotOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode,
+    LessThanOpcode, MinMaxOpcode, MulHOpcode, MulOpcode, RotateOpcode, Rv32AmoAluOpcode,
+    Rv32AmoMinMaxOpcode, Rv32AmoSwapOpcode, Rv32AuipcOpcode, Rv32BitManipUnaryOpcode,
+    Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode, Rv32Phantom,
+    ShAddOpcode, ShiftOpcode,
+};
+use openvm_stark_backend::p3_field::PrimeField32;
+use rustc_demangle::demangle;
+use strum::IntoEnumIterator;
+
+/// Number of moduli or curves assumed for the last repeated class, which no class bounds.
+const MAX_INSTANCES: usize = 16;
+
+/// A contiguous block of opcodes sharing a default offset.
+struct OpcodeClass {
+    /// Names indexed by local opcode, `None` for local opcodes the enum never produces.
+    names: Vec<Option<String>>,
+    /// Whether the block is repeated once per modulus or curve, as the algebra extensions do.
+    repeats: bool,
+    /// First global opcode past the class. A repeated block extends up to the offset of the
+    /// next class, or [MAX_INSTANCES] blocks for the last class.
+    end: usize,
+}
+
+/// Resolves global opcodes to names and formats instructions and executables.
+pub struct Disassembler {
+    classes: BTreeMap<usize, OpcodeClass>,
+}
+
+impl Default for Disassembler {
+    fn default() -> Self {
+        let mut disassembler = Self {
+            classes: BTreeMap::new(),
+        };
+        // System
+        disassembler.add::<SystemOpcode, _>("", SystemOpcode::iter(), false);
+        disassembler.add::<PublishOpcode, _>("", PublishOpcode::iter(), false);
+        disassembler.add::<Poseidon2Opcode, _>("", Poseidon2Opcode::iter(), false);
+        // Native kernel
+        disassembler.add::<NativeLoadStoreOpcode, _>(
+            "native.",
+            NativeLoadStoreOpcode::iter(),
+            false,
+        );
+        disassembler.add::<NativeBranchEqualOpcode, _>("native.", BranchEqualOpcode::iter(), false);
+        disassembler.add::<NativeJalOpcode, _>("native.", NativeJalOpcode::iter(), false);
+        disassembler.add::<NativeJalrOpcode, _>("native.", NativeJalrOpcode::iter(), false);
+        disassembler.add::<CastfOpcode, _>("native.", CastfOpcode::iter(), false);
+        disassembler.add::<FieldArithmeticOpcode, _>(
+            "native.",
+            FieldArithmeticOpcode::iter(),
+            false,
+        );
+        disassembler.add::<FieldExtensionOpcode, _>("native.", FieldExtensionOpcode::iter(), false);
+        disassembler.add::<FriOpcode, _>("native.", FriOpcode::iter(), false);
+        // RV32IM
+        disassembler.add::<BaseAluOpcode, _>("", BaseAluOpcode::iter(), false);
+        disassembler.add::<ShiftOpcode, _>("", ShiftOpcode::iter(), false);
+        disassembler.add::<LessThanOpcode, _>("", LessThanOpcode::iter(), false);
+        disassembler.add::<Rv32LoadStoreOpcode, _>("", Rv32LoadStoreOpcode::iter(), false);
+        disassembler.add::<BranchEqualOpcode, _>("", BranchEqualOpcode::iter(), false);
+        disassembler.add::<BranchLessThanOpcode, _>("", BranchLessThanOpcode::iter(), false);
+        disassembler.add::<Rv32JalLuiOpcode, _>("", Rv32JalLuiOpcode::iter(), false);
+        disassembler.add::<Rv32JalrOpcode, _>("", Rv32JalrOpcode::iter(), false);
+        disassembler.add::<Rv32AuipcOpcode, _>("", Rv32AuipcOpcode::iter(), false);
+        disassembler.add::<MulOpcode, _>("", MulOpcode::iter(), false);
+        disassembler.add::<MulHOpcode, _>("", MulHOpcode::iter(), false);
+        disassembler.add::<DivRemOpcode, _>("", DivRemOpcode::iter(), false);
+        disassembler.add::<BitwiseNotOpcode, _>("", BitwiseNotOpcode::iter(), false);
+        disassembler.add::<MinMaxOpcode, _>("", MinMaxOpcode::iter(), false);
+        disassembler.add::<RotateOpcode, _>("", RotateOpcode::iter(), false);
+        disassembler.add::<ShAddOpcode, _>("", ShAddOpcode::iter(), false);
+        disassembler.add::<Rv32BitManipUnaryOpcode, _>("", Rv32BitManipUnaryOpcode::iter(), false);
+        disassembler.add::<Rv32AmoAluOpcode, _>(
+            "AMO",
+            Rv32AmoAluOpcode::iter().map(|opcode| opcode.0),
+            false,
+        );
+        disassembler.add::<Rv32AmoMinMaxOpcode, _>("AMO", MinMaxOpcode::iter(), false);
+        disassembler.add::<Rv32AmoSwapOpcode, _>("", Rv32AmoSwapOpcode::iter(), false);
+        disassembler.add::<Rv32HintStoreOpcode, _>("", Rv32HintStoreOpcode::iter(), false);
+        // Keccak
+        disassembler.add::<Rv32KeccakOpcode, _>("", Rv32KeccakOpcode::iter(), false);
+        // 256-bit integers
+        disassembler.add::<Rv32BaseAlu256Opcode, _>("int256.", BaseAluOpcode::iter(), false);
+        disassembler.add::<Rv32Shift256Opcode, _>("int256.", ShiftOpcode::iter(), false);
+        disassembler.add::<Rv32LessThan256Opcode, _>("int256.", LessThanOpcode::iter(), false);
+        disassembler.add::<Rv32BranchEqual256Opcode, _>(
+            "int256.",
+            BranchEqualOpcode::iter(),
+            false,
+        );
+        disassembler.add::<Rv32BranchLessThan256Opcode, _>(
+            "int256.",
+            BranchLessThanOpcode::iter(),
+            false,
+        );
+        disassembler.add::<Rv32Mul256Opcode, _>("int256.", MulOpcode::iter(), false);
+        // Algebra, elliptic curves and pairings
+        disassembler.add::<Rv32ModularArithmeticOpcode, _>(
+            "modular.",
+            Rv32ModularArithmeticOpcode::iter(),
+            true,
+        );
+        disassembler.add::<Rv32WeierstrassOpcode, _>("ecc.", Rv32WeierstrassOpcode::iter(), true);
+        disassembler.add::<Fp12Opcode, _>("fp12.", Fp12Opcode::iter(), true);
+        disassembler.add::<Fp2Opcode, _>("fp2.", Fp2Opcode::iter(), true);
+        disassembler.add::<PairingOpcode, _>("pairing.", PairingOpcode::iter(), true);
+        disassembler.set_ends();
+        disassembler
+    }
+}
+
+impl Disassembler {
+    /// Registers the block at the default offset of `O`. The prefix tells apart classes reusing
+    /// the same local opcode enum.
+    fn add<O: UsizeOpcode, L: UsizeOpcode + Debug>(
+        &mut self,
+        prefix: &str,
+        local_opcodes: impl Iterator<Item = L>,
+        repeats: bool,
+    ) {
+        let mut names = Vec::new();
+        for local_opcode in local_opcodes {
+            let index = local_opcode.as_usize();
+            if names.len() <= index {
+                names.resize(index + 1, None);
+            }
+            names[index] = Some(format!("{prefix}{local_opcode:?}"));
+        }
+        self.classes.insert(
+            O::default_offset(),
+            OpcodeClass {
+                names,
+                repeats,
+                end: 0,
+            },
+        );
+    }
+
+    /// Bounds every class by the offset of the next one. Called once all classes are added.
+    fn set_ends(&mut self) {
+        let next_offsets: Vec<_> = self.classes.keys().skip(1).copied().map(Some).collect();
+        for ((offset, class), next_offset) in self
+            .classes
+            .iter_mut()
+            .zip(next_offsets.into_iter().chain([None]))
+        {
+            let len = class.names.len();
+            class.end = match (class.repeats, next_offset) {
+                (false, _) => offset + len,
+                (true, Some(next_offset)) => next_offset,
+                (true, None) => offset + len * MAX_INSTANCES,
+            };
+        }
+    }
+
+    /// Returns the name of `opcode`, or `None` if it does not belong to any known extension.
+    /// Opcodes of repeated classes are suffixed with the index of their modulus or curve.
+    pub fn opcode_name(&self, opcode: VmOpcode) -> Option<String> {
+        let opcode = opcode.as_usize();
+        let (offset, class) = self.classes.range(..=opcode).next_back()?;
+        if opcode >= class.end {
+            return None;
+        }
+        let local_opcode = opcode - offset;
+        let count = class.names.len();
+        let name = class.names[local_opcode % count].as_ref()?;
+        if local_opcode < count {
+            Some(name.clone())
+        } else {
+            Some(format!("{name}<{}>", local_opcode / count))
+        }
+    }
+
+    /// Formats a single instruction. Operands are shown as signed integers and trailing zero
+    /// operands are omitted. Phantom instructions are shown with their sub-instruction name.
+    pub fn format_instruction<F: PrimeField32>(&self, instruction: &Instruction<F>) -> String {
+        let name = self
+            .opcode_name(instruction.opcode)
+            .unwrap_or_else(|| format!("UNKNOWN({:#x})", instruction.opcode.as_usize()));
+        let mut operands = vec![
+            instruction.a,
+            instruction.b,
+            instruction.c,
+            instruction.d,
+            instruction.e,
+            instruction.f,
+            instruction.g,
+        ];
+        let mut line = name;
+        if instruction.opcode == VmOpcode::with_default_offset(SystemOpcode::PHANTOM) {
+            let c = instruction.c.as_canonical_u32();
+            let discriminant = PhantomDiscriminant(c as u16);
+            let _ = write!(line, " {}", phantom_name(discriminant));
+            operands[2] = F::from_canonical_u32(c >> 16);
+        }
+        while operands.last().is_some_and(|x| x.is_zero()) {
+            operands.pop();
+        }
+        for (i, operand) in operands.into_iter().enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            let _ = write!(line, "{sep}{}", signed(operand));
+        }
+        line
+    }
+
+    /// Formats the whole executable: `pc_start`, a summary of the initial memory image and
+    /// the instruction listing, labelled with function names when `fn_bounds` is present.
+    pub fn disassemble<F: PrimeField32>(&self, exe: &VmExe<F>) -> String {
+        let mut out = String::new();
+        let program = &exe.program;
+        let _ = writeln!(out, "pc_start: {:#010x}", exe.pc_start);
+        let _ = writeln!(
+            out,
+            "program: {} instructions, pc_base = {:#010x}, step = {}",
+            program.len(),
+            program.pc_base,
+            program.step
+        );
+
+        let regions = memory_regions(exe);
+        let _ = writeln!(
+            out,
+            "init_memory: {} cells in {} regions",
+            exe.init_memory.len(),
+            regions.len()
+        );
+        for (address_space, start, end) in regions {
+            let _ = writeln!(
+                out,
+                "  [{start:#010x}, {end:#010x})_{address_space} ({} cells)",
+                end - start
+            );
+        }
+
+        for (pc, instruction, _) in program.enumerate_by_pc() {
+            if let Some(fn_bound) = exe.fn_bounds.get(&pc) {
+                let _ = writeln!(out, "\n{:#}:", demangle(&fn_bound.name));
+            }
+            let _ = writeln!(
+                out,
+                "  {pc:#010x}: {}",
+                self.format_instruction(&instruction)
+            );
+        }
+        out
+    }
+}
+
+/// Maximal runs of consecutive initialized cells as `(address_space, start, end)`.
+fn memory_regions<F>(exe: &VmExe<F>) -> Vec<(u32, u32, u32)> {
+    let mut regions: Vec<(u32, u32, u32)> = Vec::new();
+    for &(address_space, pointer) in exe.init_memory.keys() {
+        match regions.last_mut() {
+            Some((last_as, _, end)) if *last_as == address_space && *end == pointer => {
+                *end += 1;
+            }
+            _ => regions.push((address_space, pointer, pointer + 1)),
+        }
+    }
+    regions
+}
+
+fn phantom_name(discriminant: PhantomDiscriminant) -> String {
+    let d = discriminant.0;
+    if let Some(p) = SysPhantom::from_repr(d) {
+        format!("{p:?}")
+    } else if let Some(p) = NativePhantom::from_repr(d) {
+        format!("{p:?}")
+    } else if let Some(p) = Rv32Phantom::from_repr(d) {
+        format!("{p:?}")
+    } else if let Some(p) = PairingPhantom::from_repr(d) {
+        format!("{p:?}")
+    } else if let Some(p) = EccPhantom::from_repr(d) {
+        format!("{p:?}")
+    } else {
+        format!("UNKNOWN({d:#x})")
+    }
+}
+
+/// Interprets `x` as a signed integer, mapping field elements above `p / 2` to `x - p`.
+///
+/// Operands that are negated in the field, such as negative pc offsets, are then printed as
+/// negative integers. Sign-extended 24-bit immediates of RV32 ALU instructions are below `p / 2`
+/// and are printed unchanged.
+fn signed<F: PrimeField32>(x: F) -> i64 {
+    let x = x.as_canonical_u32();
+    if x > F::ORDER_U32 / 2 {
+        x as i64 - F::ORDER_U32 as i64
+    } else {
+        x as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_circuit::arch::instructions::program::Program;
+    use openvm_stark_sdk::p3_baby_bear::BabyBear;
+    use strum::EnumCount;
+
+    use super::*;
+
+    type F = BabyBear;
+
+    #[test]
+    fn test_opcode_names() {
+        let disassembler = Disassembler::default();
+        let name = |opcode| disassembler.opcode_name(opcode);
+        assert_eq!(
+            name(VmOpcode::with_default_offset(BaseAluOpcode::XOR)).as_deref(),
+            Some("XOR")
+        );
+        assert_eq!(
+            name(VmOpcode::with_default_offset(Rv32BaseAlu256Opcode(
+                BaseAluOpcode::XOR
+            )))
+            .as_deref(),
+            Some("int256.XOR")
+        );
+        assert_eq!(
+            name(VmOpcode::with_default_offset(Rv32AmoAluOpcode(
+                BaseAluOpcode::ADD
+            )))
+            .as_deref(),
+            Some("AMOADD")
+        );
+        // SUB has no atomic counterpart
+        assert_eq!(
+            name(VmOpcode::from_usize(
+                Rv32AmoAluOpcode::default_offset() + BaseAluOpcode::SUB as usize
+            )),
+            None
+        );
+        // MUL for the second modulus
+        assert_eq!(
+            name(VmOpcode::from_usize(
+                Rv32ModularArithmeticOpcode::default_offset()
+                    + Rv32ModularArithmeticOpcode::COUNT
+                    + Rv32ModularArithmeticOpcode::MUL as usize
+            ))
+            .as_deref(),
+            Some("modular.MUL<1>")
+        );
+        // The last repeated class is bounded
+        let pairing_end = PairingOpcode::default_offset() + PairingOpcode::COUNT * MAX_INSTANCES;
+        assert_eq!(
+            name(VmOpcode::from_usize(pairing_end - 1)).as_deref(),
+            Some("pairing.MUL_BY_02345<15>")
+        );
+        assert_eq!(name(VmOpcode::from_usize(pairing_end)), None);
+        assert_eq!(name(VmOpcode::from_usize(0x1fff)), None);
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let instructions = vec![
+            Instruction::<F>::from_isize(
+                VmOpcode::with_default_offset(BaseAluOpcode::ADD),
+                4,
+                8,
+                -1,
+                1,
+                0,
+            ),
+            Instruction::phantom(
+                PhantomDiscriminant(SysPhantom::Nop as u16),
+                F::ZERO,
+                F::ZERO,
+                0,
+            ),
+            Instruction::from_usize(VmOpcode::with_default_offset(SystemOpcode::TERMINATE), []),
+        ];
+        let exe = VmExe::new(Program::from_instructions(&instructions))
+            .with_init_memory([((2, 16), F::ONE), ((2, 17), F::TWO)].into());
+        let listing = Disassembler::default().disassemble(&exe);
+        assert!(listing.contains("init_memory: 2 cells in 1 regions"));
+        assert!(listing.contains("0x00000000: ADD 4, 8, -1, 1"));
+        assert!(listing.contains("0x00000004: PHANTOM Nop"));
+        assert!(listing.contains("0x00000008: TERMINATE"));
+    }
+}
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// @title OpenVmHalo2Verifier
+/// @notice Verifies OpenVM EVM proofs against the commitments and user public values they prove.
+/// Generated by the OpenVM SDK.
+/// @dev Deploys the halo2 verifier generated for the same aggregation proving key in its
+/// constructor and forwards proofs to it, with the instances laid out as the root verifier
+/// exposes them: the KZG accumulator, the exe commit, the leaf verifier commit and one instance per
+/// user public value byte.
+contract OpenVmHalo2Verifier {
+    uint256 public constant NUM_USER_PUBLIC_VALUES = {{NUM_USER_PUBLIC_VALUES}};
+
+    /// Size of the KZG accumulator the proof starts with: 12 instances of 32 bytes.
+    uint256 internal constant ACCUMULATOR_SIZE = 384;
+    uint256 internal constant BN254_SCALAR_MODULUS =
+        0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+
+    /// The halo2 verifier proofs are forwarded to.
+    address public immutable halo2Verifier;
+
+    constructor(bytes memory halo2VerifierDeploymentCode) {
+        address verifier;
+        assembly {
+            verifier := create(0, add(halo2VerifierDeploymentCode, 32), mload(halo2VerifierDeploymentCode))
+        }
+        require(verifier != address(0), "OpenVmHalo2Verifier: halo2 verifier deployment failed");
+        halo2Verifier = verifier;
+    }
+
+    /// @notice Reverts unless `proof` proves an execution of the exe with commit `exeCommit` in the
+    /// app VM with commit `vmCommit` which revealed `userPublicValues`.
+    /// @param proof The KZG accumulator followed by the halo2 proof.
+    /// @param exeCommit The exe commit as a BN254 scalar.
+    /// @param vmCommit The leaf verifier commit of the app VM as a BN254 scalar.
+    /// @param userPublicValues The user public values, one byte each.
+    function verify(bytes calldata proof, bytes32 exeCommit, bytes32 vmCommit, bytes calldata userPublicValues)
+        external
+        view
+    {
+        require(proof.length > ACCUMULATOR_SIZE, "OpenVmHalo2Verifier: invalid proof length");
+        require(
+            userPublicValues.length == NUM_USER_PUBLIC_VALUES,
+            "OpenVmHalo2Verifier: invalid number of user public values"
+        );
+        require(
+            uint256(exeCommit) < BN254_SCALAR_MODULUS && uint256(vmCommit) < BN254_SCALAR_MODULUS,
+            "OpenVmHalo2Verifier: commit out of range"
+        );
+
+        bytes memory input = new bytes(proof.length + 64 + 32 * NUM_USER_PUBLIC_VALUES);
+        assembly {
+            let ptr := add(input, 32)
+            calldatacopy(ptr, proof.offset, ACCUMULATOR_SIZE)
+            ptr := add(ptr, ACCUMULATOR_SIZE)
+            mstore(ptr, exeCommit)
+            mstore(add(ptr, 32), vmCommit)
+            ptr := add(ptr, 64)
+            for { let i := 0 } lt(i, NUM_USER_PUBLIC_VALUES) { i := add(i, 1) } {
+                mstore(add(ptr, mul(i, 32)), byte(0, calldataload(add(userPublicValues.offset, i))))
+            }
+            ptr := add(ptr, mul(NUM_USER_PUBLIC_VALUES, 32))
+            calldatacopy(ptr, add(proof.offset, ACCUMULATOR_SIZE), sub(proof.length, ACCUMULATOR_SIZE))
+        }
+        (bool success,) = halo2Verifier.staticcall(input);
+        require(success, "OpenVmHalo2Verifier: invalid proof");
+    }
+}
@@ -0,0 +1,108 @@
+//! Solidity wrapper around the halo2 EVM verifier with typed public values.
+//!
+//! The halo2 verifier from [Sdk::generate_snark_verifier_contract] reads its instances from the
+//! calldata ahead of the proof: the KZG accumulator, the exe commit, the leaf verifier commit and
+//! one instance per user public value byte. The `OpenVmHalo2Verifier` contract rebuilds this layout
+//! from `verify(bytes proof, bytes32 exeCommit, bytes32 vmCommit, bytes userPublicValues)`, where
+//! `proof` is the accumulator followed by the halo2 proof, see [encode_verify_calldata].
+//!
+//! [Sdk::generate_snark_verifier_contract]: crate::Sdk::generate_snark_verifier_contract
+
+use eyre::Result;
+use openvm_native_recursion::halo2::{
+    wrapper::{compile_solidity, evm_deploy_and_call, EvmVerifier},
+    EvmProof, NUM_ACCUMULATOR_INSTANCES,
+};
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::public_values::EvmProofExt;
+
+const WRAPPER_TEMPLATE: &str = include_str!("OpenVmHalo2Verifier.sol");
+const VERIFY_SIGNATURE: &[u8] = b"verify(bytes,bytes32,bytes32,bytes)";
+
+/// Solidity wrapper of a halo2 EVM verifier.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvmVerifierWrapper {
+    /// Source of the `OpenVmHalo2Verifier` contract.
+    pub solidity_code: String,
+    pub num_user_public_values: usize,
+}
+
+impl EvmVerifierWrapper {
+    pub fn new(num_user_public_values: usize) -> Self {
+        let solidity_code = WRAPPER_TEMPLATE.replace(
+            "{{NUM_USER_PUBLIC_VALUES}}",
+            &num_user_public_values.to_string(),
+        );
+        Self {
+            solidity_code,
+            num_user_public_values,
+        }
+    }
+
+    /// Deployment code of the wrapper, which deploys `evm_verifier` in its constructor. Compiling
+    /// the wrapper requires `solc` in `PATH`.
+    pub fn deployment_code(&self, evm_verifier: &EvmVerifier) -> Vec<u8> {
+        let mut code = compile_solidity(&self.solidity_code);
+        // ABI encoding of the `bytes` constructor argument.
+        code.extend(abi_word(32));
+        code.extend(abi_bytes(&evm_verifier.0));
+        code
+    }
+
+    /// Deploys the wrapper and `evm_verifier` to an in-memory EVM and verifies `evm_proof` through
+    /// the wrapper. Returns the gas used by the call.
+    pub fn evm_verify(&self, evm_verifier: &EvmVerifier, evm_proof: &EvmProof) -> Result<u64> {
+        let calldata = encode_verify_calldata(evm_proof)?;
+        evm_deploy_and_call(self.deployment_code(evm_verifier), calldata)
+            .map_err(|e| eyre::eyre!("EVM proof verification failed: {e}"))
+    }
+}
+
+/// Encodes a call of the wrapper's `verify` with the proof, commitments and user public values of
+/// `evm_proof`.
+pub fn encode_verify_calldata(evm_proof: &EvmProof) -> Result<Vec<u8>> {
+    let (_, user_public_values) = evm_proof.decode()?;
+    let pvs = evm_proof.public_values_le_bytes();
+    let be_word = |le: &[u8; 32]| {
+        let mut word = *le;
+        word.reverse();
+        word
+    };
+    let mut proof = evm_proof.accumulator_be_bytes();
+    debug_assert_eq!(proof.len(), 32 * NUM_ACCUMULATOR_INSTANCES);
+    proof.extend(&evm_proof.proof);
+    let proof = abi_bytes(&proof);
+
+    let mut calldata = function_selector(VERIFY_SIGNATURE).to_vec();
+    calldata.extend(abi_word(4 * 32));
+    calldata.extend(be_word(&pvs[0]));
+    calldata.extend(be_word(&pvs[1]));
+    calldata.extend(abi_word(4 * 32 + proof.len()));
+    calldata.extend(proof);
+    calldata.extend(abi_bytes(&user_public_values));
+    Ok(calldata)
+}
+
+pub(crate) fn function_selector(signature: &[u8]) -> [u8; 4] {
+    let mut hasher = Keccak::v256();
+    hasher.update(signature);
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash[..4].try_into().unwrap()
+}
+
+pub(crate) fn abi_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+/// The length of `bytes` followed by `bytes` padded to a multiple of 32 bytes.
+pub(crate) fn abi_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = abi_word(bytes.len()).to_vec();
+    encoded.extend(bytes);
+    encoded.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+    encoded
+}
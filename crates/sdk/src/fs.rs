@@ -1,3 +1,9 @@
+//! Reading and writing of SDK artifacts.
+//!
+//! Every artifact is written in an envelope: an [ArtifactHeader] followed by the serialized
+//! artifact. Files written before the envelope was introduced have no header and are read as
+//! format version 0.
+
 use std::{
     fs::{create_dir_all, read, write},
     path::Path,
@@ -6,105 +12,572 @@ use std::{
 use eyre::Result;
 use openvm_circuit::arch::{instructions::exe::VmExe, VmConfig};
 use openvm_native_recursion::halo2::{wrapper::EvmVerifier, EvmProof};
+use openvm_stark_sdk::openvm_stark_backend::prover::types::Proof;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
-    keygen::{AggProvingKey, AppProvingKey, AppVerifyingKey},
+    keccak_verifier::KeccakStarkEvmVerifier,
+    keygen::{
+        AggProvingKey, AggStarkProvingKey, AppProvingKey, AppVerifyingKey, KeccakAggProvingKey,
+    },
+    keystore::keccak256,
     prover::vm::ContinuationVmProof,
-    F, SC,
+    KeccakRootSC, F, SC,
 };
 
+/// First bytes of every artifact file.
+pub const ARTIFACT_MAGIC: [u8; 4] = *b"OVMA";
+/// Current version of the envelope and of the serialization of the artifacts.
+pub const ARTIFACT_FORMAT_VERSION: u32 = 6;
+/// Format version of files without header.
+const LEGACY_FORMAT_VERSION: u32 = 0;
+/// Last format version whose app verifying keys don't have the memory dimensions of the app VM.
+const APP_VK_WITHOUT_MEMORY_DIMENSIONS_FORMAT_VERSION: u32 = 1;
+/// Last format version whose app keys don't record the claimed security.
+const APP_KEY_WITHOUT_SECURITY_FORMAT_VERSION: u32 = 2;
+/// Last format version whose app proofs, aggregation proving keys and EVM artifacts have a zero
+/// config digest.
+const ZERO_CONFIG_DIGEST_FORMAT_VERSION: u32 = 3;
+/// Last format version whose aggregation proving keys have a single leaf VM proving key.
+const AGG_KEY_WITH_SINGLE_LEAF_VM_FORMAT_VERSION: u32 = 4;
+/// Last format version whose aggregation proving keys don't record the claimed security.
+const AGG_KEY_WITHOUT_SECURITY_FORMAT_VERSION: u32 = 5;
+const ARTIFACT_HEADER_LEN: usize = 4 + 4 + 1 + 1 + 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ArtifactKind {
+    Exe = 1,
+    AppProvingKey = 2,
+    AppVerifyingKey = 3,
+    AppProof = 4,
+    AggProvingKey = 5,
+    EvmProof = 6,
+    EvmVerifier = 7,
+    KeccakAggProvingKey = 8,
+    KeccakRootProof = 9,
+    KeccakStarkEvmVerifier = 10,
+}
+
+impl TryFrom<u8> for ArtifactKind {
+    type Error = eyre::Report;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Ok(match value {
+            1 => Self::Exe,
+            2 => Self::AppProvingKey,
+            3 => Self::AppVerifyingKey,
+            4 => Self::AppProof,
+            5 => Self::AggProvingKey,
+            6 => Self::EvmProof,
+            7 => Self::EvmVerifier,
+            8 => Self::KeccakAggProvingKey,
+            9 => Self::KeccakRootProof,
+            10 => Self::KeccakStarkEvmVerifier,
+            _ => return Err(eyre::eyre!("Unknown artifact kind {}", value)),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Compression {
+    #[default]
+    None = 0,
+}
+
+impl TryFrom<u8> for Compression {
+    type Error = eyre::Report;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(Self::None),
+            _ => Err(eyre::eyre!("Unsupported artifact compression {}", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ArtifactHeader {
+    pub format_version: u32,
+    pub kind: ArtifactKind,
+    pub compression: Compression,
+    /// Digest of the keys an artifact belongs to, so that matching artifacts can be recognized
+    /// from their headers: the [app_vk_digest] for app keys and proofs, and the
+    /// [agg_config_digest] for aggregation proving keys, EVM proofs and EVM verifiers. Zero for
+    /// executables, which don't depend on any key.
+    pub config_digest: [u8; 32],
+}
+
+impl ArtifactHeader {
+    pub fn new(kind: ArtifactKind, config_digest: [u8; 32]) -> Self {
+        Self {
+            format_version: ARTIFACT_FORMAT_VERSION,
+            kind,
+            compression: Compression::None,
+            config_digest,
+        }
+    }
+
+    /// Whether `config_digest` was recorded, which older format versions didn't do for every kind.
+    pub fn has_config_digest(&self) -> bool {
+        match self.kind {
+            ArtifactKind::AppProvingKey | ArtifactKind::AppVerifyingKey => {
+                self.format_version != LEGACY_FORMAT_VERSION
+            }
+            ArtifactKind::Exe => false,
+            _ => self.format_version > ZERO_CONFIG_DIGEST_FORMAT_VERSION,
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut ret = Vec::with_capacity(ARTIFACT_HEADER_LEN);
+        ret.extend(ARTIFACT_MAGIC);
+        ret.extend(self.format_version.to_le_bytes());
+        ret.push(self.kind as u8);
+        ret.push(self.compression as u8);
+        ret.extend(self.config_digest);
+        ret
+    }
+
+    /// Decodes the header of an artifact file. Returns `None` for files without header.
+    fn decode(bytes: &[u8]) -> Result<Option<(Self, &[u8])>> {
+        if !bytes.starts_with(&ARTIFACT_MAGIC) {
+            return Ok(None);
+        }
+        if bytes.len() < ARTIFACT_HEADER_LEN {
+            return Err(eyre::eyre!("Truncated artifact header"));
+        }
+        let (header, payload) = bytes.split_at(ARTIFACT_HEADER_LEN);
+        let header = Self {
+            format_version: u32::from_le_bytes(header[4..8].try_into().unwrap()),
+            kind: header[8].try_into()?,
+            compression: header[9].try_into()?,
+            config_digest: header[10..].try_into().unwrap(),
+        };
+        Ok(Some((header, payload)))
+    }
+}
+
 pub fn read_exe_from_file<P: AsRef<Path>>(path: P) -> Result<VmExe<F>> {
-    read_from_file_bitcode(path)
+    read_from_file_bitcode(path, ArtifactKind::Exe)
 }
 
 pub fn write_exe_to_file<P: AsRef<Path>>(exe: VmExe<F>, path: P) -> Result<()> {
-    write_to_file_bitcode(path, exe)
+    write_to_file_bitcode(path, ArtifactKind::Exe, [0; 32], exe)
 }
 
 pub fn read_app_pk_from_file<VC: VmConfig<F>, P: AsRef<Path>>(
     path: P,
 ) -> Result<AppProvingKey<VC>> {
-    read_from_file_bitcode(path)
+    let (header, payload) = read_artifact(&path, ArtifactKind::AppProvingKey)?;
+    let app_pk: AppProvingKey<VC> = bitcode::deserialize(&payload)?;
+    check_config_digest(&path, &header, || app_vk_digest(&app_pk.get_vk()))?;
+    Ok(app_pk)
 }
 
 pub fn write_app_pk_to_file<VC: VmConfig<F>, P: AsRef<Path>>(
     app_pk: AppProvingKey<VC>,
     path: P,
 ) -> Result<()> {
-    write_to_file_bitcode(path, app_pk)
+    let config_digest = app_vk_digest(&app_pk.get_vk())?;
+    write_to_file_bitcode(path, ArtifactKind::AppProvingKey, config_digest, app_pk)
 }
 
 pub fn read_app_vk_from_file<P: AsRef<Path>>(path: P) -> Result<AppVerifyingKey> {
-    read_from_file_bitcode(path)
+    let (header, payload) = read_artifact(&path, ArtifactKind::AppVerifyingKey)?;
+    let app_vk: AppVerifyingKey = bitcode::deserialize(&payload)?;
+    check_config_digest(&path, &header, || app_vk_digest(&app_vk))?;
+    Ok(app_vk)
 }
 
 pub fn write_app_vk_to_file<P: AsRef<Path>>(app_vk: AppVerifyingKey, path: P) -> Result<()> {
-    write_to_file_bitcode(path, app_vk)
+    let config_digest = app_vk_digest(&app_vk)?;
+    write_to_file_bitcode(path, ArtifactKind::AppVerifyingKey, config_digest, app_vk)
 }
 
 pub fn read_app_proof_from_file<P: AsRef<Path>>(path: P) -> Result<ContinuationVmProof<SC>> {
-    read_from_file_bitcode(path)
+    read_from_file_bitcode(path, ArtifactKind::AppProof)
 }
 
+/// `app_vk_digest` is the [app_vk_digest] of the key the proof is verified with.
 pub fn write_app_proof_to_file<P: AsRef<Path>>(
     proof: ContinuationVmProof<SC>,
+    app_vk_digest: [u8; 32],
     path: P,
 ) -> Result<()> {
-    write_to_file_bitcode(path, proof)
+    write_to_file_bitcode(path, ArtifactKind::AppProof, app_vk_digest, proof)
 }
 
 pub fn read_agg_pk_from_file<P: AsRef<Path>>(path: P) -> Result<AggProvingKey> {
-    read_from_file_bitcode(path)
+    let (header, payload) = read_artifact(&path, ArtifactKind::AggProvingKey)?;
+    let agg_pk: AggProvingKey = bitcode::deserialize(&payload)?;
+    check_config_digest(&path, &header, || agg_config_digest(&agg_pk.agg_stark_pk))?;
+    Ok(agg_pk)
 }
 
 pub fn write_agg_pk_to_file<P: AsRef<Path>>(agg_pk: AggProvingKey, path: P) -> Result<()> {
-    write_to_file_bitcode(path, agg_pk)
+    let config_digest = agg_config_digest(&agg_pk.agg_stark_pk)?;
+    write_to_file_bitcode(path, ArtifactKind::AggProvingKey, config_digest, agg_pk)
 }
 
 pub fn read_evm_proof_from_file<P: AsRef<Path>>(path: P) -> Result<EvmProof> {
-    read_from_file_bitcode(path)
+    read_from_file_bitcode(path, ArtifactKind::EvmProof)
 }
 
-pub fn write_evm_proof_to_file<P: AsRef<Path>>(proof: EvmProof, path: P) -> Result<()> {
-    write_to_file_bitcode(path, proof)
+/// `agg_config_digest` is the [agg_config_digest] of the aggregation proving key that generated
+/// the proof.
+pub fn write_evm_proof_to_file<P: AsRef<Path>>(
+    proof: EvmProof,
+    agg_config_digest: [u8; 32],
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(path, ArtifactKind::EvmProof, agg_config_digest, proof)
 }
 
 pub fn read_evm_verifier_from_file<P: AsRef<Path>>(path: P) -> Result<EvmVerifier> {
-    read_from_file_bytes(path)
+    read_from_file_bytes(path, ArtifactKind::EvmVerifier)
 }
 
-pub fn write_evm_verifier_to_file<P: AsRef<Path>>(verifier: EvmVerifier, path: P) -> Result<()> {
-    write_to_file_bytes(path, verifier)
+/// `agg_config_digest` is the [agg_config_digest] of the aggregation proving key the verifier was
+/// generated from.
+pub fn write_evm_verifier_to_file<P: AsRef<Path>>(
+    verifier: EvmVerifier,
+    agg_config_digest: [u8; 32],
+    path: P,
+) -> Result<()> {
+    write_to_file_bytes(path, ArtifactKind::EvmVerifier, agg_config_digest, verifier)
 }
 
-pub(crate) fn read_from_file_bitcode<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
-    let data = std::fs::read(path)?;
-    let ret = bitcode::deserialize(&data)?;
-    Ok(ret)
+pub fn read_keccak_agg_pk_from_file<P: AsRef<Path>>(path: P) -> Result<KeccakAggProvingKey> {
+    let (header, payload) = read_artifact(&path, ArtifactKind::KeccakAggProvingKey)?;
+    let agg_pk: KeccakAggProvingKey = bitcode::deserialize(&payload)?;
+    check_config_digest(&path, &header, || agg_config_digest(&agg_pk.agg_stark_pk))?;
+    Ok(agg_pk)
 }
 
-pub(crate) fn write_to_file_bitcode<T: Serialize, P: AsRef<Path>>(path: P, data: T) -> Result<()> {
-    let bytes = bitcode::serialize(&data)?;
-    if let Some(parent) = path.as_ref().parent() {
-        create_dir_all(parent)?;
+pub fn write_keccak_agg_pk_to_file<P: AsRef<Path>>(
+    agg_pk: KeccakAggProvingKey,
+    path: P,
+) -> Result<()> {
+    let config_digest = agg_config_digest(&agg_pk.agg_stark_pk)?;
+    write_to_file_bitcode(
+        path,
+        ArtifactKind::KeccakAggProvingKey,
+        config_digest,
+        agg_pk,
+    )
+}
+
+pub fn read_keccak_root_proof_from_file<P: AsRef<Path>>(path: P) -> Result<Proof<KeccakRootSC>> {
+    read_from_file_bitcode(path, ArtifactKind::KeccakRootProof)
+}
+
+/// `agg_config_digest` is the [agg_config_digest] of the aggregation proving key that generated
+/// the proof.
+pub fn write_keccak_root_proof_to_file<P: AsRef<Path>>(
+    proof: Proof<KeccakRootSC>,
+    agg_config_digest: [u8; 32],
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(
+        path,
+        ArtifactKind::KeccakRootProof,
+        agg_config_digest,
+        proof,
+    )
+}
+
+pub fn read_keccak_stark_verifier_from_file<P: AsRef<Path>>(
+    path: P,
+) -> Result<KeccakStarkEvmVerifier> {
+    read_from_file_bitcode(path, ArtifactKind::KeccakStarkEvmVerifier)
+}
+
+/// `agg_config_digest` is the [agg_config_digest] of the aggregation proving key the verifier was
+/// generated from.
+pub fn write_keccak_stark_verifier_to_file<P: AsRef<Path>>(
+    verifier: KeccakStarkEvmVerifier,
+    agg_config_digest: [u8; 32],
+    path: P,
+) -> Result<()> {
+    write_to_file_bitcode(
+        path,
+        ArtifactKind::KeccakStarkEvmVerifier,
+        agg_config_digest,
+        verifier,
+    )
+}
+
+/// Reads the header of an artifact file without decoding the artifact. Returns an error for
+/// files without header.
+pub fn read_artifact_header<P: AsRef<Path>>(path: P) -> Result<ArtifactHeader> {
+    let bytes = read(&path)?;
+    match ArtifactHeader::decode(&bytes)? {
+        Some((header, _)) => Ok(header),
+        None => Err(eyre::eyre!(
+            "{} has no artifact header, it was written by an older SDK",
+            path.as_ref().display()
+        )),
+    }
+}
+
+/// Digest of an app verifying key, stored in the headers of app proving and verifying keys.
+pub fn app_vk_digest(app_vk: &AppVerifyingKey) -> Result<[u8; 32]> {
+    Ok(keccak256(&bitcode::serialize(app_vk)?))
+}
+
+/// Digest of the FRI parameters and program commitments of the aggregation layers, stored in the
+/// headers of aggregation proving keys and of the EVM proofs and verifiers generated with them.
+pub fn agg_config_digest(agg_stark_pk: &AggStarkProvingKey) -> Result<[u8; 32]> {
+    let root_verifier_pk = &agg_stark_pk.root_verifier_pk;
+    let config = (
+        agg_stark_pk.allowed_leaf_fri_params(),
+        agg_stark_pk.internal_vm_pk.fri_params,
+        root_verifier_pk.vm_pk.fri_params,
+        agg_stark_pk.internal_committed_exe.get_program_commit(),
+        root_verifier_pk.root_committed_exe.get_program_commit(),
+    );
+    Ok(keccak256(&bitcode::serialize(&config)?))
+}
+
+/// Config digest of the artifact at `path`, or `None` if it was written without one.
+pub fn read_artifact_config_digest<P: AsRef<Path>>(path: P) -> Result<Option<[u8; 32]>> {
+    let bytes = read(&path)?;
+    Ok(ArtifactHeader::decode(&bytes)?
+        .filter(|(header, _)| header.has_config_digest())
+        .map(|(header, _)| header.config_digest))
+}
+
+/// Checks that the artifact at `path` was written for the keys with `config_digest`, e.g. that an
+/// app proof was generated with the proving key of an app verifying key. Files without header
+/// are not checked.
+pub fn check_artifact_config_digest<P: AsRef<Path>>(
+    path: P,
+    config_digest: [u8; 32],
+) -> Result<()> {
+    let bytes = read(&path)?;
+    match ArtifactHeader::decode(&bytes)? {
+        Some((header, _)) => check_config_digest(&path, &header, || Ok(config_digest)),
+        None => Ok(()),
     }
-    write(path, bytes)?;
-    Ok(())
 }
 
-pub(crate) fn read_from_file_bytes<T: From<Vec<u8>>, P: AsRef<Path>>(path: P) -> Result<T> {
+/// Reads the payload of an artifact of `kind`, migrated to the current format version.
+fn read_artifact<P: AsRef<Path>>(path: P, kind: ArtifactKind) -> Result<(ArtifactHeader, Vec<u8>)> {
+    let path = path.as_ref();
     let bytes = read(path)?;
-    Ok(T::from(bytes))
+    let (header, payload) = match ArtifactHeader::decode(&bytes)? {
+        Some((header, payload)) => (header, payload.to_vec()),
+        None => {
+            let header = ArtifactHeader {
+                format_version: LEGACY_FORMAT_VERSION,
+                ..ArtifactHeader::new(kind, [0; 32])
+            };
+            (header, bytes)
+        }
+    };
+    if header.kind != kind {
+        return Err(eyre::eyre!(
+            "{} contains {:?}, expected {:?}",
+            path.display(),
+            header.kind,
+            kind
+        ));
+    }
+    let payload = migrate_payload(&header, payload).map_err(|e| {
+        eyre::eyre!(
+            "Failed to read {} of format version {}: {}",
+            path.display(),
+            header.format_version,
+            e
+        )
+    })?;
+    Ok((header, payload))
+}
+
+/// Migration hook: converts the payload of an artifact of an older format version to the
+/// current one. Add a case here whenever the serialization of an artifact changes.
+fn migrate_payload(header: &ArtifactHeader, payload: Vec<u8>) -> Result<Vec<u8>> {
+    match header.format_version {
+        // The memory dimensions and the security model can't be recovered, and the config digest
+        // of app proving keys covers the app verifying key.
+        LEGACY_FORMAT_VERSION
+        | APP_VK_WITHOUT_MEMORY_DIMENSIONS_FORMAT_VERSION
+        | APP_KEY_WITHOUT_SECURITY_FORMAT_VERSION
+            if matches!(
+                header.kind,
+                ArtifactKind::AppVerifyingKey | ArtifactKind::AppProvingKey
+            ) =>
+        {
+            Err(eyre::eyre!(
+                "app key is outdated, regenerate it with `cargo openvm keygen`"
+            ))
+        }
+        // The internal verifier program of older aggregation keys doesn't read the index of the
+        // leaf FRI parameters, and the security model of their layers can't be recovered.
+        version
+            if version <= AGG_KEY_WITHOUT_SECURITY_FORMAT_VERSION
+                && matches!(
+                    header.kind,
+                    ArtifactKind::AggProvingKey | ArtifactKind::KeccakAggProvingKey
+                ) =>
+        {
+            Err(eyre::eyre!(
+                "aggregation proving key is outdated, regenerate it with `cargo openvm setup`"
+            ))
+        }
+        // Version 0 files are the bare payload of version 1, which is the same as the later
+        // versions except for app and aggregation keys.
+        LEGACY_FORMAT_VERSION
+        | APP_VK_WITHOUT_MEMORY_DIMENSIONS_FORMAT_VERSION
+        | APP_KEY_WITHOUT_SECURITY_FORMAT_VERSION
+        | ZERO_CONFIG_DIGEST_FORMAT_VERSION
+        | AGG_KEY_WITH_SINGLE_LEAF_VM_FORMAT_VERSION
+        | AGG_KEY_WITHOUT_SECURITY_FORMAT_VERSION
+        | ARTIFACT_FORMAT_VERSION => Ok(payload),
+        version if version > ARTIFACT_FORMAT_VERSION => Err(eyre::eyre!(
+            "written by a newer SDK (format version {} > {})",
+            version,
+            ARTIFACT_FORMAT_VERSION
+        )),
+        version => Err(eyre::eyre!("unsupported format version {}", version)),
+    }
+}
+
+fn check_config_digest<P: AsRef<Path>>(
+    path: P,
+    header: &ArtifactHeader,
+    digest: impl FnOnce() -> Result<[u8; 32]>,
+) -> Result<()> {
+    if header.has_config_digest() && header.config_digest != digest()? {
+        return Err(eyre::eyre!(
+            "Config digest mismatch in {}",
+            path.as_ref().display()
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn read_from_file_bitcode<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+    kind: ArtifactKind,
+) -> Result<T> {
+    let (_, payload) = read_artifact(path, kind)?;
+    let ret = bitcode::deserialize(&payload)?;
+    Ok(ret)
+}
+
+pub(crate) fn write_to_file_bitcode<T: Serialize, P: AsRef<Path>>(
+    path: P,
+    kind: ArtifactKind,
+    config_digest: [u8; 32],
+    data: T,
+) -> Result<()> {
+    let bytes = bitcode::serialize(&data)?;
+    write_to_file_bytes(path, kind, config_digest, bytes)
+}
+
+pub(crate) fn read_from_file_bytes<T: From<Vec<u8>>, P: AsRef<Path>>(
+    path: P,
+    kind: ArtifactKind,
+) -> Result<T> {
+    let (_, payload) = read_artifact(path, kind)?;
+    Ok(T::from(payload))
 }
 
 pub(crate) fn write_to_file_bytes<T: Into<Vec<u8>>, P: AsRef<Path>>(
     path: P,
+    kind: ArtifactKind,
+    config_digest: [u8; 32],
     data: T,
 ) -> Result<()> {
     if let Some(parent) = path.as_ref().parent() {
         create_dir_all(parent)?;
     }
-    write(path, data.into())?;
+    let mut bytes = ArtifactHeader::new(kind, config_digest).encode();
+    bytes.extend(data.into());
+    write(path, bytes)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_artifact_envelope() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verifier.sol");
+        let verifier = EvmVerifier(vec![1, 2, 3]);
+        write_evm_verifier_to_file(verifier.clone(), [7; 32], &path).unwrap();
+
+        let header = read_artifact_header(&path).unwrap();
+        assert_eq!(
+            header,
+            ArtifactHeader::new(ArtifactKind::EvmVerifier, [7; 32])
+        );
+        check_artifact_config_digest(&path, [7; 32]).unwrap();
+        let err = check_artifact_config_digest(&path, [8; 32]).unwrap_err();
+        assert!(err.to_string().contains("Config digest mismatch"));
+        assert_eq!(read_evm_verifier_from_file(&path).unwrap().0, verifier.0);
+        // Reading another kind of artifact fails instead of decoding garbage.
+        let err = read_evm_proof_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("expected EvmProof"));
+    }
+
+    #[test]
+    fn test_artifact_format_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verifier.sol");
+
+        // Files written before the envelope are still readable.
+        std::fs::write(&path, [1, 2, 3]).unwrap();
+        assert_eq!(read_evm_verifier_from_file(&path).unwrap().0, vec![1, 2, 3]);
+        assert!(read_artifact_header(&path).is_err());
+
+        let mut bytes = ArtifactHeader {
+            format_version: ARTIFACT_FORMAT_VERSION + 1,
+            ..ArtifactHeader::new(ArtifactKind::EvmVerifier, [0; 32])
+        }
+        .encode();
+        bytes.extend([1, 2, 3]);
+        std::fs::write(&path, bytes).unwrap();
+        let err = read_evm_verifier_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("newer SDK"));
+
+        // Config digests of older versions were zero and are not checked.
+        let mut bytes = ArtifactHeader {
+            format_version: ZERO_CONFIG_DIGEST_FORMAT_VERSION,
+            ..ArtifactHeader::new(ArtifactKind::EvmVerifier, [0; 32])
+        }
+        .encode();
+        bytes.extend([1, 2, 3]);
+        std::fs::write(&path, bytes).unwrap();
+        check_artifact_config_digest(&path, [7; 32]).unwrap();
+
+        // App keys of older versions can't be migrated.
+        let path = dir.path().join("app.vk");
+        let mut bytes = ArtifactHeader {
+            format_version: APP_KEY_WITHOUT_SECURITY_FORMAT_VERSION,
+            ..ArtifactHeader::new(ArtifactKind::AppVerifyingKey, [0; 32])
+        }
+        .encode();
+        bytes.extend([1, 2, 3]);
+        std::fs::write(&path, bytes).unwrap();
+        let err = read_app_vk_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("outdated"));
+
+        // Neither can aggregation keys without the claimed security.
+        let path = dir.path().join("agg.pk");
+        let mut bytes = ArtifactHeader {
+            format_version: AGG_KEY_WITHOUT_SECURITY_FORMAT_VERSION,
+            ..ArtifactHeader::new(ArtifactKind::AggProvingKey, [0; 32])
+        }
+        .encode();
+        bytes.extend([1, 2, 3]);
+        std::fs::write(&path, bytes).unwrap();
+        let err = read_agg_pk_from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("outdated"));
+    }
+}
@@ -0,0 +1,1084 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// @title OpenVmStarkVerifier
+/// @notice Verifies STARK proofs of the OpenVM root verifier which use a Keccak-256 transcript and
+/// Keccak-256 Merkle trees over BabyBear. Generated by the OpenVM SDK.
+/// @dev The verifying key is passed with every call and must hash to `VK_HASH`. See the
+/// `keccak_verifier` module of the SDK for the encodings of the verifying key and the proof.
+/// Extension field elements of BabyBear[X]/(X^4 - 11) are packed into a `uint256` with 64 bits
+/// per limb, the constant term in the lowest bits.
+contract OpenVmStarkVerifier {
+    bytes32 public constant VK_HASH = {{VK_HASH}};
+
+    uint256 internal constant P = 2013265921;
+    uint256 internal constant W = 11;
+    uint256 internal constant GENERATOR = 31;
+    /// -1/2 mod P.
+    uint256 internal constant NEG_INV_TWO = 1006632960;
+    uint256 internal constant LIMB_MASK = 0xffffffffffffffff;
+    uint256 internal constant LANE_MASK = 0xffffffffffffffffffffffffffffffff;
+    /// P in every limb.
+    uint256 internal constant P_LIMBS = 0x0000000078000001000000007800000100000000780000010000000078000001;
+
+    /// Two-adic generators, 32 bits each, indexed by their log order.
+    uint256 internal constant GENERATORS_0 = {{GENERATORS_0}};
+    uint256 internal constant GENERATORS_1 = {{GENERATORS_1}};
+    uint256 internal constant GENERATORS_2 = {{GENERATORS_2}};
+    uint256 internal constant GENERATORS_3 = {{GENERATORS_3}};
+    uint256 internal constant INV_GENERATORS_0 = {{INV_GENERATORS_0}};
+    uint256 internal constant INV_GENERATORS_1 = {{INV_GENERATORS_1}};
+    uint256 internal constant INV_GENERATORS_2 = {{INV_GENERATORS_2}};
+    uint256 internal constant INV_GENERATORS_3 = {{INV_GENERATORS_3}};
+
+    // Opcodes of the constraint tape.
+    uint256 internal constant OP_CONST = 0;
+    uint256 internal constant OP_PREPROCESSED_LOCAL = 1;
+    uint256 internal constant OP_PREPROCESSED_NEXT = 2;
+    uint256 internal constant OP_MAIN_LOCAL = 3;
+    uint256 internal constant OP_MAIN_NEXT = 4;
+    uint256 internal constant OP_PERMUTATION_LOCAL = 5;
+    uint256 internal constant OP_PERMUTATION_NEXT = 6;
+    uint256 internal constant OP_PUBLIC = 7;
+    uint256 internal constant OP_CHALLENGE = 8;
+    uint256 internal constant OP_EXPOSED = 9;
+    uint256 internal constant OP_IS_FIRST_ROW = 10;
+    uint256 internal constant OP_IS_LAST_ROW = 11;
+    uint256 internal constant OP_IS_TRANSITION = 12;
+    uint256 internal constant OP_ADD = 13;
+    uint256 internal constant OP_SUB = 14;
+    uint256 internal constant OP_NEG = 15;
+    uint256 internal constant OP_MUL = 16;
+    uint256 internal constant OP_ASSERT = 17;
+
+    struct Air {
+        uint256 logDegree;
+        uint256 logQuotientDegree;
+        bool hasPreprocessed;
+        bytes32 preprocessedCommit;
+        uint256 preprocessedWidth;
+        uint256 numCachedMains;
+        /// Widths of the cached mains, followed by the width of the common main if it is not empty.
+        uint256[] mainWidths;
+        /// Width of the after challenge trace in extension field elements.
+        uint256 permWidth;
+        uint256 numExposed;
+        uint256 numPublicValues;
+        // Calldata offsets into the verifying key.
+        uint256 chunkConstantsOffset;
+        uint256 tapeOffset;
+        uint256 tapeLength;
+        // Calldata offsets into the proof.
+        uint256 publicValuesOffset;
+        uint256 exposedOffset;
+        uint256 preprocessedOffset;
+        uint256[] mainOffsets;
+        uint256 permOffset;
+        uint256 quotientOffset;
+    }
+
+    struct Vk {
+        uint256 logBlowup;
+        uint256 numQueries;
+        uint256 powBits;
+        bytes32 programCommit;
+        uint256 programCommitIndex;
+        uint256 connectorAir;
+        uint256 publicValuesAir;
+        uint256 numPhases;
+        Air[] airs;
+        uint256 numMainCommits;
+        uint256 numSlots;
+    }
+
+    /// Keccak-256 challenger over bytes, with BabyBear elements serialized as 4 little-endian bytes.
+    struct Challenger {
+        bytes input;
+        uint256 inputLength;
+        bytes32 output;
+        uint256 outputLength;
+    }
+
+    struct Transcript {
+        uint256[] challenges;
+        bytes32[] mainCommits;
+        bytes32 permCommit;
+        bytes32 quotientCommit;
+        uint256 alpha;
+        uint256 zeta;
+        uint256 friAlpha;
+        bytes32[] friCommits;
+        uint256[] betas;
+        uint256 finalPoly;
+    }
+
+    /// A matrix opened by the PCS.
+    struct Mat {
+        uint256 logHeight;
+        uint256 width;
+        /// Calldata offset of the opened values at zeta, followed by the ones at the next point.
+        uint256 offset;
+        bool twoPoints;
+        /// Powers of the FRI alpha the matrix starts at, and the alpha-weighted sums of its opened
+        /// values, for both points.
+        uint256 alphaPow0;
+        uint256 sum0;
+        uint256 alphaPow1;
+        uint256 sum1;
+    }
+
+    struct Round {
+        bytes32 commit;
+        Mat[] mats;
+    }
+
+    struct QueryContext {
+        uint256 logMaxHeight;
+        /// Powers of the FRI alpha, the limbs packed in two 128-bit lanes per word.
+        uint256[] alphaPowLo;
+        uint256[] alphaPowHi;
+        /// Per log height: whether any matrix has this height, and the opening points.
+        bool[] hasHeight;
+        uint256[] zetaNext;
+        /// Per log height accumulators, reset for every query.
+        uint256[] numerators0;
+        uint256[] numerators1;
+        uint256[] reducedOpenings;
+    }
+
+    struct Selectors {
+        uint256 isFirstRow;
+        uint256 isLastRow;
+        uint256 isTransition;
+        uint256 invZeroifier;
+    }
+
+    struct Cursor {
+        uint256 pos;
+    }
+
+    /// @notice Verifies `proof` and returns the public values of the root verifier, i.e. the exe
+    /// commit, the leaf verifier commit and the user public values. Reverts if the proof is invalid.
+    function verify(bytes calldata vk, bytes calldata proof) external pure returns (uint256[] memory) {
+        require(hashCalldata(vk.offset, vk.length) == VK_HASH, "OpenVmStarkVerifier: vk mismatch");
+        Vk memory key = parseVk(vk.offset);
+        Cursor memory cursor = Cursor(proof.offset);
+        Challenger memory challenger = newChallenger(key);
+        Transcript memory t;
+
+        readCommitments(key, t, challenger, cursor);
+        readOpenedValues(key, cursor);
+        Round[] memory rounds = buildRounds(key, t);
+        verifyFri(key, t, challenger, cursor, rounds);
+        require(cursor.pos == proof.offset + proof.length, "OpenVmStarkVerifier: invalid proof length");
+
+        uint256[] memory slots = new uint256[](key.numSlots);
+        for (uint256 i = 0; i < key.airs.length; i++) {
+            verifyAirConstraints(key.airs[i], t, slots);
+        }
+        return rootPublicValues(key);
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Verifying key and proof layout
+    // ---------------------------------------------------------------------------------------------
+
+    function parseVk(uint256 offset) internal pure returns (Vk memory key) {
+        Cursor memory c = Cursor(offset);
+        key.logBlowup = readU32(c);
+        key.numQueries = readU32(c);
+        key.powBits = readU32(c);
+        key.programCommit = readDigest(c);
+        key.programCommitIndex = readU32(c);
+        key.connectorAir = readU32(c);
+        key.publicValuesAir = readU32(c);
+        key.numPhases = readU32(c);
+        key.airs = new Air[](readU32(c));
+        uint256 numCachedMains = 0;
+        for (uint256 i = 0; i < key.airs.length; i++) {
+            Air memory air = key.airs[i];
+            parseAir(air, c);
+            numCachedMains += air.numCachedMains;
+            if (air.tapeLength > 0 && key.numSlots < readSlots(air)) {
+                key.numSlots = readSlots(air);
+            }
+        }
+        key.numMainCommits = numCachedMains + 1;
+    }
+
+    function parseAir(Air memory air, Cursor memory c) internal pure {
+        air.logDegree = readU32(c);
+        air.logQuotientDegree = readU32(c);
+        if (readU32(c) == 1) {
+            air.hasPreprocessed = true;
+            air.preprocessedCommit = readDigest(c);
+            air.preprocessedWidth = readU32(c);
+        }
+        air.numCachedMains = readU32(c);
+        uint256 cachedWidthsOffset = c.pos;
+        c.pos += 4 * air.numCachedMains;
+        uint256 commonWidth = readU32(c);
+        uint256 numMains = air.numCachedMains + (commonWidth > 0 ? 1 : 0);
+        air.mainWidths = new uint256[](numMains);
+        air.mainOffsets = new uint256[](numMains);
+        for (uint256 j = 0; j < air.numCachedMains; j++) {
+            air.mainWidths[j] = u32At(cachedWidthsOffset + 4 * j);
+        }
+        if (commonWidth > 0) {
+            air.mainWidths[numMains - 1] = commonWidth;
+        }
+        air.permWidth = readU32(c);
+        air.numExposed = readU32(c);
+        air.numPublicValues = readU32(c);
+        air.chunkConstantsOffset = c.pos;
+        c.pos += 8 << air.logQuotientDegree;
+        // The number of slots is read again by `readSlots`.
+        c.pos += 4;
+        air.tapeLength = readU32(c);
+        air.tapeOffset = c.pos;
+        c.pos += 8 * air.tapeLength;
+    }
+
+    function readSlots(Air memory air) internal pure returns (uint256) {
+        return u32At(air.tapeOffset - 8);
+    }
+
+    function readCommitments(Vk memory key, Transcript memory t, Challenger memory ch, Cursor memory c)
+        internal
+        pure
+    {
+        Air[] memory airs = key.airs;
+        for (uint256 i = 0; i < airs.length; i++) {
+            airs[i].publicValuesOffset = c.pos;
+            for (uint256 j = 0; j < airs[i].numPublicValues; j++) {
+                observeU32(ch, readBase(c));
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].hasPreprocessed) {
+                observeDigest(ch, airs[i].preprocessedCommit);
+            }
+        }
+        t.mainCommits = new bytes32[](key.numMainCommits);
+        for (uint256 i = 0; i < key.numMainCommits; i++) {
+            t.mainCommits[i] = readDigest(c);
+            observeDigest(ch, t.mainCommits[i]);
+        }
+        require(
+            t.mainCommits[key.programCommitIndex] == key.programCommit, "OpenVmStarkVerifier: program commit mismatch"
+        );
+        for (uint256 i = 0; i < airs.length; i++) {
+            observeU32(ch, airs[i].logDegree);
+        }
+
+        if (key.numPhases == 1) {
+            t.challenges = new uint256[](2);
+            t.challenges[0] = sampleExt(ch);
+            t.challenges[1] = sampleExt(ch);
+            uint256 cumulativeSum = 0;
+            for (uint256 i = 0; i < airs.length; i++) {
+                airs[i].exposedOffset = c.pos;
+                for (uint256 j = 0; j < airs[i].numExposed; j++) {
+                    uint256 value = readExt(c);
+                    observeExt(ch, value);
+                    cumulativeSum = eAdd(cumulativeSum, value);
+                }
+            }
+            require(cumulativeSum == 0, "OpenVmStarkVerifier: nonzero cumulative sum");
+            t.permCommit = readDigest(c);
+            observeDigest(ch, t.permCommit);
+        }
+
+        t.alpha = sampleExt(ch);
+        t.quotientCommit = readDigest(c);
+        observeDigest(ch, t.quotientCommit);
+        t.zeta = sampleExt(ch);
+    }
+
+    /// Records the offsets of the opened values. Each value is an extension field element.
+    function readOpenedValues(Vk memory key, Cursor memory c) internal pure {
+        Air[] memory airs = key.airs;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].hasPreprocessed) {
+                airs[i].preprocessedOffset = c.pos;
+                c.pos += 32 * airs[i].preprocessedWidth;
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            for (uint256 j = 0; j < airs[i].numCachedMains; j++) {
+                airs[i].mainOffsets[j] = c.pos;
+                c.pos += 32 * airs[i].mainWidths[j];
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            uint256 numMains = airs[i].mainWidths.length;
+            if (numMains > airs[i].numCachedMains) {
+                airs[i].mainOffsets[numMains - 1] = c.pos;
+                c.pos += 32 * airs[i].mainWidths[numMains - 1];
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].permWidth > 0) {
+                airs[i].permOffset = c.pos;
+                c.pos += 128 * airs[i].permWidth;
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            airs[i].quotientOffset = c.pos;
+            c.pos += 64 << airs[i].logQuotientDegree;
+        }
+    }
+
+    /// The PCS rounds in the order of the prover: preprocessed traces, cached mains, common mains,
+    /// after challenge traces and quotient chunks.
+    function buildRounds(Vk memory key, Transcript memory t) internal pure returns (Round[] memory rounds) {
+        Air[] memory airs = key.airs;
+        uint256 numPreprocessed = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].hasPreprocessed) numPreprocessed++;
+        }
+        rounds = new Round[](numPreprocessed + key.numMainCommits + key.numPhases + 1);
+        uint256 lb = key.logBlowup;
+        uint256 r = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].hasPreprocessed) {
+                rounds[r++] = singleMatRound(
+                    airs[i].preprocessedCommit,
+                    Mat(airs[i].logDegree + lb, airs[i].preprocessedWidth, airs[i].preprocessedOffset, true, 0, 0, 0, 0)
+                );
+            }
+        }
+        for (uint256 i = 0; i < airs.length; i++) {
+            for (uint256 j = 0; j < airs[i].numCachedMains; j++) {
+                rounds[r] = singleMatRound(
+                    t.mainCommits[r - numPreprocessed],
+                    Mat(airs[i].logDegree + lb, airs[i].mainWidths[j], airs[i].mainOffsets[j], true, 0, 0, 0, 0)
+                );
+                r++;
+            }
+        }
+        rounds[r++] = Round(t.mainCommits[key.numMainCommits - 1], commonMainMats(airs, lb));
+        if (key.numPhases == 1) {
+            rounds[r++] = Round(t.permCommit, permMats(airs, lb));
+        }
+        rounds[r] = Round(t.quotientCommit, quotientMats(airs, lb));
+    }
+
+    function singleMatRound(bytes32 commit, Mat memory mat) internal pure returns (Round memory round) {
+        round.commit = commit;
+        round.mats = new Mat[](1);
+        round.mats[0] = mat;
+    }
+
+    function commonMainMats(Air[] memory airs, uint256 lb) internal pure returns (Mat[] memory mats) {
+        uint256 n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].mainWidths.length > airs[i].numCachedMains) n++;
+        }
+        mats = new Mat[](n);
+        n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            uint256 k = airs[i].mainWidths.length;
+            if (k > airs[i].numCachedMains) {
+                mats[n++] = Mat(airs[i].logDegree + lb, airs[i].mainWidths[k - 1], airs[i].mainOffsets[k - 1], true, 0, 0, 0, 0);
+            }
+        }
+    }
+
+    function permMats(Air[] memory airs, uint256 lb) internal pure returns (Mat[] memory mats) {
+        uint256 n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].permWidth > 0) n++;
+        }
+        mats = new Mat[](n);
+        n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            if (airs[i].permWidth > 0) {
+                // The after challenge trace is committed over the base field.
+                mats[n++] = Mat(airs[i].logDegree + lb, 4 * airs[i].permWidth, airs[i].permOffset, true, 0, 0, 0, 0);
+            }
+        }
+    }
+
+    function quotientMats(Air[] memory airs, uint256 lb) internal pure returns (Mat[] memory mats) {
+        uint256 n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            n += 1 << airs[i].logQuotientDegree;
+        }
+        mats = new Mat[](n);
+        n = 0;
+        for (uint256 i = 0; i < airs.length; i++) {
+            for (uint256 j = 0; j < (1 << airs[i].logQuotientDegree); j++) {
+                mats[n++] = Mat(airs[i].logDegree + lb, 4, airs[i].quotientOffset + 64 * j, false, 0, 0, 0, 0);
+            }
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // FRI
+    // ---------------------------------------------------------------------------------------------
+
+    function verifyFri(
+        Vk memory key,
+        Transcript memory t,
+        Challenger memory ch,
+        Cursor memory c,
+        Round[] memory rounds
+    ) internal pure {
+        t.friAlpha = sampleExt(ch);
+        uint256 logMaxHeight = key.airs[0].logDegree + key.logBlowup;
+        uint256 numCommits = logMaxHeight - key.logBlowup;
+        t.friCommits = new bytes32[](numCommits);
+        t.betas = new uint256[](numCommits);
+        for (uint256 i = 0; i < numCommits; i++) {
+            t.friCommits[i] = readDigest(c);
+            observeDigest(ch, t.friCommits[i]);
+            t.betas[i] = sampleExt(ch);
+        }
+        t.finalPoly = readExt(c);
+        observeExt(ch, t.finalPoly);
+        observeU32(ch, readBase(c));
+        require(sampleBits(ch, key.powBits) == 0, "OpenVmStarkVerifier: invalid proof of work");
+
+        uint256[] memory indices = new uint256[](key.numQueries);
+        for (uint256 q = 0; q < key.numQueries; q++) {
+            indices[q] = sampleBits(ch, logMaxHeight);
+        }
+        QueryContext memory ctx = prepareQueries(t, rounds, logMaxHeight, key.logBlowup);
+        for (uint256 q = 0; q < key.numQueries; q++) {
+            verifyQuery(t, rounds, ctx, c, indices[q]);
+        }
+    }
+
+    /// Precomputes everything about the reduced openings which doesn't depend on the query index.
+    function prepareQueries(Transcript memory t, Round[] memory rounds, uint256 logMaxHeight, uint256 logBlowup)
+        internal
+        pure
+        returns (QueryContext memory ctx)
+    {
+        ctx.logMaxHeight = logMaxHeight;
+        ctx.hasHeight = new bool[](logMaxHeight + 1);
+        ctx.zetaNext = new uint256[](logMaxHeight + 1);
+        ctx.numerators0 = new uint256[](logMaxHeight + 1);
+        ctx.numerators1 = new uint256[](logMaxHeight + 1);
+        ctx.reducedOpenings = new uint256[](logMaxHeight + 1);
+
+        uint256 maxWidth = 0;
+        for (uint256 r = 0; r < rounds.length; r++) {
+            require(rounds[r].mats.length > 0, "OpenVmStarkVerifier: empty round");
+            for (uint256 m = 0; m < rounds[r].mats.length; m++) {
+                Mat memory mat = rounds[r].mats[m];
+                if (mat.width > maxWidth) maxWidth = mat.width;
+                if (!ctx.hasHeight[mat.logHeight]) {
+                    ctx.hasHeight[mat.logHeight] = true;
+                    ctx.zetaNext[mat.logHeight] = eMulBase(t.zeta, twoAdicGenerator(mat.logHeight - logBlowup));
+                }
+            }
+        }
+
+        uint256[] memory alphaPows = new uint256[](maxWidth + 1);
+        ctx.alphaPowLo = new uint256[](maxWidth);
+        ctx.alphaPowHi = new uint256[](maxWidth);
+        alphaPows[0] = 1;
+        for (uint256 i = 0; i < maxWidth; i++) {
+            uint256 a = alphaPows[i];
+            ctx.alphaPowLo[i] = (a & LIMB_MASK) | (((a >> 64) & LIMB_MASK) << 128);
+            ctx.alphaPowHi[i] = ((a >> 128) & LIMB_MASK) | ((a >> 192) << 128);
+            alphaPows[i + 1] = eMul(a, t.friAlpha);
+        }
+
+        // Running power of alpha per log height, continued across rounds.
+        uint256[] memory alphaPowByHeight = new uint256[](logMaxHeight + 1);
+        for (uint256 h = 0; h <= logMaxHeight; h++) {
+            alphaPowByHeight[h] = 1;
+        }
+        for (uint256 r = 0; r < rounds.length; r++) {
+            for (uint256 m = 0; m < rounds[r].mats.length; m++) {
+                Mat memory mat = rounds[r].mats[m];
+                uint256 shift = alphaPows[mat.width];
+                mat.alphaPow0 = alphaPowByHeight[mat.logHeight];
+                mat.sum0 = eMul(mat.alphaPow0, hornerAt(mat.offset, mat.width, t.friAlpha));
+                alphaPowByHeight[mat.logHeight] = eMul(mat.alphaPow0, shift);
+                if (mat.twoPoints) {
+                    mat.alphaPow1 = alphaPowByHeight[mat.logHeight];
+                    mat.sum1 = eMul(mat.alphaPow1, hornerAt(mat.offset + 16 * mat.width, mat.width, t.friAlpha));
+                    alphaPowByHeight[mat.logHeight] = eMul(mat.alphaPow1, shift);
+                }
+            }
+        }
+    }
+
+    /// Returns sum_i alpha^i * v_i for the `width` extension field elements at `offset`.
+    function hornerAt(uint256 offset, uint256 width, uint256 alpha) internal pure returns (uint256 acc) {
+        for (uint256 i = width; i > 0; i--) {
+            acc = eAdd(eMul(acc, alpha), extAt(offset + 16 * (i - 1)));
+        }
+    }
+
+    function verifyQuery(
+        Transcript memory t,
+        Round[] memory rounds,
+        QueryContext memory ctx,
+        Cursor memory c,
+        uint256 index
+    ) internal pure {
+        uint256 logMaxHeight = ctx.logMaxHeight;
+        for (uint256 h = 0; h <= logMaxHeight; h++) {
+            ctx.numerators0[h] = 0;
+            ctx.numerators1[h] = 0;
+        }
+        for (uint256 r = 0; r < rounds.length; r++) {
+            openRound(rounds[r], ctx, c, index >> (logMaxHeight - rounds[r].mats[0].logHeight));
+        }
+        computeReducedOpenings(t, ctx, index);
+        foldQuery(t, ctx, c, index);
+    }
+
+    /// Checks the opened rows of a round against its commitment and accumulates their reduced
+    /// openings.
+    function openRound(Round memory round, QueryContext memory ctx, Cursor memory c, uint256 index) internal pure {
+        Mat[] memory mats = round.mats;
+        uint256 rowsOffset = c.pos;
+        for (uint256 m = 0; m < mats.length; m++) {
+            Mat memory mat = mats[m];
+            uint256 b = dotRow(c.pos, mat.width, ctx.alphaPowLo, ctx.alphaPowHi);
+            c.pos += 4 * mat.width;
+            uint256 h = mat.logHeight;
+            ctx.numerators0[h] = eAdd(ctx.numerators0[h], eSub(mat.sum0, eMul(mat.alphaPow0, b)));
+            if (mat.twoPoints) {
+                ctx.numerators1[h] = eAdd(ctx.numerators1[h], eSub(mat.sum1, eMul(mat.alphaPow1, b)));
+            }
+        }
+        uint256 pathOffset = c.pos;
+        c.pos += 32 * mats[0].logHeight;
+        require(
+            verifyBatch(mats, rowsOffset, pathOffset, index) == round.commit, "OpenVmStarkVerifier: invalid opening proof"
+        );
+    }
+
+    /// Returns sum_i alpha^i * row_i for the little-endian row of `width` base field elements at
+    /// `offset`.
+    function dotRow(uint256 offset, uint256 width, uint256[] memory alphaPowLo, uint256[] memory alphaPowHi)
+        internal
+        pure
+        returns (uint256)
+    {
+        uint256 lo;
+        uint256 hi;
+        assembly {
+            let loPtr := add(alphaPowLo, 32)
+            let hiPtr := add(alphaPowHi, 32)
+            for { let i := 0 } lt(i, width) { i := add(i, 1) } {
+                let raw := shr(224, calldataload(add(offset, mul(i, 4))))
+                let v := or(
+                    or(shl(24, and(raw, 0xff)), shl(8, and(raw, 0xff00))), or(and(shr(8, raw), 0xff00), shr(24, raw))
+                )
+                lo := add(lo, mul(v, mload(add(loPtr, mul(i, 32)))))
+                hi := add(hi, mul(v, mload(add(hiPtr, mul(i, 32)))))
+            }
+        }
+        return ((lo & LANE_MASK) % P) | (((lo >> 128) % P) << 64) | (((hi & LANE_MASK) % P) << 128)
+            | (((hi >> 128) % P) << 192);
+    }
+
+    /// Computes the root of a batch of matrices sorted by height from their opened rows and the
+    /// Merkle path at `pathOffset`.
+    function verifyBatch(Mat[] memory mats, uint256 rowsOffset, uint256 pathOffset, uint256 index)
+        internal
+        pure
+        returns (bytes32 root)
+    {
+        uint256 height = mats[0].logHeight;
+        uint256 m = 0;
+        uint256 rowsLength = 0;
+        while (m < mats.length && mats[m].logHeight == height) {
+            rowsLength += 4 * mats[m++].width;
+        }
+        root = hashCalldata(rowsOffset, rowsLength);
+        rowsOffset += rowsLength;
+        for (uint256 i = 0; i < mats[0].logHeight; i++) {
+            bytes32 sibling = bytes32At(pathOffset + 32 * i);
+            root = index & 1 == 0 ? compress(root, sibling) : compress(sibling, root);
+            index >>= 1;
+            height--;
+            if (m < mats.length && mats[m].logHeight == height) {
+                rowsLength = 0;
+                while (m < mats.length && mats[m].logHeight == height) {
+                    rowsLength += 4 * mats[m++].width;
+                }
+                root = compress(root, hashCalldata(rowsOffset, rowsLength));
+                rowsOffset += rowsLength;
+            }
+        }
+    }
+
+    function computeReducedOpenings(Transcript memory t, QueryContext memory ctx, uint256 index) internal pure {
+        uint256 logMaxHeight = ctx.logMaxHeight;
+        // Two denominators per height, inverted together.
+        uint256[] memory denominators = new uint256[](2 * (logMaxHeight + 1));
+        for (uint256 h = 0; h <= logMaxHeight; h++) {
+            if (ctx.hasHeight[h]) {
+                uint256 x = mulmod(
+                    GENERATOR, basePow(twoAdicGenerator(h), reverseBits(index >> (logMaxHeight - h), h)), P
+                );
+                denominators[2 * h] = eSub(t.zeta, x);
+                denominators[2 * h + 1] = eSub(ctx.zetaNext[h], x);
+            } else {
+                denominators[2 * h] = 1;
+                denominators[2 * h + 1] = 1;
+            }
+        }
+        batchInverse(denominators);
+        for (uint256 h = 0; h <= logMaxHeight; h++) {
+            ctx.reducedOpenings[h] = ctx.hasHeight[h]
+                ? eAdd(eMul(ctx.numerators0[h], denominators[2 * h]), eMul(ctx.numerators1[h], denominators[2 * h + 1]))
+                : 0;
+        }
+    }
+
+    function foldQuery(Transcript memory t, QueryContext memory ctx, Cursor memory c, uint256 index) internal pure {
+        uint256 logMaxHeight = ctx.logMaxHeight;
+        uint256 rev = reverseBits(index, logMaxHeight);
+        uint256 x = basePow(twoAdicGenerator(logMaxHeight), rev);
+        uint256 xInv = basePow(inverseTwoAdicGenerator(logMaxHeight), rev);
+        uint256 folded = 0;
+        for (uint256 i = 0; i < t.friCommits.length; i++) {
+            folded = eAdd(folded, ctx.reducedOpenings[logMaxHeight - i]);
+            uint256 sibling = readExt(c);
+            bool isLeft = (index >> i) & 1 == 0;
+            uint256 e0 = isLeft ? folded : sibling;
+            uint256 e1 = isLeft ? sibling : folded;
+
+            uint256 pathLength = logMaxHeight - i - 1;
+            bytes32 root = verifyPath(hashExtPair(e0, e1), c.pos, pathLength, index >> (i + 1));
+            require(root == t.friCommits[i], "OpenVmStarkVerifier: invalid commit phase opening");
+            c.pos += 32 * pathLength;
+
+            // e0 + (beta - x0) * (e1 - e0) / (x1 - x0) with x0 = +-x and x1 = -x0.
+            uint256 x0 = isLeft ? x : P - x;
+            uint256 invDenominator = mulmod(NEG_INV_TWO, isLeft ? xInv : P - xInv, P);
+            folded = eAdd(e0, eMulBase(eMul(eSub(t.betas[i], x0), eSub(e1, e0)), invDenominator));
+            x = mulmod(x, x, P);
+            xInv = mulmod(xInv, xInv, P);
+        }
+        require(folded == t.finalPoly, "OpenVmStarkVerifier: final polynomial mismatch");
+    }
+
+    function verifyPath(bytes32 root, uint256 pathOffset, uint256 pathLength, uint256 index)
+        internal
+        pure
+        returns (bytes32)
+    {
+        for (uint256 i = 0; i < pathLength; i++) {
+            bytes32 sibling = bytes32At(pathOffset + 32 * i);
+            root = index & 1 == 0 ? compress(root, sibling) : compress(sibling, root);
+            index >>= 1;
+        }
+        return root;
+    }
+
+    /// Leaf hash of a row of two extension field elements, serialized as 8 little-endian `u32`s.
+    function hashExtPair(uint256 e0, uint256 e1) internal pure returns (bytes32 h) {
+        uint256 word = 0;
+        for (uint256 i = 0; i < 4; i++) {
+            word |= byteSwap32((e0 >> (64 * i)) & LIMB_MASK) << (224 - 32 * i);
+            word |= byteSwap32((e1 >> (64 * i)) & LIMB_MASK) << (96 - 32 * i);
+        }
+        assembly {
+            mstore(0, word)
+            h := keccak256(0, 32)
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Constraints
+    // ---------------------------------------------------------------------------------------------
+
+    function verifyAirConstraints(Air memory air, Transcript memory t, uint256[] memory slots) internal pure {
+        uint256 zeta = t.zeta;
+        uint256 zh = eSub(eExpPowerOf2(zeta, air.logDegree), 1);
+        Selectors memory sels;
+        sels.isTransition = eSub(zeta, inverseTwoAdicGenerator(air.logDegree));
+        sels.isFirstRow = eMul(zh, eInv(eSub(zeta, 1)));
+        sels.isLastRow = eMul(zh, eInv(sels.isTransition));
+        sels.invZeroifier = eInv(zh);
+
+        uint256 folded = evalTape(air, t, sels, slots);
+        require(
+            eMul(folded, sels.invZeroifier) == recomputeQuotient(air, zeta), "OpenVmStarkVerifier: constraints not satisfied"
+        );
+    }
+
+    /// Folds the constraints of `air` with powers of alpha by running its constraint tape.
+    function evalTape(Air memory air, Transcript memory t, Selectors memory sels, uint256[] memory slots)
+        internal
+        pure
+        returns (uint256 acc)
+    {
+        uint256 end = air.tapeOffset + 8 * air.tapeLength;
+        for (uint256 offset = air.tapeOffset; offset < end; offset += 8) {
+            uint256 instr;
+            assembly {
+                instr := shr(192, calldataload(offset))
+            }
+            uint256 op = instr >> 56;
+            uint256 a = (instr >> 24) & 0xffff;
+            uint256 b = instr & 0xffffff;
+            uint256 value;
+            if (op == OP_MUL) {
+                value = eMul(slots[a], slots[b]);
+            } else if (op == OP_ADD) {
+                value = eAdd(slots[a], slots[b]);
+            } else if (op == OP_SUB) {
+                value = eSub(slots[a], slots[b]);
+            } else if (op == OP_NEG) {
+                value = eSub(0, slots[a]);
+            } else if (op == OP_ASSERT) {
+                acc = eAdd(eMul(acc, t.alpha), slots[a]);
+                continue;
+            } else {
+                value = evalLeaf(air, t, sels, op, a, b);
+            }
+            slots[(instr >> 40) & 0xffff] = value;
+        }
+    }
+
+    function evalLeaf(Air memory air, Transcript memory t, Selectors memory sels, uint256 op, uint256 a, uint256 b)
+        internal
+        pure
+        returns (uint256)
+    {
+        if (op == OP_CONST) return (a << 24) | b;
+        if (op == OP_MAIN_LOCAL) return extAt(air.mainOffsets[a] + 16 * b);
+        if (op == OP_MAIN_NEXT) return extAt(air.mainOffsets[a] + 16 * (air.mainWidths[a] + b));
+        if (op == OP_PREPROCESSED_LOCAL) return extAt(air.preprocessedOffset + 16 * b);
+        if (op == OP_PREPROCESSED_NEXT) return extAt(air.preprocessedOffset + 16 * (air.preprocessedWidth + b));
+        if (op == OP_PERMUTATION_LOCAL) return flattenedExtAt(air.permOffset + 64 * b);
+        if (op == OP_PERMUTATION_NEXT) return flattenedExtAt(air.permOffset + 64 * (air.permWidth + b));
+        if (op == OP_PUBLIC) return u32At(air.publicValuesOffset + 4 * b) % P;
+        if (op == OP_CHALLENGE) return t.challenges[b];
+        if (op == OP_EXPOSED) return extAt(air.exposedOffset + 16 * b);
+        if (op == OP_IS_FIRST_ROW) return sels.isFirstRow;
+        if (op == OP_IS_LAST_ROW) return sels.isLastRow;
+        if (op == OP_IS_TRANSITION) return sels.isTransition;
+        revert("OpenVmStarkVerifier: unknown opcode");
+    }
+
+    /// Recombines the quotient chunks opened at zeta.
+    function recomputeQuotient(Air memory air, uint256 zeta) internal pure returns (uint256 quotient) {
+        uint256 numChunks = 1 << air.logQuotientDegree;
+        uint256 constantsOffset = air.chunkConstantsOffset;
+        uint256[] memory zps = new uint256[](numChunks);
+        for (uint256 j = 0; j < numChunks; j++) {
+            uint256 shiftInv = u32At(constantsOffset + 8 * j);
+            zps[j] = eSub(eExpPowerOf2(eMulBase(zeta, shiftInv), air.logDegree), 1);
+        }
+        for (uint256 i = 0; i < numChunks; i++) {
+            uint256 weight = u32At(constantsOffset + 8 * i + 4);
+            for (uint256 j = 0; j < numChunks; j++) {
+                if (j != i) weight = eMul(weight, zps[j]);
+            }
+            quotient = eAdd(quotient, eMul(weight, flattenedExtAt(air.quotientOffset + 64 * i)));
+        }
+    }
+
+    function rootPublicValues(Vk memory key) internal pure returns (uint256[] memory publicValues) {
+        // Connector public values: initial pc, final pc, exit code, is terminate.
+        uint256 connectorOffset = key.airs[key.connectorAir].publicValuesOffset;
+        require(u32At(connectorOffset) % P == 0, "OpenVmStarkVerifier: initial pc is not zero");
+        require(u32At(connectorOffset + 8) % P == 0, "OpenVmStarkVerifier: nonzero exit code");
+        require(u32At(connectorOffset + 12) % P == 1, "OpenVmStarkVerifier: execution did not terminate");
+
+        Air memory pvAir = key.airs[key.publicValuesAir];
+        publicValues = new uint256[](pvAir.numPublicValues);
+        for (uint256 i = 0; i < publicValues.length; i++) {
+            publicValues[i] = u32At(pvAir.publicValuesOffset + 4 * i) % P;
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Challenger
+    // ---------------------------------------------------------------------------------------------
+
+    function newChallenger(Vk memory key) internal pure returns (Challenger memory ch) {
+        // Enough for everything observed between two samples.
+        uint256 capacity = 256 + 32 * key.numMainCommits;
+        for (uint256 i = 0; i < key.airs.length; i++) {
+            capacity += 4 * key.airs[i].numPublicValues + 72;
+        }
+        ch.input = new bytes(capacity);
+    }
+
+    function observeU32(Challenger memory ch, uint256 value) internal pure {
+        uint256 length = ch.inputLength;
+        bytes memory input = ch.input;
+        require(length + 4 <= input.length, "OpenVmStarkVerifier: challenger overflow");
+        assembly {
+            let ptr := add(add(input, 32), length)
+            mstore8(ptr, and(value, 0xff))
+            mstore8(add(ptr, 1), and(shr(8, value), 0xff))
+            mstore8(add(ptr, 2), and(shr(16, value), 0xff))
+            mstore8(add(ptr, 3), and(shr(24, value), 0xff))
+        }
+        ch.inputLength = length + 4;
+        ch.outputLength = 0;
+    }
+
+    function observeDigest(Challenger memory ch, bytes32 digest) internal pure {
+        uint256 length = ch.inputLength;
+        bytes memory input = ch.input;
+        require(length + 32 <= input.length, "OpenVmStarkVerifier: challenger overflow");
+        assembly {
+            mstore(add(add(input, 32), length), digest)
+        }
+        ch.inputLength = length + 32;
+        ch.outputLength = 0;
+    }
+
+    function observeExt(Challenger memory ch, uint256 value) internal pure {
+        for (uint256 i = 0; i < 4; i++) {
+            observeU32(ch, (value >> (64 * i)) & LIMB_MASK);
+        }
+    }
+
+    function sampleByte(Challenger memory ch) internal pure returns (uint256) {
+        if (ch.outputLength == 0) {
+            bytes memory input = ch.input;
+            uint256 length = ch.inputLength;
+            bytes32 output;
+            assembly {
+                output := keccak256(add(input, 32), length)
+                mstore(add(input, 32), output)
+            }
+            ch.output = output;
+            ch.outputLength = 32;
+            ch.inputLength = 32;
+        }
+        ch.outputLength--;
+        return uint8(ch.output[ch.outputLength]);
+    }
+
+    function sampleU32(Challenger memory ch) internal pure returns (uint256 value) {
+        for (uint256 i = 0; i < 4; i++) {
+            value |= sampleByte(ch) << (8 * i);
+        }
+    }
+
+    function sampleBase(Challenger memory ch) internal pure returns (uint256 value) {
+        do {
+            value = sampleU32(ch) & 0x7fffffff;
+        } while (value >= P);
+    }
+
+    function sampleExt(Challenger memory ch) internal pure returns (uint256 value) {
+        for (uint256 i = 0; i < 4; i++) {
+            value |= sampleBase(ch) << (64 * i);
+        }
+    }
+
+    function sampleBits(Challenger memory ch, uint256 bits) internal pure returns (uint256) {
+        return sampleU32(ch) & ((1 << bits) - 1);
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Calldata
+    // ---------------------------------------------------------------------------------------------
+
+    function u32At(uint256 offset) internal pure returns (uint256 value) {
+        assembly {
+            value := shr(224, calldataload(offset))
+        }
+    }
+
+    function bytes32At(uint256 offset) internal pure returns (bytes32 value) {
+        assembly {
+            value := calldataload(offset)
+        }
+    }
+
+    /// Extension field element encoded as 4 big-endian `u32`s.
+    function extAt(uint256 offset) internal pure returns (uint256) {
+        uint256 word;
+        assembly {
+            word := calldataload(offset)
+        }
+        return ((word >> 224) % P) | ((((word >> 192) & 0xffffffff) % P) << 64)
+            | ((((word >> 160) & 0xffffffff) % P) << 128) | ((((word >> 128) & 0xffffffff) % P) << 192);
+    }
+
+    /// Extension field element flattened into 4 consecutive extension field elements, i.e.
+    /// sum_i X^i * v_i.
+    function flattenedExtAt(uint256 offset) internal pure returns (uint256 acc) {
+        for (uint256 i = 4; i > 0; i--) {
+            acc = eAdd(eMulX(acc), extAt(offset + 16 * (i - 1)));
+        }
+    }
+
+    function hashCalldata(uint256 offset, uint256 length) internal pure returns (bytes32 h) {
+        assembly {
+            let ptr := mload(0x40)
+            calldatacopy(ptr, offset, length)
+            h := keccak256(ptr, length)
+        }
+    }
+
+    function readU32(Cursor memory c) internal pure returns (uint256 value) {
+        value = u32At(c.pos);
+        c.pos += 4;
+    }
+
+    function readBase(Cursor memory c) internal pure returns (uint256) {
+        return readU32(c) % P;
+    }
+
+    function readExt(Cursor memory c) internal pure returns (uint256 value) {
+        value = extAt(c.pos);
+        c.pos += 16;
+    }
+
+    function readDigest(Cursor memory c) internal pure returns (bytes32 value) {
+        value = bytes32At(c.pos);
+        c.pos += 32;
+    }
+
+    function compress(bytes32 left, bytes32 right) internal pure returns (bytes32 h) {
+        assembly {
+            mstore(0, left)
+            mstore(32, right)
+            h := keccak256(0, 64)
+        }
+    }
+
+    // ---------------------------------------------------------------------------------------------
+    // Field arithmetic
+    // ---------------------------------------------------------------------------------------------
+
+    function twoAdicGenerator(uint256 bits) internal pure returns (uint256) {
+        uint256 word = bits < 8 ? GENERATORS_0 : bits < 16 ? GENERATORS_1 : bits < 24 ? GENERATORS_2 : GENERATORS_3;
+        return (word >> (32 * (bits % 8))) & 0xffffffff;
+    }
+
+    function inverseTwoAdicGenerator(uint256 bits) internal pure returns (uint256) {
+        uint256 word = bits < 8
+            ? INV_GENERATORS_0
+            : bits < 16 ? INV_GENERATORS_1 : bits < 24 ? INV_GENERATORS_2 : INV_GENERATORS_3;
+        return (word >> (32 * (bits % 8))) & 0xffffffff;
+    }
+
+    function basePow(uint256 x, uint256 e) internal pure returns (uint256 r) {
+        r = 1;
+        while (e > 0) {
+            if (e & 1 == 1) r = mulmod(r, x, P);
+            x = mulmod(x, x, P);
+            e >>= 1;
+        }
+    }
+
+    function baseInv(uint256 x) internal pure returns (uint256) {
+        return basePow(x, P - 2);
+    }
+
+    function reverseBits(uint256 x, uint256 bits) internal pure returns (uint256 r) {
+        for (uint256 i = 0; i < bits; i++) {
+            r = (r << 1) | (x & 1);
+            x >>= 1;
+        }
+    }
+
+    function byteSwap32(uint256 x) internal pure returns (uint256) {
+        return ((x & 0xff) << 24) | ((x & 0xff00) << 8) | ((x >> 8) & 0xff00) | (x >> 24);
+    }
+
+    /// Reduces every limb of `x` modulo P.
+    function reduce(uint256 x) internal pure returns (uint256) {
+        return ((x & LIMB_MASK) % P) | ((((x >> 64) & LIMB_MASK) % P) << 64)
+            | ((((x >> 128) & LIMB_MASK) % P) << 128) | (((x >> 192) % P) << 192);
+    }
+
+    function eAdd(uint256 a, uint256 b) internal pure returns (uint256) {
+        unchecked {
+            return reduce(a + b);
+        }
+    }
+
+    function eSub(uint256 a, uint256 b) internal pure returns (uint256) {
+        unchecked {
+            return reduce(a + (P_LIMBS - b));
+        }
+    }
+
+    function eMulBase(uint256 a, uint256 s) internal pure returns (uint256) {
+        unchecked {
+            return reduce(a * s);
+        }
+    }
+
+    /// Multiplies by X.
+    function eMulX(uint256 a) internal pure returns (uint256) {
+        unchecked {
+            return (((a >> 192) * W) % P) | ((a & ((1 << 192) - 1)) << 64);
+        }
+    }
+
+    function eMul(uint256 a, uint256 b) internal pure returns (uint256 r) {
+        unchecked {
+            uint256 a0 = a & LIMB_MASK;
+            uint256 a1 = (a >> 64) & LIMB_MASK;
+            uint256 a2 = (a >> 128) & LIMB_MASK;
+            uint256 a3 = a >> 192;
+            uint256 b0 = b & LIMB_MASK;
+            uint256 b1 = (b >> 64) & LIMB_MASK;
+            uint256 b2 = (b >> 128) & LIMB_MASK;
+            uint256 b3 = b >> 192;
+            r = (a0 * b0 + W * (a1 * b3 + a2 * b2 + a3 * b1)) % P;
+            r |= ((a0 * b1 + a1 * b0 + W * (a2 * b3 + a3 * b2)) % P) << 64;
+            r |= ((a0 * b2 + a1 * b1 + a2 * b0 + W * a3 * b3) % P) << 128;
+            r |= ((a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0) % P) << 192;
+        }
+    }
+
+    function eExpPowerOf2(uint256 a, uint256 k) internal pure returns (uint256) {
+        for (uint256 i = 0; i < k; i++) {
+            a = eMul(a, a);
+        }
+        return a;
+    }
+
+    /// Inverse in the tower BabyBear[Y]/(Y^2 - 11)[X]/(X^2 - Y). Returns 0 for 0.
+    function eInv(uint256 a) internal pure returns (uint256 r) {
+        unchecked {
+            uint256 a0 = a & LIMB_MASK;
+            uint256 a1 = (a >> 64) & LIMB_MASK;
+            uint256 a2 = (a >> 128) & LIMB_MASK;
+            uint256 a3 = a >> 192;
+            // (a0 + a2 Y)^2 - Y (a1 + a3 Y)^2 = c0 + c1 Y
+            uint256 c0 = (a0 * a0 + W * a2 % P * a2 + 2 * P - (2 * W * a1 % P) * a3 % P) % P;
+            uint256 c1 = (2 * a0 * a2 + 2 * P - a1 * a1 % P - (W * a3 % P) * a3 % P) % P;
+            uint256 nInv = baseInv((c0 * c0 + P - (W * c1 % P) * c1 % P) % P);
+            uint256 d0 = c0 * nInv % P;
+            uint256 d1 = (P - c1) * nInv % P;
+            // (a0 - a1 X + a2 Y - a3 XY) * (d0 + d1 Y)
+            r = (a0 * d0 + W * (a2 * d1 % P)) % P;
+            r |= (((P - a1) * d0 + W * ((P - a3) * d1 % P)) % P) << 64;
+            r |= ((a0 * d1 + a2 * d0) % P) << 128;
+            r |= (((P - a1) * d1 + (P - a3) * d0) % P) << 192;
+        }
+    }
+
+    /// Inverts every element of `xs` in place with a single field inversion.
+    function batchInverse(uint256[] memory xs) internal pure {
+        uint256 n = xs.length;
+        uint256[] memory prefix = new uint256[](n);
+        uint256 acc = 1;
+        for (uint256 i = 0; i < n; i++) {
+            prefix[i] = acc;
+            acc = eMul(acc, xs[i]);
+        }
+        acc = eInv(acc);
+        for (uint256 i = n; i > 0; i--) {
+            uint256 x = xs[i - 1];
+            xs[i - 1] = eMul(acc, prefix[i - 1]);
+            acc = eMul(acc, x);
+        }
+    }
+}
@@ -0,0 +1,597 @@
+//! Solidity verifier for root proofs of the [FinalStage::KeccakStark] final stage.
+//!
+//! The generated contract is a generic FRI verifier. Everything specific to the root verifier,
+//! i.e. the trace shapes, the preprocessed commitments and the constraints, is encoded into a
+//! verifying key blob which is passed along with every proof. The contract pins its hash.
+//!
+//! All integers in the blobs are big-endian `u32`s and digests are 32 bytes. The rows opened by
+//! FRI queries are encoded as little-endian `u32`s instead, so the contract can hash them in
+//! place.
+//!
+//! [FinalStage::KeccakStark]: crate::config::FinalStage::KeccakStark
+
+use std::collections::HashMap;
+
+use eyre::{ensure, Result};
+use openvm_circuit::arch::PROGRAM_CACHED_TRACE_INDEX;
+use openvm_native_recursion::halo2::wrapper::{compile_solidity, evm_deploy_and_call};
+use openvm_stark_sdk::openvm_stark_backend::{
+    air_builders::symbolic::{symbolic_expression::SymbolicExpression, symbolic_variable::Entry},
+    config::StarkGenericConfig,
+    keygen::types::StarkVerifyingKey,
+    p3_field::{AbstractExtensionField, AbstractField, Field, PrimeField32, TwoAdicField},
+    p3_util::log2_strict_usize,
+    prover::types::Proof,
+};
+use serde::{Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    evm_wrapper::{abi_bytes, abi_word, function_selector},
+    keygen::KeccakRootProvingKey,
+    KeccakRootSC, F,
+};
+
+type EF = <KeccakRootSC as StarkGenericConfig>::Challenge;
+
+const VERIFIER_TEMPLATE: &str = include_str!("OpenVmStarkVerifier.sol");
+const VERIFY_SIGNATURE: &[u8] = b"verify(bytes,bytes)";
+
+/// Largest `bits` with a two-adic generator of BabyBear.
+const MAX_TWO_ADICITY: usize = 27;
+
+// Opcodes of the constraint tape. Must match the contract.
+const OP_CONST: u64 = 0;
+const OP_PREPROCESSED: u64 = 1;
+const OP_MAIN: u64 = 3;
+const OP_PERMUTATION: u64 = 5;
+const OP_PUBLIC: u64 = 7;
+const OP_CHALLENGE: u64 = 8;
+const OP_EXPOSED: u64 = 9;
+const OP_IS_FIRST_ROW: u64 = 10;
+const OP_IS_LAST_ROW: u64 = 11;
+const OP_IS_TRANSITION: u64 = 12;
+const OP_ADD: u64 = 13;
+const OP_SUB: u64 = 14;
+const OP_NEG: u64 = 15;
+const OP_MUL: u64 = 16;
+const OP_ASSERT: u64 = 17;
+
+/// Solidity verifier of Keccak root proofs, with the verifying key data it is pinned to.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct KeccakStarkEvmVerifier {
+    /// Source of the `OpenVmStarkVerifier` contract.
+    pub solidity_code: String,
+    /// Verifying key data passed to `verify` with every proof. Its Keccak-256 hash is pinned in
+    /// the contract.
+    pub vk_data: Vec<u8>,
+}
+
+impl KeccakStarkEvmVerifier {
+    /// Deployment code of the contract. Compiling it requires `solc` in `PATH`.
+    pub fn deployment_code(&self) -> Vec<u8> {
+        compile_solidity(&self.solidity_code)
+    }
+
+    /// Encodes a call of `verify` with the verifying key data and `proof`, which is encoded with
+    /// [KeccakRootProvingKey::encode_proof_for_evm].
+    pub fn encode_verify_calldata(&self, proof: &[u8]) -> Vec<u8> {
+        let vk_data = abi_bytes(&self.vk_data);
+        let mut calldata = function_selector(VERIFY_SIGNATURE).to_vec();
+        calldata.extend(abi_word(2 * 32));
+        calldata.extend(abi_word(2 * 32 + vk_data.len()));
+        calldata.extend(vk_data);
+        calldata.extend(abi_bytes(proof));
+        calldata
+    }
+
+    /// Deploys the contract to an in-memory EVM and verifies `proof`, which is encoded with
+    /// [KeccakRootProvingKey::encode_proof_for_evm]. Returns the gas used by the call.
+    pub fn evm_verify(&self, proof: &[u8]) -> Result<u64> {
+        evm_deploy_and_call(self.deployment_code(), self.encode_verify_calldata(proof))
+            .map_err(|e| eyre::eyre!("EVM proof verification failed: {e}"))
+    }
+}
+
+impl KeccakRootProvingKey {
+    /// Generates the Solidity verifier for proofs of this root verifier.
+    pub fn generate_evm_verifier(&self) -> KeccakStarkEvmVerifier {
+        let vk_data = self.encode_vk_for_evm();
+        let mut hasher = Keccak::v256();
+        hasher.update(&vk_data);
+        let mut vk_hash = [0u8; 32];
+        hasher.finalize(&mut vk_hash);
+
+        let generators = (0..=MAX_TWO_ADICITY)
+            .map(F::two_adic_generator)
+            .collect::<Vec<_>>();
+        let inv_generators = generators.iter().map(|g| g.inverse()).collect::<Vec<_>>();
+        let mut solidity_code = VERIFIER_TEMPLATE.replace("{{VK_HASH}}", &to_hex(&vk_hash));
+        for (i, (gens, inv_gens)) in generators
+            .chunks(8)
+            .zip(inv_generators.chunks(8))
+            .enumerate()
+        {
+            solidity_code = solidity_code
+                .replace(&format!("{{{{GENERATORS_{i}}}}}"), &pack_u32s(gens))
+                .replace(&format!("{{{{INV_GENERATORS_{i}}}}}"), &pack_u32s(inv_gens));
+        }
+        KeccakStarkEvmVerifier {
+            solidity_code,
+            vk_data,
+        }
+    }
+
+    /// Encodes `proof` into the format expected by the Solidity verifier. The proof must be
+    /// generated with this proving key.
+    pub fn encode_proof_for_evm(&self, proof: &Proof<KeccakRootSC>) -> Result<Vec<u8>> {
+        let shapes = self.air_shapes();
+        let fri_params = self.vm_pk.fri_params;
+        let mut w = Writer::default();
+
+        ensure!(
+            proof.per_air.len() == shapes.len(),
+            "Proof has {} AIRs, expected {}",
+            proof.per_air.len(),
+            shapes.len()
+        );
+        for (i, (air_proof, shape)) in proof.per_air.iter().zip(&shapes).enumerate() {
+            ensure!(air_proof.air_id == i, "AIRs of the proof are not ordered");
+            ensure!(
+                air_proof.degree == 1 << shape.log_degree,
+                "Trace height of AIR {i} doesn't match the verifying key"
+            );
+            ensure!(
+                air_proof.public_values.len() == shape.num_public_values,
+                "Number of public values of AIR {i} doesn't match the verifying key"
+            );
+            air_proof.public_values.iter().for_each(|&x| w.base(x));
+        }
+
+        let commitments = &proof.commitments;
+        let num_cached_mains: usize = shapes.iter().map(|s| s.cached_mains.len()).sum();
+        ensure!(
+            commitments.main_trace.len() == num_cached_mains + 1,
+            "Unexpected number of main trace commitments"
+        );
+        commitments
+            .main_trace
+            .iter()
+            .for_each(|&c| w.digest(c.into()));
+        let num_phases = self.num_phases();
+        ensure!(
+            commitments.after_challenge.len() == num_phases,
+            "Unexpected number of after challenge commitments"
+        );
+        if num_phases == 1 {
+            for (air_proof, shape) in proof.per_air.iter().zip(&shapes) {
+                let exposed = air_proof
+                    .exposed_values_after_challenge
+                    .first()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                ensure!(
+                    exposed.len() == shape.num_exposed,
+                    "Unexpected number of exposed values"
+                );
+                exposed.iter().for_each(|&x| w.ext(x));
+            }
+            w.digest(commitments.after_challenge[0].into());
+        }
+        w.digest(commitments.quotient.into());
+
+        let values = &proof.opening.values;
+        for opened in values
+            .preprocessed
+            .iter()
+            .chain(values.main.iter().flatten())
+            .chain(values.after_challenge.iter().flatten())
+        {
+            opened.local.iter().for_each(|&x| w.ext(x));
+            opened.next.iter().for_each(|&x| w.ext(x));
+        }
+        for (chunks, shape) in values.quotient.iter().zip(&shapes) {
+            ensure!(
+                chunks.len() == 1 << shape.log_quotient_degree,
+                "Unexpected number of quotient chunks"
+            );
+            chunks.iter().flatten().for_each(|&x| w.ext(x));
+        }
+
+        let fri_proof = &proof.opening.proof;
+        let max_log_degree = shapes[0].log_degree;
+        ensure!(
+            fri_proof.commit_phase_commits.len() == max_log_degree,
+            "Unexpected number of FRI commit phase commitments"
+        );
+        fri_proof
+            .commit_phase_commits
+            .iter()
+            .for_each(|&c| w.digest(c.into()));
+        w.ext(fri_proof.final_poly);
+        w.base(fri_proof.pow_witness);
+        ensure!(
+            fri_proof.query_proofs.len() == fri_params.num_queries,
+            "Unexpected number of FRI queries"
+        );
+        for query_proof in &fri_proof.query_proofs {
+            for batch_opening in &query_proof.input_proof {
+                batch_opening
+                    .opened_values
+                    .iter()
+                    .for_each(|row| w.row(row));
+                batch_opening
+                    .opening_proof
+                    .iter()
+                    .for_each(|&d| w.digest(d));
+            }
+            for step in &query_proof.commit_phase_openings {
+                w.ext(step.sibling_value);
+                step.opening_proof.iter().for_each(|&d| w.digest(d));
+            }
+        }
+        Ok(w.0)
+    }
+
+    fn encode_vk_for_evm(&self) -> Vec<u8> {
+        let vk = self.vm_pk.vm_pk.get_vk();
+        let fri_params = self.vm_pk.fri_params;
+        let special_air_ids = self.air_id_permutation().get_special_air_ids();
+        let shapes = self.air_shapes();
+        let mut w = Writer::default();
+
+        w.u32(fri_params.log_blowup);
+        w.u32(fri_params.num_queries);
+        w.u32(fri_params.proof_of_work_bits);
+        w.digest(self.root_committed_exe.get_program_commit().into());
+        w.u32(PROGRAM_CACHED_TRACE_INDEX);
+        w.u32(special_air_ids.connector_air_id);
+        w.u32(special_air_ids.public_values_air_id);
+        w.u32(self.num_phases());
+        w.u32(shapes.len());
+        for (air_vk, shape) in vk.per_air.iter().zip(&shapes) {
+            w.u32(shape.log_degree);
+            w.u32(shape.log_quotient_degree);
+            match &air_vk.preprocessed_data {
+                Some(data) => {
+                    w.u32(1);
+                    w.digest(data.commit.into());
+                    w.u32(air_vk.params.width.preprocessed.unwrap());
+                }
+                None => w.u32(0),
+            }
+            w.u32(shape.cached_mains.len());
+            shape.cached_mains.iter().for_each(|&width| w.u32(width));
+            w.u32(shape.common_main);
+            w.u32(shape.after_challenge);
+            w.u32(shape.num_exposed);
+            w.u32(shape.num_public_values);
+            for (shift_inv, lagrange_inv) in quotient_chunk_constants(shape) {
+                w.base(shift_inv);
+                w.base(lagrange_inv);
+            }
+            let tape = ConstraintTape::build(air_vk);
+            w.u32(tape.num_slots);
+            w.u32(tape.instrs.len());
+            tape.instrs
+                .iter()
+                .for_each(|instr| w.0.extend(instr.to_be_bytes()));
+        }
+        w.0
+    }
+
+    fn num_phases(&self) -> usize {
+        let num_challenges = self.vm_pk.vm_pk.get_vk().num_challenges_per_phase();
+        assert!(
+            num_challenges.is_empty() || num_challenges == [2],
+            "Only a single phase with 2 challenges is supported"
+        );
+        num_challenges.len()
+    }
+
+    /// Trace shapes of the AIRs, in the order of the proving key.
+    fn air_shapes(&self) -> Vec<AirShape> {
+        let perm = self.air_id_permutation().perm;
+        let vk = self.vm_pk.vm_pk.get_vk();
+        let shapes: Vec<_> = perm
+            .iter()
+            .zip(&vk.per_air)
+            .map(|(&air_id, air_vk)| {
+                let width = &air_vk.params.width;
+                assert!(
+                    width.after_challenge.len() <= 1,
+                    "Only a single phase is supported"
+                );
+                let num_exposed: usize = air_vk
+                    .params
+                    .num_exposed_values_after_challenge
+                    .iter()
+                    .sum();
+                assert!(num_exposed <= 1, "Only the cumulative sum can be exposed");
+                let log_degree = log2_strict_usize(self.air_heights[air_id]);
+                assert!(log_degree > 0, "Traces with a single row are not supported");
+                AirShape {
+                    log_degree,
+                    log_quotient_degree: log2_strict_usize(air_vk.quotient_degree),
+                    cached_mains: width.cached_mains.clone(),
+                    common_main: width.common_main,
+                    after_challenge: width.after_challenge.first().copied().unwrap_or(0),
+                    num_exposed,
+                    num_public_values: air_vk.params.num_public_values,
+                }
+            })
+            .collect();
+        // The verifier assumes that AIRs are sorted by trace height, so the matrices of every
+        // commitment are already in the order of the MMCS.
+        assert!(shapes
+            .windows(2)
+            .all(|w| w[0].log_degree >= w[1].log_degree));
+        shapes
+    }
+}
+
+struct AirShape {
+    log_degree: usize,
+    log_quotient_degree: usize,
+    cached_mains: Vec<usize>,
+    common_main: usize,
+    /// Width of the after challenge trace in extension field elements.
+    after_challenge: usize,
+    num_exposed: usize,
+    num_public_values: usize,
+}
+
+/// For each quotient chunk domain, returns the inverse of its shift and the inverse of the
+/// product of the other chunks' vanishing polynomials at its first point.
+fn quotient_chunk_constants(shape: &AirShape) -> Vec<(F, F)> {
+    let num_chunks = 1 << shape.log_quotient_degree;
+    let g = F::two_adic_generator(shape.log_degree + shape.log_quotient_degree);
+    let shifts: Vec<F> = (0..num_chunks)
+        .map(|i| F::generator() * g.exp_u64(i as u64))
+        .collect();
+    let shift_invs: Vec<F> = shifts.iter().map(|s| s.inverse()).collect();
+    shifts
+        .iter()
+        .zip(&shift_invs)
+        .enumerate()
+        .map(|(i, (&shift, &shift_inv))| {
+            let denominator: F = (0..num_chunks)
+                .filter(|&j| j != i)
+                .map(|j| (shift * shift_invs[j]).exp_power_of_2(shape.log_degree) - F::ONE)
+                .product();
+            (shift_inv, denominator.inverse())
+        })
+        .collect()
+}
+
+/// Packs field elements into a `uint256` literal, 32 bits each, the first one in the lowest bits.
+fn pack_u32s(xs: &[F]) -> String {
+    let mut bytes = [0u8; 32];
+    for (i, x) in xs.iter().enumerate() {
+        bytes[28 - 4 * i..32 - 4 * i].copy_from_slice(&x.as_canonical_u32().to_be_bytes());
+    }
+    to_hex(&bytes)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    format!("0x{digits}")
+}
+
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u32(&mut self, x: usize) {
+        self.0
+            .extend(u32::try_from(x).expect("value overflows u32").to_be_bytes());
+    }
+    fn base(&mut self, x: F) {
+        self.0.extend(x.as_canonical_u32().to_be_bytes());
+    }
+    fn ext(&mut self, x: EF) {
+        x.as_base_slice().iter().for_each(|&x| self.base(x));
+    }
+    fn digest(&mut self, x: [u8; 32]) {
+        self.0.extend(x);
+    }
+    /// Rows are hashed by the contract, so they are encoded exactly as the Merkle tree hashes
+    /// them.
+    fn row(&mut self, row: &[F]) {
+        row.iter()
+            .for_each(|x| self.0.extend(x.as_canonical_u32().to_le_bytes()));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Leaf { op: u64, a: u64, b: u64 },
+    Neg(usize),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+}
+
+impl Node {
+    fn operands(&self) -> Vec<usize> {
+        match *self {
+            Node::Leaf { .. } => vec![],
+            Node::Neg(x) => vec![x],
+            Node::Add(x, y) | Node::Sub(x, y) | Node::Mul(x, y) => {
+                if x == y {
+                    vec![x]
+                } else {
+                    vec![x, y]
+                }
+            }
+        }
+    }
+}
+
+enum Step {
+    Eval(usize),
+    Assert(usize),
+}
+
+/// Straight-line program folding the constraints of an AIR at the out-of-domain point.
+///
+/// Each instruction is `op << 56 | dst << 40 | a << 24 | b`, where `dst` is the slot the result
+/// is written to. Common subexpressions are evaluated once and slots are reused once their value
+/// is dead, so the contract only needs `num_slots` words of memory.
+struct ConstraintTape {
+    instrs: Vec<u64>,
+    num_slots: usize,
+}
+
+impl ConstraintTape {
+    fn build(vk: &StarkVerifyingKey<KeccakRootSC>) -> Self {
+        let mut builder = TapeBuilder::default();
+        for constraint in &vk.symbolic_constraints.constraints {
+            let id = builder.eval(constraint);
+            builder.steps.push(Step::Assert(id));
+        }
+        builder.finish()
+    }
+}
+
+#[derive(Default)]
+struct TapeBuilder {
+    nodes: Vec<Node>,
+    steps: Vec<Step>,
+    node_ids: HashMap<Node, usize>,
+    expr_ids: HashMap<*const SymbolicExpression<F>, usize>,
+}
+
+impl TapeBuilder {
+    fn eval(&mut self, expr: &SymbolicExpression<F>) -> usize {
+        let ptr = expr as *const _;
+        if let Some(&id) = self.expr_ids.get(&ptr) {
+            return id;
+        }
+        let node = match expr {
+            SymbolicExpression::Variable(var) => {
+                let (op, a) = match var.entry {
+                    Entry::Preprocessed { offset } => (OP_PREPROCESSED + offset as u64, 0),
+                    Entry::Main { part_index, offset } => {
+                        (OP_MAIN + offset as u64, part_index as u64)
+                    }
+                    Entry::Permutation { offset } => (OP_PERMUTATION + offset as u64, 0),
+                    Entry::Public => (OP_PUBLIC, 0),
+                    Entry::Challenge => (OP_CHALLENGE, 0),
+                    Entry::Exposed => (OP_EXPOSED, 0),
+                };
+                if let Entry::Preprocessed { offset }
+                | Entry::Main { offset, .. }
+                | Entry::Permutation { offset } = var.entry
+                {
+                    assert!(offset <= 1, "Only local and next rows can be accessed");
+                }
+                Node::Leaf {
+                    op,
+                    a,
+                    b: var.index as u64,
+                }
+            }
+            SymbolicExpression::IsFirstRow => Node::Leaf {
+                op: OP_IS_FIRST_ROW,
+                a: 0,
+                b: 0,
+            },
+            SymbolicExpression::IsLastRow => Node::Leaf {
+                op: OP_IS_LAST_ROW,
+                a: 0,
+                b: 0,
+            },
+            SymbolicExpression::IsTransition => Node::Leaf {
+                op: OP_IS_TRANSITION,
+                a: 0,
+                b: 0,
+            },
+            SymbolicExpression::Constant(c) => {
+                let c = c.as_canonical_u32() as u64;
+                Node::Leaf {
+                    op: OP_CONST,
+                    a: c >> 24,
+                    b: c & 0xffffff,
+                }
+            }
+            SymbolicExpression::Add { x, y, .. } => Node::Add(self.eval(x), self.eval(y)),
+            SymbolicExpression::Sub { x, y, .. } => Node::Sub(self.eval(x), self.eval(y)),
+            SymbolicExpression::Neg { x, .. } => Node::Neg(self.eval(x)),
+            SymbolicExpression::Mul { x, y, .. } => Node::Mul(self.eval(x), self.eval(y)),
+        };
+        let id = match self.node_ids.get(&node) {
+            Some(&id) => id,
+            None => {
+                let id = self.nodes.len();
+                self.nodes.push(node);
+                self.node_ids.insert(node, id);
+                self.steps.push(Step::Eval(id));
+                id
+            }
+        };
+        self.expr_ids.insert(ptr, id);
+        id
+    }
+
+    fn finish(self) -> ConstraintTape {
+        let mut last_use = vec![0; self.nodes.len()];
+        for (pos, step) in self.steps.iter().enumerate() {
+            match *step {
+                Step::Eval(id) => {
+                    for x in self.nodes[id].operands() {
+                        last_use[x] = pos;
+                    }
+                }
+                Step::Assert(id) => last_use[id] = pos,
+            }
+        }
+
+        let mut slots = vec![0; self.nodes.len()];
+        let mut free_slots = vec![];
+        let mut num_slots = 0;
+        let mut instrs = Vec::with_capacity(self.steps.len());
+        for (pos, step) in self.steps.iter().enumerate() {
+            match *step {
+                Step::Eval(id) => {
+                    let node = self.nodes[id];
+                    let (op, a, b) = match node {
+                        Node::Leaf { op, a, b } => (op, a, b),
+                        Node::Neg(x) => (OP_NEG, slots[x] as u64, 0),
+                        Node::Add(x, y) => (OP_ADD, slots[x] as u64, slots[y] as u64),
+                        Node::Sub(x, y) => (OP_SUB, slots[x] as u64, slots[y] as u64),
+                        Node::Mul(x, y) => (OP_MUL, slots[x] as u64, slots[y] as u64),
+                    };
+                    // Operands are read before the result is written, so their slots can be
+                    // reused right away.
+                    for x in node.operands() {
+                        if last_use[x] == pos {
+                            free_slots.push(slots[x]);
+                        }
+                    }
+                    let dst = free_slots.pop().unwrap_or_else(|| {
+                        num_slots += 1;
+                        num_slots - 1
+                    });
+                    slots[id] = dst;
+                    instrs.push(encode_instr(op, dst as u64, a, b));
+                }
+                Step::Assert(id) => {
+                    instrs.push(encode_instr(OP_ASSERT, 0, slots[id] as u64, 0));
+                    if last_use[id] == pos {
+                        free_slots.push(slots[id]);
+                    }
+                }
+            }
+        }
+        ConstraintTape { instrs, num_slots }
+    }
+}
+
+fn encode_instr(op: u64, dst: u64, a: u64, b: u64) -> u64 {
+    assert!(
+        dst < 1 << 16 && a < 1 << 16 && b < 1 << 24,
+        "Constraint tape operand overflow"
+    );
+    op << 56 | dst << 40 | a << 24 | b
+}
@@ -1,20 +1,22 @@
 use std::sync::Arc;
 
+use openvm::deferral::StarkClaim;
 use openvm_circuit::{
     arch::{
         instructions::{
             exe::VmExe, instruction::Instruction, program::Program, SystemOpcode::TERMINATE,
             VmOpcode,
         },
-        SingleSegmentVmExecutor, VirtualMachine, VmComplexTraceHeights, VmConfig, VmExecutor,
+        SingleSegmentVmExecutor, Streams, VirtualMachine, VmComplexTraceHeights, VmConfig,
+        VmExecutor,
     },
     system::program::trace::VmCommittedExe,
     utils::next_power_of_two_or_zero,
 };
 use openvm_native_circuit::NativeConfig;
-use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::hints::Hintable;
-use openvm_rv32im_circuit::Rv32ImConfig;
+use openvm_rv32im_circuit::{adapters::RV32_REGISTER_NUM_LIMBS, Rv32ImConfig};
+use openvm_rv32im_transpiler::{BaseAluOpcode, Rv32LoadStoreOpcode, ShiftOpcode};
 use openvm_stark_sdk::{
     config::{
         baby_bear_poseidon2::BabyBearPoseidon2Engine,
@@ -22,16 +24,24 @@ use openvm_stark_sdk::{
     },
     engine::StarkFriEngine,
     openvm_stark_backend::{
-        config::StarkGenericConfig, p3_field::AbstractField, prover::types::Proof, Chip,
+        config::StarkGenericConfig,
+        p3_field::{AbstractField, PrimeField32},
+        prover::types::Proof,
+        Chip,
     },
 };
 
 use crate::{
+    keygen::AppProvingKey,
     prover::vm::{
         local::VmLocalProver, types::VmProvingKey, ContinuationVmProof, ContinuationVmProver,
         SingleSegmentVmProver,
     },
     verifier::{
+        batch::types::BatchRunCommit,
+        deferral::types::{
+            stark_claim_to_public_values, DeferralRootVmVerifierInput, DeferredClaimLayout,
+        },
         internal::types::InternalVmVerifierInput,
         leaf::{types::LeafVmVerifierInput, LeafVmVerifierConfig},
         root::types::RootVmVerifierInput,
@@ -47,15 +57,10 @@ use crate::{
 pub(super) fn compute_root_proof_heights(
     root_vm_config: NativeConfig,
     root_exe: VmExe<F>,
-    dummy_internal_proof: &Proof<SC>,
+    root_input: impl Into<Streams<F>>,
 ) -> (Vec<usize>, VmComplexTraceHeights) {
-    let num_user_public_values = root_vm_config.system.num_public_values - 2 * DIGEST_SIZE;
-    let root_input = RootVmVerifierInput {
-        proofs: vec![dummy_internal_proof.clone()],
-        public_values: vec![F::ZERO; num_user_public_values],
-    };
     let vm = SingleSegmentVmExecutor::new(root_vm_config);
-    let res = vm.execute(root_exe, root_input.write()).unwrap();
+    let res = vm.execute(root_exe, root_input).unwrap();
     let air_heights: Vec<_> = res
         .air_heights
         .into_iter()
@@ -66,13 +71,17 @@ pub(super) fn compute_root_proof_heights(
     (air_heights, internal_heights)
 }
 
+/// `leaf_fri_params_index` is the index of the FRI parameters of `leaf_proof` in the allowed leaf
+/// FRI parameters of the internal verifier.
 pub(super) fn dummy_internal_proof(
     internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     internal_exe: Arc<NonRootCommittedExe>,
     leaf_proof: Proof<SC>,
+    leaf_fri_params_index: usize,
 ) -> Proof<SC> {
     let mut internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
         internal_exe.get_program_commit().into(),
+        leaf_fri_params_index,
         &[leaf_proof],
         1,
     );
@@ -92,7 +101,110 @@ pub(super) fn dummy_internal_proof_riscv_app_vm(
 ) -> Proof<SC> {
     let fri_params = standard_fri_params_with_100_bits_conjectured_security(1);
     let leaf_proof = dummy_leaf_proof_riscv_app_vm(leaf_vm_pk, num_public_values, fri_params);
-    dummy_internal_proof(internal_vm_pk, internal_exe, leaf_proof)
+    dummy_internal_proof(internal_vm_pk, internal_exe, leaf_proof, 0)
+}
+
+/// Dummy internal proof of an execution under `app_pk`, whose leaf verifier commit is the one of
+/// `app_pk`. `leaf_vm_pk` must be the leaf VM proving key of `leaf_fri_params_index`.
+pub(super) fn dummy_internal_proof_for_app<VC: VmConfig<F>>(
+    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    leaf_fri_params_index: usize,
+    internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    internal_exe: Arc<NonRootCommittedExe>,
+    app_pk: &AppProvingKey<VC>,
+) -> Proof<SC>
+where
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    let app_proof = dummy_app_proof_impl(app_pk.app_vm_pk.clone(), None);
+    let leaf_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
+        leaf_vm_pk,
+        app_pk.leaf_committed_exe.clone(),
+    );
+    let mut leaf_inputs = LeafVmVerifierInput::chunk_continuation_vm_proof(&app_proof, 1);
+    let leaf_input = leaf_inputs.pop().unwrap();
+    let leaf_proof = SingleSegmentVmProver::prove(&leaf_prover, leaf_input.write_to_stream());
+    dummy_internal_proof(
+        internal_vm_pk,
+        internal_exe,
+        leaf_proof,
+        leaf_fri_params_index,
+    )
+}
+
+/// Dummy internal proof of an execution which reveals `public_values`.
+pub(super) fn dummy_internal_proof_riscv_app_vm_with_public_values(
+    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    internal_exe: Arc<NonRootCommittedExe>,
+    public_values: &[u8],
+) -> Proof<SC> {
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(1);
+    let app_vm_pk = Arc::new(dummy_riscv_app_vm_pk(public_values.len(), fri_params));
+    let e = BabyBearPoseidon2Engine::new(fri_params);
+    let dummy_exe = Arc::new(VmCommittedExe::<SC>::commit(
+        dummy_app_program_with_public_values(public_values).into(),
+        e.config.pcs(),
+    ));
+    let app_proof = dummy_app_proof_for_exe(app_vm_pk.clone(), dummy_exe, None);
+    let leaf_proof = dummy_leaf_proof_impl(leaf_vm_pk, app_vm_pk, &app_proof);
+    dummy_internal_proof(internal_vm_pk, internal_exe, leaf_proof, 0)
+}
+
+/// Dummy input of the deferral root verifier: an app execution which reveals the claims of dummy
+/// executions at the offsets of `deferred_claims`, together with the proofs of these executions.
+pub(super) fn dummy_deferral_root_input(
+    leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    internal_exe: Arc<NonRootCommittedExe>,
+    num_public_values: usize,
+    deferred_claims: &[DeferredClaimLayout],
+) -> DeferralRootVmVerifierInput<SC> {
+    let mut app_public_values = vec![0u8; num_public_values];
+    let deferred = deferred_claims
+        .iter()
+        .map(|layout| {
+            let proof = dummy_internal_proof_riscv_app_vm(
+                leaf_vm_pk.clone(),
+                internal_vm_pk.clone(),
+                internal_exe.clone(),
+                layout.num_public_values,
+            );
+            let run_commit = BatchRunCommit::from_internal_proof(&proof);
+            let claim = StarkClaim {
+                exe_commit: run_commit.exe_commit.map(|f| f.as_canonical_u32()),
+                leaf_verifier_commit: run_commit
+                    .leaf_verifier_commit
+                    .map(|f| f.as_canonical_u32()),
+                public_values: vec![0; layout.num_public_values],
+            };
+            app_public_values[layout.byte_offset..][..layout.num_bytes()]
+                .copy_from_slice(&stark_claim_to_public_values(&claim));
+            RootVmVerifierInput {
+                leaf_fri_params_index: 0,
+                proofs: vec![proof],
+                public_values: vec![F::ZERO; layout.num_public_values],
+            }
+        })
+        .collect();
+    let app_proof = dummy_internal_proof_riscv_app_vm_with_public_values(
+        leaf_vm_pk,
+        internal_vm_pk,
+        internal_exe,
+        &app_public_values,
+    );
+    DeferralRootVmVerifierInput {
+        app: RootVmVerifierInput {
+            leaf_fri_params_index: 0,
+            proofs: vec![app_proof],
+            public_values: app_public_values
+                .into_iter()
+                .map(F::from_canonical_u8)
+                .collect(),
+        },
+        deferred,
+    }
 }
 
 #[allow(dead_code)]
@@ -169,8 +281,19 @@ where
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
-    let fri_params = app_vm_pk.fri_params;
-    let dummy_exe = dummy_app_committed_exe(fri_params);
+    let dummy_exe = dummy_app_committed_exe(app_vm_pk.fri_params);
+    dummy_app_proof_for_exe(app_vm_pk, dummy_exe, overridden_heights)
+}
+
+fn dummy_app_proof_for_exe<VC: VmConfig<F>>(
+    app_vm_pk: Arc<VmProvingKey<SC, VC>>,
+    dummy_exe: Arc<NonRootCommittedExe>,
+    overridden_heights: Option<VmComplexTraceHeights>,
+) -> ContinuationVmProof<SC>
+where
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
     // Enforce each AIR to have at least 1 row.
     let overridden_heights = if let Some(overridden_heights) = overridden_heights {
         overridden_heights
@@ -214,3 +337,67 @@ fn dummy_app_program() -> Program<F> {
     ret.max_num_public_values = 0;
     ret
 }
+
+/// RV32 program which reveals `public_values` word by word and terminates.
+fn dummy_app_program_with_public_values(public_values: &[u8]) -> Program<F> {
+    let words = public_values.chunks_exact(RV32_REGISTER_NUM_LIMBS);
+    assert!(
+        words.remainder().iter().all(|&b| b == 0),
+        "Trailing public values must be zero"
+    );
+    // Pointer of register x1.
+    let reg = RV32_REGISTER_NUM_LIMBS as isize;
+    let mut instructions = vec![];
+    for (i, word) in words.enumerate() {
+        if word.iter().all(|&b| b == 0) {
+            continue;
+        }
+        // Builds the word from its most significant byte.
+        instructions.push(Instruction::from_isize(
+            VmOpcode::with_default_offset(BaseAluOpcode::ADD),
+            reg,
+            0,
+            word[3] as isize,
+            1,
+            0,
+        ));
+        for &byte in word[..3].iter().rev() {
+            instructions.push(Instruction::from_isize(
+                VmOpcode::with_default_offset(ShiftOpcode::SLL),
+                reg,
+                reg,
+                8,
+                1,
+                0,
+            ));
+            instructions.push(Instruction::from_isize(
+                VmOpcode::with_default_offset(BaseAluOpcode::ADD),
+                reg,
+                reg,
+                byte as isize,
+                1,
+                0,
+            ));
+        }
+        // REVEAL x1 at byte offset 4 * i, see the RV32 transpiler.
+        instructions.push(Instruction::from_isize(
+            VmOpcode::with_default_offset(Rv32LoadStoreOpcode::STOREW),
+            reg,
+            0,
+            (RV32_REGISTER_NUM_LIMBS * i) as isize,
+            1,
+            3,
+        ));
+    }
+    instructions.push(Instruction::from_isize(
+        VmOpcode::with_default_offset(TERMINATE),
+        0,
+        0,
+        0,
+        0,
+        0,
+    ));
+    let mut ret = Program::from_instructions(&instructions);
+    ret.max_num_public_values = public_values.len();
+    ret
+}
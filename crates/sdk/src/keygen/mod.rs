@@ -1,25 +1,35 @@
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
 use derivative::Derivative;
-use dummy::{compute_root_proof_heights, dummy_internal_proof_riscv_app_vm};
+use dummy::{
+    compute_root_proof_heights, dummy_deferral_root_input, dummy_internal_proof,
+    dummy_internal_proof_for_app, dummy_internal_proof_riscv_app_vm,
+};
 use openvm_circuit::{
-    arch::{VirtualMachine, VmConfig},
-    system::program::trace::VmCommittedExe,
+    arch::{instructions::program::Program, Streams, VirtualMachine, VmConfig},
+    system::{memory::dimensions::MemoryDimensions, program::trace::VmCommittedExe},
 };
 use openvm_native_circuit::NativeConfig;
 use openvm_native_compiler::ir::DIGEST_SIZE;
-use openvm_native_recursion::halo2::{
-    utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey, wrapper::Halo2WrapperProvingKey,
+use openvm_native_recursion::{
+    halo2::{
+        utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey,
+        wrapper::Halo2WrapperProvingKey,
+    },
+    hints::Hintable,
 };
 use openvm_stark_sdk::{
     config::{
-        baby_bear_poseidon2::BabyBearPoseidon2Engine,
-        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine, FriParameters,
+        baby_bear_keccak::BabyBearKeccakEngine,
+        baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine,
+        FriParameters,
     },
-    engine::StarkFriEngine,
+    engine::{StarkEngine, StarkFriEngine},
     openvm_stark_backend::{
-        config::{Com, StarkGenericConfig},
+        config::{Com, StarkGenericConfig, Val},
         keygen::types::MultiStarkVerifyingKey,
+        p3_field::{AbstractField, PrimeField32},
         prover::types::Proof,
         Chip,
     },
@@ -29,13 +39,28 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     commit::babybear_digest_to_bn254,
-    config::{AggConfig, AggStarkConfig, AppConfig},
+    config::{
+        AggConfig, AggSecurityLevels, AggStarkConfig, AppConfig, FinalStage, Halo2Config,
+        SecurityLevel, SecurityModel,
+    },
     keygen::perm::AirIdPermutation,
-    prover::vm::types::VmProvingKey,
+    prover::{
+        vm::{types::VmProvingKey, SingleSegmentVmProver},
+        RootVerifierLocalProver,
+    },
     verifier::{
-        internal::InternalVmVerifierConfig, leaf::LeafVmVerifierConfig, root::RootVmVerifierConfig,
+        batch::{
+            types::leaf_verifier_whitelist_commit, BatchRootVmVerifierConfig, BatchVmVerifierConfig,
+        },
+        deferral::{
+            types::{DeferralRootVmVerifierInput, DeferredClaimLayout},
+            DeferralRootVmVerifierConfig,
+        },
+        internal::InternalVmVerifierConfig,
+        leaf::LeafVmVerifierConfig,
+        root::{types::RootVmVerifierInput, RootVmVerifierConfig},
     },
-    NonRootCommittedExe, RootSC, F, SC,
+    KeccakRootSC, NonRootCommittedExe, RootSC, F, SC,
 };
 
 pub(crate) mod dummy;
@@ -46,12 +71,25 @@ pub struct AppProvingKey<VC> {
     pub leaf_committed_exe: Arc<NonRootCommittedExe>,
     pub leaf_fri_params: FriParameters,
     pub app_vm_pk: Arc<VmProvingKey<SC, VC>>,
+    /// Model in which the security of the app proofs is claimed in the verifying key.
+    pub security_model: SecurityModel,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct AppVerifyingKey {
+/// Verifying key of an app VM in the STARK config `SC`.
+#[derive(Serialize, Deserialize, Derivative)]
+#[serde(bound(
+    serialize = "MultiStarkVerifyingKey<SC>: Serialize",
+    deserialize = "MultiStarkVerifyingKey<SC>: Deserialize<'de>"
+))]
+#[derivative(Clone(bound = "Com<SC>: Clone"))]
+pub struct AppVerifyingKey<SC: StarkGenericConfig = BabyBearPoseidon2Config> {
     pub fri_params: FriParameters,
+    /// Security of the app proofs claimed for `fri_params`.
+    pub security: SecurityLevel,
     pub app_vm_vk: MultiStarkVerifyingKey<SC>,
+    /// Needed to check the user public values against the final memory state.
+    pub memory_dimensions: MemoryDimensions,
+    pub num_public_values: usize,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -60,12 +98,66 @@ pub struct AggProvingKey {
     pub halo2_pk: Halo2ProvingKey,
 }
 
+/// Proving keys of the aggregation pipeline when [FinalStage::KeccakStark] is selected.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct KeccakAggProvingKey {
+    pub agg_stark_pk: AggStarkProvingKey,
+    /// Root verifier proven with a Keccak-based STARK config. Its proofs are verified on-chain by
+    /// the Solidity verifier generated from it.
+    pub keccak_root_pk: KeccakRootProvingKey,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AggStarkProvingKey {
     pub leaf_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
+    /// Leaf VM proving keys of the additional leaf FRI parameters accepted by the internal
+    /// verifier, see [AggStarkConfig::additional_leaf_fri_params].
+    pub additional_leaf_vm_pks: Vec<Arc<VmProvingKey<SC, NativeConfig>>>,
     pub internal_vm_pk: Arc<VmProvingKey<SC, NativeConfig>>,
     pub internal_committed_exe: Arc<NonRootCommittedExe>,
     pub root_verifier_pk: RootVerifierProvingKey,
+    /// FRI parameters and claimed security of the leaf, internal and root layers.
+    pub security: AggSecurityLevels,
+}
+
+/// Proving keys to aggregate the proofs of many independent app executions into a single proof.
+/// See [BatchCommitTree](crate::verifier::batch::types::BatchCommitTree).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BatchAggStarkProvingKey {
+    /// Batch verifier program, which runs on the internal VM of [AggStarkProvingKey].
+    pub batch_committed_exe: Arc<NonRootCommittedExe>,
+    /// Commitments of the leaf verifier programs accepted by the batch verifier.
+    pub leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+    /// Root verifier of a batch. It has the same VM config as the root verifier of
+    /// [AggStarkProvingKey], but runs a different program.
+    pub root_verifier_pk: RootVerifierProvingKey,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BatchAggProvingKey {
+    pub agg_stark_pk: AggStarkProvingKey,
+    pub batch_stark_pk: BatchAggStarkProvingKey,
+    /// Static verifier and wrapper of the batch root verifier.
+    pub halo2_pk: Halo2ProvingKey,
+}
+
+/// Proving key to aggregate an app execution together with the executions it verifies with
+/// [verify_stark](openvm::deferral::verify_stark).
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeferralAggStarkProvingKey {
+    /// Layouts of the claims in the public values of the app.
+    pub deferred_claims: Vec<DeferredClaimLayout>,
+    /// Deferral root verifier. It has the same VM config as the root verifier of
+    /// [AggStarkProvingKey], but runs a different program.
+    pub root_verifier_pk: RootVerifierProvingKey,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeferralAggProvingKey {
+    pub agg_stark_pk: AggStarkProvingKey,
+    pub deferral_stark_pk: DeferralAggStarkProvingKey,
+    /// Static verifier and wrapper of the deferral root verifier.
+    pub halo2_pk: Halo2ProvingKey,
 }
 
 /// Attention: the size of this struct is VERY large, usually >10GB.
@@ -82,22 +174,12 @@ where
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
-    pub fn keygen(config: AppConfig<VC>) -> Self {
-        let app_engine = BabyBearPoseidon2Engine::new(config.app_fri_params.fri_params);
-        let app_vm_pk = {
-            let vm = VirtualMachine::new(app_engine, config.app_vm_config.clone());
-            let vm_pk = vm.keygen();
-            assert!(
-                vm_pk.max_constraint_degree
-                    <= config.app_fri_params.fri_params.max_constraint_degree()
-            );
-            assert!(config.app_vm_config.system().continuation_enabled);
-            VmProvingKey {
-                fri_params: config.app_fri_params.fri_params,
-                vm_config: config.app_vm_config.clone(),
-                vm_pk,
-            }
-        };
+    pub fn keygen(mut config: AppConfig<VC>) -> Self {
+        config.apply_security_target();
+        let app_vm_pk = app_vm_keygen::<SC, BabyBearPoseidon2Engine, VC>(
+            config.app_fri_params.fri_params,
+            config.app_vm_config.clone(),
+        );
         let leaf_committed_exe = {
             let leaf_engine = BabyBearPoseidon2Engine::new(config.leaf_fri_params.fri_params);
             let leaf_program = LeafVmVerifierConfig {
@@ -115,6 +197,7 @@ where
             leaf_committed_exe,
             leaf_fri_params: config.leaf_fri_params.fri_params,
             app_vm_pk: Arc::new(app_vm_pk),
+            security_model: config.security_model(),
         }
     }
 
@@ -123,10 +206,7 @@ where
     }
 
     pub fn get_vk(&self) -> AppVerifyingKey {
-        AppVerifyingKey {
-            fri_params: self.app_vm_pk.fri_params,
-            app_vm_vk: self.app_vm_pk.vm_pk.get_vk(),
-        }
+        AppVerifyingKey::new(&self.app_vm_pk, self.security_model)
     }
 
     pub fn app_fri_params(&self) -> FriParameters {
@@ -142,29 +222,82 @@ where
     }
 }
 
+impl<SC: StarkGenericConfig> AppVerifyingKey<SC> {
+    pub fn new<VC: VmConfig<Val<SC>>>(
+        app_vm_pk: &VmProvingKey<SC, VC>,
+        security_model: SecurityModel,
+    ) -> Self
+    where
+        Val<SC>: PrimeField32,
+    {
+        Self {
+            fri_params: app_vm_pk.fri_params,
+            security: security_model.security_level(&app_vm_pk.fri_params),
+            app_vm_vk: app_vm_pk.vm_pk.get_vk(),
+            memory_dimensions: app_vm_pk
+                .vm_config
+                .system()
+                .memory_config
+                .memory_dimensions(),
+            num_public_values: app_vm_pk.vm_config.system().num_public_values,
+        }
+    }
+}
+
+/// Keygen of an app VM with continuations in the STARK config `SC`, using the engine `E`. The
+/// leaf verifier of [AppProvingKey] only verifies app proofs in the BabyBear Poseidon2 config.
+pub fn app_vm_keygen<SC, E, VC>(fri_params: FriParameters, vm_config: VC) -> VmProvingKey<SC, VC>
+where
+    SC: StarkGenericConfig,
+    E: StarkFriEngine<SC>,
+    Val<SC>: PrimeField32,
+    VC: VmConfig<Val<SC>>,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    assert!(vm_config.system().continuation_enabled);
+    let vm = VirtualMachine::new(E::new(fri_params), vm_config.clone());
+    let vm_pk = vm.keygen();
+    assert!(vm_pk.max_constraint_degree <= fri_params.max_constraint_degree());
+    VmProvingKey {
+        fri_params,
+        vm_config,
+        vm_pk,
+    }
+}
+
 impl AggStarkProvingKey {
     pub fn keygen(config: AggStarkConfig) -> Self {
         tracing::info_span!("agg_stark_keygen", group = "agg_stark_keygen")
             .in_scope(|| Self::dummy_proof_and_keygen(config).0)
     }
 
-    pub fn dummy_proof_and_keygen(config: AggStarkConfig) -> (Self, Proof<SC>) {
+    pub fn dummy_proof_and_keygen(mut config: AggStarkConfig) -> (Self, Proof<SC>) {
+        config.apply_security_target();
+        let security = config.security_levels();
         let leaf_vm_config = config.leaf_vm_config();
         let internal_vm_config = config.internal_vm_config();
-        let root_vm_config = config.root_verifier_vm_config();
 
-        let leaf_engine = BabyBearPoseidon2Engine::new(config.leaf_fri_params);
-        let leaf_vm_pk = Arc::new({
-            let vm = VirtualMachine::new(leaf_engine, leaf_vm_config.clone());
-            let vm_pk = vm.keygen();
-            assert!(vm_pk.max_constraint_degree <= config.leaf_fri_params.max_constraint_degree());
-            VmProvingKey {
-                fri_params: config.leaf_fri_params,
-                vm_config: leaf_vm_config,
-                vm_pk,
-            }
-        });
-        let leaf_vm_vk = leaf_vm_pk.vm_pk.get_vk();
+        let mut leaf_vm_pks = config
+            .allowed_leaf_fri_params()
+            .into_iter()
+            .map(|fri_params| {
+                let leaf_engine = BabyBearPoseidon2Engine::new(fri_params);
+                let vm = VirtualMachine::new(leaf_engine, leaf_vm_config.clone());
+                let vm_pk = vm.keygen();
+                assert!(vm_pk.max_constraint_degree <= fri_params.max_constraint_degree());
+                Arc::new(VmProvingKey {
+                    fri_params,
+                    vm_config: leaf_vm_config.clone(),
+                    vm_pk,
+                })
+            });
+        let leaf_vm_pk = leaf_vm_pks.next().unwrap();
+        let additional_leaf_vm_pks: Vec<_> = leaf_vm_pks.collect();
+        let leaf_vm_vks: Vec<_> = iter::once(&leaf_vm_pk)
+            .chain(&additional_leaf_vm_pks)
+            .map(|pk| pk.vm_pk.get_vk())
+            .collect();
 
         let internal_engine = BabyBearPoseidon2Engine::new(config.internal_fri_params);
         let internal_vm = VirtualMachine::new(internal_engine, internal_vm_config.clone());
@@ -182,11 +315,11 @@ impl AggStarkProvingKey {
         let internal_vm_vk = internal_vm_pk.vm_pk.get_vk();
 
         let internal_program = InternalVmVerifierConfig {
-            leaf_fri_params: config.leaf_fri_params,
+            allowed_leaf_fri_params: config.allowed_leaf_fri_params(),
             internal_fri_params: config.internal_fri_params,
             compiler_options: config.compiler_options,
         }
-        .build_program(&leaf_vm_vk, &internal_vm_vk);
+        .build_program(&leaf_vm_vks, &internal_vm_vk);
         let internal_committed_exe = Arc::new(VmCommittedExe::<SC>::commit(
             internal_program.into(),
             internal_vm.engine.config.pcs(),
@@ -200,49 +333,34 @@ impl AggStarkProvingKey {
         );
 
         let root_verifier_pk = {
-            let root_engine = BabyBearPoseidon2RootEngine::new(config.root_fri_params);
             let root_program = RootVmVerifierConfig {
-                leaf_fri_params: config.leaf_fri_params,
+                allowed_leaf_fri_params: config.allowed_leaf_fri_params(),
                 internal_fri_params: config.internal_fri_params,
                 num_public_values: config.max_num_user_public_values,
                 internal_vm_verifier_commit: internal_committed_exe.get_program_commit().into(),
                 compiler_options: config.compiler_options,
             }
-            .build_program(&leaf_vm_vk, &internal_vm_vk);
-            let root_committed_exe = Arc::new(VmCommittedExe::<RootSC>::commit(
-                root_program.into(),
-                root_engine.config.pcs(),
-            ));
-
-            let vm = VirtualMachine::new(root_engine, root_vm_config.clone());
-            let mut vm_pk = vm.keygen();
-            assert!(vm_pk.max_constraint_degree <= config.root_fri_params.max_constraint_degree());
-
-            let (air_heights, _internal_heights) = compute_root_proof_heights(
-                root_vm_config.clone(),
-                root_committed_exe.exe.clone(),
-                &internal_proof,
-            );
-            let root_air_perm = AirIdPermutation::compute(&air_heights);
-            root_air_perm.permute(&mut vm_pk.per_air);
-
-            RootVerifierProvingKey {
-                vm_pk: Arc::new(VmProvingKey {
-                    fri_params: config.root_fri_params,
-                    vm_config: root_vm_config,
-                    vm_pk,
-                }),
-                root_committed_exe,
-                air_heights,
-            }
+            .build_program(&leaf_vm_vks, &internal_vm_vk);
+            RootVerifierProvingKey::keygen(
+                &config,
+                root_program,
+                RootVmVerifierInput {
+                    leaf_fri_params_index: 0,
+                    proofs: vec![internal_proof.clone()],
+                    public_values: vec![F::ZERO; config.max_num_user_public_values],
+                }
+                .write(),
+            )
         };
 
         (
             Self {
                 leaf_vm_pk,
+                additional_leaf_vm_pks,
                 internal_vm_pk,
                 internal_committed_exe,
                 root_verifier_pk,
+                security,
             },
             internal_proof,
         )
@@ -252,6 +370,28 @@ impl AggStarkProvingKey {
         self.internal_committed_exe.get_program_commit().into()
     }
 
+    /// FRI parameters of the accepted leaf verifier proofs, in the order of their indices in the
+    /// internal and root verifier inputs.
+    pub fn allowed_leaf_fri_params(&self) -> Vec<FriParameters> {
+        self.leaf_vm_pks().map(|pk| pk.fri_params).collect()
+    }
+
+    /// Returns the index and the leaf VM proving key of `leaf_fri_params`, or `None` if the
+    /// internal verifier doesn't accept leaf verifier proofs with these FRI parameters.
+    pub fn leaf_vm_pk_for(
+        &self,
+        leaf_fri_params: &FriParameters,
+    ) -> Option<(usize, Arc<VmProvingKey<SC, NativeConfig>>)> {
+        self.leaf_vm_pks()
+            .enumerate()
+            .find(|(_, pk)| pk.fri_params == *leaf_fri_params)
+            .map(|(index, pk)| (index, pk.clone()))
+    }
+
+    fn leaf_vm_pks(&self) -> impl Iterator<Item = &Arc<VmProvingKey<SC, NativeConfig>>> {
+        iter::once(&self.leaf_vm_pk).chain(&self.additional_leaf_vm_pks)
+    }
+
     pub fn num_public_values(&self) -> usize {
         self.root_verifier_pk
             .vm_pk
@@ -284,6 +424,96 @@ pub struct RootVerifierProvingKey {
 }
 
 impl RootVerifierProvingKey {
+    /// Keygen for a root verifier program running on the root VM of `config`. The trace heights
+    /// are fixed to the ones of executing `root_input`.
+    fn keygen(
+        config: &AggStarkConfig,
+        root_program: Program<F>,
+        root_input: impl Into<Streams<F>>,
+    ) -> Self {
+        let root_vm_config = config.root_verifier_vm_config();
+        let root_engine = BabyBearPoseidon2RootEngine::new(config.root_fri_params);
+        let root_committed_exe = Arc::new(VmCommittedExe::<RootSC>::commit(
+            root_program.into(),
+            root_engine.config.pcs(),
+        ));
+
+        let vm = VirtualMachine::new(root_engine, root_vm_config.clone());
+        let mut vm_pk = vm.keygen();
+        assert!(vm_pk.max_constraint_degree <= config.root_fri_params.max_constraint_degree());
+
+        let (air_heights, _internal_heights) = compute_root_proof_heights(
+            root_vm_config.clone(),
+            root_committed_exe.exe.clone(),
+            root_input,
+        );
+        let root_air_perm = AirIdPermutation::compute(&air_heights);
+        root_air_perm.permute(&mut vm_pk.per_air);
+
+        Self {
+            vm_pk: Arc::new(VmProvingKey {
+                fri_params: config.root_fri_params,
+                vm_config: root_vm_config,
+                vm_pk,
+            }),
+            root_committed_exe,
+            air_heights,
+        }
+    }
+
+    pub fn air_id_permutation(&self) -> AirIdPermutation {
+        AirIdPermutation::compute(&self.air_heights)
+    }
+}
+
+/// Proving key for the root verifier with a Keccak transcript and Keccak Merkle hashing.
+/// Properties:
+/// - Runs the same program on the same VM config as the [RootVerifierProvingKey] it is derived
+///   from, with the same FRI parameters.
+/// - Traces heights and AIR order are the ones of that [RootVerifierProvingKey].
+#[derive(Serialize, Deserialize, Derivative)]
+#[derivative(Clone(bound = "Com<KeccakRootSC>: Clone"))]
+pub struct KeccakRootProvingKey {
+    /// VM Proving key for the root verifier. AIR proving key in `MultiStarkProvingKey` is ordered
+    /// by trace height.
+    pub vm_pk: Arc<VmProvingKey<KeccakRootSC, NativeConfig>>,
+    /// Committed executable for the root VM.
+    pub root_committed_exe: Arc<VmCommittedExe<KeccakRootSC>>,
+    /// The constant trace heights, ordered by AIR ID.
+    pub air_heights: Vec<usize>,
+}
+
+impl KeccakRootProvingKey {
+    pub fn keygen(root_verifier_pk: &RootVerifierProvingKey) -> Self {
+        let RootVerifierProvingKey {
+            vm_pk: root_vm_pk,
+            root_committed_exe: root_exe,
+            air_heights,
+        } = root_verifier_pk;
+        let engine = BabyBearKeccakEngine::new(root_vm_pk.fri_params);
+        let root_committed_exe = Arc::new(VmCommittedExe::<KeccakRootSC>::commit(
+            root_exe.exe.clone(),
+            engine.config().pcs(),
+        ));
+
+        let vm = VirtualMachine::new(engine, root_vm_pk.vm_config.clone());
+        let mut vm_pk = vm.keygen();
+        assert!(vm_pk.max_constraint_degree <= root_vm_pk.fri_params.max_constraint_degree());
+        root_verifier_pk
+            .air_id_permutation()
+            .permute(&mut vm_pk.per_air);
+
+        Self {
+            vm_pk: Arc::new(VmProvingKey {
+                fri_params: root_vm_pk.fri_params,
+                vm_config: root_vm_pk.vm_config.clone(),
+                vm_pk,
+            }),
+            root_committed_exe,
+            air_heights: air_heights.clone(),
+        }
+    }
+
     pub fn air_id_permutation(&self) -> AirIdPermutation {
         AirIdPermutation::compute(&self.air_heights)
     }
@@ -298,14 +528,60 @@ impl AggProvingKey {
         let AggConfig {
             agg_stark_config,
             halo2_config,
+            ..
         } = config;
         let (agg_stark_pk, dummy_internal_proof) =
             AggStarkProvingKey::dummy_proof_and_keygen(agg_stark_config);
         let dummy_root_proof = agg_stark_pk
             .root_verifier_pk
             .generate_dummy_root_proof(dummy_internal_proof);
+        let halo2_pk = Halo2ProvingKey::keygen(
+            halo2_config,
+            reader,
+            &agg_stark_pk.root_verifier_pk,
+            dummy_root_proof,
+        );
+        Self {
+            agg_stark_pk,
+            halo2_pk,
+        }
+    }
+}
+
+impl KeccakAggProvingKey {
+    /// Attention: this function is as expensive as [AggStarkProvingKey::keygen], but it does not
+    /// need KZG parameters.
+    #[tracing::instrument(level = "info", fields(group = "keccak_agg_keygen"), skip_all)]
+    pub fn keygen(config: AggConfig) -> Self {
+        let AggConfig {
+            agg_stark_config,
+            final_stage,
+            ..
+        } = config;
+        assert_eq!(
+            final_stage,
+            FinalStage::KeccakStark,
+            "Keccak root keygen requires the KeccakStark final stage"
+        );
+        let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config);
+        let keccak_root_pk = KeccakRootProvingKey::keygen(&agg_stark_pk.root_verifier_pk);
+        Self {
+            agg_stark_pk,
+            keccak_root_pk,
+        }
+    }
+}
+
+impl Halo2ProvingKey {
+    /// Keygen the static verifier of `root_verifier_pk` and its wrapper.
+    fn keygen(
+        halo2_config: Halo2Config,
+        reader: &impl Halo2ParamsReader,
+        root_verifier_pk: &RootVerifierProvingKey,
+        dummy_root_proof: Proof<RootSC>,
+    ) -> Self {
         // FIXME: Halo2VerifierProvingKey is not Send + Sync because Array/Usize use Rc<RefCell>.
-        let verifier = agg_stark_pk.root_verifier_pk.keygen_static_verifier(
+        let verifier = root_verifier_pk.keygen_static_verifier(
             &reader.read_params(halo2_config.verifier_k),
             dummy_root_proof,
         );
@@ -315,9 +591,256 @@ impl AggProvingKey {
         } else {
             Halo2WrapperProvingKey::keygen_auto_tune(reader, dummy_snark)
         };
-        let halo2_pk = Halo2ProvingKey { verifier, wrapper };
+        Self { verifier, wrapper }
+    }
+}
+
+impl BatchAggStarkProvingKey {
+    /// Keygen for a batch verifier which accepts executions proven with any of the leaf verifier
+    /// programs in `leaf_verifier_whitelist`. `app_pk` must be one of them and is only used to
+    /// generate dummy proofs.
+    pub fn keygen<VC: VmConfig<F>>(
+        config: &AggStarkConfig,
+        agg_stark_pk: &AggStarkProvingKey,
+        leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+        app_pk: &AppProvingKey<VC>,
+    ) -> Self
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        tracing::info_span!("batch_agg_stark_keygen", group = "batch_agg_stark_keygen").in_scope(
+            || {
+                Self::dummy_proof_and_keygen(config, agg_stark_pk, leaf_verifier_whitelist, app_pk)
+                    .0
+            },
+        )
+    }
+
+    /// Returns the proving key and a dummy proof of the batch verifier which aggregates a dummy
+    /// execution under `app_pk`.
+    pub fn dummy_proof_and_keygen<VC: VmConfig<F>>(
+        config: &AggStarkConfig,
+        agg_stark_pk: &AggStarkProvingKey,
+        leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+        app_pk: &AppProvingKey<VC>,
+    ) -> (Self, Proof<SC>)
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let mut config = config.clone();
+        config.apply_security_target();
+        let config = &config;
+        assert!(
+            leaf_verifier_whitelist.contains(&app_pk.commit_in_babybear()),
+            "The leaf verifier of the app proving key must be whitelisted"
+        );
+        let internal_vm_vk = agg_stark_pk.internal_vm_pk.vm_pk.get_vk();
+        let batch_program = BatchVmVerifierConfig {
+            internal_fri_params: config.internal_fri_params,
+            internal_vm_verifier_commit: agg_stark_pk.internal_program_commit(),
+            leaf_verifier_whitelist: leaf_verifier_whitelist.clone(),
+            compiler_options: config.compiler_options,
+        }
+        .build_program(&internal_vm_vk);
+        let internal_engine = BabyBearPoseidon2Engine::new(config.internal_fri_params);
+        let batch_committed_exe = Arc::new(VmCommittedExe::<SC>::commit(
+            batch_program.into(),
+            internal_engine.config.pcs(),
+        ));
+        // The dummy run must be proven with a whitelisted leaf verifier.
+        let (leaf_fri_params_index, leaf_vm_pk) = agg_stark_pk
+            .leaf_vm_pk_for(&app_pk.leaf_fri_params)
+            .expect("leaf FRI parameters of the app proving key must be allowed");
+        let internal_proof = dummy_internal_proof_for_app(
+            leaf_vm_pk,
+            leaf_fri_params_index,
+            agg_stark_pk.internal_vm_pk.clone(),
+            agg_stark_pk.internal_committed_exe.clone(),
+            app_pk,
+        );
+        // The batch verifier reads the same input as the internal verifier.
+        let batch_proof = dummy_internal_proof(
+            agg_stark_pk.internal_vm_pk.clone(),
+            batch_committed_exe.clone(),
+            internal_proof,
+            0,
+        );
+
+        let root_program = BatchRootVmVerifierConfig {
+            internal_fri_params: config.internal_fri_params,
+            num_public_values: config.max_num_user_public_values,
+            batch_vm_verifier_commit: batch_committed_exe.get_program_commit().into(),
+            leaf_verifier_whitelist_commit: leaf_verifier_whitelist_commit(
+                &leaf_verifier_whitelist,
+            ),
+            compiler_options: config.compiler_options,
+        }
+        .build_program(&internal_vm_vk);
+        let root_verifier_pk = RootVerifierProvingKey::keygen(
+            config,
+            root_program,
+            RootVmVerifierInput {
+                leaf_fri_params_index: 0,
+                proofs: vec![batch_proof.clone()],
+                public_values: vec![],
+            }
+            .write(),
+        );
+
+        (
+            Self {
+                batch_committed_exe,
+                leaf_verifier_whitelist,
+                root_verifier_pk,
+            },
+            batch_proof,
+        )
+    }
+
+    pub fn batch_program_commit(&self) -> [F; DIGEST_SIZE] {
+        self.batch_committed_exe.get_program_commit().into()
+    }
+}
+
+impl BatchAggProvingKey {
+    /// Attention: this function is as expensive as [AggProvingKey::keygen].
+    ///
+    /// See [BatchAggStarkProvingKey::keygen] for `leaf_verifier_whitelist` and `app_pk`.
+    #[tracing::instrument(level = "info", fields(group = "batch_agg_keygen"), skip_all)]
+    pub fn keygen<VC: VmConfig<F>>(
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+        leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+        app_pk: &AppProvingKey<VC>,
+    ) -> Self
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let AggConfig {
+            agg_stark_config,
+            halo2_config,
+            ..
+        } = config;
+        let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config.clone());
+        let (batch_stark_pk, dummy_batch_proof) = BatchAggStarkProvingKey::dummy_proof_and_keygen(
+            &agg_stark_config,
+            &agg_stark_pk,
+            leaf_verifier_whitelist,
+            app_pk,
+        );
+        let dummy_root_proof = SingleSegmentVmProver::prove(
+            &RootVerifierLocalProver::new(batch_stark_pk.root_verifier_pk.clone()),
+            RootVmVerifierInput {
+                leaf_fri_params_index: 0,
+                proofs: vec![dummy_batch_proof],
+                public_values: vec![],
+            }
+            .write(),
+        );
+        let halo2_pk = Halo2ProvingKey::keygen(
+            halo2_config,
+            reader,
+            &batch_stark_pk.root_verifier_pk,
+            dummy_root_proof,
+        );
+        Self {
+            agg_stark_pk,
+            batch_stark_pk,
+            halo2_pk,
+        }
+    }
+}
+
+impl DeferralAggStarkProvingKey {
+    pub fn keygen(
+        config: &AggStarkConfig,
+        agg_stark_pk: &AggStarkProvingKey,
+        deferred_claims: Vec<DeferredClaimLayout>,
+    ) -> Self {
+        tracing::info_span!(
+            "deferral_agg_stark_keygen",
+            group = "deferral_agg_stark_keygen"
+        )
+        .in_scope(|| Self::dummy_input_and_keygen(config, agg_stark_pk, deferred_claims).0)
+    }
+
+    /// Returns the proving key and a dummy input of the deferral root verifier.
+    pub fn dummy_input_and_keygen(
+        config: &AggStarkConfig,
+        agg_stark_pk: &AggStarkProvingKey,
+        deferred_claims: Vec<DeferredClaimLayout>,
+    ) -> (Self, DeferralRootVmVerifierInput<SC>) {
+        let mut config = config.clone();
+        config.apply_security_target();
+        let config = &config;
+        let leaf_vm_vks: Vec<_> = agg_stark_pk
+            .leaf_vm_pks()
+            .map(|pk| pk.vm_pk.get_vk())
+            .collect();
+        let internal_vm_vk = agg_stark_pk.internal_vm_pk.vm_pk.get_vk();
+        let root_program = DeferralRootVmVerifierConfig {
+            allowed_leaf_fri_params: agg_stark_pk.allowed_leaf_fri_params(),
+            internal_fri_params: config.internal_fri_params,
+            num_public_values: config.max_num_user_public_values,
+            internal_vm_verifier_commit: agg_stark_pk.internal_program_commit(),
+            deferred_claims: deferred_claims.clone(),
+            compiler_options: config.compiler_options,
+        }
+        .build_program(&leaf_vm_vks, &internal_vm_vk);
+        let dummy_input = dummy_deferral_root_input(
+            agg_stark_pk.leaf_vm_pk.clone(),
+            agg_stark_pk.internal_vm_pk.clone(),
+            agg_stark_pk.internal_committed_exe.clone(),
+            config.max_num_user_public_values,
+            &deferred_claims,
+        );
+        let root_verifier_pk =
+            RootVerifierProvingKey::keygen(config, root_program, dummy_input.write());
+        (
+            Self {
+                deferred_claims,
+                root_verifier_pk,
+            },
+            dummy_input,
+        )
+    }
+}
+
+impl DeferralAggProvingKey {
+    /// Attention: this function is as expensive as [AggProvingKey::keygen].
+    #[tracing::instrument(level = "info", fields(group = "deferral_agg_keygen"), skip_all)]
+    pub fn keygen(
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+        deferred_claims: Vec<DeferredClaimLayout>,
+    ) -> Self {
+        let AggConfig {
+            agg_stark_config,
+            halo2_config,
+            ..
+        } = config;
+        let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config.clone());
+        let (deferral_stark_pk, dummy_input) = DeferralAggStarkProvingKey::dummy_input_and_keygen(
+            &agg_stark_config,
+            &agg_stark_pk,
+            deferred_claims,
+        );
+        let dummy_root_proof = SingleSegmentVmProver::prove(
+            &RootVerifierLocalProver::new(deferral_stark_pk.root_verifier_pk.clone()),
+            dummy_input.write(),
+        );
+        let halo2_pk = Halo2ProvingKey::keygen(
+            halo2_config,
+            reader,
+            &deferral_stark_pk.root_verifier_pk,
+            dummy_root_proof,
+        );
         Self {
             agg_stark_pk,
+            deferral_stark_pk,
             halo2_pk,
         }
     }
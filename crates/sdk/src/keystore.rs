@@ -0,0 +1,238 @@
+//! Content-addressed cache of proving keys.
+//!
+//! Keys are stored under the digest of the config they were generated from, so a key can only be
+//! loaded for the exact config (and SDK version) it was generated for.
+
+use std::{
+    fs::{create_dir_all, read, rename, write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use eyre::Result;
+use openvm_circuit::arch::VmConfig;
+use openvm_native_circuit::NativeConfig;
+use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
+use openvm_stark_sdk::{config::FriParameters, openvm_stark_backend::Chip};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{
+    config::{AggConfig, AggStarkConfig, AppConfig},
+    keygen::{leaf_keygen, AggProvingKey, AggStarkProvingKey, AppProvingKey},
+    prover::vm::types::VmProvingKey,
+    F, SC,
+};
+
+const SDK_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A key as stored on disk. The digests are checked on load.
+#[derive(Serialize, Deserialize)]
+struct KeyStoreEntry {
+    config_digest: [u8; 32],
+    key_digest: [u8; 32],
+    key: Vec<u8>,
+}
+
+/// Stores each proving key under `<root>/<kind>/<config digest>.pk`, where the digest commits to
+/// the kind of key, the serialized config and the SDK version.
+#[derive(Clone, Debug)]
+pub struct KeyStore {
+    root: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn app_keygen<VC: VmConfig<F> + Serialize + DeserializeOwned>(
+        &self,
+        config: AppConfig<VC>,
+    ) -> Result<AppProvingKey<VC>>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        self.get_or_keygen("app", &config.clone(), || AppProvingKey::keygen(config))
+    }
+
+    pub fn agg_stark_keygen(&self, config: AggStarkConfig) -> Result<AggStarkProvingKey> {
+        self.get_or_keygen("agg_stark", &config, || AggStarkProvingKey::keygen(config))
+    }
+
+    /// The digest also commits to the KZG params read by `reader` for the configured `k`s, so keys
+    /// generated with another trusted setup are not reused. An auto-tuned wrapper is assumed to
+    /// read its params from the same setup as the verifier.
+    pub fn agg_keygen(
+        &self,
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+    ) -> Result<AggProvingKey> {
+        let params_digest = kzg_params_digest(&config, reader)?;
+        self.get_or_keygen("agg", &(config.clone(), params_digest), || {
+            AggProvingKey::keygen(config, reader)
+        })
+    }
+
+    pub fn leaf_keygen(
+        &self,
+        fri_params: FriParameters,
+    ) -> Result<Arc<VmProvingKey<SC, NativeConfig>>> {
+        self.get_or_keygen("leaf", &fri_params, || leaf_keygen(fri_params))
+    }
+
+    /// Loads the key of `kind` generated for `config`, or generates and stores it.
+    pub fn get_or_keygen<C: Serialize, K: Serialize + DeserializeOwned>(
+        &self,
+        kind: &str,
+        config: &C,
+        keygen: impl FnOnce() -> K,
+    ) -> Result<K> {
+        let config_digest = config_digest(kind, config)?;
+        let path = self.path(kind, &config_digest);
+        if path.exists() {
+            return load_entry(&path, &config_digest);
+        }
+        let key = keygen();
+        store_entry(&path, config_digest, &key)?;
+        Ok(key)
+    }
+
+    /// Path of the key of `kind` generated for `config`, whether it exists or not.
+    pub fn key_path<C: Serialize>(&self, kind: &str, config: &C) -> Result<PathBuf> {
+        Ok(self.path(kind, &config_digest(kind, config)?))
+    }
+
+    fn path(&self, kind: &str, config_digest: &[u8; 32]) -> PathBuf {
+        let name: String = config_digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.root.join(kind).join(format!("{}.pk", name))
+    }
+}
+
+fn config_digest<C: Serialize>(kind: &str, config: &C) -> Result<[u8; 32]> {
+    let config = bitcode::serialize(config)?;
+    let mut hasher = Keccak::v256();
+    for part in [SDK_VERSION.as_bytes(), kind.as_bytes(), &config] {
+        hasher.update(&(part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    Ok(digest)
+}
+
+/// Digest of the first G1 power and the G2 points of the params for each `k` used by `config`.
+fn kzg_params_digest(config: &AggConfig, reader: &impl Halo2ParamsReader) -> Result<[u8; 32]> {
+    let halo2_config = &config.halo2_config;
+    let mut parts = Vec::new();
+    for k in std::iter::once(halo2_config.verifier_k).chain(halo2_config.wrapper_k) {
+        let params = reader.read_params(k);
+        parts.push((k, params.get_g()[0], params.g2(), params.s_g2()));
+    }
+    Ok(keccak256(&bitcode::serialize(&parts)?))
+}
+
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut digest = [0u8; 32];
+    hasher.finalize(&mut digest);
+    digest
+}
+
+fn load_entry<K: DeserializeOwned>(path: &Path, config_digest: &[u8; 32]) -> Result<K> {
+    let entry: KeyStoreEntry = bitcode::deserialize(&read(path)?)
+        .map_err(|e| eyre::eyre!("Corrupted key store entry {}: {}", path.display(), e))?;
+    if &entry.config_digest != config_digest {
+        return Err(eyre::eyre!(
+            "Key store entry {} was generated for a different config",
+            path.display()
+        ));
+    }
+    if keccak256(&entry.key) != entry.key_digest {
+        return Err(eyre::eyre!(
+            "Corrupted key store entry {}: key digest mismatch",
+            path.display()
+        ));
+    }
+    let key = bitcode::deserialize(&entry.key)?;
+    Ok(key)
+}
+
+fn store_entry<K: Serialize>(path: &Path, config_digest: [u8; 32], key: &K) -> Result<()> {
+    let key = bitcode::serialize(key)?;
+    let entry = KeyStoreEntry {
+        config_digest,
+        key_digest: keccak256(&key),
+        key,
+    };
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)?;
+    }
+    // Write to a temporary file first so a concurrent reader never sees a partial entry.
+    let tmp_path = path.with_extension(format!("pk.tmp-{}", std::process::id()));
+    write(&tmp_path, bitcode::serialize(&entry)?)?;
+    rename(tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, fs};
+
+    use super::*;
+
+    #[test]
+    fn test_key_store_reuses_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KeyStore::new(dir.path());
+        let num_keygens = Cell::new(0);
+        let keygen = |value: u32| {
+            num_keygens.set(num_keygens.get() + 1);
+            vec![value; 4]
+        };
+
+        let key: Vec<u32> = store.get_or_keygen("test", &1u32, || keygen(1)).unwrap();
+        assert_eq!(key, vec![1; 4]);
+        let key: Vec<u32> = store.get_or_keygen("test", &1u32, || keygen(1)).unwrap();
+        assert_eq!(key, vec![1; 4]);
+        assert_eq!(num_keygens.get(), 1);
+
+        // A different config or kind gets its own key.
+        let key: Vec<u32> = store.get_or_keygen("test", &2u32, || keygen(2)).unwrap();
+        assert_eq!(key, vec![2; 4]);
+        let key: Vec<u32> = store.get_or_keygen("other", &1u32, || keygen(3)).unwrap();
+        assert_eq!(key, vec![3; 4]);
+        assert_eq!(num_keygens.get(), 3);
+    }
+
+    #[test]
+    fn test_key_store_integrity_checks() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = KeyStore::new(dir.path());
+        store
+            .get_or_keygen("test", &1u32, || vec![1u32; 4])
+            .unwrap();
+        let path = store.key_path("test", &1u32).unwrap();
+
+        // An entry copied under the digest of another config is rejected.
+        let other_path = store.key_path("test", &2u32).unwrap();
+        fs::copy(&path, &other_path).unwrap();
+        assert!(store
+            .get_or_keygen("test", &2u32, || vec![2u32; 4])
+            .is_err());
+
+        // A tampered key is rejected.
+        let mut entry: KeyStoreEntry = bitcode::deserialize(&fs::read(&path).unwrap()).unwrap();
+        entry.key = bitcode::serialize(&vec![5u32; 4]).unwrap();
+        fs::write(&path, bitcode::serialize(&entry).unwrap()).unwrap();
+        assert!(store
+            .get_or_keygen("test", &1u32, || vec![1u32; 4])
+            .is_err());
+    }
+}
@@ -1,8 +1,13 @@
 extern crate core;
 
-use std::{fs::read, panic::catch_unwind, path::Path, sync::Arc};
+use std::{
+    fs::read,
+    panic::catch_unwind,
+    path::Path,
+    sync::{mpsc::Sender, Arc},
+};
 
-use commit::commit_app_exe;
+use commit::{commit_app_exe, AppExecutionCommit};
 use config::AppConfig;
 use eyre::Result;
 use keygen::{AppProvingKey, AppVerifyingKey};
@@ -13,23 +18,25 @@ use openvm_circuit::{
     arch::{instructions::exe::VmExe, ExecutionError, VmConfig, VmExecutor},
     system::{memory::tree::public_values::extract_public_values, program::trace::VmCommittedExe},
 };
+use openvm_native_compiler::ir::DIGEST_SIZE;
 use openvm_native_recursion::{
     halo2::{
         utils::Halo2ParamsReader,
         wrapper::{EvmVerifier, Halo2WrapperProvingKey},
-        EvmProof,
+        EvmProof, NUM_ACCUMULATOR_INSTANCES,
     },
     types::InnerConfig,
 };
 use openvm_stark_backend::engine::StarkEngine;
 use openvm_stark_sdk::{
     config::{
+        baby_bear_keccak::BabyBearKeccakConfig,
         baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
         baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig,
         FriParameters,
     },
     engine::StarkFriEngine,
-    openvm_stark_backend::{verifier::VerificationError, Chip},
+    openvm_stark_backend::{prover::types::Proof, Chip},
     p3_baby_bear::BabyBear,
 };
 use openvm_transpiler::{
@@ -41,11 +48,17 @@ use openvm_transpiler::{
 use prover::vm::ContinuationVmProof;
 
 pub mod commit;
+pub mod compat;
 pub mod config;
+pub mod disasm;
+pub mod evm_wrapper;
+pub mod keccak_verifier;
 pub mod prover;
+pub mod public_values;
 pub mod static_verifier;
 
 pub mod keygen;
+pub mod keystore;
 pub mod verifier;
 
 mod stdin;
@@ -53,15 +66,33 @@ pub use stdin::*;
 pub mod fs;
 
 use crate::{
-    config::AggConfig,
-    keygen::AggProvingKey,
-    prover::{AppProver, ContinuationProver},
+    compat::{check_exe_compatible, ExeIncompatibility},
+    config::{AggConfig, FinalStage, SdkVmConfig},
+    evm_wrapper::EvmVerifierWrapper,
+    keccak_verifier::KeccakStarkEvmVerifier,
+    keygen::{
+        AggProvingKey, AggStarkProvingKey, BatchAggProvingKey, DeferralAggProvingKey,
+        KeccakAggProvingKey,
+    },
+    prover::{
+        vm::SingleSegmentVmProver, AppProver, AppRunProof, BatchAggStarkProver, ContinuationProver,
+        DeferralAggStarkProver, EvmProofJob, Halo2Prover, KeccakRootVerifierLocalProver,
+        ProofEvent, ProofJob, ProofObserver, StarkProver,
+    },
+    public_values::EvmProofExt,
+    static_verifier::StaticVerifierStats,
+    verifier::{
+        app::{verify_app_proof, VerifiedAppProof},
+        batch::types::BatchCommitTree,
+        deferral::types::DeferredClaimLayout,
+    },
 };
 
 pub(crate) type SC = BabyBearPoseidon2Config;
 pub(crate) type C = InnerConfig;
 pub(crate) type F = BabyBear;
 pub(crate) type RootSC = BabyBearPoseidon2RootConfig;
+pub(crate) type KeccakRootSC = BabyBearKeccakConfig;
 pub type NonRootCommittedExe = VmCommittedExe<SC>;
 
 pub struct Sdk;
@@ -99,6 +130,15 @@ impl Sdk {
         VmExe::from_elf(elf, transpiler)
     }
 
+    /// Lists the instructions of `exe` that `vm_config` cannot execute, without running it.
+    pub fn check_exe_compatible(
+        &self,
+        exe: &VmExe<F>,
+        vm_config: &SdkVmConfig,
+    ) -> Result<Vec<ExeIncompatibility>> {
+        check_exe_compatible(exe, vm_config)
+    }
+
     pub fn execute<VC: VmConfig<F>>(
         &self,
         exe: VmExe<F>,
@@ -152,17 +192,48 @@ impl Sdk {
         Ok(proof)
     }
 
+    /// Generates the app proof on a background thread, reporting its progress to `events`.
+    pub fn spawn_app_proof<VC: VmConfig<F> + Send + Sync + 'static>(
+        &self,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_committed_exe: Arc<NonRootCommittedExe>,
+        inputs: StdIn,
+        events: Sender<ProofEvent>,
+    ) -> ProofJob<ContinuationVmProof<SC>>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        ProofJob::spawn(ProofObserver::new(events), move |observer| {
+            AppProver::new(app_pk.app_vm_pk.clone(), app_committed_exe)
+                .with_observer(observer.clone())
+                .try_generate_app_proof(inputs)
+        })
+    }
+
+    /// Verifies the app proof and returns the exe commit and the user public values it proves.
     pub fn verify_app_proof(
         &self,
         app_vk: &AppVerifyingKey,
         proof: &ContinuationVmProof<SC>,
-    ) -> Result<(), VerificationError> {
-        let e = BabyBearPoseidon2Engine::new(app_vk.fri_params);
-        for seg_proof in &proof.per_segment {
-            e.verify(&app_vk.app_vm_vk, seg_proof)?
+    ) -> Result<VerifiedAppProof> {
+        verify_app_proof(app_vk, proof)
+    }
+
+    /// Verifies the app proof and checks that it proves an execution of the exe with
+    /// `exe_commit`, which is `AppExecutionCommit::exe_commit` or the output of
+    /// [compute_app_exe_commit](commit::compute_app_exe_commit). Returns the user public values.
+    pub fn verify_app_proof_for_exe(
+        &self,
+        app_vk: &AppVerifyingKey,
+        proof: &ContinuationVmProof<SC>,
+        exe_commit: &[F; DIGEST_SIZE],
+    ) -> Result<Vec<F>> {
+        let verified = verify_app_proof(app_vk, proof)?;
+        if &verified.exe_commit != exe_commit {
+            return Err(eyre::eyre!("App proof is not for the expected exe"));
         }
-        // TODO: verify continuation.
-        Ok(())
+        Ok(verified.user_public_values)
     }
 
     pub fn agg_keygen(
@@ -170,10 +241,82 @@ impl Sdk {
         config: AggConfig,
         reader: &impl Halo2ParamsReader,
     ) -> Result<AggProvingKey> {
+        if config.final_stage != FinalStage::Halo2 {
+            return Err(eyre::eyre!(
+                "agg_keygen requires the halo2 final stage, use keccak_agg_keygen instead"
+            ));
+        }
         let agg_pk = AggProvingKey::keygen(config, reader);
         Ok(agg_pk)
     }
 
+    /// Keygen for the [FinalStage::KeccakStark] final stage. No halo2 params are needed.
+    pub fn keccak_agg_keygen(&self, config: AggConfig) -> Result<KeccakAggProvingKey> {
+        if config.final_stage != FinalStage::KeccakStark {
+            return Err(eyre::eyre!(
+                "keccak_agg_keygen requires the keccak-stark final stage"
+            ));
+        }
+        Ok(KeccakAggProvingKey::keygen(config))
+    }
+
+    /// Generates a root verifier proof with the Keccak STARK config, to be verified by the
+    /// contract from [Self::generate_keccak_stark_verifier_contract].
+    pub fn generate_keccak_root_proof<VC: VmConfig<F>>(
+        &self,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_exe: Arc<NonRootCommittedExe>,
+        agg_pk: KeccakAggProvingKey,
+        inputs: StdIn,
+    ) -> Result<Proof<KeccakRootSC>>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let KeccakAggProvingKey {
+            agg_stark_pk,
+            keccak_root_pk,
+        } = agg_pk;
+        let stark_prover = StarkProver::new(app_pk, app_exe, agg_stark_pk);
+        let root_input = stark_prover.try_generate_root_input(inputs)?;
+        let root_prover = KeccakRootVerifierLocalProver::new(keccak_root_pk);
+        Ok(SingleSegmentVmProver::prove(
+            &root_prover,
+            root_input.write(),
+        ))
+    }
+
+    pub fn generate_keccak_stark_verifier_contract(
+        &self,
+        agg_pk: &KeccakAggProvingKey,
+    ) -> Result<KeccakStarkEvmVerifier> {
+        Ok(agg_pk.keccak_root_pk.generate_evm_verifier())
+    }
+
+    /// Statistics of the static verifier circuit for `config` and the `k` auto-selected for its
+    /// wrapper. This only runs the STARK keygen of the aggregation VMs, so it's much cheaper than
+    /// [Self::agg_keygen].
+    pub fn static_verifier_stats(
+        &self,
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+    ) -> Result<StaticVerifierStats> {
+        let AggConfig {
+            agg_stark_config,
+            halo2_config,
+            ..
+        } = config;
+        let (agg_stark_pk, dummy_internal_proof) =
+            AggStarkProvingKey::dummy_proof_and_keygen(agg_stark_config);
+        let root_verifier_pk = agg_stark_pk.root_verifier_pk;
+        let dummy_root_proof = root_verifier_pk.generate_dummy_root_proof(dummy_internal_proof);
+        Ok(root_verifier_pk.static_verifier_stats(
+            reader,
+            halo2_config.verifier_k,
+            dummy_root_proof,
+        ))
+    }
+
     pub fn generate_evm_proof<VC: VmConfig<F>>(
         &self,
         reader: &impl Halo2ParamsReader,
@@ -191,6 +334,57 @@ impl Sdk {
         Ok(proof)
     }
 
+    /// Generates the root verifier proof, i.e. the STARK part of an EVM proof, on a background
+    /// thread, reporting its progress to `events`.
+    pub fn spawn_proof<VC: VmConfig<F> + Send + Sync + 'static>(
+        &self,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_exe: Arc<NonRootCommittedExe>,
+        agg_stark_pk: AggStarkProvingKey,
+        inputs: StdIn,
+        events: Sender<ProofEvent>,
+    ) -> ProofJob<Proof<RootSC>>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        ProofJob::spawn(ProofObserver::new(events), move |observer| {
+            let mut stark_prover = StarkProver::new(app_pk, app_exe, agg_stark_pk);
+            stark_prover.set_observer(observer.clone());
+            stark_prover.try_generate_proof_for_outer_recursion(inputs)
+        })
+    }
+
+    /// Same as [Self::generate_evm_proof], but generates the STARK part on a background thread
+    /// and reports the progress to `events`. The halo2 part runs in [EvmProofJob::join], so the
+    /// channel disconnects once the job is joined.
+    pub fn spawn_evm_proof<VC: VmConfig<F> + Send + Sync + 'static>(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        app_pk: Arc<AppProvingKey<VC>>,
+        app_exe: Arc<NonRootCommittedExe>,
+        agg_pk: AggProvingKey,
+        inputs: StdIn,
+        events: Sender<ProofEvent>,
+    ) -> EvmProofJob
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let AggProvingKey {
+            agg_stark_pk,
+            halo2_pk,
+        } = agg_pk;
+        let observer = ProofObserver::new(events);
+        let halo2_prover = Halo2Prover::new(reader, halo2_pk).with_observer(observer.clone());
+        let stark_job = ProofJob::spawn(observer, move |observer| {
+            let mut stark_prover = StarkProver::new(app_pk, app_exe, agg_stark_pk);
+            stark_prover.set_observer(observer.clone());
+            stark_prover.try_generate_proof_for_outer_recursion(inputs)
+        });
+        EvmProofJob::new(stark_job, halo2_prover)
+    }
+
     pub fn generate_snark_verifier_contract(
         &self,
         reader: &impl Halo2ParamsReader,
@@ -201,6 +395,167 @@ impl Sdk {
         Ok(evm_verifier)
     }
 
+    /// Generates the Solidity wrapper of the contract from [Self::generate_snark_verifier_contract],
+    /// which takes the commitments and user public values of a proof as typed arguments.
+    pub fn generate_snark_verifier_wrapper_contract(
+        &self,
+        agg_pk: &AggProvingKey,
+    ) -> Result<EvmVerifierWrapper> {
+        // The static verifier exposes the exe commit and the leaf verifier commit, followed by the
+        // user public values.
+        let num_pvs = agg_pk.halo2_pk.wrapper.pinning.metadata.num_pvs[0];
+        let num_user_public_values = num_pvs
+            .checked_sub(NUM_ACCUMULATOR_INSTANCES + 2)
+            .ok_or_else(|| eyre::eyre!("Halo2 wrapper has too few public values"))?;
+        Ok(EvmVerifierWrapper::new(num_user_public_values))
+    }
+
+    /// Keygen for batch aggregation of executions proven with any of the app proving keys whose
+    /// leaf verifier commits are in `leaf_verifier_whitelist`, see
+    /// [AppProvingKey::commit_in_babybear]. `app_pk` must be one of them.
+    pub fn batch_agg_keygen<VC: VmConfig<F>>(
+        &self,
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+        leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+        app_pk: &AppProvingKey<VC>,
+    ) -> Result<BatchAggProvingKey>
+    where
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        if !leaf_verifier_whitelist.contains(&app_pk.commit_in_babybear()) {
+            return Err(eyre::eyre!(
+                "The leaf verifier of the app proving key is not whitelisted"
+            ));
+        }
+        let batch_agg_pk =
+            BatchAggProvingKey::keygen(config, reader, leaf_verifier_whitelist, app_pk);
+        Ok(batch_agg_pk)
+    }
+
+    /// Aggregates the app proofs of many executions into a single EVM proof. Each app proof is
+    /// given with the leaf verifier program and leaf FRI parameters of the app proving key it was
+    /// generated with, see [AppRunProof::new]. The leaf verifier program must be whitelisted in
+    /// `batch_agg_pk` and the leaf FRI parameters allowed by its aggregation key. The executions
+    /// may be of different exes and app VM configs.
+    ///
+    /// The returned tree commits to the exe commit, public values and leaf verifier commit of
+    /// each execution, and its root is the public input of the EVM proof in place of the exe
+    /// commit. See [BatchCommitTree::inclusion_proof].
+    pub fn generate_batch_evm_proof(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        app_proofs: Vec<AppRunProof>,
+        batch_agg_pk: BatchAggProvingKey,
+    ) -> Result<(EvmProof, BatchCommitTree<F>)> {
+        let BatchAggProvingKey {
+            agg_stark_pk,
+            batch_stark_pk,
+            halo2_pk,
+        } = batch_agg_pk;
+        if app_proofs.is_empty() {
+            return Err(eyre::eyre!("Batch must contain at least 1 app proof"));
+        }
+        for run in &app_proofs {
+            check_leaf_fri_params(&agg_stark_pk, run)?;
+            let leaf_verifier_commit: [F; DIGEST_SIZE] =
+                run.leaf_committed_exe.get_program_commit().into();
+            if !batch_stark_pk
+                .leaf_verifier_whitelist
+                .contains(&leaf_verifier_commit)
+            {
+                return Err(eyre::eyre!(
+                    "Leaf verifier {:?} is not whitelisted",
+                    leaf_verifier_commit
+                ));
+            }
+        }
+        let batch_prover = BatchAggStarkProver::new(agg_stark_pk, batch_stark_pk);
+        let (root_proof, commit_tree) = batch_prover.generate_batch_agg_proof(app_proofs);
+        let halo2_prover = Halo2Prover::new(reader, halo2_pk);
+        let proof = halo2_prover.prove_for_evm(&root_proof);
+        Ok((proof, commit_tree))
+    }
+
+    pub fn generate_batch_snark_verifier_contract(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        batch_agg_pk: &BatchAggProvingKey,
+    ) -> Result<EvmVerifier> {
+        let wrapper = &batch_agg_pk.halo2_pk.wrapper;
+        let params = reader.read_params(wrapper.pinning.metadata.config_params.k);
+        let evm_verifier = wrapper.generate_evm_verifier(&params);
+        Ok(evm_verifier)
+    }
+
+    /// Keygen for aggregating an app execution together with the executions it verifies with
+    /// [verify_stark](openvm::deferral::verify_stark) at the given claim layouts.
+    pub fn deferral_agg_keygen(
+        &self,
+        config: AggConfig,
+        reader: &impl Halo2ParamsReader,
+        deferred_claims: Vec<DeferredClaimLayout>,
+    ) -> Result<DeferralAggProvingKey> {
+        let num_public_values = config.agg_stark_config.max_num_user_public_values;
+        for layout in &deferred_claims {
+            if !layout.is_valid(num_public_values) {
+                return Err(eyre::eyre!(
+                    "Invalid deferred claim layout {:?} for {} public values",
+                    layout,
+                    num_public_values
+                ));
+            }
+        }
+        let deferral_agg_pk = DeferralAggProvingKey::keygen(config, reader, deferred_claims);
+        Ok(deferral_agg_pk)
+    }
+
+    /// Aggregates the app proof of an execution and the app proofs of the executions it verified
+    /// with [verify_stark](openvm::deferral::verify_stark), in the order of the claim layouts of
+    /// `deferral_agg_pk`. Each app proof is given with the leaf verifier program and leaf FRI
+    /// parameters of its app proving key, see [AppRunProof::new]. The EVM proof has the same
+    /// public values as the one of the app proof alone.
+    pub fn generate_deferral_evm_proof(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        app_proof: AppRunProof,
+        deferred_proofs: Vec<AppRunProof>,
+        deferral_agg_pk: DeferralAggProvingKey,
+    ) -> Result<EvmProof> {
+        let DeferralAggProvingKey {
+            agg_stark_pk,
+            deferral_stark_pk,
+            halo2_pk,
+        } = deferral_agg_pk;
+        if deferred_proofs.len() != deferral_stark_pk.deferred_claims.len() {
+            return Err(eyre::eyre!(
+                "Expected {} deferred proofs, got {}",
+                deferral_stark_pk.deferred_claims.len(),
+                deferred_proofs.len()
+            ));
+        }
+        for run in std::iter::once(&app_proof).chain(&deferred_proofs) {
+            check_leaf_fri_params(&agg_stark_pk, run)?;
+        }
+        let deferral_prover = DeferralAggStarkProver::new(agg_stark_pk, deferral_stark_pk);
+        let root_proof = deferral_prover.generate_deferral_agg_proof(app_proof, deferred_proofs);
+        let halo2_prover = Halo2Prover::new(reader, halo2_pk);
+        let proof = halo2_prover.prove_for_evm(&root_proof);
+        Ok(proof)
+    }
+
+    pub fn generate_deferral_snark_verifier_contract(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        deferral_agg_pk: &DeferralAggProvingKey,
+    ) -> Result<EvmVerifier> {
+        let wrapper = &deferral_agg_pk.halo2_pk.wrapper;
+        let params = reader.read_params(wrapper.pinning.metadata.config_params.k);
+        let evm_verifier = wrapper.generate_evm_verifier(&params);
+        Ok(evm_verifier)
+    }
+
     pub fn verify_evm_proof(&self, evm_verifier: &EvmVerifier, evm_proof: &EvmProof) -> bool {
         // FIXME: we should return the concrete error.
         catch_unwind(|| {
@@ -208,4 +563,51 @@ impl Sdk {
         })
         .is_ok()
     }
+
+    /// Verifies the EVM proof and checks that it proves an execution of the exe committed by
+    /// `app_commit` with the given user public values. `expected_public_values` may be shorter
+    /// than the public values of the proof, in which case the remaining ones must be zero.
+    pub fn verify_evm_proof_for(
+        &self,
+        evm_verifier: &EvmVerifier,
+        evm_proof: &EvmProof,
+        app_commit: &AppExecutionCommit<F>,
+        expected_public_values: &[u8],
+    ) -> Result<()> {
+        let (proof_commit, public_values) = evm_proof.decode()?;
+        if proof_commit.exe_commit != app_commit.exe_commit {
+            return Err(eyre::eyre!("EVM proof is not for the expected exe"));
+        }
+        if proof_commit.leaf_vm_verifier_commit != app_commit.leaf_vm_verifier_commit {
+            return Err(eyre::eyre!(
+                "EVM proof is not for the expected app VM config"
+            ));
+        }
+        if expected_public_values.len() > public_values.len() {
+            return Err(eyre::eyre!(
+                "Expected {} public values, but the EVM proof only has {}",
+                expected_public_values.len(),
+                public_values.len()
+            ));
+        }
+        let (prefix, rest) = public_values.split_at(expected_public_values.len());
+        if prefix != expected_public_values || rest.iter().any(|&x| x != 0) {
+            return Err(eyre::eyre!("EVM proof public values mismatch"));
+        }
+        if !self.verify_evm_proof(evm_verifier, evm_proof) {
+            return Err(eyre::eyre!("EVM proof verification failed"));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the leaf verifier of `run` can be aggregated with `agg_stark_pk`.
+fn check_leaf_fri_params(agg_stark_pk: &AggStarkProvingKey, run: &AppRunProof) -> Result<()> {
+    if agg_stark_pk.leaf_vm_pk_for(&run.leaf_fri_params).is_none() {
+        return Err(eyre::eyre!(
+            "Leaf FRI parameters {:?} are not allowed by the aggregation key",
+            run.leaf_fri_params
+        ));
+    }
+    Ok(())
 }
@@ -6,7 +6,8 @@ use openvm_circuit::arch::Streams;
 use openvm_native_circuit::NativeConfig;
 use openvm_native_recursion::hints::Hintable;
 use openvm_stark_sdk::{
-    config::baby_bear_poseidon2::BabyBearPoseidon2Engine, engine::StarkFriEngine,
+    config::{baby_bear_poseidon2::BabyBearPoseidon2Engine, FriParameters},
+    engine::StarkFriEngine,
     openvm_stark_backend::prover::types::Proof,
 };
 use tracing::info_span;
@@ -16,6 +17,7 @@ use super::vm::types::VmProvingKey;
 use crate::{
     keygen::AggStarkProvingKey,
     prover::{
+        job::{ProofCancelled, ProofEvent, ProofObserver},
         vm::{local::VmLocalProver, ContinuationVmProof, SingleSegmentVmProver},
         RootVerifierLocalProver,
     },
@@ -32,6 +34,8 @@ const DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS: usize = 4;
 
 pub struct AggStarkProver {
     leaf_prover: LeafProver,
+    /// Index of the FRI parameters of the leaf prover in the allowed leaf FRI parameters.
+    leaf_fri_params_index: usize,
     internal_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
     root_prover: RootVerifierLocalProver,
 
@@ -39,19 +43,27 @@ pub struct AggStarkProver {
     pub max_internal_wrapper_layers: usize,
 
     pub profile: bool,
+    observer: ProofObserver,
 }
 pub struct LeafProver {
     prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
     pub num_children_leaf: usize,
     pub profile: bool,
+    observer: ProofObserver,
 }
 
 impl AggStarkProver {
+    /// `leaf_committed_exe` is the leaf verifier program of an app, committed with its
+    /// `leaf_fri_params`.
     pub fn new(
         agg_stark_pk: AggStarkProvingKey,
         leaf_committed_exe: Arc<NonRootCommittedExe>,
+        leaf_fri_params: FriParameters,
     ) -> Self {
-        let leaf_prover = LeafProver::new(agg_stark_pk.leaf_vm_pk, leaf_committed_exe);
+        let (leaf_fri_params_index, leaf_vm_pk) = agg_stark_pk
+            .leaf_vm_pk_for(&leaf_fri_params)
+            .expect("App VM is incompatible with Agg VM because of leaf FRI parameters");
+        let leaf_prover = LeafProver::new(leaf_vm_pk, leaf_committed_exe);
         let internal_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
             agg_stark_pk.internal_vm_pk,
             agg_stark_pk.internal_committed_exe,
@@ -59,11 +71,13 @@ impl AggStarkProver {
         let root_prover = RootVerifierLocalProver::new(agg_stark_pk.root_verifier_pk);
         Self {
             leaf_prover,
+            leaf_fri_params_index,
             internal_prover,
             root_prover,
             num_children_internal: DEFAULT_NUM_CHILDREN_INTERNAL,
             max_internal_wrapper_layers: DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS,
             profile: false,
+            observer: ProofObserver::default(),
         }
     }
 
@@ -92,12 +106,47 @@ impl AggStarkProver {
         self
     }
 
+    pub fn set_observer(&mut self, observer: ProofObserver) -> &mut Self {
+        self.leaf_prover.observer = observer.clone();
+        self.observer = observer;
+        self
+    }
+    pub fn with_observer(mut self, observer: ProofObserver) -> Self {
+        self.set_observer(observer);
+        self
+    }
+
     /// Generate a proof to aggregate app proofs.
     pub fn generate_agg_proof(&self, app_proofs: ContinuationVmProof<SC>) -> Proof<RootSC> {
-        let leaf_proofs = self.leaf_prover.generate_proof(&app_proofs);
+        self.try_generate_agg_proof(app_proofs)
+            .expect("aggregation proof was cancelled")
+    }
+
+    /// Same as [Self::generate_agg_proof], but stops early if the observer is cancelled.
+    pub fn try_generate_agg_proof(
+        &self,
+        app_proofs: ContinuationVmProof<SC>,
+    ) -> Result<Proof<RootSC>, ProofCancelled> {
+        let root_input = self.try_generate_root_input(app_proofs)?;
+        let root_proof = self.generate_root_proof_impl(root_input);
+        self.observer.emit(ProofEvent::RootLayerDone);
+        Ok(root_proof)
+    }
+
+    /// Aggregates app proofs up to the input of the root verifier. The root proof can then be
+    /// generated with another STARK config, e.g. by [KeccakRootVerifierLocalProver].
+    ///
+    /// [KeccakRootVerifierLocalProver]: crate::prover::KeccakRootVerifierLocalProver
+    pub fn try_generate_root_input(
+        &self,
+        app_proofs: ContinuationVmProof<SC>,
+    ) -> Result<RootVmVerifierInput<SC>, ProofCancelled> {
+        let leaf_proofs = self.leaf_prover.try_generate_proof(&app_proofs)?;
         let public_values = app_proofs.user_public_values.public_values;
-        let internal_proof = self.generate_internal_proof_impl(leaf_proofs, &public_values);
-        self.generate_root_proof_impl(RootVmVerifierInput {
+        let internal_proof = self.generate_internal_proof_impl(leaf_proofs, &public_values)?;
+        self.observer.check_cancelled()?;
+        Ok(RootVmVerifierInput {
+            leaf_fri_params_index: self.leaf_fri_params_index,
             proofs: vec![internal_proof],
             public_values,
         })
@@ -107,7 +156,7 @@ impl AggStarkProver {
         &self,
         leaf_proofs: Vec<Proof<SC>>,
         public_values: &[F],
-    ) -> Proof<SC> {
+    ) -> Result<Proof<SC>, ProofCancelled> {
         let mut internal_node_idx = -1;
         let mut internal_node_height = 0;
         let mut proofs = leaf_proofs;
@@ -119,6 +168,7 @@ impl AggStarkProver {
                 let actual_air_heights =
                     self.root_prover
                         .execute_for_air_heights(RootVmVerifierInput {
+                            leaf_fri_params_index: self.leaf_fri_params_index,
                             proofs: vec![proofs[0].clone()],
                             public_values: public_values.to_vec(),
                         });
@@ -134,11 +184,13 @@ impl AggStarkProver {
                 }
                 wrapper_layers += 1;
             }
+            self.observer.check_cancelled()?;
             let internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
                 self.internal_prover
                     .committed_exe
                     .get_program_commit()
                     .into(),
+                self.leaf_fri_params_index,
                 &proofs,
                 self.num_children_internal,
             );
@@ -161,9 +213,13 @@ impl AggStarkProver {
                     })
                     .collect()
             });
+            self.observer.emit(ProofEvent::InternalLayerDone {
+                height: internal_node_height,
+                num_proofs: proofs.len(),
+            });
             internal_node_height += 1;
         }
-        proofs.pop().unwrap()
+        Ok(proofs.pop().unwrap())
     }
 
     fn generate_root_proof_impl(&self, root_input: RootVmVerifierInput<SC>) -> Proof<RootSC> {
@@ -208,6 +264,7 @@ impl LeafProver {
             prover,
             num_children_leaf: DEFAULT_NUM_CHILDREN_LEAF,
             profile: false,
+            observer: ProofObserver::default(),
         }
     }
     pub fn with_num_children_leaf(mut self, num_children_leaf: usize) -> Self {
@@ -218,8 +275,21 @@ impl LeafProver {
         self.profile = true;
         self
     }
+    pub fn with_observer(mut self, observer: ProofObserver) -> Self {
+        self.observer = observer;
+        self
+    }
     pub fn generate_proof(&self, app_proofs: &ContinuationVmProof<SC>) -> Vec<Proof<SC>> {
-        info_span!("leaf verifier", group = "leaf").in_scope(|| {
+        self.try_generate_proof(app_proofs)
+            .expect("leaf proof was cancelled")
+    }
+    /// Same as [Self::generate_proof], but stops early if the observer is cancelled.
+    pub fn try_generate_proof(
+        &self,
+        app_proofs: &ContinuationVmProof<SC>,
+    ) -> Result<Vec<Proof<SC>>, ProofCancelled> {
+        self.observer.check_cancelled()?;
+        let leaf_proofs = info_span!("leaf verifier", group = "leaf").in_scope(|| {
             #[cfg(feature = "bench-metrics")]
             metrics::counter!("fri.log_blowup")
                 .absolute(self.prover.pk.fri_params.log_blowup as u64);
@@ -236,12 +306,16 @@ impl LeafProver {
                     })
                 })
                 .collect::<Vec<_>>()
-        })
+        });
+        self.observer.emit(ProofEvent::LeafLayerDone {
+            num_proofs: leaf_proofs.len(),
+        });
+        Ok(leaf_proofs)
     }
 }
 
 #[allow(unused)]
-fn single_segment_prove<E: StarkFriEngine<SC>>(
+pub(super) fn single_segment_prove<E: StarkFriEngine<SC>>(
     prover: &VmLocalProver<SC, NativeConfig, E>,
     input: impl Into<Streams<F>> + Clone,
     profile: bool,
@@ -257,7 +331,7 @@ fn single_segment_prove<E: StarkFriEngine<SC>>(
     SingleSegmentVmProver::prove(prover, input)
 }
 
-fn heights_le(a: &[usize], b: &[usize]) -> bool {
+pub(super) fn heights_le(a: &[usize], b: &[usize]) -> bool {
     assert_eq!(a.len(), b.len());
     a.iter().zip(b.iter()).all(|(a, b)| a <= b)
 }
@@ -1,42 +1,74 @@
 use std::sync::Arc;
 
-use openvm_circuit::arch::VmConfig;
 #[cfg(feature = "bench-metrics")]
 use openvm_circuit::arch::{instructions::exe::VmExe, VmExecutor};
-use openvm_stark_backend::Chip;
-use openvm_stark_sdk::config::baby_bear_poseidon2::BabyBearPoseidon2Engine;
+use openvm_circuit::{
+    arch::{Streams, VmConfig},
+    system::program::trace::VmCommittedExe,
+};
+use openvm_stark_backend::{
+    config::{StarkGenericConfig, Val},
+    p3_field::PrimeField32,
+    Chip,
+};
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+    engine::StarkFriEngine,
+};
 use tracing::info_span;
 
 use crate::{
-    prover::vm::{
-        local::VmLocalProver, types::VmProvingKey, ContinuationVmProof, ContinuationVmProver,
+    prover::{
+        job::{ProofCancelled, ProofObserver},
+        vm::{local::VmLocalProver, types::VmProvingKey, ContinuationVmProof},
     },
-    NonRootCommittedExe, StdIn, F, SC,
+    NonRootCommittedExe, F,
 };
 
-pub struct AppProver<VC> {
+/// Prover of app proofs in the STARK config `SC`, proven with the engine `E`. The VM hashes its
+/// memory with BabyBear Poseidon2, so the field of `SC` must be BabyBear, and only proofs in the
+/// default config can be aggregated by the leaf verifier.
+pub struct AppProver<
+    VC,
+    SC: StarkGenericConfig = BabyBearPoseidon2Config,
+    E: StarkFriEngine<SC> = BabyBearPoseidon2Engine,
+> {
     /// If true, will run execution once with full metric collection for
     /// flamegraphs (WARNING: this degrades performance).
     pub profile: bool,
     pub program_name: Option<String>,
-    app_prover: VmLocalProver<SC, VC, BabyBearPoseidon2Engine>,
+    app_prover: VmLocalProver<SC, VC, E>,
+    observer: ProofObserver,
 }
 
 impl<VC> AppProver<VC> {
     pub fn new(
-        app_vm_pk: Arc<VmProvingKey<SC, VC>>,
+        app_vm_pk: Arc<VmProvingKey<BabyBearPoseidon2Config, VC>>,
         app_committed_exe: Arc<NonRootCommittedExe>,
     ) -> Self
     where
         VC: VmConfig<F>,
+    {
+        Self::new_with_engine(app_vm_pk, app_committed_exe)
+    }
+}
+
+impl<VC, SC: StarkGenericConfig, E: StarkFriEngine<SC>> AppProver<VC, SC, E> {
+    /// Same as [AppProver::new], for an app VM in the STARK config `SC` proven with the engine
+    /// `E`.
+    pub fn new_with_engine(
+        app_vm_pk: Arc<VmProvingKey<SC, VC>>,
+        app_committed_exe: Arc<VmCommittedExe<SC>>,
+    ) -> Self
+    where
+        Val<SC>: PrimeField32,
+        VC: VmConfig<Val<SC>>,
     {
         Self {
             profile: false,
             program_name: None,
-            app_prover: VmLocalProver::<SC, VC, BabyBearPoseidon2Engine>::new(
-                app_vm_pk,
-                app_committed_exe,
-            ),
+            app_prover: VmLocalProver::<SC, VC, E>::new(app_vm_pk, app_committed_exe),
+            observer: ProofObserver::default(),
         }
     }
     pub fn set_profile(&mut self, profile: bool) -> &mut Self {
@@ -55,13 +87,38 @@ impl<VC> AppProver<VC> {
         self.set_program_name(program_name);
         self
     }
+    pub fn set_observer(&mut self, observer: ProofObserver) -> &mut Self {
+        self.observer = observer;
+        self
+    }
+    pub fn with_observer(mut self, observer: ProofObserver) -> Self {
+        self.set_observer(observer);
+        self
+    }
 
-    pub fn generate_app_proof(&self, input: StdIn) -> ContinuationVmProof<SC>
+    pub fn generate_app_proof(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC>
     where
-        VC: VmConfig<F>,
+        Val<SC>: PrimeField32,
+        VC: VmConfig<Val<SC>>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        self.try_generate_app_proof(input)
+            .expect("app proof was cancelled")
+    }
+
+    /// Same as [Self::generate_app_proof], but stops early if the observer is cancelled.
+    pub fn try_generate_app_proof(
+        &self,
+        input: impl Into<Streams<Val<SC>>>,
+    ) -> Result<ContinuationVmProof<SC>, ProofCancelled>
+    where
+        Val<SC>: PrimeField32,
+        VC: VmConfig<Val<SC>>,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
+        let input = input.into();
         info_span!(
             "app proof",
             group = self
@@ -72,7 +129,7 @@ impl<VC> AppProver<VC> {
         .in_scope(|| {
             #[cfg(feature = "bench-metrics")]
             if self.profile {
-                emit_app_execution_metrics(
+                emit_app_execution_metrics::<SC, VC>(
                     self.app_prover.pk.vm_config.clone(),
                     self.app_prover.committed_exe.exe.clone(),
                     input.clone(),
@@ -81,14 +138,18 @@ impl<VC> AppProver<VC> {
             #[cfg(feature = "bench-metrics")]
             metrics::counter!("fri.log_blowup")
                 .absolute(self.app_prover.pk.fri_params.log_blowup as u64);
-            ContinuationVmProver::prove(&self.app_prover, input)
+            self.app_prover.prove_with_observer(input, &self.observer)
         })
     }
 }
 
 #[cfg(feature = "bench-metrics")]
-fn emit_app_execution_metrics<VC: VmConfig<F>>(mut vm_config: VC, exe: VmExe<F>, input: StdIn)
-where
+fn emit_app_execution_metrics<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>>(
+    mut vm_config: VC,
+    exe: VmExe<Val<SC>>,
+    input: Streams<Val<SC>>,
+) where
+    Val<SC>: PrimeField32,
     VC::Executor: Chip<SC>,
     VC::Periphery: Chip<SC>,
 {
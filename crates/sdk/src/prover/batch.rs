@@ -0,0 +1,263 @@
+use openvm_native_circuit::NativeConfig;
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    openvm_stark_backend::prover::types::Proof,
+};
+use tracing::info_span;
+
+use crate::{
+    keygen::{AggStarkProvingKey, BatchAggStarkProvingKey},
+    prover::{
+        agg::{heights_le, single_segment_prove},
+        vm::{local::VmLocalProver, SingleSegmentVmProver},
+        AppRunProof, LeafProver, RootVerifierLocalProver,
+    },
+    verifier::{
+        batch::types::{BatchCommitTree, BatchRunCommit},
+        internal::types::InternalVmVerifierInput,
+        root::types::RootVmVerifierInput,
+    },
+    RootSC, F, SC,
+};
+
+const DEFAULT_NUM_CHILDREN_LEAF: usize = 2;
+const DEFAULT_NUM_CHILDREN_INTERNAL: usize = 2;
+const DEFAULT_NUM_CHILDREN_BATCH: usize = 2;
+const DEFAULT_MAX_BATCH_WRAPPER_LAYERS: usize = 4;
+
+/// Aggregates the proofs of many independent executions into a single root proof. The executions
+/// may be of different app exes and app VM configs, as long as their leaf verifier programs are
+/// whitelisted in the [BatchAggStarkProvingKey].
+///
+/// Each execution is first aggregated into a single internal verifier proof. The batch verifier
+/// then aggregates these proofs in a tree whose commitments are tracked by a [BatchCommitTree].
+pub struct BatchAggStarkProver {
+    /// Each run is proven with the leaf VM proving key of its leaf FRI parameters.
+    agg_stark_pk: AggStarkProvingKey,
+    internal_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+    batch_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+    root_prover: RootVerifierLocalProver,
+
+    pub num_children_leaf: usize,
+    pub num_children_internal: usize,
+    pub num_children_batch: usize,
+    pub max_batch_wrapper_layers: usize,
+
+    pub profile: bool,
+}
+
+impl BatchAggStarkProver {
+    pub fn new(agg_stark_pk: AggStarkProvingKey, batch_stark_pk: BatchAggStarkProvingKey) -> Self {
+        let internal_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
+            agg_stark_pk.internal_vm_pk.clone(),
+            agg_stark_pk.internal_committed_exe.clone(),
+        );
+        let batch_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
+            agg_stark_pk.internal_vm_pk.clone(),
+            batch_stark_pk.batch_committed_exe,
+        );
+        let root_prover = RootVerifierLocalProver::new(batch_stark_pk.root_verifier_pk);
+        Self {
+            agg_stark_pk,
+            internal_prover,
+            batch_prover,
+            root_prover,
+            num_children_leaf: DEFAULT_NUM_CHILDREN_LEAF,
+            num_children_internal: DEFAULT_NUM_CHILDREN_INTERNAL,
+            num_children_batch: DEFAULT_NUM_CHILDREN_BATCH,
+            max_batch_wrapper_layers: DEFAULT_MAX_BATCH_WRAPPER_LAYERS,
+            profile: false,
+        }
+    }
+
+    pub fn with_num_children_leaf(mut self, num_children_leaf: usize) -> Self {
+        self.num_children_leaf = num_children_leaf;
+        self
+    }
+
+    pub fn with_num_children_internal(mut self, num_children_internal: usize) -> Self {
+        self.num_children_internal = num_children_internal;
+        self
+    }
+
+    pub fn with_num_children_batch(mut self, num_children_batch: usize) -> Self {
+        self.num_children_batch = num_children_batch;
+        self
+    }
+
+    pub fn with_max_batch_wrapper_layers(mut self, max_batch_wrapper_layers: usize) -> Self {
+        self.max_batch_wrapper_layers = max_batch_wrapper_layers;
+        self
+    }
+
+    pub fn set_profile(&mut self, profile: bool) -> &mut Self {
+        self.profile = profile;
+        self
+    }
+    pub fn with_profiling(mut self) -> Self {
+        self.set_profile(true);
+        self
+    }
+
+    /// Generate a proof to aggregate the app proofs of all executions, in order. Returns the root
+    /// proof together with the commitment tree of the batch, whose root is exposed by the root
+    /// proof.
+    ///
+    /// Panics if the leaf FRI parameters of a run are not allowed by the aggregation key.
+    pub fn generate_batch_agg_proof(
+        &self,
+        app_proofs: Vec<AppRunProof>,
+    ) -> (Proof<RootSC>, BatchCommitTree<F>) {
+        assert!(!app_proofs.is_empty(), "Batch must contain at least 1 run");
+        let internal_proofs: Vec<_> = app_proofs
+            .iter()
+            .enumerate()
+            .map(|(run_idx, run)| {
+                info_span!("batch run", idx = run_idx).in_scope(|| self.generate_run_proof(run))
+            })
+            .collect();
+        let mut commit_tree = BatchCommitTree::new(
+            internal_proofs
+                .iter()
+                .map(BatchRunCommit::from_internal_proof)
+                .collect(),
+        );
+        let batch_proof = self.generate_batch_proof_impl(internal_proofs, &mut commit_tree);
+        let root_proof = info_span!("root verifier", group = "root").in_scope(|| {
+            SingleSegmentVmProver::prove(
+                &self.root_prover,
+                RootVmVerifierInput {
+                    leaf_fri_params_index: 0,
+                    proofs: vec![batch_proof],
+                    public_values: vec![],
+                }
+                .write(),
+            )
+        });
+        (root_proof, commit_tree)
+    }
+
+    /// Aggregates the app proof of a single execution into the internal verifier proof that the
+    /// batch verifier takes as a child.
+    pub fn generate_run_proof(&self, run: &AppRunProof) -> Proof<SC> {
+        let (leaf_fri_params_index, leaf_vm_pk) = self
+            .agg_stark_pk
+            .leaf_vm_pk_for(&run.leaf_fri_params)
+            .expect("App VM is incompatible with Agg VM because of leaf FRI parameters");
+        let mut leaf_prover = LeafProver::new(leaf_vm_pk, run.leaf_committed_exe.clone())
+            .with_num_children_leaf(self.num_children_leaf);
+        leaf_prover.profile = self.profile;
+        let leaf_proofs = leaf_prover.generate_proof(&run.app_proof);
+        self.generate_run_proof_impl(leaf_proofs, leaf_fri_params_index)
+    }
+
+    /// Aggregates the leaf proofs of a single execution into one internal verifier proof. At
+    /// least one internal layer is always applied, so the batch verifier only needs to verify
+    /// internal verifier proofs.
+    fn generate_run_proof_impl(
+        &self,
+        leaf_proofs: Vec<Proof<SC>>,
+        leaf_fri_params_index: usize,
+    ) -> Proof<SC> {
+        let mut proofs = leaf_proofs;
+        let mut internal_node_height = 0;
+        loop {
+            let internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
+                self.internal_prover
+                    .committed_exe
+                    .get_program_commit()
+                    .into(),
+                leaf_fri_params_index,
+                &proofs,
+                self.num_children_internal,
+            );
+            proofs = info_span!("internal verifier", group = "internal").in_scope(|| {
+                internal_inputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(internal_node_idx, input)| {
+                        info_span!(
+                            "Internal verifier proof",
+                            idx = internal_node_idx,
+                            hgt = internal_node_height
+                        )
+                        .in_scope(|| {
+                            single_segment_prove(&self.internal_prover, input.write(), self.profile)
+                        })
+                    })
+                    .collect()
+            });
+            internal_node_height += 1;
+            if proofs.len() == 1 {
+                break;
+            }
+        }
+        proofs.pop().unwrap()
+    }
+
+    /// Aggregates the internal verifier proofs of all executions into one batch verifier proof
+    /// that the root verifier can handle, extending `commit_tree` by one level per layer.
+    fn generate_batch_proof_impl(
+        &self,
+        internal_proofs: Vec<Proof<SC>>,
+        commit_tree: &mut BatchCommitTree<F>,
+    ) -> Proof<SC> {
+        let mut proofs = internal_proofs;
+        let mut batch_node_height = 0;
+        let mut wrapper_layers = 0;
+        loop {
+            // The root verifier only accepts batch verifier proofs.
+            if proofs.len() == 1 && batch_node_height > 0 {
+                let actual_air_heights =
+                    self.root_prover
+                        .execute_for_air_heights(RootVmVerifierInput {
+                            leaf_fri_params_index: 0,
+                            proofs: vec![proofs[0].clone()],
+                            public_values: vec![],
+                        });
+                if heights_le(
+                    &actual_air_heights,
+                    &self.root_prover.root_verifier_pk.air_heights,
+                ) {
+                    break;
+                }
+                if wrapper_layers >= self.max_batch_wrapper_layers {
+                    panic!("The heights of the root verifier still exceed the required heights after {} wrapper layers", self.max_batch_wrapper_layers);
+                }
+                wrapper_layers += 1;
+            }
+            let num_children = if proofs.len() == 1 {
+                1
+            } else {
+                self.num_children_batch
+            };
+            // The children are never leaf verifier proofs, so the leaf FRI parameters are unused.
+            let batch_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
+                self.batch_prover.committed_exe.get_program_commit().into(),
+                0,
+                &proofs,
+                num_children,
+            );
+            commit_tree.push_level(num_children);
+            proofs = info_span!("batch verifier", group = "batch").in_scope(|| {
+                batch_inputs
+                    .into_iter()
+                    .enumerate()
+                    .map(|(batch_node_idx, input)| {
+                        info_span!(
+                            "Batch verifier proof",
+                            idx = batch_node_idx,
+                            hgt = batch_node_height
+                        )
+                        .in_scope(|| {
+                            single_segment_prove(&self.batch_prover, input.write(), self.profile)
+                        })
+                    })
+                    .collect()
+            });
+            batch_node_height += 1;
+        }
+        proofs.pop().unwrap()
+    }
+}
@@ -0,0 +1,231 @@
+use openvm_native_circuit::NativeConfig;
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    openvm_stark_backend::prover::types::Proof,
+};
+use tracing::info_span;
+
+use crate::{
+    keygen::{AggStarkProvingKey, DeferralAggStarkProvingKey},
+    prover::{
+        agg::{heights_le, single_segment_prove},
+        vm::{local::VmLocalProver, SingleSegmentVmProver},
+        AppRunProof, LeafProver, RootVerifierLocalProver,
+    },
+    verifier::{
+        deferral::types::DeferralRootVmVerifierInput, internal::types::InternalVmVerifierInput,
+        root::types::RootVmVerifierInput,
+    },
+    RootSC, SC,
+};
+
+const DEFAULT_NUM_CHILDREN_LEAF: usize = 2;
+const DEFAULT_NUM_CHILDREN_INTERNAL: usize = 2;
+const DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS: usize = 4;
+
+/// Aggregates an app execution together with the executions whose claims it revealed with
+/// [verify_stark](openvm::deferral::verify_stark) into a single root proof.
+pub struct DeferralAggStarkProver {
+    /// Each execution is proven with the leaf VM proving key of its leaf FRI parameters.
+    agg_stark_pk: AggStarkProvingKey,
+    internal_prover: VmLocalProver<SC, NativeConfig, BabyBearPoseidon2Engine>,
+    root_prover: RootVerifierLocalProver,
+    num_deferred_claims: usize,
+
+    pub num_children_leaf: usize,
+    pub num_children_internal: usize,
+    pub max_internal_wrapper_layers: usize,
+
+    pub profile: bool,
+}
+
+impl DeferralAggStarkProver {
+    pub fn new(
+        agg_stark_pk: AggStarkProvingKey,
+        deferral_stark_pk: DeferralAggStarkProvingKey,
+    ) -> Self {
+        let internal_prover = VmLocalProver::<SC, NativeConfig, BabyBearPoseidon2Engine>::new(
+            agg_stark_pk.internal_vm_pk.clone(),
+            agg_stark_pk.internal_committed_exe.clone(),
+        );
+        let root_prover = RootVerifierLocalProver::new(deferral_stark_pk.root_verifier_pk);
+        Self {
+            agg_stark_pk,
+            internal_prover,
+            root_prover,
+            num_deferred_claims: deferral_stark_pk.deferred_claims.len(),
+            num_children_leaf: DEFAULT_NUM_CHILDREN_LEAF,
+            num_children_internal: DEFAULT_NUM_CHILDREN_INTERNAL,
+            max_internal_wrapper_layers: DEFAULT_MAX_INTERNAL_WRAPPER_LAYERS,
+            profile: false,
+        }
+    }
+
+    pub fn with_num_children_leaf(mut self, num_children_leaf: usize) -> Self {
+        self.num_children_leaf = num_children_leaf;
+        self
+    }
+
+    pub fn with_num_children_internal(mut self, num_children_internal: usize) -> Self {
+        self.num_children_internal = num_children_internal;
+        self
+    }
+
+    pub fn with_max_internal_wrapper_layers(mut self, max_internal_wrapper_layers: usize) -> Self {
+        self.max_internal_wrapper_layers = max_internal_wrapper_layers;
+        self
+    }
+
+    pub fn set_profile(&mut self, profile: bool) -> &mut Self {
+        self.profile = profile;
+        self
+    }
+    pub fn with_profiling(mut self) -> Self {
+        self.set_profile(true);
+        self
+    }
+
+    /// Generate a proof to aggregate the app proof together with the app proofs of the deferred
+    /// executions, in the order of the claim layouts.
+    ///
+    /// Panics if the leaf FRI parameters of an execution are not allowed by the aggregation key.
+    pub fn generate_deferral_agg_proof(
+        &self,
+        app_proof: AppRunProof,
+        deferred_proofs: Vec<AppRunProof>,
+    ) -> Proof<RootSC> {
+        let mut root_input = self.generate_root_input(app_proof, deferred_proofs);
+        self.wrap_until_root_fits(&mut root_input);
+        info_span!("root verifier", group = "root")
+            .in_scope(|| SingleSegmentVmProver::prove(&self.root_prover, root_input.write()))
+    }
+
+    /// Aggregates the proof of every execution into a single internal verifier proof and returns
+    /// the input of the deferral root verifier. The proofs are not wrapped to fit the fixed trace
+    /// heights of the root verifier.
+    pub fn generate_root_input(
+        &self,
+        app_proof: AppRunProof,
+        deferred_proofs: Vec<AppRunProof>,
+    ) -> DeferralRootVmVerifierInput<SC> {
+        assert_eq!(
+            deferred_proofs.len(),
+            self.num_deferred_claims,
+            "Number of deferred proofs doesn't match the number of claims"
+        );
+        let mut inputs: Vec<_> = std::iter::once(app_proof)
+            .chain(deferred_proofs)
+            .enumerate()
+            .map(|(run_idx, run)| {
+                info_span!("deferral run", idx = run_idx).in_scope(|| {
+                    let (leaf_fri_params_index, leaf_vm_pk) = self
+                        .agg_stark_pk
+                        .leaf_vm_pk_for(&run.leaf_fri_params)
+                        .expect(
+                            "App VM is incompatible with Agg VM because of leaf FRI parameters",
+                        );
+                    let mut leaf_prover = LeafProver::new(leaf_vm_pk, run.leaf_committed_exe)
+                        .with_num_children_leaf(self.num_children_leaf);
+                    leaf_prover.profile = self.profile;
+                    let leaf_proofs = leaf_prover.generate_proof(&run.app_proof);
+                    RootVmVerifierInput {
+                        leaf_fri_params_index,
+                        proofs: vec![
+                            self.generate_run_proof_impl(leaf_proofs, leaf_fri_params_index)
+                        ],
+                        public_values: run.app_proof.user_public_values.public_values,
+                    }
+                })
+            })
+            .collect();
+        let app = inputs.remove(0);
+        DeferralRootVmVerifierInput {
+            app,
+            deferred: inputs,
+        }
+    }
+
+    /// Aggregates the leaf proofs of a single execution into one internal verifier proof.
+    fn generate_run_proof_impl(
+        &self,
+        leaf_proofs: Vec<Proof<SC>>,
+        leaf_fri_params_index: usize,
+    ) -> Proof<SC> {
+        let mut proofs = leaf_proofs;
+        let mut internal_node_height = 0;
+        while internal_node_height == 0 || proofs.len() > 1 {
+            proofs = self.internal_layer(
+                &proofs,
+                leaf_fri_params_index,
+                self.num_children_internal,
+                internal_node_height,
+            );
+            internal_node_height += 1;
+        }
+        proofs.pop().unwrap()
+    }
+
+    /// Wraps the proof of every execution in one more internal layer until the deferral root
+    /// verifier fits in its fixed trace heights.
+    fn wrap_until_root_fits(&self, root_input: &mut DeferralRootVmVerifierInput<SC>) {
+        let mut wrapper_layers = 0;
+        loop {
+            let actual_air_heights = self
+                .root_prover
+                .execute_streams_for_air_heights(root_input.write());
+            if heights_le(
+                &actual_air_heights,
+                &self.root_prover.root_verifier_pk.air_heights,
+            ) {
+                break;
+            }
+            if wrapper_layers >= self.max_internal_wrapper_layers {
+                panic!("The heights of the root verifier still exceed the required heights after {} wrapper layers", self.max_internal_wrapper_layers);
+            }
+            for input in std::iter::once(&mut root_input.app).chain(&mut root_input.deferred) {
+                input.proofs = self.internal_layer(
+                    &input.proofs,
+                    input.leaf_fri_params_index,
+                    1,
+                    wrapper_layers,
+                );
+            }
+            wrapper_layers += 1;
+        }
+    }
+
+    fn internal_layer(
+        &self,
+        proofs: &[Proof<SC>],
+        leaf_fri_params_index: usize,
+        num_children: usize,
+        internal_node_height: usize,
+    ) -> Vec<Proof<SC>> {
+        let internal_inputs = InternalVmVerifierInput::chunk_leaf_or_internal_proofs(
+            self.internal_prover
+                .committed_exe
+                .get_program_commit()
+                .into(),
+            leaf_fri_params_index,
+            proofs,
+            num_children,
+        );
+        info_span!("internal verifier", group = "internal").in_scope(|| {
+            internal_inputs
+                .into_iter()
+                .enumerate()
+                .map(|(internal_node_idx, input)| {
+                    info_span!(
+                        "Internal verifier proof",
+                        idx = internal_node_idx,
+                        hgt = internal_node_height
+                    )
+                    .in_scope(|| {
+                        single_segment_prove(&self.internal_prover, input.write(), self.profile)
+                    })
+                })
+                .collect()
+        })
+    }
+}
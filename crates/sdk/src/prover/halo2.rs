@@ -8,11 +8,16 @@ use openvm_native_recursion::{
 use openvm_stark_sdk::openvm_stark_backend::prover::types::Proof;
 use tracing::info_span;
 
-use crate::{keygen::Halo2ProvingKey, RootSC};
+use crate::{
+    keygen::Halo2ProvingKey,
+    prover::job::{ProofCancelled, ProofEvent, ProofObserver},
+    RootSC,
+};
 pub struct Halo2Prover {
     halo2_pk: Halo2ProvingKey,
     verifier_srs: Arc<Halo2Params>,
     wrapper_srs: Arc<Halo2Params>,
+    observer: ProofObserver,
 }
 
 impl Halo2Prover {
@@ -25,17 +30,35 @@ impl Halo2Prover {
             halo2_pk,
             verifier_srs,
             wrapper_srs,
+            observer: ProofObserver::default(),
         }
     }
+    pub fn with_observer(mut self, observer: ProofObserver) -> Self {
+        self.observer = observer;
+        self
+    }
     pub fn prove_for_evm(&self, root_proof: &Proof<RootSC>) -> EvmProof {
+        self.try_prove_for_evm(root_proof)
+            .expect("halo2 proof was cancelled")
+    }
+    /// Same as [Self::prove_for_evm], but stops early if the observer is cancelled.
+    pub fn try_prove_for_evm(
+        &self,
+        root_proof: &Proof<RootSC>,
+    ) -> Result<EvmProof, ProofCancelled> {
+        self.observer.check_cancelled()?;
         let mut witness = Witness::default();
         root_proof.write(&mut witness);
         let snark = info_span!("halo2 outer recursion", group = "halo2_outer")
             .in_scope(|| self.halo2_pk.verifier.prove(&self.verifier_srs, witness));
-        info_span!("halo2_wrapper", group = "halo2_wrapper").in_scope(|| {
+        self.observer.emit(ProofEvent::Halo2VerifierDone);
+        self.observer.check_cancelled()?;
+        let proof = info_span!("halo2_wrapper", group = "halo2_wrapper").in_scope(|| {
             self.halo2_pk
                 .wrapper
                 .prove_for_evm(&self.wrapper_srs, snark)
-        })
+        });
+        self.observer.emit(ProofEvent::Halo2WrapDone);
+        Ok(proof)
     }
 }
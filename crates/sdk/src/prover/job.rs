@@ -0,0 +1,204 @@
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use eyre::Result;
+use openvm_native_recursion::halo2::EvmProof;
+use openvm_stark_sdk::openvm_stark_backend::prover::types::Proof;
+
+use crate::{prover::Halo2Prover, RootSC};
+
+/// Progress of a proof, in the order the stages complete.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProofEvent {
+    /// A segment of the app execution was executed. The number of segments is only known once
+    /// the last one is executed, and all segments are executed before the first one is proven.
+    SegmentExecuted { segment_idx: usize },
+    /// The app proof of a segment was generated.
+    SegmentProved {
+        segment_idx: usize,
+        num_segments: usize,
+    },
+    /// All leaf verifier proofs were generated.
+    LeafLayerDone { num_proofs: usize },
+    /// A layer of internal verifier proofs was generated. Wrapper layers are included.
+    InternalLayerDone { height: usize, num_proofs: usize },
+    /// The root verifier proof was generated.
+    RootLayerDone,
+    /// The halo2 proof of the static verifier was generated.
+    Halo2VerifierDone,
+    /// The halo2 wrapper proof, i.e. the EVM proof, was generated.
+    Halo2WrapDone,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofCancelled;
+
+impl std::fmt::Display for ProofCancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "proof job was cancelled")
+    }
+}
+
+impl std::error::Error for ProofCancelled {}
+
+/// Reports [ProofEvent]s of a prover and tells it whether to stop. Cancellation is cooperative:
+/// provers only check it between segments and between layers.
+#[derive(Clone, Debug, Default)]
+pub struct ProofObserver {
+    events: Option<Sender<ProofEvent>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ProofObserver {
+    pub fn new(events: Sender<ProofEvent>) -> Self {
+        Self {
+            events: Some(events),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn emit(&self, event: ProofEvent) {
+        if let Some(events) = &self.events {
+            // The receiver may have been dropped, which doesn't affect the proof.
+            let _ = events.send(event);
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// [ControlFlow::Break] once cancelled, for the hooks that let the VM stop between segments.
+    pub fn control_flow(&self) -> ControlFlow<()> {
+        if self.is_cancelled() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), ProofCancelled> {
+        if self.is_cancelled() {
+            Err(ProofCancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// A proof generated on a background thread.
+pub struct ProofJob<T> {
+    observer: ProofObserver,
+    handle: JoinHandle<Result<T, ProofCancelled>>,
+}
+
+impl<T: Send + 'static> ProofJob<T> {
+    /// Runs `prove` on a new thread, which drops its sender of `observer` once `prove` returns.
+    pub fn spawn<P>(observer: ProofObserver, prove: P) -> Self
+    where
+        P: FnOnce(&ProofObserver) -> Result<T, ProofCancelled> + Send + 'static,
+    {
+        let job_observer = observer.clone();
+        let handle = thread::spawn(move || prove(&job_observer));
+        // Only the thread keeps the sender, so the channel can disconnect when it finishes.
+        let observer = ProofObserver {
+            events: None,
+            ..observer
+        };
+        Self { observer, handle }
+    }
+}
+
+impl<T> ProofJob<T> {
+    /// Asks the job to stop at the next segment or layer boundary.
+    pub fn cancel(&self) {
+        self.observer.cancel();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Waits for the job. Returns an error if it was cancelled or panicked.
+    pub fn join(self) -> Result<T> {
+        let proof = self
+            .handle
+            .join()
+            .map_err(|_| eyre::eyre!("Proof job panicked"))??;
+        Ok(proof)
+    }
+}
+
+/// An EVM proof whose STARK part is generated on a background thread. The halo2 proving key
+/// cannot be sent to another thread, so the halo2 proofs are generated by [EvmProofJob::join] on
+/// the calling thread.
+pub struct EvmProofJob {
+    stark_job: ProofJob<Proof<RootSC>>,
+    halo2_prover: Halo2Prover,
+}
+
+impl EvmProofJob {
+    /// `halo2_prover` should report to the same observer as the STARK job.
+    pub fn new(stark_job: ProofJob<Proof<RootSC>>, halo2_prover: Halo2Prover) -> Self {
+        Self {
+            stark_job,
+            halo2_prover,
+        }
+    }
+
+    /// Asks the job to stop at the next segment or layer boundary.
+    pub fn cancel(&self) {
+        self.stark_job.cancel();
+    }
+
+    /// Whether the STARK part of the job finished.
+    pub fn is_stark_finished(&self) -> bool {
+        self.stark_job.is_finished()
+    }
+
+    /// Waits for the STARK part of the job and generates the halo2 proofs.
+    pub fn join(self) -> Result<EvmProof> {
+        let root_proof = self.stark_job.join()?;
+        let proof = self.halo2_prover.try_prove_for_evm(&root_proof)?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use super::*;
+
+    #[test]
+    fn test_proof_job_cancel() {
+        let (events, receiver) = channel();
+        let (started_tx, started_rx) = channel();
+        let job = ProofJob::spawn(ProofObserver::new(events), move |observer| {
+            observer.emit(ProofEvent::RootLayerDone);
+            started_tx.send(()).unwrap();
+            while !observer.is_cancelled() {
+                thread::yield_now();
+            }
+            observer.check_cancelled()
+        });
+        started_rx.recv().unwrap();
+        job.cancel();
+        let err = job.join().unwrap_err();
+        assert!(err.downcast_ref::<ProofCancelled>().is_some());
+        // The channel disconnects once the job finished.
+        let events: Vec<_> = receiver.iter().collect();
+        assert_eq!(events, vec![ProofEvent::RootLayerDone]);
+    }
+}
@@ -2,19 +2,28 @@ use std::sync::Arc;
 
 use openvm_circuit::arch::VmConfig;
 use openvm_native_recursion::halo2::EvmProof;
-use openvm_stark_sdk::openvm_stark_backend::Chip;
+use openvm_stark_sdk::{config::FriParameters, openvm_stark_backend::Chip};
 
-use crate::{keygen::AppProvingKey, stdin::StdIn, NonRootCommittedExe, F, SC};
+use crate::{
+    keygen::AppProvingKey, prover::vm::ContinuationVmProof, stdin::StdIn, NonRootCommittedExe, F,
+    SC,
+};
 
 mod agg;
 pub use agg::*;
 mod app;
 pub use app::*;
+mod batch;
+pub use batch::*;
+mod deferral;
+pub use deferral::*;
 use openvm_native_recursion::halo2::utils::Halo2ParamsReader;
 
 mod halo2;
 #[allow(unused_imports)]
 pub use halo2::*;
+mod job;
+pub use job::*;
 mod root;
 pub use root::*;
 mod stark;
@@ -81,3 +90,21 @@ impl<VC> ContinuationProver<VC> {
         self.halo2_prover.prove_for_evm(&root_proof)
     }
 }
+
+/// The app proof of an execution, with the leaf verifier program and leaf FRI parameters of its
+/// app proving key. Used to aggregate executions of different app VM configs together.
+pub struct AppRunProof {
+    pub leaf_committed_exe: Arc<NonRootCommittedExe>,
+    pub leaf_fri_params: FriParameters,
+    pub app_proof: ContinuationVmProof<SC>,
+}
+
+impl AppRunProof {
+    pub fn new<VC>(app_pk: &AppProvingKey<VC>, app_proof: ContinuationVmProof<SC>) -> Self {
+        Self {
+            leaf_committed_exe: app_pk.leaf_committed_exe.clone(),
+            leaf_fri_params: app_pk.leaf_fri_params,
+            app_proof,
+        }
+    }
+}
@@ -3,16 +3,19 @@ use openvm_circuit::arch::{SingleSegmentVmExecutor, Streams};
 use openvm_native_circuit::NativeConfig;
 use openvm_native_recursion::hints::Hintable;
 use openvm_stark_sdk::{
-    config::baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine,
+    config::{
+        baby_bear_keccak::BabyBearKeccakEngine,
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootEngine,
+    },
     engine::{StarkEngine, StarkFriEngine},
     openvm_stark_backend::prover::types::Proof,
 };
 
 use crate::{
-    keygen::RootVerifierProvingKey,
+    keygen::{KeccakRootProvingKey, RootVerifierProvingKey},
     prover::vm::{AsyncSingleSegmentVmProver, SingleSegmentVmProver},
     verifier::root::types::RootVmVerifierInput,
-    RootSC, F, SC,
+    KeccakRootSC, RootSC, F, SC,
 };
 
 /// Local prover for a root verifier.
@@ -31,12 +34,13 @@ impl RootVerifierLocalProver {
         }
     }
     pub fn execute_for_air_heights(&self, input: RootVmVerifierInput<SC>) -> Vec<usize> {
+        self.execute_streams_for_air_heights(input.write())
+    }
+    /// Same as [Self::execute_for_air_heights], for root programs with a different input format.
+    pub fn execute_streams_for_air_heights(&self, input: impl Into<Streams<F>>) -> Vec<usize> {
         let result = self
             .executor_for_heights
-            .execute(
-                self.root_verifier_pk.root_committed_exe.exe.clone(),
-                input.write(),
-            )
+            .execute(self.root_verifier_pk.root_committed_exe.exe.clone(), input)
             .unwrap();
         result.air_heights
     }
@@ -79,3 +83,53 @@ impl AsyncSingleSegmentVmProver<RootSC> for RootVerifierLocalProver {
         SingleSegmentVmProver::prove(self, input)
     }
 }
+
+/// Local prover for a root verifier with the Keccak STARK config. It proves the same execution
+/// as [RootVerifierLocalProver] for the root verifier it was derived from.
+pub struct KeccakRootVerifierLocalProver {
+    pub root_verifier_pk: KeccakRootProvingKey,
+}
+
+impl KeccakRootVerifierLocalProver {
+    pub fn new(root_verifier_pk: KeccakRootProvingKey) -> Self {
+        Self { root_verifier_pk }
+    }
+}
+
+impl SingleSegmentVmProver<KeccakRootSC> for KeccakRootVerifierLocalProver {
+    fn prove(&self, input: impl Into<Streams<F>>) -> Proof<KeccakRootSC> {
+        let input = input.into();
+        let vm = SingleSegmentVmExecutor::new(self.root_verifier_pk.vm_pk.vm_config.clone());
+        let mut proof_input = vm
+            .execute_and_generate(self.root_verifier_pk.root_committed_exe.clone(), input)
+            .unwrap();
+        assert_eq!(
+            proof_input.per_air.len(),
+            self.root_verifier_pk.air_heights.len(),
+            "All AIRs of root verifier should present"
+        );
+        proof_input.per_air.iter().for_each(|(air_id, input)| {
+            assert_eq!(
+                input.main_trace_height(),
+                self.root_verifier_pk.air_heights[*air_id],
+                "Trace height doesn't match"
+            );
+        });
+        // Reorder the AIRs by heights.
+        let air_id_perm = self.root_verifier_pk.air_id_permutation();
+        air_id_perm.permute(&mut proof_input.per_air);
+        for i in 0..proof_input.per_air.len() {
+            // Overwrite the AIR ID.
+            proof_input.per_air[i].0 = i;
+        }
+        let e = BabyBearKeccakEngine::new(self.root_verifier_pk.vm_pk.fri_params);
+        e.prove(&self.root_verifier_pk.vm_pk.vm_pk, proof_input)
+    }
+}
+
+#[async_trait]
+impl AsyncSingleSegmentVmProver<KeccakRootSC> for KeccakRootVerifierLocalProver {
+    async fn prove(&self, input: impl Into<Streams<F>> + Send + Sync) -> Proof<KeccakRootSC> {
+        SingleSegmentVmProver::prove(self, input)
+    }
+}
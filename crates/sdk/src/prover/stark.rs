@@ -5,7 +5,12 @@ use openvm_stark_backend::{prover::types::Proof, Chip};
 
 use crate::{
     keygen::{AggStarkProvingKey, AppProvingKey},
-    prover::{agg::AggStarkProver, app::AppProver},
+    prover::{
+        agg::AggStarkProver,
+        app::AppProver,
+        job::{ProofCancelled, ProofObserver},
+    },
+    verifier::root::types::RootVmVerifierInput,
     NonRootCommittedExe, RootSC, StdIn, F, SC,
 };
 
@@ -22,10 +27,6 @@ impl<VC> StarkProver<VC> {
     where
         VC: VmConfig<F>,
     {
-        assert_eq!(
-            app_pk.leaf_fri_params, agg_stark_pk.leaf_vm_pk.fri_params,
-            "App VM is incompatible with Agg VM because of leaf FRI parameters"
-        );
         assert_eq!(
             app_pk.app_vm_pk.vm_config.system().num_public_values,
             agg_stark_pk.num_public_values(),
@@ -34,7 +35,11 @@ impl<VC> StarkProver<VC> {
 
         Self {
             app_prover: AppProver::new(app_pk.app_vm_pk.clone(), app_committed_exe),
-            agg_prover: AggStarkProver::new(agg_stark_pk, app_pk.leaf_committed_exe.clone()),
+            agg_prover: AggStarkProver::new(
+                agg_stark_pk,
+                app_pk.leaf_committed_exe.clone(),
+                app_pk.leaf_fri_params,
+            ),
         }
     }
     pub fn set_profile(&mut self, profile: bool) -> &mut Self {
@@ -46,6 +51,11 @@ impl<VC> StarkProver<VC> {
         self.app_prover.set_program_name(program_name);
         self
     }
+    pub fn set_observer(&mut self, observer: ProofObserver) -> &mut Self {
+        self.app_prover.set_observer(observer.clone());
+        self.agg_prover.set_observer(observer);
+        self
+    }
     pub fn generate_proof_for_outer_recursion(&self, input: StdIn) -> Proof<RootSC>
     where
         VC: VmConfig<F>,
@@ -55,4 +65,32 @@ impl<VC> StarkProver<VC> {
         let app_proof = self.app_prover.generate_app_proof(input);
         self.agg_prover.generate_agg_proof(app_proof)
     }
+    /// Same as [Self::generate_proof_for_outer_recursion], but stops early if the observer is
+    /// cancelled.
+    pub fn try_generate_proof_for_outer_recursion(
+        &self,
+        input: StdIn,
+    ) -> Result<Proof<RootSC>, ProofCancelled>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let app_proof = self.app_prover.try_generate_app_proof(input)?;
+        self.agg_prover.try_generate_agg_proof(app_proof)
+    }
+
+    /// Generates the app and aggregation proofs up to the input of the root verifier.
+    pub fn try_generate_root_input(
+        &self,
+        input: StdIn,
+    ) -> Result<RootVmVerifierInput<SC>, ProofCancelled>
+    where
+        VC: VmConfig<F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        let app_proof = self.app_prover.try_generate_app_proof(input)?;
+        self.agg_prover.try_generate_root_input(app_proof)
+    }
 }
@@ -3,8 +3,8 @@ use std::{marker::PhantomData, sync::Arc};
 use async_trait::async_trait;
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, Streams, VirtualMachine, VmComplexTraceHeights,
-        VmConfig,
+        hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, Streams, VirtualMachine,
+        VmComplexTraceHeights, VmConfig,
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
@@ -16,9 +16,12 @@ use openvm_stark_backend::{
 };
 use openvm_stark_sdk::engine::StarkFriEngine;
 
-use crate::prover::vm::{
-    types::VmProvingKey, AsyncContinuationVmProver, AsyncSingleSegmentVmProver,
-    ContinuationVmProof, ContinuationVmProver, SingleSegmentVmProver,
+use crate::prover::{
+    job::{ProofCancelled, ProofEvent, ProofObserver},
+    vm::{
+        types::VmProvingKey, AsyncContinuationVmProver, AsyncSingleSegmentVmProver,
+        ContinuationVmProof, ContinuationVmProver, SingleSegmentVmProver,
+    },
 };
 
 pub struct VmLocalProver<SC: StarkGenericConfig, VC, E: StarkFriEngine<SC>> {
@@ -67,6 +70,24 @@ where
     VC::Periphery: Chip<SC>,
 {
     fn prove(&self, input: impl Into<Streams<Val<SC>>>) -> ContinuationVmProof<SC> {
+        self.prove_with_observer(input, &ProofObserver::default())
+            .expect("default observer is never cancelled")
+    }
+}
+
+impl<SC: StarkGenericConfig, VC: VmConfig<Val<SC>>, E: StarkFriEngine<SC>> VmLocalProver<SC, VC, E>
+where
+    Val<SC>: PrimeField32,
+    VC::Executor: Chip<SC>,
+    VC::Periphery: Chip<SC>,
+{
+    /// Same as [ContinuationVmProver::prove], but proves the segments one by one, reporting them
+    /// to `observer` and stopping early if it is cancelled.
+    pub fn prove_with_observer(
+        &self,
+        input: impl Into<Streams<Val<SC>>>,
+        observer: &ProofObserver,
+    ) -> Result<ContinuationVmProof<SC>, ProofCancelled> {
         assert!(self.pk.vm_config.system().continuation_enabled);
         let e = E::new(self.pk.fri_params);
         let vm = VirtualMachine::new_with_overridden_trace_heights(
@@ -74,20 +95,39 @@ where
             self.pk.vm_config.clone(),
             self.overridden_heights.clone(),
         );
-        let results = vm
-            .execute_and_generate_with_cached_program(self.committed_exe.clone(), input)
-            .unwrap();
+        // The number of segments is only known once execution terminates.
+        let results = match vm.execute_and_generate_with_hook(
+            self.committed_exe.clone(),
+            input,
+            |segment_idx| {
+                observer.emit(ProofEvent::SegmentExecuted { segment_idx });
+                observer.control_flow()
+            },
+        ) {
+            Err(ExecutionError::Interrupted { .. }) => return Err(ProofCancelled),
+            results => results.unwrap(),
+        };
         let user_public_values = UserPublicValuesProof::compute(
             self.pk.vm_config.system().memory_config.memory_dimensions(),
             self.pk.vm_config.system().num_public_values,
             &vm_poseidon2_hasher(),
             results.final_memory.as_ref().unwrap(),
         );
-        let per_segment = vm.prove(&self.pk.vm_pk, results);
-        ContinuationVmProof {
+        observer.check_cancelled()?;
+        let num_segments = results.per_segment.len();
+        let per_segment = vm
+            .prove_with_hook(&self.pk.vm_pk, results, |segment_idx| {
+                observer.emit(ProofEvent::SegmentProved {
+                    segment_idx,
+                    num_segments,
+                });
+                observer.control_flow()
+            })
+            .ok_or(ProofCancelled)?;
+        Ok(ContinuationVmProof {
             per_segment,
             user_public_values,
-        }
+        })
     }
 }
 
@@ -0,0 +1,128 @@
+//! Decoding of the public values exposed by root and EVM proofs.
+
+use eyre::Result;
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_native_recursion::halo2::EvmProof;
+use openvm_stark_sdk::{
+    openvm_stark_backend::{
+        p3_field::{AbstractField, PrimeField32},
+        prover::types::Proof,
+    },
+    p3_baby_bear::BabyBear,
+};
+
+use crate::{commit::AppExecutionCommit, verifier::root::types::RootVmVerifierPvs, RootSC, F};
+
+/// Decodes the public values of the static verifier from an [EvmProof].
+pub trait EvmProofExt {
+    /// Returns the commitments of the proven execution and its user public values.
+    fn decode(&self) -> Result<(AppExecutionCommit<F>, Vec<u8>)>;
+}
+
+/// Reads the public values of the root verifier from its proof.
+pub trait RootProofExt {
+    /// Returns the commitments of the proven execution and its user public values.
+    fn public_values(&self) -> Result<(AppExecutionCommit<F>, Vec<u8>)>;
+}
+
+impl EvmProofExt for EvmProof {
+    fn decode(&self) -> Result<(AppExecutionCommit<F>, Vec<u8>)> {
+        let pvs = self.public_values_le_bytes();
+        if pvs.len() < 2 {
+            return Err(eyre::eyre!(
+                "EVM proof has {} public values, expected at least 2",
+                pvs.len()
+            ));
+        }
+        let commit = AppExecutionCommit {
+            exe_commit: bn254_le_bytes_to_babybear_digest(&pvs[0])?,
+            leaf_vm_verifier_commit: bn254_le_bytes_to_babybear_digest(&pvs[1])?,
+        };
+        let user_public_values = pvs[2..]
+            .iter()
+            .map(|x| {
+                if x[1..].iter().any(|&b| b != 0) {
+                    return Err(eyre::eyre!("User public value is not a byte"));
+                }
+                Ok(x[0])
+            })
+            .collect::<Result<_>>()?;
+        Ok((commit, user_public_values))
+    }
+}
+
+impl RootProofExt for Proof<RootSC> {
+    fn public_values(&self) -> Result<(AppExecutionCommit<F>, Vec<u8>)> {
+        // The connector AIR also has public values, but fewer than the two digests the public
+        // values AIR of the root verifier starts with.
+        let mut pv_airs = self
+            .per_air
+            .iter()
+            .filter(|air| air.public_values.len() >= 2 * DIGEST_SIZE);
+        let pv_air = match (pv_airs.next(), pv_airs.next()) {
+            (Some(pv_air), None) => pv_air,
+            _ => return Err(eyre::eyre!("Proof is not a root verifier proof")),
+        };
+        let pvs = RootVmVerifierPvs::from_flatten(pv_air.public_values.clone());
+        let user_public_values = pvs
+            .public_values
+            .iter()
+            .map(|x| {
+                u8::try_from(x.as_canonical_u32())
+                    .map_err(|_| eyre::eyre!("User public value is not a byte"))
+            })
+            .collect::<Result<_>>()?;
+        let commit = AppExecutionCommit {
+            exe_commit: pvs.exe_commit,
+            leaf_vm_verifier_commit: pvs.leaf_verifier_commit,
+        };
+        Ok((commit, user_public_values))
+    }
+}
+
+/// Inverse of `babybear_digest_to_bn254`: splits a little-endian integer into `DIGEST_SIZE` digits
+/// in base of the BabyBear order. Fails if the integer is too large to be a compressed digest.
+pub(crate) fn bn254_le_bytes_to_babybear_digest(bytes: &[u8; 32]) -> Result<[F; DIGEST_SIZE]> {
+    let mut value = *bytes;
+    let mut digest = [F::ZERO; DIGEST_SIZE];
+    for digit in digest.iter_mut() {
+        // Long division by the BabyBear order, from the most significant byte.
+        let mut rem = 0u64;
+        for byte in value.iter_mut().rev() {
+            let cur = (rem << 8) | *byte as u64;
+            *byte = (cur / BabyBear::ORDER_U32 as u64) as u8;
+            rem = cur % BabyBear::ORDER_U32 as u64;
+        }
+        *digit = F::from_canonical_u64(rem);
+    }
+    if value.iter().any(|&b| b != 0) {
+        return Err(eyre::eyre!("Public value is not a compressed digest"));
+    }
+    Ok(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use openvm_stark_sdk::openvm_stark_backend::p3_field::PrimeField;
+
+    use super::*;
+    use crate::commit::babybear_digest_to_bn254;
+
+    #[test]
+    fn test_bn254_to_babybear_digest_round_trip() {
+        let digest: [F; DIGEST_SIZE] = std::array::from_fn(|i| {
+            F::from_canonical_u32(BabyBear::ORDER_U32 - 1 - 12345 * i as u32)
+        });
+        let mut bytes = [0u8; 32];
+        let le = babybear_digest_to_bn254(&digest)
+            .as_canonical_biguint()
+            .to_bytes_le();
+        bytes[..le.len()].copy_from_slice(&le);
+        assert_eq!(bn254_le_bytes_to_babybear_digest(&bytes).unwrap(), digest);
+
+        // Integers of at least the BabyBear order to the power of DIGEST_SIZE are out of range.
+        let mut bytes = [0u8; 32];
+        bytes[31] = 0xff;
+        assert!(bn254_le_bytes_to_babybear_digest(&bytes).is_err());
+    }
+}
@@ -5,7 +5,11 @@ use openvm_native_recursion::{
     config::outer::{new_from_outer_multi_vk, OuterConfig},
     digest::DigestVariable,
     fri::TwoAdicFriPcsVariable,
-    halo2::{verifier::Halo2VerifierProvingKey, DslOperations, Halo2Params, Halo2Prover},
+    halo2::{
+        utils::Halo2ParamsReader, verifier::Halo2VerifierProvingKey,
+        wrapper::Halo2WrapperProvingKey, DslOperations, Halo2CircuitStats, Halo2Params,
+        Halo2Prover,
+    },
     hints::Hintable,
     stark::StarkVerifier,
     utils::const_fri_config,
@@ -19,6 +23,7 @@ use openvm_stark_sdk::{
     p3_baby_bear::BabyBear,
     p3_bn254_fr::Bn254Fr,
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
     keygen::RootVerifierProvingKey,
@@ -30,6 +35,15 @@ use crate::{
     RootSC, F, SC,
 };
 
+/// Size of the static verifier and its wrapper, see
+/// [RootVerifierProvingKey::static_verifier_stats].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticVerifierStats {
+    pub verifier_k: usize,
+    pub verifier: Halo2CircuitStats,
+    pub wrapper_k: usize,
+}
+
 impl RootVerifierProvingKey {
     /// Keygen the static verifier for this root verifier.
     pub fn keygen_static_verifier(
@@ -46,6 +60,29 @@ impl RootVerifierProvingKey {
         }
     }
 
+    /// Statistics of the static verifier circuit for this root verifier at `verifier_k`, and the
+    /// `k` auto-selected for its wrapper. Neither circuit is keygened, only the verifying key of the
+    /// static verifier is generated.
+    pub fn static_verifier_stats(
+        &self,
+        reader: &impl Halo2ParamsReader,
+        verifier_k: usize,
+        root_proof: Proof<RootSC>,
+    ) -> StaticVerifierStats {
+        let mut witness = Witness::default();
+        root_proof.write(&mut witness);
+        let dsl_operations = build_static_verifier_operations(self, &root_proof);
+        let verifier =
+            Halo2Prover::circuit_stats(verifier_k, dsl_operations.clone(), witness.clone());
+        let dummy_snark =
+            Halo2Prover::dummy_snark(&reader.read_params(verifier_k), dsl_operations, witness);
+        StaticVerifierStats {
+            verifier_k,
+            verifier,
+            wrapper_k: Halo2WrapperProvingKey::select_k(dummy_snark),
+        }
+    }
+
     pub fn generate_dummy_root_proof(&self, dummy_internal_proof: Proof<SC>) -> Proof<RootSC> {
         let prover = RootVerifierLocalProver::new(self.clone());
         // 2 * DIGEST_SIZE for exe_commit and leaf_commit
@@ -59,6 +96,7 @@ impl RootVerifierProvingKey {
         SingleSegmentVmProver::prove(
             &prover,
             RootVmVerifierInput {
+                leaf_fri_params_index: 0,
                 proofs: vec![dummy_internal_proof],
                 public_values: vec![F::ZERO; num_public_values],
             }
@@ -0,0 +1,147 @@
+use eyre::Result;
+use openvm_circuit::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        verify_segments, PROGRAM_CACHED_TRACE_INDEX,
+    },
+    system::memory::{
+        dimensions::MemoryDimensions, tree::public_values::PUBLIC_VALUES_ADDRESS_SPACE_OFFSET,
+        CHUNK,
+    },
+};
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Engine,
+    engine::StarkFriEngine,
+    openvm_stark_backend::{
+        config::{Com, StarkGenericConfig, Val},
+        p3_field::PrimeField32,
+        p3_util::log2_strict_usize,
+    },
+};
+
+use crate::{
+    commit::compute_exe_commit, keygen::AppVerifyingKey, prover::vm::ContinuationVmProof, F, SC,
+};
+
+/// What a verified app proof proves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedAppProof<T = F> {
+    /// Commitment of the executable, the same as `AppExecutionCommit::exe_commit`.
+    pub exe_commit: [T; DIGEST_SIZE],
+    /// User public values at the end of the execution.
+    pub user_public_values: Vec<T>,
+}
+
+/// Verifies the proof of every segment, the continuity between segments and the user public
+/// values against the final memory state.
+pub fn verify_app_proof(
+    app_vk: &AppVerifyingKey,
+    proof: &ContinuationVmProof<SC>,
+) -> Result<VerifiedAppProof> {
+    verify_app_proof_with_engine::<SC, BabyBearPoseidon2Engine>(app_vk, proof)
+}
+
+/// Same as [verify_app_proof], for an app VM in the STARK config `SC` verified with the engine
+/// `E`. The memory of the VM is always hashed with the BabyBear Poseidon2 hasher, so the
+/// exe commit and user public values can only be checked if the field of `SC` is BabyBear.
+pub fn verify_app_proof_with_engine<SC, E>(
+    app_vk: &AppVerifyingKey<SC>,
+    proof: &ContinuationVmProof<SC>,
+) -> Result<VerifiedAppProof<Val<SC>>>
+where
+    SC: StarkGenericConfig,
+    E: StarkFriEngine<SC>,
+    Val<SC>: PrimeField32,
+    Com<SC>: Into<[Val<SC>; DIGEST_SIZE]>,
+{
+    let engine = E::new(app_vk.fri_params);
+    let payload = verify_segments(&engine, &app_vk.app_vm_vk, &proof.per_segment)?;
+
+    // The leaf verifier requires every segment to run the same program.
+    let mut program_commits = proof.per_segment.iter().map(|segment| {
+        segment
+            .commitments
+            .main_trace
+            .get(PROGRAM_CACHED_TRACE_INDEX)
+            .map(|commit| -> [Val<SC>; DIGEST_SIZE] { commit.clone().into() })
+            .ok_or_else(|| eyre::eyre!("Segment proof has no cached program trace"))
+    });
+    let program_commit = program_commits.next().unwrap()?;
+    for commit in program_commits {
+        if commit? != program_commit {
+            return Err(eyre::eyre!("Segments were proven for different programs"));
+        }
+    }
+    let exe_commit = compute_exe_commit(
+        &program_commit,
+        &payload.initial_memory_root,
+        payload.initial_pc,
+    );
+
+    verify_user_public_values(
+        app_vk.memory_dimensions,
+        app_vk.num_public_values,
+        proof,
+        &payload.final_memory_root,
+    )?;
+
+    Ok(VerifiedAppProof {
+        exe_commit,
+        user_public_values: proof.user_public_values.public_values.clone(),
+    })
+}
+
+/// Checks the Merkle proof from the user public values to the final memory root. The path must
+/// lead to the public values address space, so it is recomputed from the memory dimensions
+/// instead of trusting the bits of the proof.
+fn verify_user_public_values<SC: StarkGenericConfig>(
+    memory_dimensions: MemoryDimensions,
+    num_public_values: usize,
+    proof: &ContinuationVmProof<SC>,
+    final_memory_root: &[Val<SC>; CHUNK],
+) -> Result<()>
+where
+    Val<SC>: PrimeField32,
+{
+    let pvs_proof = &proof.user_public_values;
+    if pvs_proof.public_values.len() != num_public_values {
+        return Err(eyre::eyre!(
+            "Expected {} user public values, got {}",
+            num_public_values,
+            pvs_proof.public_values.len()
+        ));
+    }
+    let hasher = vm_poseidon2_hasher::<Val<SC>>();
+    if hasher.merkle_root(&pvs_proof.public_values) != pvs_proof.public_values_commit {
+        return Err(eyre::eyre!("User public values commit mismatch"));
+    }
+
+    let pv_height = log2_strict_usize(num_public_values / CHUNK);
+    let address_leading_zeros = memory_dimensions.address_height - pv_height;
+    // Bits from the leaf to the root. A set bit means the public values are in the right child.
+    let expected_bits = (0..address_leading_zeros).map(|_| false).chain(
+        (0..memory_dimensions.as_height)
+            .map(|i| PUBLIC_VALUES_ADDRESS_SPACE_OFFSET & (1 << i) != 0),
+    );
+    if pvs_proof.proof.len() != address_leading_zeros + memory_dimensions.as_height {
+        return Err(eyre::eyre!("User public values proof has the wrong length"));
+    }
+    let mut curr = pvs_proof.public_values_commit;
+    for (&(bit, sibling), expected_bit) in pvs_proof.proof.iter().zip(expected_bits) {
+        if bit != expected_bit {
+            return Err(eyre::eyre!("User public values proof has the wrong path"));
+        }
+        curr = if bit {
+            hasher.compress(&sibling, &curr)
+        } else {
+            hasher.compress(&curr, &sibling)
+        };
+    }
+    if &curr != final_memory_root {
+        return Err(eyre::eyre!(
+            "User public values are not in the final memory"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use std::{array, borrow::Borrow};
+
+use openvm_circuit::arch::{instructions::program::Program, PUBLIC_VALUES_AIR_ID};
+use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
+use openvm_native_recursion::{
+    challenger::duplex::DuplexChallengerVariable, fri::TwoAdicFriPcsVariable, hints::Hintable,
+    stark::StarkVerifier, types::new_from_inner_multi_vk, utils::const_fri_config,
+};
+use openvm_stark_sdk::{
+    config::{baby_bear_poseidon2::BabyBearPoseidon2Config, FriParameters},
+    openvm_stark_backend::{keygen::types::MultiStarkVerifyingKey, p3_field::AbstractField},
+};
+
+use crate::{
+    verifier::{
+        batch::types::{BatchRootVmVerifierPvs, BatchVmVerifierPvs},
+        common::{
+            assert_required_air_for_agg_vm_present, assert_single_segment_vm_exit_successfully,
+            get_program_commit,
+        },
+        internal::types::{InternalVmVerifierInput, InternalVmVerifierPvs},
+        root::types::RootVmVerifierInput,
+        utils::{assign_array_to_slice, compute_exe_commit, eq_felt_slice, VariableP2Hasher},
+    },
+    C, F, SC,
+};
+
+pub mod types;
+
+/// Config to generate the batch VM verifier program. The batch verifier runs on the internal VM
+/// and aggregates independent app executions: each child is either an internal verifier proof
+/// of a whole execution, or a proof of the batch verifier itself.
+pub struct BatchVmVerifierConfig {
+    pub internal_fri_params: FriParameters,
+    pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
+    /// Commitments of the leaf verifier programs whose executions can be aggregated. Executions
+    /// of different app VM configs can be aggregated as long as they share the leaf VM.
+    pub leaf_verifier_whitelist: Vec<[F; DIGEST_SIZE]>,
+    pub compiler_options: CompilerOptions,
+}
+
+impl BatchVmVerifierConfig {
+    pub fn build_program(
+        &self,
+        internal_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
+    ) -> Program<F> {
+        let internal_advice = new_from_inner_multi_vk(internal_vm_vk);
+        let mut builder = Builder::<C>::default();
+        {
+            builder.cycle_tracker_start("ReadProofsFromInput");
+            // Same input format as the internal verifier.
+            let input = InternalVmVerifierInput::<SC>::read(&mut builder);
+            let self_program_commit = input.self_program_commit;
+            let proofs = input.proofs;
+            builder.cycle_tracker_end("ReadProofsFromInput");
+            builder.cycle_tracker_start("InitializePcsConst");
+            let internal_pcs = TwoAdicFriPcsVariable {
+                config: const_fri_config(&mut builder, &self.internal_fri_params),
+            };
+            builder.cycle_tracker_end("InitializePcsConst");
+            let internal_program_commit: [Felt<F>; DIGEST_SIZE] =
+                array::from_fn(|i| builder.eval(self.internal_vm_verifier_commit[i]));
+            let leaf_verifier_whitelist: Vec<[Felt<F>; DIGEST_SIZE]> = self
+                .leaf_verifier_whitelist
+                .iter()
+                .map(|commit| array::from_fn(|i| builder.eval(commit[i])))
+                .collect();
+            let hasher = VariableP2Hasher::new(&mut builder);
+
+            builder.cycle_tracker_start("VerifyProofs");
+            // At least 1 proof should be provided.
+            builder.assert_ne::<Usize<_>>(proofs.len(), RVar::zero());
+            let batch_commit: [Felt<F>; DIGEST_SIZE] = array::from_fn(|_| builder.eval(F::ZERO));
+            let num_runs: Felt<F> = builder.eval(F::ZERO);
+            builder.range(0, proofs.len()).for_each(|i, builder| {
+                let proof = builder.get(&proofs, i);
+                assert_required_air_for_agg_vm_present(builder, &proof);
+                // Both the internal verifier and the batch verifier run on the internal VM.
+                StarkVerifier::verify::<DuplexChallengerVariable<C>>(
+                    builder,
+                    &internal_pcs,
+                    &internal_advice,
+                    &proof,
+                );
+                assert_single_segment_vm_exit_successfully(builder, &proof);
+
+                let proof_pvs_arr = builder
+                    .get(&proof.per_air, PUBLIC_VALUES_AIR_ID)
+                    .public_values;
+                let program_commit = get_program_commit(builder, &proof);
+                let child_commit: [Felt<F>; DIGEST_SIZE] = array::from_fn(|_| builder.uninit());
+                let is_self_program = eq_felt_slice(builder, &self_program_commit, &program_commit);
+                builder.if_eq(is_self_program, RVar::one()).then_or_else(
+                    |builder| {
+                        let flatten_pvs = BatchVmVerifierPvs::<Felt<F>>::uninit(builder).flatten();
+                        assign_array_to_slice(builder, &flatten_pvs, &proof_pvs_arr, 0);
+                        let pvs: &BatchVmVerifierPvs<_> = flatten_pvs.as_slice().borrow();
+                        builder
+                            .assert_eq::<[_; DIGEST_SIZE]>(pvs.self_program_commit, program_commit);
+                        builder.assign(&child_commit, pvs.batch_commit);
+                        builder.assign(&num_runs, num_runs + pvs.num_runs);
+                    },
+                    |builder| {
+                        builder
+                            .assert_eq::<[_; DIGEST_SIZE]>(program_commit, internal_program_commit);
+                        let flatten_pvs =
+                            InternalVmVerifierPvs::<Felt<F>>::uninit(builder).flatten();
+                        assign_array_to_slice(builder, &flatten_pvs, &proof_pvs_arr, 0);
+                        let pvs: &InternalVmVerifierPvs<_> = flatten_pvs.as_slice().borrow();
+                        builder.assert_eq::<[_; DIGEST_SIZE]>(
+                            pvs.extra_pvs.internal_program_commit,
+                            program_commit,
+                        );
+                        let vm_pvs = &pvs.vm_verifier_pvs;
+                        // The child must aggregate a whole execution which exits successfully.
+                        builder.assert_felt_eq(vm_pvs.connector.is_terminate, F::ONE);
+                        builder.assert_felt_eq(vm_pvs.connector.exit_code, F::ZERO);
+                        let exe_commit = compute_exe_commit(
+                            builder,
+                            &hasher,
+                            vm_pvs.app_commit,
+                            vm_pvs.memory.initial_root,
+                            vm_pvs.connector.initial_pc,
+                        );
+                        // The app VK of the run must be whitelisted.
+                        let leaf_verifier_commit = pvs.extra_pvs.leaf_verifier_commit;
+                        let num_matches: Var<_> = builder.eval(F::ZERO);
+                        for allowed_commit in &leaf_verifier_whitelist {
+                            let is_match =
+                                eq_felt_slice(builder, allowed_commit, &leaf_verifier_commit);
+                            builder.assign(&num_matches, num_matches + is_match);
+                        }
+                        builder.assert_var_ne(num_matches, F::ZERO);
+
+                        let run_commit = hasher.compressor.compress(
+                            builder,
+                            &exe_commit,
+                            &vm_pvs.public_values_commit,
+                        );
+                        let run_commit =
+                            hasher
+                                .compressor
+                                .compress(builder, &run_commit, &leaf_verifier_commit);
+                        builder.assign(&child_commit, run_commit);
+                        builder.assign(&num_runs, num_runs + F::ONE);
+                    },
+                );
+                let next_batch_commit =
+                    hasher
+                        .compressor
+                        .compress(builder, &batch_commit, &child_commit);
+                builder.assign(&batch_commit, next_batch_commit);
+            });
+            builder.cycle_tracker_end("VerifyProofs");
+
+            let pvs = BatchVmVerifierPvs {
+                batch_commit,
+                num_runs,
+                self_program_commit,
+            };
+            let zero: Felt<F> = builder.eval(F::ZERO);
+            let mut flatten_pvs = pvs.flatten();
+            flatten_pvs.resize(InternalVmVerifierPvs::<u8>::width(), zero);
+            for pv in flatten_pvs {
+                builder.commit_public_value(pv);
+            }
+
+            builder.halt();
+        }
+
+        builder.compile_isa_with_options(self.compiler_options)
+    }
+}
+
+/// Config to generate the root verifier program of a batch. It verifies a single proof of the
+/// batch verifier and exposes [BatchRootVmVerifierPvs].
+pub struct BatchRootVmVerifierConfig {
+    pub internal_fri_params: FriParameters,
+    pub num_public_values: usize,
+    pub batch_vm_verifier_commit: [F; DIGEST_SIZE],
+    /// See [leaf_verifier_whitelist_commit](types::leaf_verifier_whitelist_commit).
+    pub leaf_verifier_whitelist_commit: [F; DIGEST_SIZE],
+    pub compiler_options: CompilerOptions,
+}
+
+impl BatchRootVmVerifierConfig {
+    pub fn build_program(
+        &self,
+        internal_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
+    ) -> Program<F> {
+        let internal_advice = new_from_inner_multi_vk(internal_vm_vk);
+        let mut builder = Builder::<C>::default();
+        {
+            builder.cycle_tracker_start("ReadProofsFromInput");
+            let input = RootVmVerifierInput::<SC>::read(&mut builder);
+            builder.cycle_tracker_end("ReadProofsFromInput");
+            // Public values of the runs are committed in the batch commit instead.
+            builder.assert_eq::<Usize<_>>(input.public_values.len(), RVar::zero());
+            builder.assert_eq::<Usize<_>>(input.proofs.len(), RVar::one());
+            builder.cycle_tracker_start("InitializePcsConst");
+            let internal_pcs = TwoAdicFriPcsVariable {
+                config: const_fri_config(&mut builder, &self.internal_fri_params),
+            };
+            builder.cycle_tracker_end("InitializePcsConst");
+
+            builder.cycle_tracker_start("VerifyProofs");
+            let proof = builder.get(&input.proofs, 0);
+            assert_required_air_for_agg_vm_present(&mut builder, &proof);
+            StarkVerifier::verify::<DuplexChallengerVariable<C>>(
+                &mut builder,
+                &internal_pcs,
+                &internal_advice,
+                &proof,
+            );
+            assert_single_segment_vm_exit_successfully(&mut builder, &proof);
+            let program_commit = get_program_commit(&mut builder, &proof);
+            let batch_program_commit: [Felt<F>; DIGEST_SIZE] =
+                array::from_fn(|i| builder.eval(self.batch_vm_verifier_commit[i]));
+            builder.assert_eq::<[_; DIGEST_SIZE]>(program_commit, batch_program_commit);
+
+            let proof_pvs_arr = builder
+                .get(&proof.per_air, PUBLIC_VALUES_AIR_ID)
+                .public_values;
+            let flatten_pvs = BatchVmVerifierPvs::<Felt<F>>::uninit(&mut builder).flatten();
+            assign_array_to_slice(&mut builder, &flatten_pvs, &proof_pvs_arr, 0);
+            let pvs: &BatchVmVerifierPvs<_> = flatten_pvs.as_slice().borrow();
+            builder.assert_eq::<[_; DIGEST_SIZE]>(pvs.self_program_commit, program_commit);
+            builder.cycle_tracker_end("VerifyProofs");
+
+            let zero: Felt<F> = builder.eval(F::ZERO);
+            let leaf_verifier_whitelist_commit: [Felt<F>; DIGEST_SIZE] =
+                array::from_fn(|i| builder.eval(self.leaf_verifier_whitelist_commit[i]));
+            BatchRootVmVerifierPvs {
+                batch_commit: pvs.batch_commit,
+                leaf_verifier_whitelist_commit,
+                num_runs: pvs.num_runs,
+            }
+            .flatten(self.num_public_values, zero)
+            .into_iter()
+            .for_each(|v| builder.commit_public_value(v));
+
+            builder.halt();
+        }
+
+        builder.compile_isa_with_options(self.compiler_options)
+    }
+}
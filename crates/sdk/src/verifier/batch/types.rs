@@ -0,0 +1,333 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::{
+    arch::{
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        PUBLIC_VALUES_AIR_ID,
+    },
+    circuit_derive::AlignedBorrow,
+};
+use openvm_native_compiler::ir::{Builder, Config, Felt, DIGEST_SIZE};
+use openvm_stark_sdk::{
+    openvm_stark_backend::{
+        p3_field::{AbstractField, PrimeField32},
+        prover::types::Proof,
+    },
+    p3_baby_bear::BabyBear,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+
+use crate::{commit::compute_exe_commit, verifier::internal::types::InternalVmVerifierPvs, F, SC};
+
+/// Public values of the batch VM verifier. The batch verifier runs on the internal VM, so the
+/// flattened public values are padded with zeros to the width of [InternalVmVerifierPvs].
+#[derive(Debug, Clone, Copy, AlignedBorrow)]
+#[repr(C)]
+pub struct BatchVmVerifierPvs<T> {
+    /// Hash chain over the commits of the children of this node. See [BatchCommitTree].
+    pub batch_commit: [T; DIGEST_SIZE],
+    /// The number of app executions aggregated by this node.
+    pub num_runs: T,
+    /// The commitment of the batch verifier program, which cannot be hardcoded in the program
+    /// itself.
+    pub self_program_commit: [T; DIGEST_SIZE],
+}
+
+impl<F: PrimeField32> BatchVmVerifierPvs<Felt<F>> {
+    pub fn uninit<C: Config<F = F>>(builder: &mut Builder<C>) -> Self {
+        Self {
+            batch_commit: array::from_fn(|_| builder.uninit()),
+            num_runs: builder.uninit(),
+            self_program_commit: array::from_fn(|_| builder.uninit()),
+        }
+    }
+}
+
+impl<F: Default + Clone> BatchVmVerifierPvs<Felt<F>> {
+    pub fn flatten(self) -> Vec<Felt<F>> {
+        let mut v = vec![Felt(0, Default::default()); BatchVmVerifierPvs::<u8>::width()];
+        *v.as_mut_slice().borrow_mut() = self;
+        v
+    }
+}
+
+/// Public values of the root verifier of a batch. They take the place of
+/// [RootVmVerifierPvs](crate::verifier::root::types::RootVmVerifierPvs) so the static verifier
+/// is the same: the batch commit is exposed in place of the exe commit, the whitelist commit in
+/// place of the leaf verifier commit and the number of runs as the first user public value. The
+/// rest is padded with zeros.
+#[derive(Debug)]
+pub struct BatchRootVmVerifierPvs<T> {
+    pub batch_commit: [T; DIGEST_SIZE],
+    /// See [leaf_verifier_whitelist_commit].
+    pub leaf_verifier_whitelist_commit: [T; DIGEST_SIZE],
+    pub num_runs: T,
+}
+
+impl<T: Copy> BatchRootVmVerifierPvs<T> {
+    /// Flattens into `2 * DIGEST_SIZE + num_user_public_values` values, padded with `zero`.
+    pub fn flatten(self, num_user_public_values: usize, zero: T) -> Vec<T> {
+        let mut ret = self.batch_commit.to_vec();
+        ret.extend(self.leaf_verifier_whitelist_commit);
+        ret.push(self.num_runs);
+        ret.resize(2 * DIGEST_SIZE + num_user_public_values, zero);
+        ret
+    }
+    pub fn from_flatten(flatten: Vec<T>) -> Self {
+        Self {
+            batch_commit: flatten[..DIGEST_SIZE].try_into().unwrap(),
+            leaf_verifier_whitelist_commit: flatten[DIGEST_SIZE..2 * DIGEST_SIZE]
+                .try_into()
+                .unwrap(),
+            num_runs: flatten[2 * DIGEST_SIZE],
+        }
+    }
+}
+
+/// What a batch commits to for a single app execution.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchRunCommit<F> {
+    /// The commitment of the executed exe. See [AppExecutionCommit](crate::commit::AppExecutionCommit).
+    pub exe_commit: [F; DIGEST_SIZE],
+    /// Merkle root of the public values of the execution, as in `UserPublicValuesProof`.
+    pub public_values_commit: [F; DIGEST_SIZE],
+    /// The commitment of the leaf verifier program, which identifies the app VK the execution
+    /// was proven with.
+    pub leaf_verifier_commit: [F; DIGEST_SIZE],
+}
+assert_impl_all!(BatchRunCommit<BabyBear>: Serialize, DeserializeOwned);
+
+impl BatchRunCommit<F> {
+    /// Extracts the run commit from the internal verifier proof that aggregates a whole
+    /// execution.
+    pub fn from_internal_proof(proof: &Proof<SC>) -> Self {
+        let pvs = &proof
+            .per_air
+            .iter()
+            .find(|air| air.air_id == PUBLIC_VALUES_AIR_ID)
+            .expect("internal verifier proof has no public values")
+            .public_values;
+        let pvs: &InternalVmVerifierPvs<F> = pvs.as_slice().borrow();
+        let vm_pvs = &pvs.vm_verifier_pvs;
+        Self {
+            exe_commit: compute_exe_commit(
+                &vm_pvs.app_commit,
+                &vm_pvs.memory.initial_root,
+                vm_pvs.connector.initial_pc,
+            ),
+            public_values_commit: vm_pvs.public_values_commit,
+            leaf_verifier_commit: pvs.extra_pvs.leaf_verifier_commit,
+        }
+    }
+
+    /// The leaf of this run in the [BatchCommitTree].
+    pub fn commit(&self) -> [F; DIGEST_SIZE] {
+        let hasher = vm_poseidon2_hasher();
+        let run_commit = hasher.compress(&self.exe_commit, &self.public_values_commit);
+        hasher.compress(&run_commit, &self.leaf_verifier_commit)
+    }
+}
+
+/// Commitment tree of a batch aggregation. Level 0 holds the commits of the runs in order. Each
+/// node of the next level commits to a chunk of consecutive nodes `c_0, ..., c_k` of the level
+/// below as the hash chain `compress(...compress(compress(0, c_0), c_1)..., c_k)`. The single
+/// node of the last level is the batch commit exposed by the root proof.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchCommitTree<F> {
+    pub runs: Vec<BatchRunCommit<F>>,
+    /// The nodes of each level, starting from the run commits.
+    pub levels: Vec<Vec<[F; DIGEST_SIZE]>>,
+    /// The number of children of each node of `levels[i + 1]`, except possibly the last one.
+    pub num_children: Vec<usize>,
+}
+assert_impl_all!(BatchCommitTree<BabyBear>: Serialize, DeserializeOwned);
+
+/// Proof that a run is included in a batch commit.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchInclusionProof<F> {
+    pub run: BatchRunCommit<F>,
+    /// For each node on the path from the run to the batch commit, excluding the batch commit,
+    /// its index among its siblings and the commits of all its siblings including itself.
+    pub path: Vec<(usize, Vec<[F; DIGEST_SIZE]>)>,
+}
+assert_impl_all!(BatchInclusionProof<BabyBear>: Serialize, DeserializeOwned);
+
+impl BatchCommitTree<F> {
+    pub fn new(runs: Vec<BatchRunCommit<F>>) -> Self {
+        let levels = vec![runs.iter().map(BatchRunCommit::commit).collect()];
+        Self {
+            runs,
+            levels,
+            num_children: vec![],
+        }
+    }
+
+    /// Adds a level whose nodes commit to chunks of `num_children` nodes of the current top
+    /// level. Must follow the way the batch verifier proofs are chunked.
+    pub fn push_level(&mut self, num_children: usize) {
+        let level = self
+            .levels
+            .last()
+            .unwrap()
+            .chunks(num_children)
+            .map(hash_chain)
+            .collect();
+        self.levels.push(level);
+        self.num_children.push(num_children);
+    }
+
+    pub fn batch_commit(&self) -> [F; DIGEST_SIZE] {
+        let top = self.levels.last().unwrap();
+        assert_eq!(top.len(), 1, "batch commit tree is incomplete");
+        top[0]
+    }
+
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn inclusion_proof(&self, run_idx: usize) -> BatchInclusionProof<F> {
+        let mut idx = run_idx;
+        let path = self
+            .num_children
+            .iter()
+            .zip(&self.levels)
+            .map(|(&num_children, level)| {
+                let start = idx - idx % num_children;
+                let end = (start + num_children).min(level.len());
+                let siblings = level[start..end].to_vec();
+                let index = idx - start;
+                idx /= num_children;
+                (index, siblings)
+            })
+            .collect();
+        BatchInclusionProof {
+            run: self.runs[run_idx],
+            path,
+        }
+    }
+}
+
+impl BatchInclusionProof<F> {
+    /// Recomputes the batch commit from the run along the path. Returns `None` if the path
+    /// doesn't contain the run.
+    pub fn compute_batch_commit(&self) -> Option<[F; DIGEST_SIZE]> {
+        let mut node = self.run.commit();
+        for (index, siblings) in &self.path {
+            if siblings.get(*index) != Some(&node) {
+                return None;
+            }
+            node = hash_chain(siblings);
+        }
+        Some(node)
+    }
+
+    pub fn verify(&self, batch_commit: &[F; DIGEST_SIZE]) -> bool {
+        self.compute_batch_commit().as_ref() == Some(batch_commit)
+    }
+}
+
+/// Commitment to the leaf verifier programs accepted by a batch verifier, computed as the hash
+/// chain of `leaf_verifier_commits` in order. Each leaf verifier program is generated for an
+/// [AppConfig](crate::config::AppConfig), so this is the set of app VKs a batch may contain.
+pub fn leaf_verifier_whitelist_commit(
+    leaf_verifier_commits: &[[F; DIGEST_SIZE]],
+) -> [F; DIGEST_SIZE] {
+    hash_chain(leaf_verifier_commits)
+}
+
+fn hash_chain(children: &[[F; DIGEST_SIZE]]) -> [F; DIGEST_SIZE] {
+    let hasher = vm_poseidon2_hasher();
+    children.iter().fold([F::ZERO; DIGEST_SIZE], |acc, child| {
+        hasher.compress(&acc, child)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(i: u32) -> BatchRunCommit<F> {
+        BatchRunCommit {
+            exe_commit: [F::from_canonical_u32(i); DIGEST_SIZE],
+            public_values_commit: [F::from_canonical_u32(i + 100); DIGEST_SIZE],
+            leaf_verifier_commit: [F::from_canonical_u32(i % 2); DIGEST_SIZE],
+        }
+    }
+
+    fn build_tree(num_runs: u32, num_children: &[usize]) -> BatchCommitTree<F> {
+        let mut tree = BatchCommitTree::new((0..num_runs).map(run).collect());
+        for &num_children in num_children {
+            tree.push_level(num_children);
+        }
+        tree
+    }
+
+    #[test]
+    fn test_batch_commit() {
+        let tree = build_tree(3, &[2, 2]);
+        let [c0, c1, c2] = [0, 1, 2].map(|i| run(i).commit());
+        let expected = hash_chain(&[hash_chain(&[c0, c1]), hash_chain(&[c2])]);
+        assert_eq!(tree.batch_commit(), expected);
+        assert_eq!(tree.num_runs(), 3);
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        // Uneven last chunks: 5 -> 3 -> 2 -> 1 and 5 -> 2 -> 1.
+        for num_children in [&[2, 2, 2][..], &[3, 2]] {
+            let tree = build_tree(5, num_children);
+            let batch_commit = tree.batch_commit();
+            for run_idx in 0..5 {
+                let proof = tree.inclusion_proof(run_idx);
+                assert_eq!(proof.run, run(run_idx as u32));
+                assert_eq!(proof.path.len(), num_children.len());
+                assert!(proof.verify(&batch_commit));
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_run() {
+        let tree = build_tree(1, &[1]);
+        assert_eq!(tree.batch_commit(), hash_chain(&[run(0).commit()]));
+        assert!(tree.inclusion_proof(0).verify(&tree.batch_commit()));
+    }
+
+    #[test]
+    #[should_panic(expected = "incomplete")]
+    fn test_incomplete_tree() {
+        build_tree(3, &[2]).batch_commit();
+    }
+
+    #[test]
+    fn test_invalid_inclusion_proofs() {
+        let tree = build_tree(5, &[2, 2, 2]);
+        let batch_commit = tree.batch_commit();
+        let proof = tree.inclusion_proof(2);
+
+        // The index points to another node of the chunk.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.path[0].0 = 1;
+        assert_eq!(wrong_proof.compute_batch_commit(), None);
+        assert!(!wrong_proof.verify(&batch_commit));
+
+        // The run is another one of the batch.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.run = run(3);
+        assert!(!wrong_proof.verify(&batch_commit));
+
+        // A sibling on the path is tampered with.
+        let mut wrong_proof = proof.clone();
+        wrong_proof.path[1].1[0][0] += F::ONE;
+        assert!(wrong_proof.compute_batch_commit().is_some());
+        assert!(!wrong_proof.verify(&batch_commit));
+
+        // The proof is checked against a batch chunked differently.
+        assert!(!proof.verify(&build_tree(5, &[3, 2]).batch_commit()));
+    }
+}
@@ -0,0 +1,173 @@
+use std::array;
+
+use openvm::deferral::STARK_CLAIM_HEADER_BYTES;
+use openvm_circuit::arch::instructions::program::Program;
+use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
+use openvm_native_recursion::{
+    fri::TwoAdicFriPcsVariable,
+    hints::Hintable,
+    types::{new_from_inner_multi_vk, new_from_inner_multi_vks},
+    utils::{const_fri_config, select_fri_config},
+};
+use openvm_stark_sdk::{
+    config::FriParameters,
+    openvm_stark_backend::{keygen::types::MultiStarkVerifyingKey, p3_field::AbstractField},
+};
+
+use crate::{
+    verifier::{
+        common::non_leaf::NonLeafVerifierVariables,
+        deferral::types::DeferredClaimLayout,
+        root::{types::RootVmVerifierInput, verify_root_vm_verifier_input},
+        utils::VariableP2Hasher,
+    },
+    C, F, SC,
+};
+
+pub mod types;
+
+/// Config to generate the deferral root verifier program. It runs on the root VM and verifies an
+/// app execution together with the executions claimed in its public values. It exposes the same
+/// public values as the root verifier of the app execution alone.
+pub struct DeferralRootVmVerifierConfig {
+    /// FRI parameters of the accepted leaf verifier proofs, in the order of the leaf VM verifying
+    /// keys passed to `build_program`.
+    pub allowed_leaf_fri_params: Vec<FriParameters>,
+    pub internal_fri_params: FriParameters,
+    pub num_public_values: usize,
+    pub internal_vm_verifier_commit: [F; DIGEST_SIZE],
+    /// Layouts of the claims in the public values of the app.
+    pub deferred_claims: Vec<DeferredClaimLayout>,
+    pub compiler_options: CompilerOptions,
+}
+
+impl DeferralRootVmVerifierConfig {
+    pub fn build_program(
+        &self,
+        leaf_vm_vks: &[MultiStarkVerifyingKey<SC>],
+        internal_vm_vk: &MultiStarkVerifyingKey<SC>,
+    ) -> Program<F> {
+        assert_eq!(self.allowed_leaf_fri_params.len(), leaf_vm_vks.len());
+        for layout in &self.deferred_claims {
+            assert!(
+                layout.is_valid(self.num_public_values),
+                "Invalid deferred claim layout {:?}",
+                layout
+            );
+        }
+        let leaf_advice = new_from_inner_multi_vks(leaf_vm_vks);
+        let internal_advice = new_from_inner_multi_vk(internal_vm_vk);
+        let mut builder = Builder::<C>::default();
+
+        {
+            builder.cycle_tracker_start("ReadProofsFromInput");
+            let app_input = RootVmVerifierInput::<SC>::read(&mut builder);
+            let deferred_inputs: Vec<_> = self
+                .deferred_claims
+                .iter()
+                .map(|_| RootVmVerifierInput::<SC>::read(&mut builder))
+                .collect();
+            builder.cycle_tracker_end("ReadProofsFromInput");
+            builder.cycle_tracker_start("InitializePcsConst");
+            let leaf_pcs = TwoAdicFriPcsVariable {
+                config: select_fri_config(
+                    &mut builder,
+                    &self.allowed_leaf_fri_params,
+                    app_input.leaf_fri_params_index,
+                ),
+            };
+            let internal_pcs = TwoAdicFriPcsVariable {
+                config: const_fri_config(&mut builder, &self.internal_fri_params),
+            };
+            builder.cycle_tracker_end("InitializePcsConst");
+            let internal_program_commit =
+                array::from_fn(|i| builder.eval(self.internal_vm_verifier_commit[i]));
+            let mut non_leaf_verifier = NonLeafVerifierVariables {
+                internal_program_commit,
+                leaf_pcs,
+                leaf_advice,
+                internal_pcs,
+                internal_advice,
+            };
+            let hasher = VariableP2Hasher::new(&mut builder);
+            let pvs = verify_root_vm_verifier_input(
+                &mut builder,
+                &non_leaf_verifier,
+                &hasher,
+                app_input,
+                self.num_public_values,
+            );
+
+            for (layout, input) in self.deferred_claims.iter().zip(deferred_inputs) {
+                builder.cycle_tracker_start("VerifyDeferredClaim");
+                // Claimed executions may be proven with other allowed leaf FRI parameters.
+                non_leaf_verifier.leaf_pcs = TwoAdicFriPcsVariable {
+                    config: select_fri_config(
+                        &mut builder,
+                        &self.allowed_leaf_fri_params,
+                        input.leaf_fri_params_index,
+                    ),
+                };
+                let claimed_pvs = verify_root_vm_verifier_input(
+                    &mut builder,
+                    &non_leaf_verifier,
+                    &hasher,
+                    input,
+                    layout.num_public_values,
+                );
+                let claim = &pvs.public_values[layout.byte_offset..][..layout.num_bytes()];
+                let (header, claim_public_values) = claim.split_at(STARK_CLAIM_HEADER_BYTES);
+                // Each commitment is revealed as little-endian u32 words.
+                let claimed_commits = claimed_pvs
+                    .exe_commit
+                    .iter()
+                    .chain(claimed_pvs.leaf_verifier_commit.iter());
+                for (word, commit) in header.chunks_exact(4).zip(claimed_commits) {
+                    let value = canonical_word_to_felt(&mut builder, word);
+                    builder.assert_felt_eq(value, *commit);
+                }
+                for (claimed, actual) in claim_public_values.iter().zip(claimed_pvs.public_values) {
+                    builder.assert_felt_eq(*claimed, actual);
+                }
+                builder.cycle_tracker_end("VerifyDeferredClaim");
+            }
+
+            pvs.flatten()
+                .into_iter()
+                .for_each(|v| builder.commit_public_value(v));
+
+            builder.halt();
+        }
+
+        builder.compile_isa_with_options(self.compiler_options)
+    }
+}
+
+/// Recomposes a little-endian u32 word from its bytes. Asserts that the bytes are in range and
+/// that the word is a canonical field element, so that every commitment has a single encoding in
+/// the public values.
+fn canonical_word_to_felt(builder: &mut Builder<C>, word: &[Felt<F>]) -> Felt<F> {
+    for &byte in &word[..3] {
+        builder.num2bits_f(byte, 8);
+    }
+    let top_bits = builder.num2bits_f(word[3], 8);
+    let top_bits: Vec<Var<F>> = (0..8).map(|i| builder.get(&top_bits, i)).collect();
+    // The word is below the BabyBear modulus `0x78000001` iff the top byte is below `0x78`, or the
+    // word is `0x78000000`. Bit 7 of the top byte must be 0 in both cases.
+    builder.assert_var_eq(top_bits[7], F::ZERO);
+    let top_bits_set: Var<F> = builder.eval(top_bits[3] * top_bits[4] * top_bits[5] * top_bits[6]);
+    builder.if_eq(top_bits_set, RVar::one()).then(|builder| {
+        for &bit in &top_bits[..3] {
+            builder.assert_var_eq(bit, F::ZERO);
+        }
+        for &byte in &word[..3] {
+            builder.assert_felt_eq(byte, F::ZERO);
+        }
+    });
+    builder.eval(
+        word[0]
+            + word[1] * F::from_canonical_u32(1 << 8)
+            + word[2] * F::from_canonical_u32(1 << 16)
+            + word[3] * F::from_canonical_u32(1 << 24),
+    )
+}
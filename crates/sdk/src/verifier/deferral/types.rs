@@ -0,0 +1,98 @@
+use derivative::Derivative;
+use openvm::deferral::{StarkClaim, STARK_CLAIM_HEADER_BYTES};
+use openvm_native_compiler::ir::DIGEST_SIZE;
+use openvm_native_recursion::hints::Hintable;
+use openvm_stark_sdk::{
+    config::baby_bear_poseidon2::BabyBearPoseidon2Config,
+    openvm_stark_backend::{
+        config::{Com, StarkGenericConfig},
+        p3_field::PrimeField32,
+    },
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+
+use crate::{
+    commit::AppExecutionCommit, prover::vm::ContinuationVmProof,
+    verifier::root::types::RootVmVerifierInput, C, F, SC,
+};
+
+/// Where a [StarkClaim] is revealed in the public values of the app, see
+/// [verify_stark](openvm::deferral::verify_stark).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeferredClaimLayout {
+    /// Offset of the claim in the public values of the app. Must be a multiple of 4.
+    pub byte_offset: usize,
+    /// Number of public values of the claimed execution. Must be a non-zero multiple of 4.
+    pub num_public_values: usize,
+}
+
+impl DeferredClaimLayout {
+    /// Number of public values of the app the claim occupies.
+    pub fn num_bytes(&self) -> usize {
+        STARK_CLAIM_HEADER_BYTES + self.num_public_values
+    }
+
+    /// Whether the claim is word aligned and fits in `num_public_values` public values.
+    pub fn is_valid(&self, num_public_values: usize) -> bool {
+        self.byte_offset % 4 == 0
+            && self.num_public_values > 0
+            && self.num_public_values % 4 == 0
+            && self.byte_offset + self.num_bytes() <= num_public_values
+    }
+}
+
+/// Input of the deferral root verifier: the proofs of the app execution which revealed the
+/// claims and the proofs of each claimed execution, in the order of the claim layouts.
+#[derive(Serialize, Deserialize, Derivative)]
+#[serde(bound = "")]
+#[derivative(Clone(bound = "Com<SC>: Clone"))]
+pub struct DeferralRootVmVerifierInput<SC: StarkGenericConfig> {
+    pub app: RootVmVerifierInput<SC>,
+    pub deferred: Vec<RootVmVerifierInput<SC>>,
+}
+assert_impl_all!(DeferralRootVmVerifierInput<BabyBearPoseidon2Config>: Serialize, DeserializeOwned);
+
+impl DeferralRootVmVerifierInput<SC> {
+    pub fn write(&self) -> Vec<Vec<F>> {
+        let mut stream = Hintable::<C>::write(&self.app);
+        for input in &self.deferred {
+            stream.extend(Hintable::<C>::write(input));
+        }
+        stream
+    }
+}
+
+/// Returns the claim of an execution of the exe committed by `app_commit`, to be given as input
+/// to a guest which verifies it with [verify_stark](openvm::deferral::verify_stark).
+pub fn stark_claim(
+    app_commit: &AppExecutionCommit<F>,
+    app_proof: &ContinuationVmProof<SC>,
+) -> StarkClaim {
+    StarkClaim {
+        exe_commit: app_commit.exe_commit.map(|f| f.as_canonical_u32()),
+        leaf_verifier_commit: app_commit
+            .leaf_vm_verifier_commit
+            .map(|f| f.as_canonical_u32()),
+        public_values: app_proof
+            .user_public_values
+            .public_values
+            .iter()
+            .map(|f| f.as_canonical_u32() as u8)
+            .collect(),
+    }
+}
+
+/// Encodes `claim` as the public values revealed by
+/// [verify_stark](openvm::deferral::verify_stark).
+pub fn stark_claim_to_public_values(claim: &StarkClaim) -> Vec<u8> {
+    let mut ret: Vec<u8> = claim
+        .exe_commit
+        .iter()
+        .chain(claim.leaf_verifier_commit.iter())
+        .flat_map(|word| word.to_le_bytes())
+        .collect();
+    debug_assert_eq!(ret.len(), 2 * DIGEST_SIZE * 4);
+    ret.extend(&claim.public_values);
+    ret
+}
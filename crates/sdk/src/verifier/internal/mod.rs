@@ -1,8 +1,10 @@
 use openvm_circuit::arch::instructions::program::Program;
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
 use openvm_native_recursion::{
-    fri::TwoAdicFriPcsVariable, hints::Hintable, types::new_from_inner_multi_vk,
-    utils::const_fri_config,
+    fri::TwoAdicFriPcsVariable,
+    hints::Hintable,
+    types::{new_from_inner_multi_vk, new_from_inner_multi_vks},
+    utils::{const_fri_config, select_fri_config},
 };
 use openvm_stark_sdk::{
     config::{baby_bear_poseidon2::BabyBearPoseidon2Config, FriParameters},
@@ -25,7 +27,9 @@ mod vars;
 
 /// Config to generate internal VM verifier program.
 pub struct InternalVmVerifierConfig {
-    pub leaf_fri_params: FriParameters,
+    /// FRI parameters of the accepted leaf verifier proofs. The leaf VM verifying keys passed to
+    /// [InternalVmVerifierConfig::build_program] must be in the same order.
+    pub allowed_leaf_fri_params: Vec<FriParameters>,
     pub internal_fri_params: FriParameters,
     pub compiler_options: CompilerOptions,
 }
@@ -33,22 +37,28 @@ pub struct InternalVmVerifierConfig {
 impl InternalVmVerifierConfig {
     pub fn build_program(
         &self,
-        leaf_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
+        leaf_vm_vks: &[MultiStarkVerifyingKey<BabyBearPoseidon2Config>],
         internal_vm_vk: &MultiStarkVerifyingKey<BabyBearPoseidon2Config>,
     ) -> Program<F> {
-        let leaf_advice = new_from_inner_multi_vk(leaf_vm_vk);
+        assert_eq!(self.allowed_leaf_fri_params.len(), leaf_vm_vks.len());
+        let leaf_advice = new_from_inner_multi_vks(leaf_vm_vks);
         let internal_advice = new_from_inner_multi_vk(internal_vm_vk);
         let mut builder = Builder::<C>::default();
         {
             builder.cycle_tracker_start("ReadProofsFromInput");
             let InternalVmVerifierInputVariable {
                 self_program_commit,
+                leaf_fri_params_index,
                 proofs,
             } = InternalVmVerifierInput::<BabyBearPoseidon2Config>::read(&mut builder);
             builder.cycle_tracker_end("ReadProofsFromInput");
             builder.cycle_tracker_start("InitializePcsConst");
             let leaf_pcs = TwoAdicFriPcsVariable {
-                config: const_fri_config(&mut builder, &self.leaf_fri_params),
+                config: select_fri_config(
+                    &mut builder,
+                    &self.allowed_leaf_fri_params,
+                    leaf_fri_params_index,
+                ),
             };
             let internal_pcs = TwoAdicFriPcsVariable {
                 config: const_fri_config(&mut builder, &self.internal_fri_params),
@@ -25,6 +25,9 @@ use crate::{verifier::common::types::VmVerifierPvs, SC};
 #[derivative(Clone(bound = "Com<SC>: Clone"))]
 pub struct InternalVmVerifierInput<SC: StarkGenericConfig> {
     pub self_program_commit: [Val<SC>; DIGEST_SIZE],
+    /// Index of the FRI parameters of the leaf verifier proofs in the allowed leaf FRI parameters
+    /// of the internal verifier. Unused if all proofs are internal verifier proofs.
+    pub leaf_fri_params_index: usize,
     /// The proofs of leaf verifier or internal verifier in the execution order.
     pub proofs: Vec<Proof<SC>>,
 }
@@ -33,6 +36,7 @@ assert_impl_all!(InternalVmVerifierInput<BabyBearPoseidon2Config>: Serialize, De
 impl InternalVmVerifierInput<SC> {
     pub fn chunk_leaf_or_internal_proofs(
         self_program_commit: [Val<SC>; DIGEST_SIZE],
+        leaf_fri_params_index: usize,
         proofs: &[Proof<SC>],
         chunk: usize,
     ) -> Vec<Self> {
@@ -40,6 +44,7 @@ impl InternalVmVerifierInput<SC> {
             .chunks(chunk)
             .map(|chunk| Self {
                 self_program_commit,
+                leaf_fri_params_index,
                 proofs: chunk.to_vec(),
             })
             .collect()
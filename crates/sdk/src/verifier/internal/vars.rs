@@ -12,6 +12,7 @@ use crate::{
 #[derive(DslVariable, Clone)]
 pub struct InternalVmVerifierInputVariable<C: Config> {
     pub self_program_commit: [Felt<C::F>; DIGEST_SIZE],
+    pub leaf_fri_params_index: Var<C::N>,
     /// The proofs of the execution segments in the execution order.
     pub proofs: Array<C, StarkProofVariable<C>>,
 }
@@ -21,15 +22,18 @@ impl Hintable<C> for InternalVmVerifierInput<SC> {
 
     fn read(builder: &mut Builder<C>) -> Self::HintVariable {
         let self_program_commit = array::from_fn(|_| builder.hint_felt());
+        let leaf_fri_params_index = usize::read(builder);
         let proofs = Vec::<Proof<SC>>::read(builder);
         Self::HintVariable {
             self_program_commit,
+            leaf_fri_params_index,
             proofs,
         }
     }
 
     fn write(&self) -> Vec<Vec<<C as Config>::N>> {
         let mut stream = write_field_slice(&self.self_program_commit);
+        stream.extend(<usize as Hintable<C>>::write(&self.leaf_fri_params_index));
         stream.extend(self.proofs.write());
         stream
     }
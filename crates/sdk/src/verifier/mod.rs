@@ -4,7 +4,10 @@ use openvm_native_compiler::ir::DIGEST_SIZE;
 
 use crate::{config::AggStarkConfig, verifier::common::types::VmVerifierPvs};
 
+pub mod app;
+pub mod batch;
 pub mod common;
+pub mod deferral;
 pub mod internal;
 pub mod leaf;
 pub mod root;
@@ -13,10 +16,18 @@ pub(crate) mod utils;
 const SBOX_SIZE: usize = 7;
 
 impl AggStarkConfig {
+    /// The leaf VM is the same for all allowed leaf FRI parameters, so its constraint degree is
+    /// bounded by the smallest blowup among them.
     pub fn leaf_vm_config(&self) -> NativeConfig {
+        let max_constraint_degree = self
+            .allowed_leaf_fri_params()
+            .iter()
+            .map(|params| params.max_constraint_degree())
+            .min()
+            .unwrap();
         NativeConfig::aggregation(
             VmVerifierPvs::<u8>::width(),
-            SBOX_SIZE.min(self.leaf_fri_params.max_constraint_degree()),
+            SBOX_SIZE.min(max_constraint_degree),
         )
     }
     pub fn internal_vm_config(&self) -> NativeConfig {
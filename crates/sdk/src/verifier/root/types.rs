@@ -30,6 +30,9 @@ pub struct RootVmVerifierPvs<T> {
 #[serde(bound = "")]
 #[derivative(Clone(bound = "Com<SC>: Clone"))]
 pub struct RootVmVerifierInput<SC: StarkGenericConfig> {
+    /// Index of the FRI parameters of the leaf verifier proofs in the allowed leaf FRI parameters
+    /// of the root verifier. Unused if all proofs are internal verifier proofs.
+    pub leaf_fri_params_index: usize,
     /// The proofs of leaf verifier or internal verifier in the execution order.
     pub proofs: Vec<Proof<SC>>,
     /// Public values to expose directly
@@ -6,6 +6,7 @@ use crate::{verifier::root::types::RootVmVerifierInput, C, SC};
 
 #[derive(DslVariable, Clone)]
 pub struct RootVmVerifierInputVariable<C: Config> {
+    pub leaf_fri_params_index: Var<C::N>,
     /// The proofs of leaf verifier or internal verifier in the execution order.
     pub proofs: Array<C, StarkProofVariable<C>>,
     /// Public values to expose
@@ -16,16 +17,19 @@ impl Hintable<C> for RootVmVerifierInput<SC> {
     type HintVariable = RootVmVerifierInputVariable<C>;
 
     fn read(builder: &mut Builder<C>) -> Self::HintVariable {
+        let leaf_fri_params_index = usize::read(builder);
         let proofs = Vec::<Proof<SC>>::read(builder);
         let public_values = Vec::<Val<SC>>::read(builder);
         Self::HintVariable {
+            leaf_fri_params_index,
             proofs,
             public_values,
         }
     }
 
     fn write(&self) -> Vec<Vec<<C as Config>::N>> {
-        let mut stream = self.proofs.write();
+        let mut stream = <usize as Hintable<C>>::write(&self.leaf_fri_params_index);
+        stream.extend(self.proofs.write());
         stream.extend(self.public_values.write());
         stream
     }
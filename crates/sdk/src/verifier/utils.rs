@@ -157,3 +157,24 @@ impl<C: Config> VariableP2Hasher<C> {
         array::from_fn(|i| builder.get(&leaves[0], i))
     }
 }
+
+/// In-circuit version of [crate::commit::AppExecutionCommit::compute] for the exe commit.
+pub(crate) fn compute_exe_commit<C: Config>(
+    builder: &mut Builder<C>,
+    hasher: &VariableP2Hasher<C>,
+    app_commit: [Felt<C::F>; DIGEST_SIZE],
+    init_memory: [Felt<C::F>; DIGEST_SIZE],
+    pc_start: Felt<C::F>,
+) -> [Felt<C::F>; DIGEST_SIZE] {
+    let app_commit_hash = hasher.hash(builder, &app_commit);
+    let init_memory_hash = hasher.hash(builder, &init_memory);
+    let const_zero = hasher.const_zero;
+    let padded_pc_start = array::from_fn(|i| if i == 0 { pc_start } else { const_zero });
+    let pc_start_hash = hasher.hash(builder, &padded_pc_start);
+    let compress_1 = hasher
+        .compressor
+        .compress(builder, &app_commit_hash, &init_memory_hash);
+    hasher
+        .compressor
+        .compress(builder, &compress_1, &pc_start_hash)
+}
@@ -1,33 +1,59 @@
 use std::{borrow::Borrow, path::PathBuf, sync::Arc};
 
-use openvm_build::GuestOptions;
+use openvm::deferral::{StarkClaim, STARK_CLAIM_HEADER_BYTES};
+use openvm_build::{GuestOptions, TargetFilter};
 use openvm_circuit::{
     arch::{
-        hasher::poseidon2::vm_poseidon2_hasher, ExecutionError, SingleSegmentVmExecutor,
-        SystemConfig, VmConfig, VmExecutor,
+        hasher::{poseidon2::vm_poseidon2_hasher, Hasher},
+        instructions::program::Program,
+        verify_segments, ExecutionError, SingleSegmentVmExecutor, SystemConfig, VirtualMachine,
+        VmConfig, VmExecutor,
     },
     system::{memory::tree::public_values::UserPublicValuesProof, program::trace::VmCommittedExe},
 };
 use openvm_native_circuit::{Native, NativeConfig};
 use openvm_native_compiler::{conversion::CompilerOptions, prelude::*};
-use openvm_native_recursion::{halo2::utils::CacheHalo2ParamsReader, types::InnerConfig};
+use openvm_native_recursion::{
+    halo2::{utils::CacheHalo2ParamsReader, wrapper::evm_deploy_and_call},
+    hints::Hintable,
+    types::InnerConfig,
+};
 use openvm_rv32im_transpiler::{Rv32ITranspilerExtension, Rv32MTranspilerExtension};
 use openvm_sdk::{
-    config::{AggConfig, AggStarkConfig, AppConfig, Halo2Config},
-    keygen::AppProvingKey,
+    commit::{compute_app_exe_commit, AppExecutionCommit},
+    config::{AggConfig, AggStarkConfig, AppConfig, FinalStage, Halo2Config, SdkVmConfig},
+    evm_wrapper::encode_verify_calldata,
+    keygen::{
+        app_vm_keygen, AggStarkProvingKey, AppProvingKey, BatchAggStarkProvingKey,
+        DeferralAggStarkProvingKey,
+    },
+    prover::{AppProver, AppRunProof, BatchAggStarkProver, DeferralAggStarkProver, StarkProver},
     verifier::{
+        batch::types::{leaf_verifier_whitelist_commit, BatchRootVmVerifierPvs},
         common::types::VmVerifierPvs,
-        leaf::types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+        deferral::types::{stark_claim, stark_claim_to_public_values, DeferredClaimLayout},
+        internal::{types::InternalVmVerifierInput, InternalVmVerifierConfig},
+        leaf::{
+            types::{LeafVmVerifierInput, UserPublicValuesRootProof},
+            LeafVmVerifierConfig,
+        },
+        root::types::RootVmVerifierPvs,
     },
     Sdk, StdIn,
 };
 use openvm_stark_sdk::{
     config::{
+        baby_bear_keccak::{BabyBearKeccakConfig, BabyBearKeccakEngine},
         baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+        baby_bear_poseidon2_root::BabyBearPoseidon2RootConfig,
         fri_params::standard_fri_params_with_100_bits_conjectured_security,
     },
     engine::{StarkEngine, StarkFriEngine},
-    openvm_stark_backend::{p3_field::AbstractField, Chip},
+    openvm_stark_backend::{
+        p3_field::{AbstractField, PrimeField32},
+        prover::types::Proof,
+        Chip,
+    },
     p3_baby_bear::BabyBear,
 };
 use openvm_transpiler::transpiler::Transpiler;
@@ -63,27 +89,43 @@ where
 }
 
 fn app_committed_exe_for_test(app_log_blowup: usize) -> Arc<VmCommittedExe<SC>> {
-    let program = {
-        let n = 200;
-        let mut builder = Builder::<C>::default();
-        let a: Felt<F> = builder.eval(F::ZERO);
-        let b: Felt<F> = builder.eval(F::ONE);
-        let c: Felt<F> = builder.uninit();
-        builder.range(0, n).for_each(|_, builder| {
-            builder.assign(&c, a + b);
-            builder.assign(&a, b);
-            builder.assign(&b, c);
-        });
-        builder.halt();
-        builder.compile_isa()
-    };
+    fib_committed_exe_for_test(app_log_blowup, 200)
+}
+
+fn fib_committed_exe_for_test(app_log_blowup: usize, n: usize) -> Arc<VmCommittedExe<SC>> {
     Sdk.commit_app_exe(
         standard_fri_params_with_100_bits_conjectured_security(app_log_blowup),
-        program.into(),
+        fib_program(n).into(),
     )
     .unwrap()
 }
 
+fn fib_program(n: usize) -> Program<F> {
+    let mut builder = Builder::<C>::default();
+    let a: Felt<F> = builder.eval(F::ZERO);
+    let b: Felt<F> = builder.eval(F::ONE);
+    let c: Felt<F> = builder.uninit();
+    builder.range(0, n).for_each(|_, builder| {
+        builder.assign(&c, a + b);
+        builder.assign(&a, b);
+        builder.assign(&b, c);
+    });
+    builder.halt();
+    builder.compile_isa()
+}
+
+/// Public values of a root verifier proof.
+fn root_public_values(root_proof: &Proof<BabyBearPoseidon2RootConfig>) -> Vec<F> {
+    // The connector AIR also has public values, but fewer than two digests.
+    root_proof
+        .per_air
+        .iter()
+        .find(|air| air.public_values.len() >= 2 * DIGEST_SIZE)
+        .unwrap()
+        .public_values
+        .clone()
+}
+
 fn agg_config_for_test() -> AggConfig {
     AggConfig {
         agg_stark_config: agg_stark_config_for_test(),
@@ -91,6 +133,7 @@ fn agg_config_for_test() -> AggConfig {
             verifier_k: 24,
             wrapper_k: None,
         },
+        final_stage: FinalStage::Halo2,
     }
 }
 
@@ -98,6 +141,7 @@ fn agg_stark_config_for_test() -> AggStarkConfig {
     AggStarkConfig {
         max_num_user_public_values: NUM_PUB_VALUES,
         leaf_fri_params: standard_fri_params_with_100_bits_conjectured_security(LEAF_LOG_BLOWUP),
+        additional_leaf_fri_params: vec![],
         internal_fri_params: standard_fri_params_with_100_bits_conjectured_security(
             INTERNAL_LOG_BLOWUP,
         ),
@@ -107,6 +151,7 @@ fn agg_stark_config_for_test() -> AggStarkConfig {
             compile_prints: true,
             ..Default::default()
         },
+        security: None,
     }
 }
 
@@ -127,6 +172,7 @@ fn small_test_app_config(app_log_blowup: usize) -> AppConfig<NativeConfig> {
             enable_cycle_tracker: true,
             ..Default::default()
         },
+        security: None,
     }
 }
 
@@ -252,6 +298,423 @@ fn test_public_values_and_leaf_verification() {
     }
 }
 
+#[test]
+fn test_verifier_program_optimizations() {
+    let app_config = small_test_app_config(3);
+    let app_vm_vk = AppProvingKey::keygen(app_config.clone())
+        .app_vm_pk
+        .vm_pk
+        .get_vk();
+    let agg_stark_config = agg_stark_config_for_test();
+    let leaf_vm_vk = VirtualMachine::new(
+        BabyBearPoseidon2Engine::new(agg_stark_config.leaf_fri_params),
+        agg_stark_config.leaf_vm_config(),
+    )
+    .keygen()
+    .get_vk();
+    let internal_vm_vk = VirtualMachine::new(
+        BabyBearPoseidon2Engine::new(agg_stark_config.internal_fri_params),
+        agg_stark_config.internal_vm_config(),
+    )
+    .keygen()
+    .get_vk();
+
+    let num_instructions = |compiler_options: CompilerOptions| {
+        let leaf_program = LeafVmVerifierConfig {
+            app_fri_params: app_config.app_fri_params.fri_params,
+            app_system_config: app_config.app_vm_config.system().clone(),
+            compiler_options,
+        }
+        .build_program(&app_vm_vk);
+        let internal_program = InternalVmVerifierConfig {
+            allowed_leaf_fri_params: agg_stark_config.allowed_leaf_fri_params(),
+            internal_fri_params: agg_stark_config.internal_fri_params,
+            compiler_options,
+        }
+        .build_program(std::slice::from_ref(&leaf_vm_vk), &internal_vm_vk);
+        (leaf_program.len(), internal_program.len())
+    };
+    let (leaf, internal) = num_instructions(agg_stark_config.compiler_options);
+    let (optimized_leaf, optimized_internal) =
+        num_instructions(agg_stark_config.compiler_options.with_optimizations());
+    tracing::info!(
+        leaf,
+        optimized_leaf,
+        internal,
+        optimized_internal,
+        "verifier program instructions"
+    );
+    assert!(optimized_leaf < leaf);
+    assert!(optimized_internal < internal);
+}
+
+#[test]
+fn test_agg_with_different_leaf_blowups() {
+    let app_log_blowup = 1;
+    let leaf_log_blowups = [LEAF_LOG_BLOWUP, LEAF_LOG_BLOWUP + 1];
+    let agg_stark_pk = AggStarkProvingKey::keygen(AggStarkConfig {
+        additional_leaf_fri_params: vec![standard_fri_params_with_100_bits_conjectured_security(
+            leaf_log_blowups[1],
+        )],
+        ..agg_stark_config_for_test()
+    });
+    let root_verifier_pk = &agg_stark_pk.root_verifier_pk;
+    let root_vm = SingleSegmentVmExecutor::new(root_verifier_pk.vm_pk.vm_config.clone());
+
+    for (leaf_fri_params_index, leaf_log_blowup) in leaf_log_blowups.into_iter().enumerate() {
+        let app_config = AppConfig {
+            leaf_fri_params: standard_fri_params_with_100_bits_conjectured_security(
+                leaf_log_blowup,
+            )
+            .into(),
+            ..small_test_app_config(app_log_blowup)
+        };
+        let app_pk = Arc::new(Sdk.app_keygen(app_config).unwrap());
+        let leaf_verifier_commit = app_pk.commit_in_babybear();
+        let prover = StarkProver::new(
+            app_pk,
+            app_committed_exe_for_test(app_log_blowup),
+            agg_stark_pk.clone(),
+        );
+        let mut root_input = prover.try_generate_root_input(StdIn::default()).unwrap();
+        assert_eq!(root_input.leaf_fri_params_index, leaf_fri_params_index);
+
+        let public_values: Vec<_> = root_vm
+            .execute(
+                root_verifier_pk.root_committed_exe.exe.clone(),
+                root_input.write(),
+            )
+            .unwrap()
+            .public_values
+            .into_iter()
+            .map(|v| v.unwrap())
+            .collect();
+        let root_pvs = RootVmVerifierPvs::from_flatten(public_values);
+        assert_eq!(root_pvs.leaf_verifier_commit, leaf_verifier_commit);
+
+        // Failure: the index selects the FRI parameters of the other leaf VM.
+        root_input.leaf_fri_params_index = 1 - leaf_fri_params_index;
+        let execution_result = root_vm.execute(
+            root_verifier_pk.root_committed_exe.exe.clone(),
+            root_input.write(),
+        );
+        assert!(execution_result.is_err());
+    }
+}
+
+#[test]
+fn test_batch_agg_proof() {
+    let app_log_blowup = 1;
+    let app_pk = Sdk
+        .app_keygen(small_test_app_config(app_log_blowup))
+        .unwrap();
+    let app_vk = app_pk.get_vk();
+    let leaf_verifier_commit = app_pk.commit_in_babybear();
+    let agg_stark_config = agg_stark_config_for_test();
+    let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config.clone());
+    let batch_stark_pk = BatchAggStarkProvingKey::keygen(
+        &agg_stark_config,
+        &agg_stark_pk,
+        vec![leaf_verifier_commit],
+        &app_pk,
+    );
+    let app_pk = Arc::new(app_pk);
+
+    // 3 runs so the first batch layer has an uneven last chunk.
+    let app_committed_exes: Vec<_> = [100, 200, 300]
+        .map(|n| fib_committed_exe_for_test(app_log_blowup, n))
+        .into();
+    let app_proofs = app_committed_exes
+        .iter()
+        .map(|app_committed_exe| {
+            let app_proof = Sdk
+                .generate_app_proof(app_pk.clone(), app_committed_exe.clone(), StdIn::default())
+                .unwrap();
+            AppRunProof::new(&app_pk, app_proof)
+        })
+        .collect();
+    let (root_proof, commit_tree) = BatchAggStarkProver::new(agg_stark_pk, batch_stark_pk)
+        .with_num_children_batch(2)
+        .generate_batch_agg_proof(app_proofs);
+
+    let root_pvs = BatchRootVmVerifierPvs::from_flatten(root_public_values(&root_proof));
+    assert_eq!(root_pvs.batch_commit, commit_tree.batch_commit());
+    assert_eq!(commit_tree.num_runs(), 3);
+    assert_eq!(root_pvs.num_runs, F::from_canonical_usize(3));
+    for (run_idx, app_committed_exe) in app_committed_exes.iter().enumerate() {
+        let inclusion_proof = commit_tree.inclusion_proof(run_idx);
+        assert!(inclusion_proof.verify(&root_pvs.batch_commit));
+        assert_eq!(
+            inclusion_proof.run.exe_commit,
+            compute_app_exe_commit(app_vk.memory_dimensions, app_committed_exe)
+        );
+        assert_eq!(
+            inclusion_proof.run.leaf_verifier_commit,
+            leaf_verifier_commit
+        );
+    }
+}
+
+#[test]
+fn test_batch_agg_whitelist() {
+    // Apps with different app VKs, hence different leaf verifiers. Only the first two are
+    // whitelisted. The second one is aggregated with the additional leaf FRI parameters.
+    let app_log_blowups = [1, 2, 3];
+    let leaf_log_blowups = [LEAF_LOG_BLOWUP, LEAF_LOG_BLOWUP + 1, LEAF_LOG_BLOWUP];
+    let app_pks: [_; 3] = std::array::from_fn(|app_idx| {
+        let app_config = AppConfig {
+            leaf_fri_params: standard_fri_params_with_100_bits_conjectured_security(
+                leaf_log_blowups[app_idx],
+            )
+            .into(),
+            ..small_test_app_config(app_log_blowups[app_idx])
+        };
+        Arc::new(Sdk.app_keygen(app_config).unwrap())
+    });
+    let leaf_verifier_commits = app_pks.each_ref().map(|app_pk| app_pk.commit_in_babybear());
+    let whitelist = leaf_verifier_commits[..2].to_vec();
+    let agg_stark_config = AggStarkConfig {
+        additional_leaf_fri_params: vec![standard_fri_params_with_100_bits_conjectured_security(
+            LEAF_LOG_BLOWUP + 1,
+        )],
+        ..agg_stark_config_for_test()
+    };
+    let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config.clone());
+    let batch_stark_pk = BatchAggStarkProvingKey::keygen(
+        &agg_stark_config,
+        &agg_stark_pk,
+        whitelist.clone(),
+        &app_pks[0],
+    );
+    let prover = BatchAggStarkProver::new(agg_stark_pk.clone(), batch_stark_pk.clone());
+    let app_proof = |app_idx: usize| {
+        let app_pk = &app_pks[app_idx];
+        let app_proof = Sdk
+            .generate_app_proof(
+                app_pk.clone(),
+                app_committed_exe_for_test(app_log_blowups[app_idx]),
+                StdIn::default(),
+            )
+            .unwrap();
+        AppRunProof::new(app_pk, app_proof)
+    };
+
+    let (root_proof, commit_tree) =
+        prover.generate_batch_agg_proof(vec![app_proof(0), app_proof(1)]);
+    let root_pvs = BatchRootVmVerifierPvs::from_flatten(root_public_values(&root_proof));
+    assert_eq!(
+        root_pvs.leaf_verifier_whitelist_commit,
+        leaf_verifier_whitelist_commit(&whitelist)
+    );
+    assert_eq!(root_pvs.batch_commit, commit_tree.batch_commit());
+    for run_idx in 0..2 {
+        let inclusion_proof = commit_tree.inclusion_proof(run_idx);
+        assert!(inclusion_proof.verify(&root_pvs.batch_commit));
+        assert_eq!(
+            inclusion_proof.run.leaf_verifier_commit,
+            leaf_verifier_commits[run_idx]
+        );
+    }
+
+    // Failure: the batch verifier program rejects a run whose leaf verifier is not whitelisted.
+    let internal_vm = SingleSegmentVmExecutor::new(agg_stark_pk.internal_vm_pk.vm_config.clone());
+    let run_batch_verifier = |app_idx: usize| {
+        let input = InternalVmVerifierInput {
+            self_program_commit: batch_stark_pk.batch_program_commit(),
+            leaf_fri_params_index: 0,
+            proofs: vec![prover.generate_run_proof(&app_proof(app_idx))],
+        };
+        internal_vm.execute(
+            batch_stark_pk.batch_committed_exe.exe.clone(),
+            input.write(),
+        )
+    };
+    assert!(run_batch_verifier(1).is_ok());
+    assert!(matches!(
+        run_batch_verifier(2),
+        Err(ExecutionError::Fail { .. })
+    ));
+}
+
+#[test]
+fn test_deferral_agg() {
+    // The claimed execution.
+    let claimed_log_blowup = 1;
+    let claimed_app_config = small_test_app_config(claimed_log_blowup);
+    let claimed_app_pk = Arc::new(Sdk.app_keygen(claimed_app_config.clone()).unwrap());
+    let claimed_committed_exe = app_committed_exe_for_test(claimed_log_blowup);
+    let claimed_app_proof = Sdk
+        .generate_app_proof(
+            claimed_app_pk.clone(),
+            claimed_committed_exe.clone(),
+            StdIn::default(),
+        )
+        .unwrap();
+    let claim = stark_claim(
+        &AppExecutionCommit::compute(
+            &claimed_app_config.app_vm_config,
+            &claimed_committed_exe,
+            &claimed_app_pk.leaf_committed_exe,
+        ),
+        &claimed_app_proof,
+    );
+
+    // The guest revealing the claim at byte offset 0.
+    let num_public_values = 2 * STARK_CLAIM_HEADER_BYTES;
+    let app_log_blowup = 2;
+    let app_config = AppConfig {
+        app_fri_params: standard_fri_params_with_100_bits_conjectured_security(app_log_blowup)
+            .into(),
+        app_vm_config: SdkVmConfig::builder()
+            .system(
+                SystemConfig::default()
+                    .with_continuations()
+                    .with_public_values(num_public_values)
+                    .into(),
+            )
+            .rv32i(Default::default())
+            .rv32m(Default::default())
+            .io(Default::default())
+            .build(),
+        leaf_fri_params: standard_fri_params_with_100_bits_conjectured_security(LEAF_LOG_BLOWUP)
+            .into(),
+        compiler_options: Default::default(),
+        security: None,
+    };
+    let elf = Sdk
+        .build(
+            GuestOptions::default(),
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("programs"),
+            &Some(TargetFilter {
+                name: "deferral".to_string(),
+                kind: "example".to_string(),
+            }),
+        )
+        .unwrap();
+    let exe = Sdk
+        .transpile(elf, app_config.app_vm_config.transpiler())
+        .unwrap();
+    let app_committed_exe = Sdk
+        .commit_app_exe(app_config.app_fri_params.fri_params, exe)
+        .unwrap();
+    let app_pk = Arc::new(Sdk.app_keygen(app_config.clone()).unwrap());
+    let app_commit = AppExecutionCommit::compute(
+        &app_config.app_vm_config,
+        &app_committed_exe,
+        &app_pk.leaf_committed_exe,
+    );
+
+    let agg_stark_config = AggStarkConfig {
+        max_num_user_public_values: num_public_values,
+        ..agg_stark_config_for_test()
+    };
+    let agg_stark_pk = AggStarkProvingKey::keygen(agg_stark_config.clone());
+    let deferral_stark_pk = DeferralAggStarkProvingKey::keygen(
+        &agg_stark_config,
+        &agg_stark_pk,
+        vec![DeferredClaimLayout {
+            byte_offset: 0,
+            num_public_values: NUM_PUB_VALUES,
+        }],
+    );
+    let root_verifier_pk = deferral_stark_pk.root_verifier_pk.clone();
+    let root_vm = SingleSegmentVmExecutor::new(root_verifier_pk.vm_pk.vm_config.clone());
+    let prover = DeferralAggStarkProver::new(agg_stark_pk, deferral_stark_pk);
+    let run_root_verifier = |claim: &StarkClaim| {
+        let mut stdin = StdIn::default();
+        stdin.write(claim);
+        let app_proof = Sdk
+            .generate_app_proof(app_pk.clone(), app_committed_exe.clone(), stdin)
+            .unwrap();
+        let root_input = prover.generate_root_input(
+            AppRunProof::new(&app_pk, app_proof),
+            vec![AppRunProof::new(&claimed_app_pk, claimed_app_proof.clone())],
+        );
+        root_vm.execute(
+            root_verifier_pk.root_committed_exe.exe.clone(),
+            root_input.write(),
+        )
+    };
+
+    let public_values: Vec<_> = run_root_verifier(&claim)
+        .unwrap()
+        .public_values
+        .into_iter()
+        .map(|v| v.unwrap())
+        .collect();
+    let root_pvs = RootVmVerifierPvs::from_flatten(public_values);
+    assert_eq!(root_pvs.exe_commit, app_commit.exe_commit);
+    assert_eq!(
+        root_pvs.leaf_verifier_commit,
+        app_commit.leaf_vm_verifier_commit
+    );
+    let claim_public_values: Vec<_> = stark_claim_to_public_values(&claim)
+        .into_iter()
+        .map(F::from_canonical_u8)
+        .collect();
+    assert_eq!(
+        root_pvs.public_values[..claim_public_values.len()],
+        claim_public_values
+    );
+
+    // Failure: the claimed public values are not the ones of the execution.
+    let mut wrong_claim = claim.clone();
+    wrong_claim.public_values[0] ^= 1;
+    assert!(run_root_verifier(&wrong_claim).is_err());
+
+    // Failure: the claim is for another exe.
+    let mut wrong_claim = claim.clone();
+    wrong_claim.exe_commit[0] ^= 1;
+    assert!(run_root_verifier(&wrong_claim).is_err());
+
+    // Failure: a word of the exe commit is not canonical, although it is the same field element.
+    let mut wrong_claim = claim;
+    wrong_claim.exe_commit[0] += F::ORDER_U32;
+    assert!(run_root_verifier(&wrong_claim).is_err());
+}
+
+#[test]
+fn test_verify_app_proof_for_exe() {
+    let app_log_blowup = 3;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Arc::new(AppProvingKey::keygen(app_config));
+    let app_committed_exe = app_committed_exe_for_test(app_log_blowup);
+    let app_vk = app_pk.get_vk();
+
+    let app_proof = Sdk
+        .generate_app_proof(app_pk.clone(), app_committed_exe.clone(), StdIn::default())
+        .unwrap();
+    let exe_commit = compute_app_exe_commit(app_vk.memory_dimensions, &app_committed_exe);
+    let verified = Sdk.verify_app_proof(&app_vk, &app_proof).unwrap();
+    assert_eq!(verified.exe_commit, exe_commit);
+    let public_values = Sdk
+        .verify_app_proof_for_exe(&app_vk, &app_proof, &exe_commit)
+        .unwrap();
+    assert_eq!(public_values, verified.user_public_values);
+    assert_eq!(public_values.len(), NUM_PUB_VALUES);
+
+    // Failure: the proof is for another exe.
+    let mut wrong_exe_commit = exe_commit;
+    wrong_exe_commit[0] += F::ONE;
+    assert!(Sdk
+        .verify_app_proof_for_exe(&app_vk, &app_proof, &wrong_exe_commit)
+        .is_err());
+
+    // Failure: the public values are not the ones in the final memory.
+    let mut wrong_app_proof = app_proof.clone();
+    wrong_app_proof.user_public_values.public_values[0] += F::ONE;
+    wrong_app_proof.user_public_values.public_values_commit =
+        vm_poseidon2_hasher().merkle_root(&wrong_app_proof.user_public_values.public_values);
+    assert!(Sdk.verify_app_proof(&app_vk, &wrong_app_proof).is_err());
+
+    // Failure: the first segment is missing, so the execution doesn't start from the exe.
+    let mut wrong_app_proof = app_proof;
+    wrong_app_proof.per_segment.remove(0);
+    assert!(Sdk
+        .verify_app_proof_for_exe(&app_vk, &wrong_app_proof, &exe_commit)
+        .is_err());
+}
+
 #[test]
 fn test_e2e_proof_generation_and_verification() {
     let app_log_blowup = 1;
@@ -264,6 +727,10 @@ fn test_e2e_proof_generation_and_verification() {
     let evm_verifier = Sdk
         .generate_snark_verifier_contract(&params_reader, &agg_pk)
         .unwrap();
+    let wrapper = Sdk
+        .generate_snark_verifier_wrapper_contract(&agg_pk)
+        .unwrap();
+    assert_eq!(wrapper.num_user_public_values, NUM_PUB_VALUES);
 
     let evm_proof = Sdk
         .generate_evm_proof(
@@ -275,6 +742,49 @@ fn test_e2e_proof_generation_and_verification() {
         )
         .unwrap();
     assert!(Sdk.verify_evm_proof(&evm_verifier, &evm_proof));
+    wrapper.evm_verify(&evm_verifier, &evm_proof).unwrap();
+
+    // Failure: the exe commit passed to the wrapper is not the one of the proof.
+    let mut calldata = encode_verify_calldata(&evm_proof).unwrap();
+    calldata[4 + 2 * 32 - 1] ^= 1;
+    assert!(evm_deploy_and_call(wrapper.deployment_code(&evm_verifier), calldata).is_err());
+}
+
+#[test]
+fn test_e2e_keccak_stark_final_stage() {
+    let app_log_blowup = 1;
+    let app_config = small_test_app_config(app_log_blowup);
+    let app_pk = Sdk.app_keygen(app_config).unwrap();
+    let agg_config = AggConfig {
+        final_stage: FinalStage::KeccakStark,
+        ..agg_config_for_test()
+    };
+    let agg_pk = Sdk.keccak_agg_keygen(agg_config).unwrap();
+    let evm_verifier = Sdk
+        .generate_keccak_stark_verifier_contract(&agg_pk)
+        .unwrap();
+    let keccak_root_pk = agg_pk.keccak_root_pk.clone();
+
+    let root_proof = Sdk
+        .generate_keccak_root_proof(
+            Arc::new(app_pk),
+            app_committed_exe_for_test(app_log_blowup),
+            agg_pk,
+            StdIn::default(),
+        )
+        .unwrap();
+    let proof = keccak_root_pk.encode_proof_for_evm(&root_proof).unwrap();
+    evm_verifier.evm_verify(&proof).unwrap();
+
+    // Failure: a public value of the proof is changed. They come first in the encoding.
+    let mut wrong_proof = proof.clone();
+    wrong_proof[3] ^= 1;
+    assert!(evm_verifier.evm_verify(&wrong_proof).is_err());
+
+    // Failure: the opening of the last FRI query is changed.
+    let mut wrong_proof = proof;
+    *wrong_proof.last_mut().unwrap() ^= 1;
+    assert!(evm_verifier.evm_verify(&wrong_proof).is_err());
 }
 
 #[test]
@@ -299,3 +809,27 @@ fn test_sdk_guest_build_and_transpile() {
         .with_extension(Rv32MTranspilerExtension);
     let _exe = sdk.transpile(one, transpiler).unwrap();
 }
+
+#[test]
+fn test_app_proof_in_keccak_config() {
+    let app_log_blowup = 1;
+    let fri_params = standard_fri_params_with_100_bits_conjectured_security(app_log_blowup);
+    let app_vm_pk = Arc::new(
+        app_vm_keygen::<BabyBearKeccakConfig, BabyBearKeccakEngine, _>(
+            fri_params,
+            small_test_app_config(app_log_blowup).app_vm_config,
+        ),
+    );
+    let engine = BabyBearKeccakEngine::new(fri_params);
+    let app_committed_exe = Arc::new(VmCommittedExe::<BabyBearKeccakConfig>::commit(
+        fib_program(200).into(),
+        engine.config().pcs(),
+    ));
+    let app_proof = AppProver::<_, BabyBearKeccakConfig, BabyBearKeccakEngine>::new_with_engine(
+        app_vm_pk.clone(),
+        app_committed_exe,
+    )
+    .generate_app_proof(StdIn::default());
+    assert!(app_proof.per_segment.len() > 1);
+    verify_segments(&engine, &app_vm_pk.vm_pk.get_vk(), &app_proof.per_segment).unwrap();
+}
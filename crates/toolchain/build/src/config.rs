@@ -14,6 +14,8 @@ pub struct GuestOptions {
     pub options: Vec<String>,
     /// Configuration flags to build the guest with.
     pub rustc_flags: Vec<String>,
+    /// Target features to enable on top of the base `rv32im` ISA, e.g. `+zbb`.
+    pub target_features: Vec<String>,
     /// Cargo profile
     pub profile: Option<String>,
     /// Target directory
@@ -42,6 +44,16 @@ impl GuestOptions {
         self
     }
 
+    /// Add target features for building the guest.
+    pub fn with_target_features<S: AsRef<str>>(
+        mut self,
+        target_features: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.target_features
+            .extend(target_features.into_iter().map(|s| s.as_ref().to_string()));
+        self
+    }
+
     /// Set the cargo profile.
     pub fn with_profile(mut self, profile: String) -> Self {
         self.profile = Some(profile);
@@ -267,11 +267,20 @@ pub fn build_guest_package(
     let runtime_rust_flags = runtime_lib
         .map(|lib| vec![String::from("-C"), format!("link_arg={}", lib)])
         .unwrap_or_default();
+    let target_feature_flags = if guest_opts.target_features.is_empty() {
+        vec![]
+    } else {
+        vec![
+            String::from("-C"),
+            format!("target-feature={}", guest_opts.target_features.join(",")),
+        ]
+    };
     let rust_flags: Vec<_> = [
         runtime_rust_flags
             .iter()
             .map(|s| s.as_str())
             .collect::<Vec<_>>(),
+        target_feature_flags.iter().map(|s| s.as_str()).collect(),
         guest_opts.rustc_flags.iter().map(|s| s.as_str()).collect(),
     ]
     .concat();
@@ -0,0 +1,66 @@
+//! Deferred verification of OpenVM STARK proofs.
+//!
+//! A guest cannot efficiently verify a STARK proof itself. Instead, [verify_stark] reveals a
+//! [StarkClaim] in the public values of the guest. The claim is checked against the actual proof
+//! when the proof of the guest is aggregated by a deferral verifier, which is generated for a
+//! fixed layout of claims in the public values.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::io::reveal;
+
+/// Number of public values of a [StarkClaim] before the public values of the claimed execution:
+/// the exe commit followed by the leaf verifier commit, each as 8 little-endian `u32` words.
+pub const STARK_CLAIM_HEADER_BYTES: usize = 64;
+
+/// A claim that an execution was proven by OpenVM.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StarkClaim {
+    /// Commitment of the executed exe, as canonical BabyBear field elements.
+    pub exe_commit: [u32; 8],
+    /// Commitment of the leaf verifier program, as canonical BabyBear field elements. It
+    /// identifies the app VM config the execution was proven with.
+    pub leaf_verifier_commit: [u32; 8],
+    /// Public values revealed by the execution.
+    pub public_values: Vec<u8>,
+}
+
+impl StarkClaim {
+    /// Number of public values the claim occupies when revealed.
+    pub fn num_bytes(&self) -> usize {
+        STARK_CLAIM_HEADER_BYTES + self.public_values.len()
+    }
+}
+
+/// Defers the verification of the proof of `claim` by revealing it at `byte_offset` of the public
+/// values. The proof of the guest is only valid once aggregated together with a proof of the
+/// claimed execution.
+///
+/// `byte_offset` and the number of public values of the claim must be multiples of 4 and must
+/// match the layout the deferral verifier was generated with.
+/// The words of the commitments must be canonical field elements, otherwise the deferral
+/// verifier rejects the claim.
+pub fn verify_stark(claim: &StarkClaim, byte_offset: usize) {
+    assert_eq!(byte_offset % 4, 0, "byte_offset must be a multiple of 4");
+    assert_eq!(
+        claim.public_values.len() % 4,
+        0,
+        "number of public values must be a multiple of 4"
+    );
+    let words = claim
+        .exe_commit
+        .iter()
+        .chain(claim.leaf_verifier_commit.iter())
+        .copied()
+        .chain(
+            claim
+                .public_values
+                .chunks_exact(4)
+                .map(|word| u32::from_le_bytes(word.try_into().unwrap())),
+        );
+    for (i, word) in words.enumerate() {
+        reveal(word, byte_offset / 4 + i);
+    }
+}
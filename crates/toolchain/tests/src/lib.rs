@@ -48,13 +48,22 @@ pub fn build_example_program_at_path_with_features<S: AsRef<str>>(
     manifest_dir: PathBuf,
     example_name: &str,
     features: impl IntoIterator<Item = S>,
+) -> Result<Elf> {
+    build_example_program_at_path_with_options(
+        manifest_dir,
+        example_name,
+        GuestOptions::default().with_features(features),
+    )
+}
+
+pub fn build_example_program_at_path_with_options(
+    manifest_dir: PathBuf,
+    example_name: &str,
+    guest_opts: GuestOptions,
 ) -> Result<Elf> {
     let pkg = get_package(manifest_dir);
     let target_dir = tempdir()?;
-    // Build guest with default features
-    let guest_opts = GuestOptions::default()
-        .with_features(features)
-        .with_target_dir(target_dir.path());
+    let guest_opts = guest_opts.with_target_dir(target_dir.path());
     if let Err(Some(code)) = build_guest_package(
         &pkg,
         &guest_opts,
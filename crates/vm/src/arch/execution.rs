@@ -58,6 +58,8 @@ pub enum ExecutionError {
         discriminant: PhantomDiscriminant,
         inner: eyre::Error,
     },
+    #[error("execution was interrupted after segment {segment_idx}")]
+    Interrupted { segment_idx: usize },
 }
 
 pub trait InstructionExecutor<F> {
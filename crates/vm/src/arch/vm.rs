@@ -1,4 +1,6 @@
-use std::{borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, sync::Arc};
+use std::{
+    borrow::Borrow, collections::VecDeque, marker::PhantomData, mem, ops::ControlFlow, sync::Arc,
+};
 
 use openvm_instructions::exe::VmExe;
 use openvm_stark_backend::{
@@ -107,6 +109,18 @@ where
         &self,
         exe: impl Into<VmExe<F>>,
         input: impl Into<Streams<F>>,
+    ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
+        self.execute_segments_with_hook(exe, input, |_| ControlFlow::Continue(()))
+    }
+
+    /// Same as [VmExecutor::execute_segments], but calls `on_segment` with the index of every
+    /// segment once it is executed. Execution stops with [ExecutionError::Interrupted] if
+    /// `on_segment` returns [ControlFlow::Break].
+    pub fn execute_segments_with_hook(
+        &self,
+        exe: impl Into<VmExe<F>>,
+        input: impl Into<Streams<F>>,
+        mut on_segment: impl FnMut(usize) -> ControlFlow<()>,
     ) -> Result<Vec<ExecutionSegment<F, VC>>, ExecutionError> {
         #[cfg(feature = "bench-metrics")]
         let start = std::time::Instant::now();
@@ -130,6 +144,11 @@ where
             let state = tracing::info_span!("execute_segment", segment = segments.len())
                 .in_scope(|| segment.execute_from_pc(pc))?;
             pc = state.pc;
+            if on_segment(segments.len()).is_break() {
+                return Err(ExecutionError::Interrupted {
+                    segment_idx: segments.len(),
+                });
+            }
 
             if state.is_terminated {
                 break;
@@ -206,7 +225,7 @@ where
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        self.execute_and_generate_impl(exe.into(), None, input)
+        self.execute_and_generate_impl(exe.into(), None, input, |_| ControlFlow::Continue(()))
     }
 
     pub fn execute_and_generate_with_cached_program<SC: StarkGenericConfig>(
@@ -214,6 +233,22 @@ where
         commited_exe: Arc<VmCommittedExe<SC>>,
         input: impl Into<Streams<F>>,
     ) -> Result<VmExecutorResult<SC>, ExecutionError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+        VC::Executor: Chip<SC>,
+        VC::Periphery: Chip<SC>,
+    {
+        self.execute_and_generate_with_hook(commited_exe, input, |_| ControlFlow::Continue(()))
+    }
+
+    /// Same as [VmExecutor::execute_and_generate_with_cached_program], but calls `on_segment`
+    /// as in [VmExecutor::execute_segments_with_hook].
+    pub fn execute_and_generate_with_hook<SC: StarkGenericConfig>(
+        &self,
+        commited_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        on_segment: impl FnMut(usize) -> ControlFlow<()>,
+    ) -> Result<VmExecutorResult<SC>, ExecutionError>
     where
         Domain<SC>: PolynomialSpace<Val = F>,
         VC::Executor: Chip<SC>,
@@ -223,20 +258,23 @@ where
             commited_exe.exe.clone(),
             Some(commited_exe.committed_program.clone()),
             input,
+            on_segment,
         )
     }
+
     fn execute_and_generate_impl<SC: StarkGenericConfig>(
         &self,
         exe: VmExe<F>,
         committed_program: Option<CommittedTraceData<SC>>,
         input: impl Into<Streams<F>>,
+        on_segment: impl FnMut(usize) -> ControlFlow<()>,
     ) -> Result<VmExecutorResult<SC>, ExecutionError>
     where
         Domain<SC>: PolynomialSpace<Val = F>,
         VC::Executor: Chip<SC>,
         VC::Periphery: Chip<SC>,
     {
-        let mut segments = self.execute_segments(exe, input)?;
+        let mut segments = self.execute_segments_with_hook(exe, input, on_segment)?;
         let final_memory = mem::take(&mut segments.last_mut().unwrap().final_memory);
 
         #[allow(unused_variables)]
@@ -387,6 +425,12 @@ pub enum VmVerificationError {
     #[error("number of public values mismatch (expected: {expected}, actual: {actual})")]
     NumPublicValuesMismatch { expected: usize, actual: usize },
 
+    #[error("no segment proofs")]
+    NoSegments,
+
+    #[error("missing proof of air {air_id}")]
+    MissingAir { air_id: usize },
+
     #[error("stark verification error: {0}")]
     StarkError(#[from] VerificationError),
 }
@@ -477,6 +521,20 @@ where
             .execute_and_generate_with_cached_program(committed_exe, input)
     }
 
+    /// See [VmExecutor::execute_and_generate_with_hook].
+    pub fn execute_and_generate_with_hook(
+        &self,
+        committed_exe: Arc<VmCommittedExe<SC>>,
+        input: impl Into<Streams<F>>,
+        on_segment: impl FnMut(usize) -> ControlFlow<()>,
+    ) -> Result<VmExecutorResult<SC>, ExecutionError>
+    where
+        Domain<SC>: PolynomialSpace<Val = F>,
+    {
+        self.executor
+            .execute_and_generate_with_hook(committed_exe, input, on_segment)
+    }
+
     pub fn prove_single(
         &self,
         pk: &MultiStarkProvingKey<SC>,
@@ -490,17 +548,31 @@ where
         pk: &MultiStarkProvingKey<SC>,
         results: VmExecutorResult<SC>,
     ) -> Vec<Proof<SC>> {
+        self.prove_with_hook(pk, results, |_| ControlFlow::Continue(()))
+            .expect("proving is only stopped by the hook")
+    }
+
+    /// Same as [VirtualMachine::prove], but calls `on_segment` with the index of every segment
+    /// once it is proven. Returns `None` if `on_segment` returns [ControlFlow::Break], without
+    /// proving the remaining segments.
+    pub fn prove_with_hook(
+        &self,
+        pk: &MultiStarkProvingKey<SC>,
+        results: VmExecutorResult<SC>,
+        mut on_segment: impl FnMut(usize) -> ControlFlow<()>,
+    ) -> Option<Vec<Proof<SC>>> {
         #[cfg(feature = "bench-metrics")]
         metrics::counter!("num_segments").absolute(results.per_segment.len() as u64);
-        results
-            .per_segment
-            .into_iter()
-            .enumerate()
-            .map(|(seg_idx, proof_input)| {
-                tracing::info_span!("prove_segment", segment = seg_idx)
-                    .in_scope(|| self.engine.prove(pk, proof_input))
-            })
-            .collect()
+        let mut proofs = Vec::with_capacity(results.per_segment.len());
+        for (seg_idx, proof_input) in results.per_segment.into_iter().enumerate() {
+            let proof = tracing::info_span!("prove_segment", segment = seg_idx)
+                .in_scope(|| self.engine.prove(pk, proof_input));
+            proofs.push(proof);
+            if on_segment(seg_idx).is_break() {
+                return None;
+            }
+        }
+        Some(proofs)
     }
 
     pub fn verify_single(
@@ -538,80 +610,124 @@ where
     where
         Val<SC>: PrimeField32,
     {
-        let mut prev_final_memory_root = None;
-        let mut prev_final_pc = None;
-
-        for (i, proof) in proofs.iter().enumerate() {
-            let res = self.engine.verify(vk, proof);
-            match res {
-                Ok(_) => (),
-                Err(e) => return Err(VmVerificationError::StarkError(e)),
-            };
-
-            // Check public values.
-            for air_proof_data in proof.per_air.iter() {
-                let pvs = &air_proof_data.public_values;
-                let air_vk = &vk.per_air[air_proof_data.air_id];
-
-                if air_proof_data.air_id == CONNECTOR_AIR_ID {
-                    let pvs: &VmConnectorPvs<_> = pvs.as_slice().borrow();
-
-                    if i != 0 {
-                        // Check initial pc matches the previous final pc.
-                        if pvs.initial_pc != prev_final_pc.unwrap() {
-                            return Err(VmVerificationError::InitialPcMismatch {
-                                initial: pvs.initial_pc.as_canonical_u32(),
-                                prev_final: prev_final_pc.unwrap().as_canonical_u32(),
-                            });
-                        }
-                    } else {
-                        // TODO: Fetch initial pc from program
-                    }
-                    prev_final_pc = Some(pvs.final_pc);
+        verify_segments(&self.engine, vk, &proofs).map(|_| ())
+    }
+}
 
-                    let expected_is_terminate = i == proofs.len() - 1;
-                    if pvs.is_terminate != Val::<SC>::from_bool(expected_is_terminate) {
-                        return Err(VmVerificationError::IsTerminateMismatch {
-                            expected: expected_is_terminate,
-                            actual: pvs.is_terminate.as_canonical_u32() != 0,
-                        });
-                    }
+/// The boundary conditions of an execution whose segment proofs were verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifiedExecutionPayload<F> {
+    /// The pc at which the first segment starts.
+    pub initial_pc: F,
+    /// The memory merkle root before the first segment.
+    pub initial_memory_root: [F; CHUNK],
+    /// The memory merkle root after the last segment.
+    pub final_memory_root: [F; CHUNK],
+}
 
-                    let expected_exit_code = if expected_is_terminate {
-                        ExitCode::Success as u32
-                    } else {
-                        DEFAULT_SUSPEND_EXIT_CODE
-                    };
-                    if pvs.exit_code != Val::<SC>::from_canonical_u32(expected_exit_code) {
-                        return Err(VmVerificationError::ExitCodeMismatch {
-                            expected: expected_exit_code,
-                            actual: pvs.exit_code.as_canonical_u32(),
+/// Verify segment proofs with boundary condition checks for continuation between segments, and
+/// return the boundary conditions of the whole execution.
+pub fn verify_segments<SC, E>(
+    engine: &E,
+    vk: &MultiStarkVerifyingKey<SC>,
+    proofs: &[Proof<SC>],
+) -> Result<VerifiedExecutionPayload<Val<SC>>, VmVerificationError>
+where
+    SC: StarkGenericConfig,
+    E: StarkEngine<SC>,
+    Val<SC>: PrimeField32,
+{
+    if proofs.is_empty() {
+        return Err(VmVerificationError::NoSegments);
+    }
+    let mut initial_pc = None;
+    let mut initial_memory_root = None;
+    let mut prev_final_memory_root = None;
+    let mut prev_final_pc = None;
+
+    for (i, proof) in proofs.iter().enumerate() {
+        let res = engine.verify(vk, proof);
+        match res {
+            Ok(_) => (),
+            Err(e) => return Err(VmVerificationError::StarkError(e)),
+        };
+
+        for air_id in [CONNECTOR_AIR_ID, MERKLE_AIR_ID] {
+            if !proof.per_air.iter().any(|air| air.air_id == air_id) {
+                return Err(VmVerificationError::MissingAir { air_id });
+            }
+        }
+
+        // Check public values.
+        for air_proof_data in proof.per_air.iter() {
+            let pvs = &air_proof_data.public_values;
+            let air_vk = &vk.per_air[air_proof_data.air_id];
+
+            if air_proof_data.air_id == CONNECTOR_AIR_ID {
+                let pvs: &VmConnectorPvs<_> = pvs.as_slice().borrow();
+
+                if i != 0 {
+                    // Check initial pc matches the previous final pc.
+                    if pvs.initial_pc != prev_final_pc.unwrap() {
+                        return Err(VmVerificationError::InitialPcMismatch {
+                            initial: pvs.initial_pc.as_canonical_u32(),
+                            prev_final: prev_final_pc.unwrap().as_canonical_u32(),
                         });
                     }
-                } else if air_proof_data.air_id == MERKLE_AIR_ID {
-                    let pvs: &MemoryMerklePvs<_, CHUNK> = pvs.as_slice().borrow();
+                } else {
+                    initial_pc = Some(pvs.initial_pc);
+                }
+                prev_final_pc = Some(pvs.final_pc);
+
+                let expected_is_terminate = i == proofs.len() - 1;
+                if pvs.is_terminate != Val::<SC>::from_bool(expected_is_terminate) {
+                    return Err(VmVerificationError::IsTerminateMismatch {
+                        expected: expected_is_terminate,
+                        actual: pvs.is_terminate.as_canonical_u32() != 0,
+                    });
+                }
 
-                    // Check that initial root matches the previous final root.
-                    if i != 0 && pvs.initial_root != prev_final_memory_root.unwrap() {
-                        return Err(VmVerificationError::InitialMemoryRootMismatch);
-                    }
-                    prev_final_memory_root = Some(pvs.final_root);
+                let expected_exit_code = if expected_is_terminate {
+                    ExitCode::Success as u32
                 } else {
-                    if !pvs.is_empty() {
-                        return Err(VmVerificationError::UnexpectedPvs {
-                            expected: 0,
-                            actual: pvs.len(),
-                        });
-                    }
-                    if air_vk.params.num_public_values != 0 {
-                        return Err(VmVerificationError::NumPublicValuesMismatch {
-                            expected: 0,
-                            actual: air_vk.params.num_public_values,
-                        });
-                    }
+                    DEFAULT_SUSPEND_EXIT_CODE
+                };
+                if pvs.exit_code != Val::<SC>::from_canonical_u32(expected_exit_code) {
+                    return Err(VmVerificationError::ExitCodeMismatch {
+                        expected: expected_exit_code,
+                        actual: pvs.exit_code.as_canonical_u32(),
+                    });
+                }
+            } else if air_proof_data.air_id == MERKLE_AIR_ID {
+                let pvs: &MemoryMerklePvs<_, CHUNK> = pvs.as_slice().borrow();
+
+                // Check that initial root matches the previous final root.
+                if i != 0 && pvs.initial_root != prev_final_memory_root.unwrap() {
+                    return Err(VmVerificationError::InitialMemoryRootMismatch);
+                }
+                if i == 0 {
+                    initial_memory_root = Some(pvs.initial_root);
+                }
+                prev_final_memory_root = Some(pvs.final_root);
+            } else {
+                if !pvs.is_empty() {
+                    return Err(VmVerificationError::UnexpectedPvs {
+                        expected: 0,
+                        actual: pvs.len(),
+                    });
+                }
+                if air_vk.params.num_public_values != 0 {
+                    return Err(VmVerificationError::NumPublicValuesMismatch {
+                        expected: 0,
+                        actual: air_vk.params.num_public_values,
+                    });
                 }
             }
         }
-        Ok(())
     }
+    Ok(VerifiedExecutionPayload {
+        initial_pc: initial_pc.unwrap(),
+        initial_memory_root: initial_memory_root.unwrap(),
+        final_memory_root: prev_final_memory_root.unwrap(),
+    })
 }
@@ -1,4 +1,5 @@
 use branch_native_adapter::BranchNativeAdapterChip;
+use convert_adapter::ConvertAdapterChip;
 use derive_more::derive::From;
 use jal_native_adapter::JalNativeAdapterChip;
 use loadstore_native_adapter::NativeLoadStoreAdapterChip;
@@ -17,7 +18,7 @@ use openvm_instructions::{
 };
 use openvm_native_compiler::{
     FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
-    NativeJalOpcode, NativeLoadStoreOpcode, NativePhantom,
+    NativeJalOpcode, NativeJalrOpcode, NativeLoadStoreOpcode, NativePhantom,
 };
 use openvm_poseidon2_air::Poseidon2Config;
 use openvm_rv32im_circuit::BranchEqualCoreChip;
@@ -74,6 +75,7 @@ pub enum NativeExecutor<F: PrimeField32> {
     LoadStore(NativeLoadStoreChip<F, 1>),
     BranchEqual(NativeBranchEqChip<F>),
     Jal(NativeJalChip<F>),
+    Jalr(NativeJalrChip<F>),
     FieldArithmetic(FieldArithmeticChip<F>),
     FieldExtension(FieldExtensionChip<F>),
     Poseidon2(NativePoseidon2Chip<F>),
@@ -141,6 +143,20 @@ impl<F: PrimeField32> VmExtension<F> for Native {
             NativeJalOpcode::iter().map(VmOpcode::with_default_offset),
         )?;
 
+        let jalr_chip = NativeJalrChip::new(
+            ConvertAdapterChip::<_, 1, 1>::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+            ),
+            JalrCoreChip::new(NativeJalrOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            jalr_chip,
+            NativeJalrOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
         let field_arithmetic_chip = FieldArithmeticChip::new(
             NativeAdapterChip::<F, 2, 1>::new(
                 execution_bus,
@@ -0,0 +1,134 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, program::DEFAULT_PC_STEP, UsizeOpcode};
+use openvm_native_compiler::NativeJalrOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::BaseAir,
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct JalrCoreCols<T> {
+    pub target: T,
+    pub is_valid: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct JalrCoreAir {
+    offset: usize,
+}
+
+impl<F: Field> BaseAir<F> for JalrCoreAir {
+    fn width(&self) -> usize {
+        JalrCoreCols::<F>::width()
+    }
+}
+
+impl<F: Field> BaseAirWithPublicValues<F> for JalrCoreAir {}
+
+impl<AB, I> VmCoreAir<AB, I> for JalrCoreAir
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; 1]; 1]>,
+    I::Writes: From<[[AB::Expr; 1]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        _builder: &mut AB,
+        local_core: &[AB::Var],
+        from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &JalrCoreCols<_> = local_core.borrow();
+
+        // The target is not range checked here: the program bus interaction of the next
+        // instruction only balances if it is a pc of the program.
+        AdapterAirContext {
+            to_pc: Some(cols.target.into()),
+            reads: [[cols.target.into()]].into(),
+            writes: [[from_pc.into() + AB::Expr::from_canonical_u32(DEFAULT_PC_STEP)]].into(),
+            instruction: MinimalInstruction {
+                is_valid: cols.is_valid.into(),
+                opcode: AB::Expr::from_canonical_usize(
+                    NativeJalrOpcode::JALR as usize + self.offset,
+                ),
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct JalrRecord<F> {
+    pub target: F,
+}
+
+#[derive(Debug)]
+pub struct JalrCoreChip {
+    pub air: JalrCoreAir,
+}
+
+impl JalrCoreChip {
+    pub fn new(offset: usize) -> Self {
+        Self {
+            air: JalrCoreAir { offset },
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>> VmCoreChip<F, I> for JalrCoreChip
+where
+    I::Reads: Into<[[F; 1]; 1]>,
+    I::Writes: From<[[F; 1]; 1]>,
+{
+    type Record = JalrRecord<F>;
+    type Air = JalrCoreAir;
+
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        assert_eq!(
+            NativeJalrOpcode::from_usize(opcode.local_opcode_idx(self.air.offset)),
+            NativeJalrOpcode::JALR
+        );
+
+        let target = reads.into()[0][0];
+        let output = AdapterRuntimeContext {
+            to_pc: Some(target.as_canonical_u32()),
+            writes: [[F::from_canonical_u32(from_pc + DEFAULT_PC_STEP)]].into(),
+        };
+
+        Ok((output, JalrRecord { target }))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            NativeJalrOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let JalrRecord { target } = record;
+        let row_slice: &mut JalrCoreCols<_> = row_slice.borrow_mut();
+        row_slice.target = target;
+        row_slice.is_valid = F::ONE;
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
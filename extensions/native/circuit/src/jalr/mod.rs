@@ -0,0 +1,12 @@
+use openvm_circuit::arch::{VmAirWrapper, VmChipWrapper};
+
+use super::adapters::convert_adapter::{ConvertAdapterAir, ConvertAdapterChip};
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+pub type NativeJalrAir = VmAirWrapper<ConvertAdapterAir<1, 1>, JalrCoreAir>;
+pub type NativeJalrChip<F> = VmChipWrapper<F, ConvertAdapterChip<F, 1, 1>, JalrCoreChip>;
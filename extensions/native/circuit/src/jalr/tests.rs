@@ -0,0 +1,113 @@
+use std::borrow::BorrowMut;
+
+use openvm_circuit::arch::{testing::VmChipTestBuilder, VmAdapterChip};
+use openvm_instructions::{
+    instruction::Instruction,
+    program::{DEFAULT_PC_STEP, PC_BITS},
+    UsizeOpcode, VmOpcode,
+};
+use openvm_native_compiler::NativeJalrOpcode::{self, *};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::{AbstractField, PrimeField32},
+    p3_matrix::{dense::RowMajorMatrix, Matrix},
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    Chip, ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{
+    super::adapters::convert_adapter::ConvertAdapterChip, JalrCoreChip, JalrCoreCols,
+    NativeJalrChip,
+};
+type F = BabyBear;
+
+fn set_and_execute(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut NativeJalrChip<F>,
+    rng: &mut StdRng,
+    same_cell: bool,
+) {
+    let target = rng.gen_range(0..(1 << PC_BITS)) & !(DEFAULT_PC_STEP - 1);
+    let d = rng.gen_range(1..3);
+    let a = rng.gen_range(0..32) << 2;
+    let (b, e) = if same_cell {
+        (a, d)
+    } else {
+        (rng.gen_range(32..64) << 2, rng.gen_range(1..3))
+    };
+    tester.write_cell(e, b, F::from_canonical_u32(target));
+
+    tester.execute_with_pc(
+        chip,
+        Instruction::from_usize(VmOpcode::with_default_offset(JALR), [a, b, 0, d, e]),
+        rng.gen_range(0..(1 << PC_BITS)),
+    );
+    let initial_pc = tester.execution.last_from_pc().as_canonical_u32();
+    let final_pc = tester.execution.last_to_pc().as_canonical_u32();
+
+    assert_eq!(target, final_pc);
+    assert_eq!(
+        initial_pc + DEFAULT_PC_STEP,
+        tester.read::<1>(d, a)[0].as_canonical_u32()
+    );
+}
+
+fn setup() -> (StdRng, VmChipTestBuilder<F>, NativeJalrChip<F>) {
+    let rng = create_seeded_rng();
+    let tester = VmChipTestBuilder::default();
+
+    let adapter = ConvertAdapterChip::<F, 1, 1>::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+    );
+    let inner = JalrCoreChip::new(NativeJalrOpcode::default_offset());
+    let chip = NativeJalrChip::<F>::new(adapter, inner, tester.memory_controller());
+    (rng, tester, chip)
+}
+
+#[test]
+fn rand_jalr_test() {
+    let (mut rng, mut tester, mut chip) = setup();
+    let num_tests: usize = 100;
+    for i in 0..num_tests {
+        set_and_execute(&mut tester, &mut chip, &mut rng, i % 2 == 0);
+    }
+
+    let tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn negative_jalr_test() {
+    let (mut rng, mut tester, mut chip) = setup();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+    set_and_execute(&mut tester, &mut chip, &mut rng, false);
+
+    let jalr_trace_width = chip.trace_width();
+    let mut chip_input = chip.generate_air_proof_input();
+    let jalr_trace = chip_input.raw.common_main.as_mut().unwrap();
+    {
+        let mut trace_row = jalr_trace.row_slice(0).to_vec();
+        let (_, core_row) = trace_row.split_at_mut(adapter_width);
+        let core_cols: &mut JalrCoreCols<F> = core_row.borrow_mut();
+        core_cols.target += F::from_canonical_u32(DEFAULT_PC_STEP);
+        *jalr_trace = RowMajorMatrix::new(trace_row, jalr_trace_width);
+    }
+    disable_debug_builder();
+    let tester = tester.build().load_air_proof_input(chip_input).finalize();
+    let msg = format!(
+        "Expected verification to fail with {:?}, but it didn't",
+        VerificationError::ChallengePhaseError
+    );
+    let result = tester.simple_test();
+    assert_eq!(
+        result.err(),
+        Some(VerificationError::ChallengePhaseError),
+        "{}",
+        msg
+    );
+}
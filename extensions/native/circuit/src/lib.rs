@@ -6,6 +6,7 @@ mod field_arithmetic;
 mod field_extension;
 mod fri;
 mod jal;
+mod jalr;
 mod loadstore;
 mod poseidon2;
 
@@ -15,6 +16,7 @@ pub use field_arithmetic::*;
 pub use field_extension::*;
 pub use fri::*;
 pub use jal::*;
+pub use jalr::*;
 pub use loadstore::*;
 pub use poseidon2::*;
 
@@ -1,7 +1,7 @@
 use openvm_circuit::arch::instructions::program::Program;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32, TwoAdicField};
 
-use super::{config::AsmConfig, AsmCompiler};
+use super::{config::AsmConfig, optimize, AsmCompiler};
 use crate::{
     conversion::{convert_program, CompilerOptions},
     prelude::Builder,
@@ -18,7 +18,16 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmBu
     pub fn compile_isa_with_options(self, options: CompilerOptions) -> Program<F> {
         let mut compiler = AsmCompiler::new(options.word_size);
         compiler.build(self.operations);
-        let asm_code = compiler.code();
+        let mut asm_code = compiler.code();
+        if options.optimize {
+            let size = asm_code.size();
+            optimize(&mut asm_code);
+            tracing::info!(
+                "optimized assembly from {} to {} instructions",
+                size,
+                asm_code.size()
+            );
+        }
         convert_program(asm_code, options)
     }
 }
@@ -4,7 +4,7 @@ use core::{fmt, fmt::Display};
 use openvm_circuit::arch::instructions::instruction::DebugInfo;
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32};
 
-use super::AsmInstruction;
+use super::{AsmInstruction, CYCLE_TRACKER_PREFIX};
 
 /// A basic block of assembly instructions.
 #[derive(Debug, Clone, Default)]
@@ -47,6 +47,11 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AssemblyCode<F, EF> {
     }
 }
 
+/// Writes the code in the textual assembly format, which is parsed back by [str::parse].
+///
+/// Every basic block starts with its label on a line of its own, `.L{index}` unless the block has
+/// a name in `labels`, followed by its instructions indented by whitespace. Cycle tracker
+/// instructions are followed by the name of their span. Other debug info is not written.
 impl<F: PrimeField32, EF: ExtensionField<F>> Display for AssemblyCode<F, EF> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for (i, block) in self.blocks.iter().enumerate() {
@@ -57,9 +62,21 @@ impl<F: PrimeField32, EF: ExtensionField<F>> Display for AssemblyCode<F, EF> {
                     .get(&F::from_canonical_u32(i as u32))
                     .unwrap_or(&format!(".L{}", i))
             )?;
-            for instruction in &block.0 {
+            for (instruction, debug_info) in block.0.iter().zip(block.1.iter()) {
                 write!(f, "        ")?;
                 instruction.fmt(&self.labels, f)?;
+                if let (
+                    AsmInstruction::CycleTrackerStart() | AsmInstruction::CycleTrackerEnd(),
+                    Some(debug_info),
+                ) = (instruction, debug_info)
+                {
+                    if let Some(name) = debug_info
+                        .dsl_instruction
+                        .strip_prefix(CYCLE_TRACKER_PREFIX)
+                    {
+                        write!(f, " {}", name)?;
+                    }
+                }
                 writeln!(f)?;
             }
         }
@@ -25,6 +25,9 @@ pub(crate) const A0: i32 = HEAP_START_ADDRESS - 8;
 /// The memory location for the top of the stack.
 pub(crate) const STACK_TOP: i32 = HEAP_START_ADDRESS - 64;
 
+/// Prefix of the debug info of cycle tracker instructions, followed by the name of the span.
+pub(crate) const CYCLE_TRACKER_PREFIX: &str = "CT-";
+
 /// The assembly compiler.
 // #[derive(Debug, Clone, Default)]
 pub struct AsmCompiler<F, EF> {
@@ -34,10 +37,20 @@ pub struct AsmCompiler<F, EF> {
     break_counter: usize,
     contains_break: BTreeSet<F>,
     function_labels: BTreeMap<String, F>,
+    functions: BTreeMap<u32, CompiledFunction<F>>,
     trap_label: F,
     word_size: usize,
 }
 
+/// A function compiled by [AsmCompiler].
+///
+/// Calls jump to the entry of the function, storing the return pc in the call site var. The
+/// function returns with an indirect jump to the stored pc.
+struct CompiledFunction<F> {
+    label: F,
+    call_site: i32,
+}
+
 impl<F> Var<F> {
     /// Gets the frame pointer for a var.
     pub const fn fp(&self) -> i32 {
@@ -78,6 +91,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
             break_label_map: BTreeMap::new(),
             contains_break: BTreeSet::new(),
             function_labels: BTreeMap::new(),
+            functions: BTreeMap::new(),
             break_counter: 0,
             trap_label: F::ONE,
             word_size,
@@ -371,6 +385,21 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                     self.contains_break.insert(current_block);
                     self.push(AsmInstruction::Break(label), debug_info);
                 }
+                DslIr::Function(id, name, call_site, body) => {
+                    self.function(id, name, call_site, body, debug_info);
+                }
+                DslIr::Call(id, call_site) => {
+                    let function = self
+                        .functions
+                        .get(&id)
+                        .unwrap_or_else(|| panic!("function {id} called before its definition"));
+                    assert_eq!(function.call_site, call_site.fp());
+                    let label = function.label;
+
+                    self.push(AsmInstruction::Jump(call_site.fp(), label), debug_info);
+                    // The function returns to the start of the next block.
+                    self.basic_block();
+                }
                 DslIr::For(start, end, step_size, loop_var, block) => {
                     let for_compiler = ForCompiler {
                         compiler: self,
@@ -546,7 +575,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                     self.push(
                         AsmInstruction::CycleTrackerStart(),
                         Some(DebugInfo {
-                            dsl_instruction: format!("CT-{}", name),
+                            dsl_instruction: format!("{}{}", CYCLE_TRACKER_PREFIX, name),
                             trace: None,
                         }),
                     );
@@ -555,7 +584,7 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
                     self.push(
                         AsmInstruction::CycleTrackerEnd(),
                         Some(DebugInfo {
-                            dsl_instruction: format!("CT-{}", name),
+                            dsl_instruction: format!("{}{}", CYCLE_TRACKER_PREFIX, name),
                             trace: None,
                         }),
                     );
@@ -638,6 +667,52 @@ impl<F: PrimeField32 + TwoAdicField, EF: ExtensionField<F> + TwoAdicField> AsmCo
         if_compiler.then_label(trap_label, debug_info);
     }
 
+    /// Compiles the body of a function out of line, between a jump over it and the code that
+    /// follows the definition.
+    fn function(
+        &mut self,
+        id: u32,
+        name: String,
+        call_site: Var<F>,
+        body: TracedVec<DslIr<AsmConfig<F, EF>>>,
+        debug_info: Option<DebugInfo>,
+    ) {
+        let definition_label = self.block_label();
+
+        // Function entry block.
+        self.basic_block();
+        let label = self.block_label();
+        assert!(
+            self.function_labels.insert(name, label).is_none(),
+            "function defined twice"
+        );
+
+        // The body can't break out of loops around the definition.
+        let break_label = self.break_label.take();
+        self.build(body);
+        self.break_label = break_label;
+
+        // Return block.
+        self.basic_block();
+        self.push(
+            AsmInstruction::JumpIndirect(A0, call_site.fp()),
+            debug_info.clone(),
+        );
+        self.functions.insert(
+            id,
+            CompiledFunction {
+                label,
+                call_site: call_site.fp(),
+            },
+        );
+
+        // After function block.
+        self.basic_block();
+        let after_function_label = self.block_label();
+        let instr = AsmInstruction::j(after_function_label);
+        self.push_to_block(definition_label, instr, debug_info);
+    }
+
     pub fn code(self) -> AssemblyCode<F, EF> {
         let labels = self
             .function_labels
@@ -70,6 +70,9 @@ pub enum AsmInstruction<F, EF> {
     /// Jump.
     Jump(i32, F),
 
+    /// Jump to the pc stored in src, dst = pc of the next instruction.
+    JumpIndirect(i32, i32),
+
     /// Branch not equal.
     Bne(F, i32, i32),
 
@@ -146,7 +149,23 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
         AsmInstruction::Jump(A0, label)
     }
 
+    /// Writes the instruction in the textual assembly format, see [AssemblyCode].
+    ///
+    /// Slots are written as `(addr)fp`, field immediates as canonical integers, extension field
+    /// immediates as `[c0, c1, ..]` and jump targets as the label of the basic block.
+    ///
+    /// [AssemblyCode]: super::AssemblyCode
     pub fn fmt(&self, labels: &BTreeMap<F, String>, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = |label: &F| {
+            labels
+                .get(label)
+                .cloned()
+                .unwrap_or_else(|| format!(".L{}", label))
+        };
+        let ext = |value: &EF| {
+            let coeffs: Vec<_> = value.as_base_slice().iter().map(F::to_string).collect();
+            format!("[{}]", coeffs.join(", "))
+        };
         match self {
             AsmInstruction::Break(_) => panic!("Unresolved break instruction"),
             AsmInstruction::LoadF(dst, src, var_index, size, offset) => {
@@ -178,10 +197,10 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
                 )
             }
             AsmInstruction::ImmF(dst, src) => {
-                write!(f, "imm   ({})fp, ({})", dst, src)
+                write!(f, "imm   ({})fp, {}", dst, src)
             }
             AsmInstruction::CopyF(dst, src) => {
-                write!(f, "copy  ({})fp, ({})", dst, src)
+                write!(f, "copy  ({})fp, ({})fp", dst, src)
             }
             AsmInstruction::AddF(dst, lhs, rhs) => {
                 write!(f, "add   ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
@@ -211,10 +230,10 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
                 write!(f, "divi  ({})fp, ({})fp, {}", dst, lhs, rhs)
             }
             AsmInstruction::DivFIN(dst, lhs, rhs) => {
-                write!(f, "divi  ({})fp, {}, ({})fp", dst, lhs, rhs)
+                write!(f, "divin ({})fp, {}, ({})fp", dst, lhs, rhs)
             }
             AsmInstruction::AddE(dst, lhs, rhs) => {
-                write!(f, "eadd ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
+                write!(f, "eadd  ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
             }
             AsmInstruction::SubE(dst, lhs, rhs) => {
                 write!(f, "esub  ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
@@ -225,85 +244,35 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
             AsmInstruction::DivE(dst, lhs, rhs) => {
                 write!(f, "ediv  ({})fp, ({})fp, ({})fp", dst, lhs, rhs)
             }
-            AsmInstruction::Jump(dst, label) => {
-                write!(
-                    f,
-                    "j     ({})fp, {}",
-                    dst,
-                    labels.get(label).unwrap_or(&format!(".L{}", label))
-                )
+            AsmInstruction::Jump(dst, target) => {
+                write!(f, "j     ({})fp, {}", dst, label(target))
             }
-            AsmInstruction::Bne(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "bne   {}, ({})fp, ({})fp",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::JumpIndirect(dst, src) => {
+                write!(f, "jr    ({})fp, ({})fp", dst, src)
             }
-            AsmInstruction::BneI(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "bnei  {}, ({})fp, {}",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::Bne(target, lhs, rhs) => {
+                write!(f, "bne   {}, ({})fp, ({})fp", label(target), lhs, rhs)
             }
-            AsmInstruction::Beq(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "beq  {}, ({})fp, ({})fp",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::BneI(target, lhs, rhs) => {
+                write!(f, "bnei  {}, ({})fp, {}", label(target), lhs, rhs)
             }
-            AsmInstruction::BeqI(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "beqi {}, ({})fp, {}",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::Beq(target, lhs, rhs) => {
+                write!(f, "beq   {}, ({})fp, ({})fp", label(target), lhs, rhs)
             }
-            AsmInstruction::BneE(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "ebne  {}, ({})fp, ({})fp",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::BeqI(target, lhs, rhs) => {
+                write!(f, "beqi  {}, ({})fp, {}", label(target), lhs, rhs)
             }
-            AsmInstruction::BneEI(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "ebnei {}, ({})fp, {}",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::BneE(target, lhs, rhs) => {
+                write!(f, "ebne  {}, ({})fp, ({})fp", label(target), lhs, rhs)
             }
-            AsmInstruction::BeqE(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "ebeq  {}, ({})fp, ({})fp",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::BneEI(target, lhs, rhs) => {
+                write!(f, "ebnei {}, ({})fp, {}", label(target), lhs, ext(rhs))
             }
-            AsmInstruction::BeqEI(label, lhs, rhs) => {
-                write!(
-                    f,
-                    "ebeqi {}, ({})fp, {}",
-                    labels.get(label).unwrap_or(&format!(".L{}", label)),
-                    lhs,
-                    rhs
-                )
+            AsmInstruction::BeqE(target, lhs, rhs) => {
+                write!(f, "ebeq  {}, ({})fp, ({})fp", label(target), lhs, rhs)
+            }
+            AsmInstruction::BeqEI(target, lhs, rhs) => {
+                write!(f, "ebeqi {}, ({})fp, {}", label(target), lhs, ext(rhs))
             }
             AsmInstruction::Trap => write!(f, "trap"),
             AsmInstruction::Halt => write!(f, "halt"),
@@ -329,10 +298,10 @@ impl<F: PrimeField32, EF: ExtensionField<F>> AsmInstruction<F, EF> {
             }
             AsmInstruction::HintInputVec() => write!(f, "hint_vec"),
             AsmInstruction::StoreHintWordI(dst, offset) => {
-                write!(f, "shintw ({})fp {}", dst, offset)
+                write!(f, "shintw ({})fp, {}", dst, offset)
             }
             AsmInstruction::Publish(val, index) => {
-                write!(f, "commit ({})fp ({})fp", val, index)
+                write!(f, "commit ({})fp, ({})fp", val, index)
             }
             AsmInstruction::CycleTrackerStart() => {
                 write!(f, "cycle_tracker_start")
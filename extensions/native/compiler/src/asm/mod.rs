@@ -3,6 +3,8 @@ mod code;
 mod compiler;
 mod config;
 mod instruction;
+mod optimizer;
+mod parser;
 mod utils;
 
 pub use builder::*;
@@ -10,4 +12,6 @@ pub use code::*;
 pub use compiler::*;
 pub use config::*;
 pub use instruction::*;
+pub use optimizer::*;
+pub use parser::*;
 pub use utils::*;
@@ -0,0 +1,474 @@
+//! Optimization passes over [AssemblyCode], enabled by [CompilerOptions::optimize].
+//!
+//! The stack slots of `Var`, `Felt` and `Ext` are only accessed by address, never through a
+//! pointer, so the passes treat every slot as a register. Jumps only target the start of a basic
+//! block, so the values of slots are tracked within a basic block:
+//! - constant folding of field arithmetic and of branches on constants,
+//! - immediate-operand folding, e.g. `add` of a constant slot becomes `addi`,
+//! - copy propagation,
+//! - common subexpression elimination of field arithmetic.
+//!
+//! Dead store elimination then removes pure instructions whose result is overwritten in the same
+//! basic block before it is read, or is never read by any instruction of the program.
+//!
+//! [CompilerOptions::optimize]: crate::conversion::CompilerOptions::optimize
+
+use std::collections::{HashMap, HashSet};
+
+use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32};
+
+use super::{AsmInstruction, AssemblyCode, BasicBlock};
+
+/// Optimizes `code` in place, running the passes until they stop removing instructions.
+pub fn optimize<F: PrimeField32, EF: ExtensionField<F>>(code: &mut AssemblyCode<F, EF>) {
+    loop {
+        let size = code.size();
+        for block in code.blocks.iter_mut() {
+            simplify_block(block);
+            eliminate_overwritten_stores(block);
+        }
+        eliminate_unread_stores(code);
+        if code.size() == size {
+            break;
+        }
+    }
+}
+
+/// Slots read and written by an instruction. Memory accessed through pointers is on the heap and
+/// not included.
+fn accesses<F: PrimeField32, EF: ExtensionField<F>>(
+    instruction: &AsmInstruction<F, EF>,
+) -> (Vec<i32>, Vec<i32>) {
+    let ext = |addr: i32| (addr..addr + EF::D as i32).collect::<Vec<_>>();
+    match *instruction {
+        AsmInstruction::LoadF(dst, src, index, _, _) => (vec![src, index], vec![dst]),
+        AsmInstruction::LoadFI(dst, src, _, _, _) => (vec![src], vec![dst]),
+        AsmInstruction::StoreF(val, addr, index, _, _) => (vec![val, addr, index], vec![]),
+        AsmInstruction::StoreFI(val, addr, _, _, _) => (vec![val, addr], vec![]),
+        AsmInstruction::ImmF(dst, _) => (vec![], vec![dst]),
+        AsmInstruction::CopyF(dst, src) => (vec![src], vec![dst]),
+        AsmInstruction::AddF(dst, lhs, rhs)
+        | AsmInstruction::SubF(dst, lhs, rhs)
+        | AsmInstruction::MulF(dst, lhs, rhs)
+        | AsmInstruction::DivF(dst, lhs, rhs) => (vec![lhs, rhs], vec![dst]),
+        AsmInstruction::AddFI(dst, lhs, _)
+        | AsmInstruction::SubFI(dst, lhs, _)
+        | AsmInstruction::MulFI(dst, lhs, _)
+        | AsmInstruction::DivFI(dst, lhs, _) => (vec![lhs], vec![dst]),
+        AsmInstruction::SubFIN(dst, _, rhs) | AsmInstruction::DivFIN(dst, _, rhs) => {
+            (vec![rhs], vec![dst])
+        }
+        AsmInstruction::AddE(dst, lhs, rhs)
+        | AsmInstruction::SubE(dst, lhs, rhs)
+        | AsmInstruction::MulE(dst, lhs, rhs)
+        | AsmInstruction::DivE(dst, lhs, rhs) => ([ext(lhs), ext(rhs)].concat(), ext(dst)),
+        AsmInstruction::Jump(dst, _) => (vec![], vec![dst]),
+        AsmInstruction::JumpIndirect(dst, src) => (vec![src], vec![dst]),
+        AsmInstruction::Bne(_, lhs, rhs) | AsmInstruction::Beq(_, lhs, rhs) => {
+            (vec![lhs, rhs], vec![])
+        }
+        AsmInstruction::BneI(_, lhs, _) | AsmInstruction::BeqI(_, lhs, _) => (vec![lhs], vec![]),
+        AsmInstruction::BneE(_, lhs, rhs) | AsmInstruction::BeqE(_, lhs, rhs) => {
+            ([ext(lhs), ext(rhs)].concat(), vec![])
+        }
+        AsmInstruction::BneEI(_, lhs, _) | AsmInstruction::BeqEI(_, lhs, _) => (ext(lhs), vec![]),
+        AsmInstruction::Trap | AsmInstruction::Halt | AsmInstruction::Break(_) => (vec![], vec![]),
+        AsmInstruction::Poseidon2Permute(dst, src) => (vec![dst, src], vec![]),
+        AsmInstruction::Poseidon2Compress(dst, lhs, rhs) => (vec![dst, lhs, rhs], vec![]),
+        AsmInstruction::FriReducedOpening(a, b, res, len, alpha, alpha_pow) => (
+            [vec![a, b, len], ext(alpha), ext(alpha_pow)].concat(),
+            [ext(res), ext(alpha_pow)].concat(),
+        ),
+        AsmInstruction::PrintV(src) | AsmInstruction::PrintF(src) => (vec![src], vec![]),
+        AsmInstruction::PrintE(src) => (ext(src), vec![]),
+        AsmInstruction::HintInputVec() => (vec![], vec![]),
+        AsmInstruction::HintBits(src, _) => (vec![src], vec![]),
+        AsmInstruction::StoreHintWordI(val, _) => (vec![val], vec![]),
+        AsmInstruction::Publish(val, index) => (vec![val, index], vec![]),
+        AsmInstruction::CycleTrackerStart() | AsmInstruction::CycleTrackerEnd() => (vec![], vec![]),
+    }
+}
+
+/// Whether the instruction may transfer control out of the basic block.
+fn is_control<F, EF>(instruction: &AsmInstruction<F, EF>) -> bool {
+    matches!(
+        instruction,
+        AsmInstruction::Jump(..)
+            | AsmInstruction::JumpIndirect(..)
+            | AsmInstruction::Bne(..)
+            | AsmInstruction::BneI(..)
+            | AsmInstruction::Beq(..)
+            | AsmInstruction::BeqI(..)
+            | AsmInstruction::BneE(..)
+            | AsmInstruction::BneEI(..)
+            | AsmInstruction::BeqE(..)
+            | AsmInstruction::BeqEI(..)
+            | AsmInstruction::Trap
+            | AsmInstruction::Halt
+            | AsmInstruction::Break(_)
+    )
+}
+
+/// Whether the only effect of the instruction is writing its result. Divisions are excluded as
+/// they fail on division by zero.
+fn is_pure<F, EF>(instruction: &AsmInstruction<F, EF>) -> bool {
+    matches!(
+        instruction,
+        AsmInstruction::ImmF(..)
+            | AsmInstruction::CopyF(..)
+            | AsmInstruction::AddF(..)
+            | AsmInstruction::AddFI(..)
+            | AsmInstruction::SubF(..)
+            | AsmInstruction::SubFI(..)
+            | AsmInstruction::SubFIN(..)
+            | AsmInstruction::MulF(..)
+            | AsmInstruction::MulFI(..)
+            | AsmInstruction::AddE(..)
+            | AsmInstruction::SubE(..)
+            | AsmInstruction::MulE(..)
+    )
+}
+
+fn retain<F: PrimeField32, EF: ExtensionField<F>>(
+    block: &mut BasicBlock<F, EF>,
+    mut keep: impl FnMut(usize, &AsmInstruction<F, EF>) -> bool,
+) {
+    let instructions = std::mem::take(&mut block.0);
+    let debug_infos = std::mem::take(&mut block.1);
+    for (i, (instruction, debug_info)) in instructions.into_iter().zip(debug_infos).enumerate() {
+        if keep(i, &instruction) {
+            block.push(instruction, debug_info);
+        }
+    }
+}
+
+/// Constant folding, immediate-operand folding, copy propagation and common subexpression
+/// elimination within a basic block.
+fn simplify_block<F: PrimeField32, EF: ExtensionField<F>>(block: &mut BasicBlock<F, EF>) {
+    let instructions = std::mem::take(&mut block.0);
+    let debug_infos = std::mem::take(&mut block.1);
+    let mut values = SlotValues::default();
+    for (instruction, debug_info) in instructions.into_iter().zip(debug_infos) {
+        let instruction = values.propagate_copies(instruction);
+        let Some(instruction) = values.fold_constants(instruction) else {
+            continue;
+        };
+        let Some(instruction) = values.eliminate_common_subexpression(instruction) else {
+            continue;
+        };
+        values.update(&instruction);
+        let ends_block = matches!(
+            instruction,
+            AsmInstruction::Jump(..)
+                | AsmInstruction::JumpIndirect(..)
+                | AsmInstruction::Trap
+                | AsmInstruction::Halt
+        );
+        block.push(instruction, debug_info);
+        // The rest of the block is unreachable, as jumps only target the start of a block.
+        if ends_block {
+            break;
+        }
+    }
+}
+
+/// Removes pure instructions whose result is overwritten before it is read within a basic block.
+fn eliminate_overwritten_stores<F: PrimeField32, EF: ExtensionField<F>>(
+    block: &mut BasicBlock<F, EF>,
+) {
+    let mut overwritten = HashSet::new();
+    let mut dead = vec![false; block.0.len()];
+    for (i, instruction) in block.0.iter().enumerate().rev() {
+        if is_control(instruction) {
+            overwritten.clear();
+        }
+        let (reads, writes) = accesses(instruction);
+        if is_pure(instruction) && writes.iter().all(|w| overwritten.contains(w)) {
+            dead[i] = true;
+            continue;
+        }
+        overwritten.extend(writes);
+        for r in reads {
+            overwritten.remove(&r);
+        }
+    }
+    retain(block, |i, _| !dead[i]);
+}
+
+/// Removes pure instructions whose result is never read by the program.
+fn eliminate_unread_stores<F: PrimeField32, EF: ExtensionField<F>>(code: &mut AssemblyCode<F, EF>) {
+    loop {
+        let read: HashSet<i32> = code
+            .blocks
+            .iter()
+            .flat_map(|block| block.0.iter())
+            .flat_map(|instruction| accesses(instruction).0)
+            .collect();
+        let size = code.size();
+        for block in code.blocks.iter_mut() {
+            retain(block, |_, instruction| {
+                !is_pure(instruction) || accesses(instruction).1.iter().any(|w| read.contains(w))
+            });
+        }
+        if code.size() == size {
+            break;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Operand {
+    /// A slot at a version.
+    Slot(i32, u32),
+    Imm(u32),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Expression(Operation, Operand, Operand);
+
+/// What is known about the values of slots at a point of a basic block.
+///
+/// Every write to a slot bumps its version, which invalidates the facts recorded about the
+/// previous value of the slot.
+#[derive(Default)]
+struct SlotValues<F> {
+    versions: HashMap<i32, u32>,
+    /// The constant value of a slot, by slot, with the version of the slot.
+    constants: HashMap<i32, (F, u32)>,
+    /// The slot a slot is a copy of, by slot, with the versions of both slots.
+    copies: HashMap<i32, (i32, u32, u32)>,
+    /// The slot holding the value of an expression, with the version of the slot.
+    expressions: HashMap<Expression, (i32, u32)>,
+}
+
+impl<F: PrimeField32> SlotValues<F> {
+    fn version(&self, slot: i32) -> u32 {
+        self.versions.get(&slot).copied().unwrap_or_default()
+    }
+
+    fn constant(&self, slot: i32) -> Option<F> {
+        match self.constants.get(&slot) {
+            Some(&(value, version)) if version == self.version(slot) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The slot `slot` is a copy of, or `slot` itself.
+    fn source(&self, slot: i32) -> i32 {
+        match self.copies.get(&slot) {
+            Some(&(src, src_version, version))
+                if version == self.version(slot) && src_version == self.version(src) =>
+            {
+                src
+            }
+            _ => slot,
+        }
+    }
+
+    fn propagate_copies<EF>(&self, instruction: AsmInstruction<F, EF>) -> AsmInstruction<F, EF> {
+        let s = |slot| self.source(slot);
+        match instruction {
+            AsmInstruction::LoadF(dst, src, index, size, offset) => {
+                AsmInstruction::LoadF(dst, s(src), s(index), size, offset)
+            }
+            AsmInstruction::LoadFI(dst, src, index, size, offset) => {
+                AsmInstruction::LoadFI(dst, s(src), index, size, offset)
+            }
+            AsmInstruction::StoreF(val, addr, index, size, offset) => {
+                AsmInstruction::StoreF(s(val), s(addr), s(index), size, offset)
+            }
+            AsmInstruction::StoreFI(val, addr, index, size, offset) => {
+                AsmInstruction::StoreFI(s(val), s(addr), index, size, offset)
+            }
+            AsmInstruction::CopyF(dst, src) => AsmInstruction::CopyF(dst, s(src)),
+            AsmInstruction::AddF(dst, lhs, rhs) => AsmInstruction::AddF(dst, s(lhs), s(rhs)),
+            AsmInstruction::SubF(dst, lhs, rhs) => AsmInstruction::SubF(dst, s(lhs), s(rhs)),
+            AsmInstruction::MulF(dst, lhs, rhs) => AsmInstruction::MulF(dst, s(lhs), s(rhs)),
+            AsmInstruction::DivF(dst, lhs, rhs) => AsmInstruction::DivF(dst, s(lhs), s(rhs)),
+            AsmInstruction::AddFI(dst, lhs, rhs) => AsmInstruction::AddFI(dst, s(lhs), rhs),
+            AsmInstruction::SubFI(dst, lhs, rhs) => AsmInstruction::SubFI(dst, s(lhs), rhs),
+            AsmInstruction::MulFI(dst, lhs, rhs) => AsmInstruction::MulFI(dst, s(lhs), rhs),
+            AsmInstruction::DivFI(dst, lhs, rhs) => AsmInstruction::DivFI(dst, s(lhs), rhs),
+            AsmInstruction::SubFIN(dst, lhs, rhs) => AsmInstruction::SubFIN(dst, lhs, s(rhs)),
+            AsmInstruction::DivFIN(dst, lhs, rhs) => AsmInstruction::DivFIN(dst, lhs, s(rhs)),
+            AsmInstruction::Bne(label, lhs, rhs) => AsmInstruction::Bne(label, s(lhs), s(rhs)),
+            AsmInstruction::Beq(label, lhs, rhs) => AsmInstruction::Beq(label, s(lhs), s(rhs)),
+            AsmInstruction::BneI(label, lhs, rhs) => AsmInstruction::BneI(label, s(lhs), rhs),
+            AsmInstruction::BeqI(label, lhs, rhs) => AsmInstruction::BeqI(label, s(lhs), rhs),
+            AsmInstruction::Publish(val, index) => AsmInstruction::Publish(s(val), s(index)),
+            instruction => instruction,
+        }
+    }
+
+    /// Folds constant operands into the instruction. Returns `None` if the instruction has no
+    /// effect.
+    fn fold_constants<EF: ExtensionField<F>>(
+        &self,
+        instruction: AsmInstruction<F, EF>,
+    ) -> Option<AsmInstruction<F, EF>> {
+        let c = |slot| self.constant(slot);
+        let folded = match instruction {
+            AsmInstruction::CopyF(dst, src) if dst == src => return None,
+            AsmInstruction::CopyF(dst, src) => match c(src) {
+                Some(value) => AsmInstruction::ImmF(dst, value),
+                None => instruction,
+            },
+            AsmInstruction::AddF(dst, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (Some(l), Some(r)) => AsmInstruction::ImmF(dst, l + r),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::AddFI(dst, lhs, r)),
+                (Some(l), _) => return self.fold_constants(AsmInstruction::AddFI(dst, rhs, l)),
+                _ => instruction,
+            },
+            AsmInstruction::SubF(dst, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (Some(l), Some(r)) => AsmInstruction::ImmF(dst, l - r),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::SubFI(dst, lhs, r)),
+                (Some(l), _) => AsmInstruction::SubFIN(dst, l, rhs),
+                _ => instruction,
+            },
+            AsmInstruction::MulF(dst, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (Some(l), Some(r)) => AsmInstruction::ImmF(dst, l * r),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::MulFI(dst, lhs, r)),
+                (Some(l), _) => return self.fold_constants(AsmInstruction::MulFI(dst, rhs, l)),
+                _ => instruction,
+            },
+            AsmInstruction::DivF(dst, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (_, Some(r)) if r.is_zero() => instruction,
+                (Some(l), Some(r)) => AsmInstruction::ImmF(dst, l / r),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::DivFI(dst, lhs, r)),
+                (Some(l), _) => AsmInstruction::DivFIN(dst, l, rhs),
+                _ => instruction,
+            },
+            AsmInstruction::AddFI(dst, lhs, rhs) => match c(lhs) {
+                Some(l) => AsmInstruction::ImmF(dst, l + rhs),
+                None if rhs.is_zero() => {
+                    return self.fold_constants(AsmInstruction::CopyF(dst, lhs))
+                }
+                None => instruction,
+            },
+            AsmInstruction::SubFI(dst, lhs, rhs) => match c(lhs) {
+                Some(l) => AsmInstruction::ImmF(dst, l - rhs),
+                None if rhs.is_zero() => {
+                    return self.fold_constants(AsmInstruction::CopyF(dst, lhs))
+                }
+                None => instruction,
+            },
+            AsmInstruction::SubFIN(dst, lhs, rhs) => match c(rhs) {
+                Some(r) => AsmInstruction::ImmF(dst, lhs - r),
+                None => instruction,
+            },
+            AsmInstruction::MulFI(dst, lhs, rhs) => match c(lhs) {
+                Some(l) => AsmInstruction::ImmF(dst, l * rhs),
+                None if rhs.is_zero() => AsmInstruction::ImmF(dst, F::ZERO),
+                None if rhs.is_one() => {
+                    return self.fold_constants(AsmInstruction::CopyF(dst, lhs))
+                }
+                None => instruction,
+            },
+            AsmInstruction::DivFI(dst, lhs, rhs) if !rhs.is_zero() => match c(lhs) {
+                Some(l) => AsmInstruction::ImmF(dst, l / rhs),
+                None if rhs.is_one() => {
+                    return self.fold_constants(AsmInstruction::CopyF(dst, lhs))
+                }
+                None => instruction,
+            },
+            AsmInstruction::DivFIN(dst, lhs, rhs) => match c(rhs) {
+                Some(r) if !r.is_zero() => AsmInstruction::ImmF(dst, lhs / r),
+                _ => instruction,
+            },
+            AsmInstruction::Bne(label, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (Some(l), Some(r)) => return (l != r).then(|| AsmInstruction::j(label)),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::BneI(label, lhs, r)),
+                (Some(l), _) => return self.fold_constants(AsmInstruction::BneI(label, rhs, l)),
+                _ => instruction,
+            },
+            AsmInstruction::Beq(label, lhs, rhs) => match (c(lhs), c(rhs)) {
+                (Some(l), Some(r)) => return (l == r).then(|| AsmInstruction::j(label)),
+                (_, Some(r)) => return self.fold_constants(AsmInstruction::BeqI(label, lhs, r)),
+                (Some(l), _) => return self.fold_constants(AsmInstruction::BeqI(label, rhs, l)),
+                _ => instruction,
+            },
+            AsmInstruction::BneI(label, lhs, rhs) => match c(lhs) {
+                Some(l) => return (l != rhs).then(|| AsmInstruction::j(label)),
+                None => instruction,
+            },
+            AsmInstruction::BeqI(label, lhs, rhs) => match c(lhs) {
+                Some(l) => return (l == rhs).then(|| AsmInstruction::j(label)),
+                None => instruction,
+            },
+            instruction => instruction,
+        };
+        Some(folded)
+    }
+
+    /// The slot written by a field arithmetic instruction and the expression it computes.
+    fn expression<EF>(&self, instruction: &AsmInstruction<F, EF>) -> Option<(i32, Expression)> {
+        let s = |slot| Operand::Slot(slot, self.version(slot));
+        let i = |value: F| Operand::Imm(value.as_canonical_u32());
+        let (dst, operation, lhs, rhs) = match *instruction {
+            AsmInstruction::AddF(dst, lhs, rhs) => (dst, Operation::Add, s(lhs), s(rhs)),
+            AsmInstruction::AddFI(dst, lhs, rhs) => (dst, Operation::Add, s(lhs), i(rhs)),
+            AsmInstruction::SubF(dst, lhs, rhs) => (dst, Operation::Sub, s(lhs), s(rhs)),
+            AsmInstruction::SubFI(dst, lhs, rhs) => (dst, Operation::Sub, s(lhs), i(rhs)),
+            AsmInstruction::SubFIN(dst, lhs, rhs) => (dst, Operation::Sub, i(lhs), s(rhs)),
+            AsmInstruction::MulF(dst, lhs, rhs) => (dst, Operation::Mul, s(lhs), s(rhs)),
+            AsmInstruction::MulFI(dst, lhs, rhs) => (dst, Operation::Mul, s(lhs), i(rhs)),
+            AsmInstruction::DivF(dst, lhs, rhs) => (dst, Operation::Div, s(lhs), s(rhs)),
+            AsmInstruction::DivFI(dst, lhs, rhs) => (dst, Operation::Div, s(lhs), i(rhs)),
+            AsmInstruction::DivFIN(dst, lhs, rhs) => (dst, Operation::Div, i(lhs), s(rhs)),
+            _ => return None,
+        };
+        // Addition and multiplication are commutative.
+        let (lhs, rhs) = match operation {
+            Operation::Add | Operation::Mul if rhs < lhs => (rhs, lhs),
+            _ => (lhs, rhs),
+        };
+        Some((dst, Expression(operation, lhs, rhs)))
+    }
+
+    /// Replaces the instruction by a copy if a slot already holds its result. Returns `None` if
+    /// the destination already holds the result.
+    fn eliminate_common_subexpression<EF>(
+        &self,
+        instruction: AsmInstruction<F, EF>,
+    ) -> Option<AsmInstruction<F, EF>> {
+        let Some((dst, expression)) = self.expression(&instruction) else {
+            return Some(instruction);
+        };
+        match self.expressions.get(&expression) {
+            Some(&(slot, version)) if version == self.version(slot) => {
+                (slot != dst).then_some(AsmInstruction::CopyF(dst, slot))
+            }
+            _ => Some(instruction),
+        }
+    }
+
+    /// Records the effect of the instruction.
+    fn update<EF: ExtensionField<F>>(&mut self, instruction: &AsmInstruction<F, EF>) {
+        let expression = self.expression(instruction);
+        for slot in accesses(instruction).1 {
+            *self.versions.entry(slot).or_default() += 1;
+        }
+        match *instruction {
+            AsmInstruction::ImmF(dst, value) => {
+                self.constants.insert(dst, (value, self.version(dst)));
+            }
+            AsmInstruction::CopyF(dst, src) => {
+                let versions = (self.version(src), self.version(dst));
+                self.copies.insert(dst, (src, versions.0, versions.1));
+            }
+            _ => {}
+        }
+        if let Some((dst, expression)) = expression {
+            self.expressions
+                .insert(expression, (dst, self.version(dst)));
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use alloc::{collections::BTreeMap, format};
+use core::{fmt, str::FromStr};
+
+use openvm_circuit::arch::instructions::instruction::DebugInfo;
+use openvm_stark_backend::p3_field::{ExtensionField, PrimeField32};
+
+use super::{AsmInstruction, AssemblyCode, BasicBlock, CYCLE_TRACKER_PREFIX};
+
+/// An error parsing the textual assembly format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAsmError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseAsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseAsmError {}
+
+/// Parses the textual assembly format written by the [Display](fmt::Display) implementation of
+/// [AssemblyCode].
+///
+/// Blank lines and lines starting with `#` are ignored, so code can be annotated when written by
+/// hand.
+impl<F: PrimeField32, EF: ExtensionField<F>> FromStr for AssemblyCode<F, EF> {
+    type Err = ParseAsmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Collect the labels first, as jumps can target later basic blocks.
+        let mut block_labels = BTreeMap::new();
+        let mut labels = BTreeMap::new();
+        for (line, text) in s.lines().enumerate() {
+            let Some(name) = parse_label(text) else {
+                continue;
+            };
+            let error = |message| ParseAsmError {
+                line: line + 1,
+                message,
+            };
+            if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ',') {
+                return Err(error(format!("invalid label {:?}", name)));
+            }
+            let index = block_labels.len();
+            let label = F::from_canonical_usize(index);
+            if block_labels.insert(name.to_string(), label).is_some() {
+                return Err(error(format!("duplicate label {}", name)));
+            }
+            if name != format!(".L{}", index) {
+                labels.insert(label, name.to_string());
+            }
+        }
+
+        let mut blocks: Vec<BasicBlock<F, EF>> = Vec::new();
+        for (line, text) in s.lines().enumerate() {
+            let error = |message| ParseAsmError {
+                line: line + 1,
+                message,
+            };
+            let trimmed = text.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            if parse_label(text).is_some() {
+                blocks.push(BasicBlock::new());
+                continue;
+            }
+            let block = blocks
+                .last_mut()
+                .ok_or_else(|| error("instruction outside of a basic block".to_string()))?;
+            let (instruction, debug_info) =
+                parse_instruction(trimmed, &block_labels).map_err(error)?;
+            block.push(instruction, debug_info);
+        }
+
+        Ok(AssemblyCode::new(blocks, labels))
+    }
+}
+
+/// Returns the name of the label declared on `line`, if any.
+///
+/// Labels start at the beginning of a line and end with a colon, instructions are indented.
+fn parse_label(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+        return None;
+    }
+    line.trim_end().strip_suffix(':')
+}
+
+fn parse_instruction<F: PrimeField32, EF: ExtensionField<F>>(
+    line: &str,
+    labels: &BTreeMap<String, F>,
+) -> Result<(AsmInstruction<F, EF>, Option<DebugInfo>), String> {
+    let (mnemonic, operands) = match line.split_once(char::is_whitespace) {
+        Some((mnemonic, operands)) => (mnemonic, operands.trim()),
+        None => (line, ""),
+    };
+
+    // The operand of cycle tracker instructions is the name of the span, which can contain any
+    // character.
+    if let "cycle_tracker_start" | "cycle_tracker_end" = mnemonic {
+        let debug_info = (!operands.is_empty())
+            .then(|| DebugInfo::new(format!("{}{}", CYCLE_TRACKER_PREFIX, operands), None));
+        let instruction = if mnemonic == "cycle_tracker_start" {
+            AsmInstruction::CycleTrackerStart()
+        } else {
+            AsmInstruction::CycleTrackerEnd()
+        };
+        return Ok((instruction, debug_info));
+    }
+
+    let ops = split_operands(operands);
+    let expect = |count: usize| {
+        if ops.len() == count {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} expects {} operands, found {}",
+                mnemonic,
+                count,
+                ops.len()
+            ))
+        }
+    };
+    let label = |name: &str| {
+        labels
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("unknown label {}", name))
+    };
+
+    let instruction = match mnemonic {
+        "lw" => {
+            expect(5)?;
+            AsmInstruction::LoadF(
+                slot(ops[0])?,
+                slot(ops[1])?,
+                slot(ops[2])?,
+                felt(ops[3])?,
+                felt(ops[4])?,
+            )
+        }
+        "lwi" => {
+            expect(5)?;
+            AsmInstruction::LoadFI(
+                slot(ops[0])?,
+                slot(ops[1])?,
+                felt(ops[2])?,
+                felt(ops[3])?,
+                felt(ops[4])?,
+            )
+        }
+        "sw" => {
+            expect(5)?;
+            AsmInstruction::StoreF(
+                slot(ops[0])?,
+                slot(ops[1])?,
+                slot(ops[2])?,
+                felt(ops[3])?,
+                felt(ops[4])?,
+            )
+        }
+        "swi" => {
+            expect(5)?;
+            AsmInstruction::StoreFI(
+                slot(ops[0])?,
+                slot(ops[1])?,
+                felt(ops[2])?,
+                felt(ops[3])?,
+                felt(ops[4])?,
+            )
+        }
+        "imm" => {
+            expect(2)?;
+            AsmInstruction::ImmF(slot(ops[0])?, felt(ops[1])?)
+        }
+        "copy" => {
+            expect(2)?;
+            AsmInstruction::CopyF(slot(ops[0])?, slot(ops[1])?)
+        }
+        "add" | "sub" | "mul" | "div" | "eadd" | "esub" | "emul" | "ediv" => {
+            expect(3)?;
+            let (dst, lhs, rhs) = (slot(ops[0])?, slot(ops[1])?, slot(ops[2])?);
+            match mnemonic {
+                "add" => AsmInstruction::AddF(dst, lhs, rhs),
+                "sub" => AsmInstruction::SubF(dst, lhs, rhs),
+                "mul" => AsmInstruction::MulF(dst, lhs, rhs),
+                "div" => AsmInstruction::DivF(dst, lhs, rhs),
+                "eadd" => AsmInstruction::AddE(dst, lhs, rhs),
+                "esub" => AsmInstruction::SubE(dst, lhs, rhs),
+                "emul" => AsmInstruction::MulE(dst, lhs, rhs),
+                _ => AsmInstruction::DivE(dst, lhs, rhs),
+            }
+        }
+        "addi" | "subi" | "muli" | "divi" => {
+            expect(3)?;
+            let (dst, lhs, rhs) = (slot(ops[0])?, slot(ops[1])?, felt(ops[2])?);
+            match mnemonic {
+                "addi" => AsmInstruction::AddFI(dst, lhs, rhs),
+                "subi" => AsmInstruction::SubFI(dst, lhs, rhs),
+                "muli" => AsmInstruction::MulFI(dst, lhs, rhs),
+                _ => AsmInstruction::DivFI(dst, lhs, rhs),
+            }
+        }
+        "subin" | "divin" => {
+            expect(3)?;
+            let (dst, lhs, rhs) = (slot(ops[0])?, felt(ops[1])?, slot(ops[2])?);
+            match mnemonic {
+                "subin" => AsmInstruction::SubFIN(dst, lhs, rhs),
+                _ => AsmInstruction::DivFIN(dst, lhs, rhs),
+            }
+        }
+        "j" => {
+            expect(2)?;
+            AsmInstruction::Jump(slot(ops[0])?, label(ops[1])?)
+        }
+        "jr" => {
+            expect(2)?;
+            AsmInstruction::JumpIndirect(slot(ops[0])?, slot(ops[1])?)
+        }
+        "bne" | "beq" | "ebne" | "ebeq" => {
+            expect(3)?;
+            let (target, lhs, rhs) = (label(ops[0])?, slot(ops[1])?, slot(ops[2])?);
+            match mnemonic {
+                "bne" => AsmInstruction::Bne(target, lhs, rhs),
+                "beq" => AsmInstruction::Beq(target, lhs, rhs),
+                "ebne" => AsmInstruction::BneE(target, lhs, rhs),
+                _ => AsmInstruction::BeqE(target, lhs, rhs),
+            }
+        }
+        "bnei" | "beqi" => {
+            expect(3)?;
+            let (target, lhs, rhs) = (label(ops[0])?, slot(ops[1])?, felt(ops[2])?);
+            match mnemonic {
+                "bnei" => AsmInstruction::BneI(target, lhs, rhs),
+                _ => AsmInstruction::BeqI(target, lhs, rhs),
+            }
+        }
+        "ebnei" | "ebeqi" => {
+            expect(3)?;
+            let (target, lhs, rhs) = (label(ops[0])?, slot(ops[1])?, ext(ops[2])?);
+            match mnemonic {
+                "ebnei" => AsmInstruction::BneEI(target, lhs, rhs),
+                _ => AsmInstruction::BeqEI(target, lhs, rhs),
+            }
+        }
+        "trap" => {
+            expect(0)?;
+            AsmInstruction::Trap
+        }
+        "halt" => {
+            expect(0)?;
+            AsmInstruction::Halt
+        }
+        "hint_bits" => {
+            expect(2)?;
+            let len = ops[1]
+                .parse()
+                .map_err(|_| format!("invalid length {}", ops[1]))?;
+            AsmInstruction::HintBits(slot(ops[0])?, len)
+        }
+        "poseidon2_permute" => {
+            expect(2)?;
+            AsmInstruction::Poseidon2Permute(slot(ops[0])?, slot(ops[1])?)
+        }
+        "poseidon2_compress" => {
+            expect(3)?;
+            AsmInstruction::Poseidon2Compress(slot(ops[0])?, slot(ops[1])?, slot(ops[2])?)
+        }
+        "print_v" | "print_f" | "print_e" => {
+            expect(1)?;
+            let src = slot(ops[0])?;
+            match mnemonic {
+                "print_v" => AsmInstruction::PrintV(src),
+                "print_f" => AsmInstruction::PrintF(src),
+                _ => AsmInstruction::PrintE(src),
+            }
+        }
+        "hint_vec" => {
+            expect(0)?;
+            AsmInstruction::HintInputVec()
+        }
+        "shintw" => {
+            expect(2)?;
+            AsmInstruction::StoreHintWordI(slot(ops[0])?, felt(ops[1])?)
+        }
+        "commit" => {
+            expect(2)?;
+            AsmInstruction::Publish(slot(ops[0])?, slot(ops[1])?)
+        }
+        "fri_mat_opening" => {
+            expect(6)?;
+            AsmInstruction::FriReducedOpening(
+                slot(ops[0])?,
+                slot(ops[1])?,
+                slot(ops[2])?,
+                slot(ops[3])?,
+                slot(ops[4])?,
+                slot(ops[5])?,
+            )
+        }
+        _ => return Err(format!("unknown instruction {}", mnemonic)),
+    };
+    Ok((instruction, None))
+}
+
+/// Splits operands on the commas outside of extension field immediates.
+fn split_operands(operands: &str) -> Vec<&str> {
+    if operands.is_empty() {
+        return vec![];
+    }
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in operands.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(operands[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(operands[start..].trim());
+    result
+}
+
+/// Parses a slot `(addr)fp`.
+fn slot(operand: &str) -> Result<i32, String> {
+    operand
+        .strip_prefix('(')
+        .and_then(|operand| operand.strip_suffix(")fp"))
+        .and_then(|addr| addr.parse().ok())
+        .ok_or_else(|| format!("invalid slot {}", operand))
+}
+
+/// Parses a field element in canonical form.
+fn felt<F: PrimeField32>(operand: &str) -> Result<F, String> {
+    match operand.parse::<u32>() {
+        Ok(value) if value < F::ORDER_U32 => Ok(F::from_canonical_u32(value)),
+        _ => Err(format!("invalid field element {}", operand)),
+    }
+}
+
+/// Parses an extension field element `[c0, c1, ..]` from its coefficients.
+fn ext<F: PrimeField32, EF: ExtensionField<F>>(operand: &str) -> Result<EF, String> {
+    let coeffs = operand
+        .strip_prefix('[')
+        .and_then(|operand| operand.strip_suffix(']'))
+        .ok_or_else(|| format!("invalid extension field element {}", operand))?
+        .split(',')
+        .map(|coeff| felt(coeff.trim()))
+        .collect::<Result<Vec<F>, _>>()?;
+    if coeffs.len() != EF::D {
+        return Err(format!(
+            "extension field element {} has {} coefficients, expected {}",
+            operand,
+            coeffs.len(),
+            EF::D
+        ));
+    }
+    Ok(EF::from_base_slice(&coeffs))
+}
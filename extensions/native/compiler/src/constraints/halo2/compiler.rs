@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::Debug,
     marker::PhantomData,
     panic::{catch_unwind, AssertUnwindSafe},
@@ -20,7 +20,7 @@ use snark_verifier_sdk::snark_verifier::{
     util::arithmetic::PrimeField as _,
 };
 
-use super::stats::Halo2Stats;
+use super::stats::{DslOperationStats, Halo2Stats};
 use crate::{
     constraints::halo2::{
         baby_bear::{
@@ -130,6 +130,32 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
     pub fn constrain_halo2(&self, halo2_state: &mut Halo2State<C>, operations: TracedVec<DslIr<C>>)
     where
         C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        self.constrain_halo2_impl(halo2_state, operations, None);
+    }
+
+    /// Same as [Self::constrain_halo2], but also returns the cells used by each kind of DSL
+    /// operation, keyed by the name of the [DslIr] variant.
+    pub fn constrain_halo2_with_stats(
+        &self,
+        halo2_state: &mut Halo2State<C>,
+        operations: TracedVec<DslIr<C>>,
+    ) -> BTreeMap<String, DslOperationStats>
+    where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
+    {
+        let mut stats = BTreeMap::new();
+        self.constrain_halo2_impl(halo2_state, operations, Some(&mut stats));
+        stats
+    }
+
+    fn constrain_halo2_impl(
+        &self,
+        halo2_state: &mut Halo2State<C>,
+        operations: TracedVec<DslIr<C>>,
+        mut op_stats: Option<&mut BTreeMap<String, DslOperationStats>>,
+    ) where
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BabyBearExt4>,
     {
         let mut cell_tracker = CycleTracker::new();
         let range = Arc::new(halo2_state.builder.range_chip());
@@ -153,6 +179,9 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
             if self.collect_metrics {
                 old_stats = stats_snapshot(ctx, range.clone());
             }
+            let op_snapshot = op_stats
+                .is_some()
+                .then(|| (instruction.to_string(), stats_snapshot(ctx, range.clone())));
             let res = catch_unwind(AssertUnwindSafe(|| {
                 match instruction {
                     DslIr::ImmV(a, b) => {
@@ -437,6 +466,13 @@ impl<C: Config + Debug> Halo2ConstraintCompiler<C> {
                 new_stats.diff(&old_stats);
                 new_stats.increment(cell_tracker.get_full_name());
             }
+            if let (Some(op_stats), Some((name, before))) = (op_stats.as_mut(), op_snapshot) {
+                let mut cells = stats_snapshot(ctx, range.clone());
+                cells.diff(&before);
+                let entry = op_stats.entry(name).or_default();
+                entry.num_operations += 1;
+                entry.cells.add_assign(&cells);
+            }
         }
 
         halo2_state.builder.assigned_instances = vec![public_values];
@@ -457,7 +493,6 @@ pub fn convert_efr<F: PrimeField, EF: ExtensionField<F>>(a: &EF) -> Vec<Fr> {
 }
 
 // Unfortunately `builder.statistics()` cannot be called when `ctx` exists.
-#[allow(dead_code)] // used only in bench-metrics
 fn stats_snapshot(ctx: &Context<Fr>, range_chip: Arc<RangeChip<Fr>>) -> Halo2Stats {
     Halo2Stats {
         total_gate_cell: ctx.advice.len(),
@@ -1,17 +1,34 @@
-#[derive(Default, Clone)]
-pub(crate) struct Halo2Stats {
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Halo2Stats {
     pub total_gate_cell: usize,
     pub total_fixed: usize,
     pub total_lookup_cell: usize,
 }
 
 impl Halo2Stats {
-    #[allow(dead_code)]
     pub fn add_assign(&mut self, b: &Self) {
         self.total_gate_cell += b.total_gate_cell;
         self.total_fixed += b.total_fixed;
         self.total_lookup_cell += b.total_lookup_cell;
     }
+
+    pub fn diff(&mut self, another: &Self) {
+        *self = Self {
+            total_gate_cell: self.total_gate_cell - another.total_gate_cell,
+            total_fixed: self.total_fixed - another.total_fixed,
+            total_lookup_cell: self.total_lookup_cell - another.total_lookup_cell,
+        };
+    }
+}
+
+/// Cells used by all DSL operations of the same kind.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct DslOperationStats {
+    pub num_operations: usize,
+    #[serde(flatten)]
+    pub cells: Halo2Stats,
 }
 
 #[cfg(feature = "bench-metrics")]
@@ -21,13 +38,6 @@ mod emit {
     use super::Halo2Stats;
 
     impl Halo2Stats {
-        pub fn diff(&mut self, another: &Self) {
-            *self = Self {
-                total_gate_cell: self.total_gate_cell - another.total_gate_cell,
-                total_fixed: self.total_fixed - another.total_fixed,
-                total_lookup_cell: self.total_lookup_cell - another.total_lookup_cell,
-            };
-        }
         pub fn increment(&self, span_name: String) {
             let labels = [("cell_tracker_span", span_name)];
             counter!("simple_advice_cells", &labels).increment(self.total_gate_cell as u64);
@@ -12,7 +12,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     asm::{AsmInstruction, AssemblyCode},
     FieldArithmeticOpcode, FieldExtensionOpcode, FriOpcode, NativeBranchEqualOpcode,
-    NativeJalOpcode, NativeLoadStoreOpcode, NativePhantom,
+    NativeJalOpcode, NativeJalrOpcode, NativeLoadStoreOpcode, NativePhantom,
 };
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -23,6 +23,9 @@ pub struct CompilerOptions {
     pub enable_cycle_tracker: bool,
     pub field_arithmetic_enabled: bool,
     pub field_extension_enabled: bool,
+    /// Optimize the assembly code before converting it, see [crate::asm::optimize].
+    #[serde(default)]
+    pub optimize: bool,
 }
 
 impl Default for CompilerOptions {
@@ -33,6 +36,7 @@ impl Default for CompilerOptions {
             enable_cycle_tracker: false,
             field_arithmetic_enabled: true,
             field_extension_enabled: true,
+            optimize: false,
         }
     }
 }
@@ -46,6 +50,10 @@ impl CompilerOptions {
         self.enable_cycle_tracker = true;
         self
     }
+    pub fn with_optimizations(mut self) -> Self {
+        self.optimize = true;
+        self
+    }
 }
 
 fn inst<F: PrimeField64>(opcode: VmOpcode, a: F, b: F, c: F, d: AS, e: AS) -> Instruction<F> {
@@ -417,6 +425,17 @@ fn convert_instruction<F: PrimeField32, EF: ExtensionField<F>>(
                 ),
             ]
         }
+        AsmInstruction::JumpIndirect(dst, src) => vec![
+            // pc <- mem[src], mem[dst] <- pc
+            inst(
+                options.opcode_with_offset(NativeJalrOpcode::JALR),
+                i32_f(dst),
+                i32_f(src),
+                F::ZERO,
+                AS::Memory,
+                AS::Memory,
+            ),
+        ],
         AsmInstruction::Bne(label, lhs, rhs) => vec![
             // if mem[lhs] != mem[rhs], pc <- labels[label]
             inst(
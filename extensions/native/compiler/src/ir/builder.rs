@@ -1,12 +1,12 @@
-use std::{iter::Zip, vec::IntoIter};
+use std::{cell::RefCell, iter::Zip, rc::Rc, vec::IntoIter};
 
 use backtrace::Backtrace;
 use openvm_stark_backend::p3_field::AbstractField;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    Array, Config, DslIr, Ext, Felt, FromConstant, MemIndex, MemVariable, RVar, SymbolicExt,
-    SymbolicFelt, SymbolicVar, Usize, Var, Variable,
+    function::FunctionTable, Array, Config, DslIr, Ext, Felt, FromConstant, MemIndex, MemVariable,
+    RVar, SymbolicExt, SymbolicFelt, SymbolicVar, Usize, Var, Variable,
 };
 
 /// TracedVec is a Vec wrapper that records a trace whenever an element is pushed. When extending
@@ -116,6 +116,9 @@ pub struct Builder<C: Config> {
     pub(crate) witness_ext_count: u32,
     pub flags: BuilderFlags,
     pub is_sub_builder: bool,
+    pub(crate) functions: Rc<RefCell<FunctionTable>>,
+    /// End of the variable ids of the function frame being built, `None` outside of functions.
+    pub(crate) frame_end: Option<u32>,
 }
 
 impl<C: Config> Builder<C> {
@@ -134,6 +137,8 @@ impl<C: Config> Builder<C> {
             nb_public_values: self.nb_public_values,
             flags: self.flags,
             is_sub_builder: true,
+            functions: self.functions.clone(),
+            frame_end: self.frame_end,
         }
     }
 
@@ -0,0 +1,105 @@
+use openvm_stark_backend::p3_field::AbstractField;
+
+use super::{Array, Builder, Config, RangeBuilder, Usize, Var};
+
+/// An array of bytes, each stored in a `Var` in `[0, 256)`.
+///
+/// Hash functions need the length of their input at compile time, so byte arrays should be
+/// created with [Builder::bytes] or [Builder::constant_bytes] rather than [Builder::array].
+pub type ByteArray<C> = Array<C, Var<<C as Config>::N>>;
+
+/// An array of bits, each stored in a `Var` in `{0, 1}`.
+///
+/// Bit `i` of byte `j` of a byte string is at index `8 * j + i`, so the bits of a byte array are
+/// ordered as in [Builder::bytes2bits].
+pub type BitArray<C> = Array<C, Var<<C as Config>::N>>;
+
+impl<C: Config> Builder<C> {
+    /// Creates an uninitialized byte array of length `len`.
+    pub fn bytes(&mut self, len: usize) -> ByteArray<C> {
+        self.const_len_array(len)
+    }
+
+    /// Creates a byte array holding `bytes`.
+    pub fn constant_bytes(&mut self, bytes: &[u8]) -> ByteArray<C> {
+        let array = self.bytes(bytes.len());
+        for (i, &byte) in bytes.iter().enumerate() {
+            let byte: Var<_> = self.eval(C::N::from_canonical_u8(byte));
+            self.set_value(&array, i, byte);
+        }
+        array
+    }
+
+    /// Decomposes `bytes` into bits, constraining every byte to be in `[0, 256)`.
+    pub fn bytes2bits(&mut self, bytes: &ByteArray<C>) -> BitArray<C> {
+        let len = const_len(bytes);
+        let bits = self.const_len_array(8 * len);
+        for i in 0..len {
+            let byte = self.get(bytes, i);
+            for (j, bit) in self.byte2bits(byte).into_iter().enumerate() {
+                self.set_value(&bits, 8 * i + j, bit);
+            }
+        }
+        bits
+    }
+
+    /// Recomposes the bytes of `bits`, whose length must be a multiple of 8.
+    pub fn bits2bytes(&mut self, bits: &BitArray<C>) -> ByteArray<C> {
+        let len = const_len(bits);
+        assert_eq!(len % 8, 0, "number of bits must be a multiple of 8");
+        let bytes = self.bytes(len / 8);
+        for i in 0..len / 8 {
+            let byte_bits: Vec<_> = (0..8).map(|j| self.get(bits, 8 * i + j)).collect();
+            let byte = self.bits2num_v_circuit(&byte_bits);
+            self.set_value(&bytes, i, byte);
+        }
+        bytes
+    }
+
+    /// Decomposes a byte into 8 little-endian bits.
+    pub(crate) fn byte2bits(&mut self, byte: Var<C::N>) -> Vec<Var<C::N>> {
+        if self.flags.static_only {
+            self.num2bits_v_circuit(byte, 8)
+        } else {
+            let bits = self.num2bits_v(byte, 8);
+            (0..8).map(|i| self.get(&bits, i)).collect()
+        }
+    }
+
+    /// Computes `a ^ b` for bits `a` and `b`, as `(a - b)^2`.
+    pub(crate) fn xor_bits(&mut self, a: Var<C::N>, b: Var<C::N>) -> Var<C::N> {
+        let diff: Var<_> = self.eval(a - b);
+        self.eval(diff * diff)
+    }
+
+    /// Creates an uninitialized array whose length is known at compile time, also in dynamic mode.
+    pub(crate) fn const_len_array(&mut self, len: usize) -> Array<C, Var<C::N>> {
+        if self.flags.static_only {
+            self.uninit_fixed_array(len)
+        } else {
+            let ptr = self.alloc(len, 1);
+            Array::Dyn(ptr, Usize::from(len))
+        }
+    }
+
+    /// Iterates over `start..end`, unrolled in static mode and as a loop otherwise, which keeps the
+    /// size of the program independent of the number of iterations.
+    pub(crate) fn runtime_range(&mut self, start: usize, end: usize) -> RangeBuilder<C> {
+        if self.flags.static_only {
+            self.range(start, end)
+        } else {
+            let end: Var<_> = self.eval(C::N::from_canonical_usize(end));
+            self.range(start, end)
+        }
+    }
+}
+
+/// Returns the length of `array`, which must be known at compile time.
+pub(crate) fn const_len<C: Config>(array: &Array<C, Var<C::N>>) -> usize {
+    let len = array.len();
+    assert!(
+        len.is_const(),
+        "length of the array must be known at compile time"
+    );
+    len.value()
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeMap;
+
+use super::{Builder, Config, DslIr, Var, Variable};
+
+/// Variable ids of function frames start here, above the ids used by the program itself.
+const FUNCTION_FRAME_START: u32 = 1 << 20;
+/// Number of variable ids reserved for the frame of each function.
+const FUNCTION_FRAME_SIZE: u32 = 1 << 16;
+/// Maximum number of functions in a program, bounded by the size of the stack.
+const MAX_NUM_FUNCTIONS: u32 = 15;
+
+/// The functions defined while building a program, shared between a builder and its sub-builders.
+#[derive(Debug, Default)]
+pub(crate) struct FunctionTable {
+    num_functions: u32,
+    /// The id and signature of each function by name, `None` while its body is being built.
+    functions: BTreeMap<String, Option<(u32, &'static str)>>,
+}
+
+/// The frame of a function.
+///
+/// The parameters, return value and locals of a function live in its own frame, so calls do not
+/// clobber the variables of the caller. As there is a single frame per function, functions are not
+/// reentrant and recursion is not supported.
+struct Frame<C: Config, Args, Ret> {
+    /// The pc the function returns to, stored by the call.
+    call_site: Var<C::N>,
+    params: Args,
+    ret: Ret,
+}
+
+impl<C: Config> Builder<C> {
+    /// Calls the function `name` with `args`.
+    ///
+    /// The first call compiles `body` into a subroutine, which later calls of `name` jump to. In
+    /// static mode `body` is inlined at every call instead. Arguments and return values are copied,
+    /// so they can be any variable, including dynamic arrays and structs of variables, but not
+    /// fixed arrays.
+    ///
+    /// `body` is a function pointer rather than a closure: as it is only compiled once, variables
+    /// captured from the first call site would be used by every later call. Pass everything the
+    /// body depends on through `args`.
+    pub fn call<Args, Ret>(
+        &mut self,
+        name: &str,
+        args: Args,
+        body: fn(&mut Builder<C>, Args) -> Ret,
+    ) -> Ret
+    where
+        Args: Variable<C> + Into<Args::Expression>,
+        Ret: Variable<C> + Into<Ret::Expression>,
+    {
+        if self.flags.static_only {
+            return body(self, args);
+        }
+
+        let signature = std::any::type_name::<(Args, Ret)>();
+        let entry = self.functions.borrow().functions.get(name).cloned();
+        let (id, frame) = match entry {
+            Some(Some((id, function_signature))) => {
+                assert_eq!(
+                    signature, function_signature,
+                    "function {name} called with a different signature"
+                );
+                (id, self.frame(id).0)
+            }
+            Some(None) => panic!("recursive call of function {name}"),
+            None => self.define_function(name, signature, body),
+        };
+
+        frame.params.assign(args.into(), self);
+        self.operations.push(DslIr::Call(id, frame.call_site));
+        // Copy the return value out of the frame before the next call overwrites it.
+        let ret = Ret::uninit(self);
+        ret.assign(frame.ret.into(), self);
+        ret
+    }
+
+    fn define_function<Args, Ret>(
+        &mut self,
+        name: &str,
+        signature: &'static str,
+        body: fn(&mut Builder<C>, Args) -> Ret,
+    ) -> (u32, Frame<C, Args, Ret>)
+    where
+        Args: Variable<C>,
+        Ret: Variable<C> + Into<Ret::Expression>,
+    {
+        let id = {
+            let mut table = self.functions.borrow_mut();
+            let id = table.num_functions;
+            assert!(id < MAX_NUM_FUNCTIONS, "too many functions");
+            table.num_functions += 1;
+            // Mark the function as being defined to catch recursive calls.
+            table.functions.insert(name.to_string(), None);
+            id
+        };
+
+        let (frame, mut frame_builder) = self.frame::<Args, Ret>(id);
+        let ret = body(&mut frame_builder, frame.params.clone());
+        frame.ret.assign(ret.into(), &mut frame_builder);
+
+        self.operations.push(DslIr::Function(
+            id,
+            name.to_string(),
+            frame.call_site,
+            frame_builder.operations,
+        ));
+        self.functions
+            .borrow_mut()
+            .functions
+            .insert(name.to_string(), Some((id, signature)));
+        (id, frame)
+    }
+
+    /// Asserts that a newly allocated variable id stays in the id range of the builder: below the
+    /// function frames for the program itself, or within the frame of the function being built.
+    ///
+    /// Static mode inlines functions, so it has no frames and no limit.
+    pub(crate) fn check_variable_id(&self, id: u32) {
+        if self.flags.static_only {
+            return;
+        }
+        match self.frame_end {
+            None => assert!(
+                id < FUNCTION_FRAME_START,
+                "too many variables: ids from {FUNCTION_FRAME_START} are reserved for function frames"
+            ),
+            Some(frame_end) => assert!(id < frame_end, "function frame is too large"),
+        }
+    }
+
+    /// Allocates the frame of function `id`, returning a builder for the body of the function.
+    ///
+    /// Variables are allocated deterministically, so every call gets the same frame.
+    fn frame<Args: Variable<C>, Ret: Variable<C>>(
+        &self,
+        id: u32,
+    ) -> (Frame<C, Args, Ret>, Builder<C>) {
+        let frame_start = FUNCTION_FRAME_START + id * FUNCTION_FRAME_SIZE;
+        let mut frame_builder = self.create_sub_builder();
+        frame_builder.var_count = frame_start;
+        frame_builder.felt_count = frame_start;
+        frame_builder.ext_count = frame_start;
+        frame_builder.frame_end = Some(frame_start + FUNCTION_FRAME_SIZE);
+
+        let frame = Frame {
+            call_site: frame_builder.uninit(),
+            params: Args::uninit(&mut frame_builder),
+            ret: Ret::uninit(&mut frame_builder),
+        };
+        (frame, frame_builder)
+    }
+}
@@ -142,6 +142,12 @@ pub enum DslIr<C: Config> {
     IfNeI(Var<C::N>, C::N, TracedVec<DslIr<C>>, TracedVec<DslIr<C>>),
     /// Break out of a loop.
     Break,
+    /// Defines a function with the parameters (function id, name, call site var, body). The body is
+    /// skipped when the definition is reached and only runs when the function is called.
+    Function(u32, String, Var<C::N>, TracedVec<DslIr<C>>),
+    /// Calls a function with the parameters (function id, call site var). The call stores the return
+    /// pc in the call site var.
+    Call(u32, Var<C::N>),
 
     // Assertions.
     /// Assert that two variables are equal (var == var).
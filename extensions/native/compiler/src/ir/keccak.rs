@@ -0,0 +1,201 @@
+use openvm_stark_backend::p3_field::AbstractField;
+
+use super::{bytes::const_len, BitArray, Builder, ByteArray, Config, Var};
+
+/// Number of bits of the Keccak-f[1600] state.
+pub const KECCAK_STATE_BITS: usize = 1600;
+/// Number of bytes absorbed by each permutation of Keccak-256.
+pub const KECCAK256_RATE: usize = 136;
+/// Number of bytes of a Keccak-256 digest.
+pub const KECCAK256_DIGEST_BYTES: usize = 32;
+
+const KECCAK_ROUNDS: usize = 24;
+const LANE_BITS: usize = 64;
+
+/// Rotation offsets of ρ, indexed by `x + 5 * y`.
+const RHO_OFFSETS: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// Round constants of ι.
+const ROUND_CONSTANTS: [u64; KECCAK_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The only bits of the lane that can be set in a round constant.
+const ROUND_CONSTANT_BITS: [usize; 7] = [0, 1, 3, 7, 15, 31, 63];
+
+/// Index of bit `z` of lane `(x, y)` in the state.
+const fn lane_bit(x: usize, y: usize, z: usize) -> usize {
+    LANE_BITS * (x + 5 * y) + z
+}
+
+impl<C: Config> Builder<C> {
+    /// Applies the Keccak-f[1600] permutation to `state` in place.
+    ///
+    /// `state` holds the bits of the state as bytes, see [BitArray]: lane `(x, y)` is at bits
+    /// `64 * (x + 5 * y)..64 * (x + 5 * y + 1)`. In dynamic mode, the permutation is compiled into
+    /// a function once and `state` must be a dynamic array.
+    ///
+    /// Reference: <https://keccak.team/keccak_specs_summary.html>
+    pub fn keccak_f(&mut self, state: &BitArray<C>) {
+        self.call("keccak_f", state.clone(), |builder, state| {
+            builder.keccak_permute(&state);
+            state
+        });
+    }
+
+    /// Computes the Keccak-256 digest of `bytes`, as used by Ethereum.
+    pub fn keccak256(&mut self, bytes: &ByteArray<C>) -> ByteArray<C> {
+        let len = const_len(bytes);
+        let zero: Var<_> = self.eval(C::N::ZERO);
+        let state = self.const_len_array(KECCAK_STATE_BITS);
+        for i in 0..KECCAK_STATE_BITS {
+            self.set_value(&state, i, zero);
+        }
+
+        // Absorb the message padded with pad10*1 and the domain separator of Keccak.
+        for block in 0..len / KECCAK256_RATE + 1 {
+            for i in 0..KECCAK256_RATE {
+                let index = block * KECCAK256_RATE + i;
+                if index < len {
+                    let byte = self.get(bytes, index);
+                    for (j, bit) in self.byte2bits(byte).into_iter().enumerate() {
+                        let state_bit = self.get(&state, 8 * i + j);
+                        let state_bit = self.xor_bits(state_bit, bit);
+                        self.set_value(&state, 8 * i + j, state_bit);
+                    }
+                } else {
+                    let mut padding = 0u8;
+                    if index == len {
+                        padding |= 0x01;
+                    }
+                    if i == KECCAK256_RATE - 1 {
+                        padding |= 0x80;
+                    }
+                    for j in (0..8).filter(|j| padding >> j & 1 == 1) {
+                        let state_bit = self.get(&state, 8 * i + j);
+                        let state_bit: Var<_> = self.eval(C::N::ONE - state_bit);
+                        self.set_value(&state, 8 * i + j, state_bit);
+                    }
+                }
+            }
+            self.keccak_f(&state);
+        }
+
+        let digest = self.bytes(KECCAK256_DIGEST_BYTES);
+        for i in 0..KECCAK256_DIGEST_BYTES {
+            let bits: Vec<_> = (0..8).map(|j| self.get(&state, 8 * i + j)).collect();
+            let byte = self.bits2num_v_circuit(&bits);
+            self.set_value(&digest, i, byte);
+        }
+        digest
+    }
+
+    fn keccak_permute(&mut self, state: &BitArray<C>) {
+        // The bits of the round constants, indexed by `7 * round + i` for bit
+        // `ROUND_CONSTANT_BITS[i]`.
+        let round_constants = self.const_len_array(KECCAK_ROUNDS * ROUND_CONSTANT_BITS.len());
+        for (round, constant) in ROUND_CONSTANTS.iter().enumerate() {
+            for (i, bit) in ROUND_CONSTANT_BITS.iter().enumerate() {
+                let value: Var<_> = self.eval(C::N::from_bool(constant >> bit & 1 == 1));
+                self.set_value(
+                    &round_constants,
+                    ROUND_CONSTANT_BITS.len() * round + i,
+                    value,
+                );
+            }
+        }
+
+        self.runtime_range(0, KECCAK_ROUNDS)
+            .for_each(|round, builder| {
+                builder.keccak_round(state);
+
+                // ι
+                for (i, &bit) in ROUND_CONSTANT_BITS.iter().enumerate() {
+                    let index = builder.eval_expr(
+                        round * C::N::from_canonical_usize(ROUND_CONSTANT_BITS.len())
+                            + C::N::from_canonical_usize(i),
+                    );
+                    let constant = builder.get(&round_constants, index);
+                    let state_bit = builder.get(state, bit);
+                    let state_bit = builder.xor_bits(state_bit, constant);
+                    builder.set_value(state, bit, state_bit);
+                }
+            });
+    }
+
+    /// Applies θ, ρ, π and χ to `state`.
+    fn keccak_round(&mut self, state: &BitArray<C>) {
+        let a: Vec<_> = (0..KECCAK_STATE_BITS).map(|i| self.get(state, i)).collect();
+
+        // θ
+        let mut c = vec![vec![]; 5];
+        for (x, parities) in c.iter_mut().enumerate() {
+            for z in 0..LANE_BITS {
+                let mut parity = a[lane_bit(x, 0, z)];
+                for y in 1..5 {
+                    parity = self.xor_bits(parity, a[lane_bit(x, y, z)]);
+                }
+                parities.push(parity);
+            }
+        }
+        let mut d = vec![vec![]; 5];
+        for (x, lane) in d.iter_mut().enumerate() {
+            for z in 0..LANE_BITS {
+                let bit = self.xor_bits(c[(x + 4) % 5][z], c[(x + 1) % 5][(z + 63) % 64]);
+                lane.push(bit);
+            }
+        }
+
+        // The end of θ, then ρ and π
+        let mut b = a.clone();
+        for x in 0..5 {
+            for y in 0..5 {
+                let offset = RHO_OFFSETS[x + 5 * y];
+                for z in 0..LANE_BITS {
+                    let bit = self.xor_bits(a[lane_bit(x, y, z)], d[x][z]);
+                    b[lane_bit(y, (2 * x + 3 * y) % 5, (z + offset) % LANE_BITS)] = bit;
+                }
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                for z in 0..LANE_BITS {
+                    let b1 = b[lane_bit((x + 1) % 5, y, z)];
+                    let b2 = b[lane_bit((x + 2) % 5, y, z)];
+                    // !b1 & b2 = b2 - b1 * b2
+                    let and: Var<_> = self.eval(b1 * b2);
+                    let and_not: Var<_> = self.eval(b2 - and);
+                    let bit = self.xor_bits(b[lane_bit(x, y, z)], and_not);
+                    self.set_value(state, lane_bit(x, y, z), bit);
+                }
+            }
+        }
+    }
+}
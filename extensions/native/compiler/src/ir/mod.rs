@@ -1,11 +1,14 @@
 pub use builder::*;
+pub use bytes::{BitArray, ByteArray};
 pub use collections::*;
 pub use instructions::*;
+pub use keccak::{KECCAK256_DIGEST_BYTES, KECCAK256_RATE, KECCAK_STATE_BITS};
 use openvm_stark_backend::p3_field::{ExtensionField, PrimeField, TwoAdicField};
 pub use poseidon::{DIGEST_SIZE, PERMUTATION_WIDTH};
 pub use ptr::*;
 pub use ref_ptr::*;
 pub use select::*;
+pub use sha256::{SHA256_BLOCK_BYTES, SHA256_DIGEST_BYTES};
 pub use symbolic::*;
 pub use types::*;
 pub use utils::{LIMB_BITS, NUM_LIMBS};
@@ -13,13 +16,17 @@ pub use var::*;
 
 mod bits;
 mod builder;
+mod bytes;
 mod collections;
 mod fri;
+mod function;
 mod instructions;
+mod keccak;
 mod poseidon;
 mod ptr;
 mod ref_ptr;
 mod select;
+mod sha256;
 mod symbolic;
 mod types;
 mod utils;
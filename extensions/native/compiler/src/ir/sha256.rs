@@ -0,0 +1,269 @@
+use openvm_native_compiler_derive::DslVariable;
+use openvm_stark_backend::p3_field::AbstractField;
+
+use super::{
+    bytes::const_len, Array, BitArray, Builder, ByteArray, Config, MemIndex, MemVariable, Ptr,
+    SymbolicVar, Var, Variable,
+};
+
+/// Number of bytes of a SHA-256 block.
+pub const SHA256_BLOCK_BYTES: usize = 64;
+/// Number of bytes of a SHA-256 digest, which is also the size of the state.
+pub const SHA256_DIGEST_BYTES: usize = 32;
+
+const SHA256_ROUNDS: usize = 64;
+const WORD_BITS: usize = 32;
+
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA256_K: [u32; SHA256_ROUNDS] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Index of bit `k` of the big-endian word `w` of a byte string, in the bits of the byte string.
+const fn word_bit(w: usize, k: usize) -> usize {
+    8 * (4 * w + 3 - k / 8) + k % 8
+}
+
+#[derive(DslVariable, Clone)]
+struct Sha256CompressArgs<C: Config> {
+    state: Array<C, Var<C::N>>,
+    block: Array<C, Var<C::N>>,
+}
+
+impl<C: Config> Builder<C> {
+    /// Applies the SHA-256 compression function to `state` in place, with the message `block`.
+    ///
+    /// `state` holds the bits of the 32 bytes of the state and `block` the bits of the 64 bytes
+    /// of the block, see [BitArray]. In dynamic mode, the compression function is compiled into a
+    /// function once and both arrays must be dynamic arrays.
+    ///
+    /// Reference: [FIPS 180-4](https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf)
+    pub fn sha256_compress(&mut self, state: &BitArray<C>, block: &BitArray<C>) {
+        let args = Sha256CompressArgs {
+            state: state.clone(),
+            block: block.clone(),
+        };
+        self.call("sha256_compress", args, |builder, args| {
+            builder.sha256_compress_block(&args.state, &args.block);
+            args.state
+        });
+    }
+
+    /// Computes the SHA-256 digest of `bytes`.
+    pub fn sha256(&mut self, bytes: &ByteArray<C>) -> ByteArray<C> {
+        let len = const_len(bytes);
+        let zero: Var<_> = self.eval(C::N::ZERO);
+        let one: Var<_> = self.eval(C::N::ONE);
+        let bit = |value: bool| if value { one } else { zero };
+
+        let state = self.const_len_array(8 * SHA256_DIGEST_BYTES);
+        for (w, word) in SHA256_IV.iter().enumerate() {
+            for k in 0..WORD_BITS {
+                self.set_value(&state, word_bit(w, k), bit(word >> k & 1 == 1));
+            }
+        }
+
+        // The message is padded with a one bit, zeros and its length in bits as a big-endian u64.
+        let num_blocks = (len + 9).div_ceil(SHA256_BLOCK_BYTES);
+        let padded_len = num_blocks * SHA256_BLOCK_BYTES;
+        let bit_len = 8 * len as u64;
+        for block_index in 0..num_blocks {
+            let block = self.const_len_array(8 * SHA256_BLOCK_BYTES);
+            for i in 0..SHA256_BLOCK_BYTES {
+                let index = block_index * SHA256_BLOCK_BYTES + i;
+                if index < len {
+                    let byte = self.get(bytes, index);
+                    for (j, byte_bit) in self.byte2bits(byte).into_iter().enumerate() {
+                        self.set_value(&block, 8 * i + j, byte_bit);
+                    }
+                } else {
+                    let padding = if index == len {
+                        0x80
+                    } else if index >= padded_len - 8 {
+                        (bit_len >> (8 * (padded_len - 1 - index))) as u8
+                    } else {
+                        0
+                    };
+                    for j in 0..8 {
+                        self.set_value(&block, 8 * i + j, bit(padding >> j & 1 == 1));
+                    }
+                }
+            }
+            self.sha256_compress(&state, &block);
+        }
+
+        self.bits2bytes(&state)
+    }
+
+    fn sha256_compress_block(&mut self, state: &BitArray<C>, block: &BitArray<C>) {
+        let zero: Var<_> = self.eval(C::N::ZERO);
+        let one: Var<_> = self.eval(C::N::ONE);
+
+        // Words are stored as little-endian bits from here on, word `t` at bits `32 * t..`.
+        let round_constants = self.const_len_array(SHA256_ROUNDS * WORD_BITS);
+        for (t, constant) in SHA256_K.iter().enumerate() {
+            for k in 0..WORD_BITS {
+                let value = if constant >> k & 1 == 1 { one } else { zero };
+                self.set_value(&round_constants, WORD_BITS * t + k, value);
+            }
+        }
+
+        // Message schedule.
+        let schedule = self.const_len_array(SHA256_ROUNDS * WORD_BITS);
+        for t in 0..16 {
+            for k in 0..WORD_BITS {
+                let value = self.get(block, word_bit(t, k));
+                self.set_value(&schedule, WORD_BITS * t + k, value);
+            }
+        }
+        self.runtime_range(16, SHA256_ROUNDS)
+            .for_each(|t, builder| {
+                let word =
+                    |offset: usize| SymbolicVar::from(t) - C::N::from_canonical_usize(offset);
+                let w2 = builder.get_word(&schedule, word(2));
+                let w7 = builder.get_word(&schedule, word(7));
+                let w15 = builder.get_word(&schedule, word(15));
+                let w16 = builder.get_word(&schedule, word(16));
+                let s0 = builder.sigma(&w15, [7, 18, 3], true);
+                let s1 = builder.sigma(&w2, [17, 19, 10], true);
+                let w = builder.add_words(&w16, &s0);
+                let w = builder.add_words(&w, &w7);
+                let w = builder.add_words(&w, &s1);
+                builder.set_word(&schedule, t.into(), &w);
+            });
+
+        // Rounds, on the working variables `a, b, c, d, e, f, g, h`.
+        let working = self.const_len_array(8 * WORD_BITS);
+        for w in 0..8 {
+            for k in 0..WORD_BITS {
+                let value = self.get(state, word_bit(w, k));
+                self.set_value(&working, WORD_BITS * w + k, value);
+            }
+        }
+        self.runtime_range(0, SHA256_ROUNDS).for_each(|t, builder| {
+            let vars: Vec<_> = (0..8)
+                .map(|w| builder.get_word(&working, C::N::from_canonical_usize(w).into()))
+                .collect();
+            let [a, b, c, d, e, f, g, h] = &vars[..] else {
+                unreachable!()
+            };
+
+            let s1 = builder.sigma(e, [6, 11, 25], false);
+            // ch(e, f, g) = (e & f) ^ (!e & g) = g + e * (f - g)
+            let ch: Vec<Var<_>> = (0..WORD_BITS)
+                .map(|k| {
+                    let diff: Var<_> = builder.eval(f[k] - g[k]);
+                    builder.eval(g[k] + e[k] * diff)
+                })
+                .collect();
+            let constant = builder.get_word(&round_constants, t.into());
+            let w = builder.get_word(&schedule, t.into());
+            let temp1 = builder.add_words(h, &s1);
+            let temp1 = builder.add_words(&temp1, &ch);
+            let temp1 = builder.add_words(&temp1, &constant);
+            let temp1 = builder.add_words(&temp1, &w);
+
+            let s0 = builder.sigma(a, [2, 13, 22], false);
+            // maj(a, b, c) = (a & b) ^ (a & c) ^ (b & c) = a * b + c * (a ^ b)
+            let maj: Vec<Var<_>> = (0..WORD_BITS)
+                .map(|k| {
+                    let and: Var<_> = builder.eval(a[k] * b[k]);
+                    let xor = builder.xor_bits(a[k], b[k]);
+                    builder.eval(and + c[k] * xor)
+                })
+                .collect();
+            let temp2 = builder.add_words(&s0, &maj);
+
+            let new_a = builder.add_words(&temp1, &temp2);
+            let new_e = builder.add_words(d, &temp1);
+            for (w, word) in [&new_a, a, b, c, &new_e, e, f, g].into_iter().enumerate() {
+                builder.set_word(&working, C::N::from_canonical_usize(w).into(), word);
+            }
+        });
+
+        for w in 0..8 {
+            let lhs: Vec<_> = (0..WORD_BITS)
+                .map(|k| self.get(state, word_bit(w, k)))
+                .collect();
+            let rhs: Vec<_> = (0..WORD_BITS)
+                .map(|k| self.get(&working, WORD_BITS * w + k))
+                .collect();
+            let sum = self.add_words(&lhs, &rhs);
+            for (k, value) in sum.into_iter().enumerate() {
+                self.set_value(state, word_bit(w, k), value);
+            }
+        }
+    }
+
+    /// Reads word `word` of an array of little-endian words.
+    fn get_word(&mut self, array: &BitArray<C>, word: SymbolicVar<C::N>) -> Vec<Var<C::N>> {
+        let start = self.eval_expr(word * C::N::from_canonical_usize(WORD_BITS));
+        (0..WORD_BITS)
+            .map(|k| {
+                let index = self.eval_expr(start + C::N::from_canonical_usize(k));
+                self.get(array, index)
+            })
+            .collect()
+    }
+
+    /// Writes `value` to word `word` of an array of little-endian words.
+    fn set_word(&mut self, array: &BitArray<C>, word: SymbolicVar<C::N>, value: &[Var<C::N>]) {
+        let start = self.eval_expr(word * C::N::from_canonical_usize(WORD_BITS));
+        for (k, &bit) in value.iter().enumerate() {
+            let index = self.eval_expr(start + C::N::from_canonical_usize(k));
+            self.set_value(array, index, bit);
+        }
+    }
+
+    /// Computes `rotr(x, r0) ^ rotr(x, r1) ^ rotr(x, r2)`, or `shr(x, r2)` for the last term if
+    /// `shift` is set.
+    fn sigma(&mut self, x: &[Var<C::N>], [r0, r1, r2]: [usize; 3], shift: bool) -> Vec<Var<C::N>> {
+        (0..WORD_BITS)
+            .map(|k| {
+                let bit = self.xor_bits(x[(k + r0) % WORD_BITS], x[(k + r1) % WORD_BITS]);
+                if shift && k + r2 >= WORD_BITS {
+                    bit
+                } else {
+                    self.xor_bits(bit, x[(k + r2) % WORD_BITS])
+                }
+            })
+            .collect()
+    }
+
+    /// Adds two little-endian words modulo `2^32`.
+    fn add_words(&mut self, lhs: &[Var<C::N>], rhs: &[Var<C::N>]) -> Vec<Var<C::N>> {
+        let mut carry: Option<Var<C::N>> = None;
+        let mut sum = Vec::with_capacity(WORD_BITS);
+        for k in 0..WORD_BITS {
+            let (a, b) = (lhs[k], rhs[k]);
+            let xor = self.xor_bits(a, b);
+            let last = k == WORD_BITS - 1;
+            match carry {
+                None => {
+                    sum.push(xor);
+                    carry = Some(self.eval(a * b));
+                }
+                Some(c) => {
+                    sum.push(self.xor_bits(xor, c));
+                    if !last {
+                        // The carry is set if both bits are set, or if one of them and the
+                        // previous carry are.
+                        let and: Var<_> = self.eval(a * b);
+                        carry = Some(self.eval(and + c * xor));
+                    }
+                }
+            }
+        }
+        sum
+    }
+}
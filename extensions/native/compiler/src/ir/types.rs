@@ -415,6 +415,7 @@ impl<C: Config> Variable<C> for Var<C::N> {
 
     fn uninit(builder: &mut Builder<C>) -> Self {
         builder.var_count += 1;
+        builder.check_variable_id(builder.var_count);
         Var(builder.var_count, PhantomData)
     }
 
@@ -781,6 +782,7 @@ impl<C: Config> Variable<C> for Felt<C::F> {
 
     fn uninit(builder: &mut Builder<C>) -> Self {
         builder.felt_count += 1;
+        builder.check_variable_id(builder.felt_count);
         Felt(builder.felt_count, PhantomData)
     }
 
@@ -1208,6 +1210,7 @@ impl<C: Config> Variable<C> for Ext<C::F, C::EF> {
 
     fn uninit(builder: &mut Builder<C>) -> Self {
         builder.ext_count += 1;
+        builder.check_variable_id(builder.ext_count);
         Ext(builder.ext_count, PhantomData)
     }
 
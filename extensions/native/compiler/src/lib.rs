@@ -57,6 +57,17 @@ pub enum NativeJalOpcode {
     JAL,
 }
 
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x116]
+#[repr(usize)]
+pub enum NativeJalrOpcode {
+    /// `[a]_d <- pc + DEFAULT_PC_STEP; pc <- [b]_e`. Reads the target before writing the link, so
+    /// `a` and `b` may name the same cell.
+    JALR,
+}
+
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
 )]
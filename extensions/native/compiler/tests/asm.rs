@@ -0,0 +1,132 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::{AsmBuilder, AsmCompiler, AssemblyCode},
+    conversion::{convert_program, CompilerOptions},
+    ir::{Ext, Felt, Var},
+};
+use openvm_stark_backend::p3_field::{
+    extension::BinomialExtensionField, AbstractExtensionField, AbstractField,
+};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+const WORD_SIZE: usize = 1;
+
+fn build_code() -> AssemblyCode<F, EF> {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    builder.cycle_tracker_start("round trip");
+
+    let a: Felt<_> = builder.eval(F::from_canonical_u32(3));
+    let b: Felt<_> = builder.eval(a * F::from_canonical_u32(5) - F::ONE);
+    let c: Felt<_> = builder.eval(F::from_canonical_u32(7) / b);
+    builder.assert_felt_eq(c * b, F::from_canonical_u32(7));
+
+    let x: Ext<_, _> = builder.eval(EF::from_base_slice(&[
+        F::ONE,
+        F::TWO,
+        F::from_canonical_u32(3),
+        F::from_canonical_u32(4),
+    ]));
+    let y: Ext<_, _> = builder.eval(x * x + a);
+    builder.assert_ext_ne(y, EF::ONE);
+    builder.print_e(y);
+
+    let values = builder.array::<Felt<_>>(16);
+    builder.range(0, 16).for_each(|i, builder| {
+        let value: Felt<_> = builder.eval(a);
+        builder.set(&values, i, value);
+    });
+    builder.poseidon2_permute_mut(&values);
+    let bits = builder.num2bits_f(b, 8);
+    let bit = builder.get(&bits, 1);
+    builder.assert_var_eq(bit, F::ONE);
+
+    let n: Var<_> = builder.eval(F::from_canonical_u32(10));
+    let sum = builder.call("sum_to", n, |builder, n| {
+        let sum: Var<_> = builder.eval(F::ZERO);
+        builder.range(0, n).for_each(|i, builder| {
+            builder.if_ne(i, F::from_canonical_u32(3)).then(|builder| {
+                builder.assign(&sum, sum + i);
+            });
+        });
+        sum
+    });
+    builder.assert_var_eq(sum, F::from_canonical_u32(42));
+    builder.commit_public_value(c);
+
+    builder.cycle_tracker_end("round trip");
+    builder.halt();
+
+    let mut compiler = AsmCompiler::new(WORD_SIZE);
+    compiler.build(builder.operations);
+    compiler.code()
+}
+
+#[test]
+fn test_asm_round_trip() {
+    let options = CompilerOptions {
+        enable_cycle_tracker: true,
+        ..Default::default()
+    };
+
+    let text = build_code().to_string();
+    let parsed: AssemblyCode<F, EF> = text.parse().unwrap();
+    assert_eq!(parsed.to_string(), text);
+    assert!(text.contains("sum_to:"));
+    assert!(text.contains("cycle_tracker_start round trip"));
+
+    let program = convert_program(build_code(), options);
+    let parsed_program = convert_program(parsed, options);
+    assert_eq!(parsed_program.instructions(), program.instructions());
+
+    execute_program(parsed_program, vec![]);
+}
+
+#[test]
+fn test_asm_parse() {
+    let text = "
+# Sums the numbers below 10.
+.L0:
+        imm   (100)fp, 0
+        imm   (104)fp, 0
+loop:
+        beqi  done, (104)fp, 10
+        add   (100)fp, (100)fp, (104)fp
+        addi  (104)fp, (104)fp, 1
+        j     (108)fp, loop
+done:
+        bnei  fail, (100)fp, 45
+        halt
+fail:
+        trap
+";
+    let code: AssemblyCode<F, EF> = text.parse().unwrap();
+    assert_eq!(code.blocks.len(), 4);
+    assert_eq!(code.labels.len(), 3);
+
+    let program = convert_program(code, CompilerOptions::default());
+    execute_program(program, vec![]);
+}
+
+#[test]
+fn test_asm_parse_errors() {
+    let error = |text: &str| text.parse::<AssemblyCode<F, EF>>().err().unwrap();
+
+    let err = error(".L0:\n        j     (0)fp, missing\n");
+    assert_eq!(err.line, 2);
+    assert_eq!(err.message, "unknown label missing");
+
+    let err = error("        halt\n");
+    assert_eq!(err.line, 1);
+
+    let err = error(".L0:\n        add   (0)fp, (4)fp\n");
+    assert_eq!(err.message, "add expects 3 operands, found 2");
+
+    let err = error(".L0:\n        imm   (0)fp, 2013265921\n");
+    assert_eq!(err.message, "invalid field element 2013265921");
+
+    let err = error(".L0:\n.L0:\n        halt\n");
+    assert_eq!(err.line, 2);
+}
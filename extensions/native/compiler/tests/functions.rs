@@ -0,0 +1,125 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    ir::{Array, Config, Ext, Felt, Var},
+    prelude::{Builder, MemIndex, MemVariable, Ptr, Variable},
+};
+use openvm_native_compiler_derive::DslVariable;
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+#[derive(DslVariable, Clone)]
+pub struct ScaledSumArgs<C: Config> {
+    values: Array<C, Felt<C::F>>,
+    scale: Felt<C::F>,
+}
+
+fn scaled_sum<C: Config>(builder: &mut Builder<C>, args: ScaledSumArgs<C>) -> Felt<C::F> {
+    builder.call("scaled_sum", args, |builder, args| {
+        let sum: Felt<_> = builder.eval(C::F::ZERO);
+        builder.range(0, args.values.len()).for_each(|i, builder| {
+            let value = builder.get(&args.values, i);
+            builder.assign(&sum, sum + value);
+        });
+        builder.eval(sum * args.scale)
+    })
+}
+
+fn square<C: Config>(builder: &mut Builder<C>, x: Ext<C::F, C::EF>) -> Ext<C::F, C::EF> {
+    builder.call("square", x, |builder, x| builder.eval(x * x))
+}
+
+#[test]
+fn test_compiler_function_calls() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    let values = builder.array::<Felt<_>>(4);
+    for i in 0..4 {
+        builder.set(&values, i, F::from_canonical_usize(i + 1));
+    }
+
+    // Variables of the caller survive calls.
+    let before: Var<_> = builder.eval(F::from_canonical_u32(7));
+    let scale: Felt<_> = builder.eval(F::TWO);
+    let sum = scaled_sum(
+        &mut builder,
+        ScaledSumArgs {
+            values: values.clone(),
+            scale,
+        },
+    );
+    builder.assert_felt_eq(sum, F::from_canonical_u32(20));
+    builder.assert_var_eq(before, F::from_canonical_u32(7));
+
+    // Calls from a loop return to the loop.
+    let total: Felt<_> = builder.eval(F::ZERO);
+    let scale: Felt<_> = builder.eval(F::ZERO);
+    builder.range(0, 3).for_each(|_, builder| {
+        let sum = scaled_sum(
+            builder,
+            ScaledSumArgs {
+                values: values.clone(),
+                scale,
+            },
+        );
+        builder.assign(&total, total + sum);
+        builder.assign(&scale, scale + F::ONE);
+    });
+    builder.assert_felt_eq(total, F::from_canonical_u32(30));
+
+    // Functions can call other functions.
+    let x: Ext<_, _> = builder.eval(F::from_canonical_u32(3));
+    let x4 = builder.call("fourth_power", x, |builder, x| {
+        let x2 = square(builder, x);
+        square(builder, x2)
+    });
+    builder.assert_ext_eq(x4, EF::from_canonical_u32(81));
+    let x2 = square(&mut builder, x);
+    builder.assert_ext_eq(x2, EF::from_canonical_u32(9));
+
+    // The return value is copied out of the frame of the function.
+    let y = square(&mut builder, x2);
+    builder.assert_ext_eq(x2, EF::from_canonical_u32(9));
+    builder.assert_ext_eq(y, EF::from_canonical_u32(81));
+
+    builder.halt();
+
+    let program = builder.compile_isa();
+    execute_program(program, vec![]);
+}
+
+fn countdown<C: Config>(builder: &mut Builder<C>, n: Var<C::N>) -> Var<C::N> {
+    builder.call("countdown", n, |builder, n| {
+        let ret: Var<_> = builder.eval(C::N::ZERO);
+        builder.if_ne(n, C::N::ZERO).then(|builder| {
+            let next: Var<_> = builder.eval(n - C::N::ONE);
+            let inner = countdown(builder, next);
+            builder.assign(&ret, inner);
+        });
+        ret
+    })
+}
+
+#[test]
+#[should_panic(expected = "recursive call of function countdown")]
+fn test_compiler_recursive_function() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let n: Var<_> = builder.eval(F::from_canonical_u32(3));
+    countdown(&mut builder, n);
+}
+
+#[test]
+#[should_panic(expected = "function frame is too large")]
+fn test_compiler_function_frame_too_large() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let n: Var<_> = builder.eval(F::ONE);
+    builder.call("too_large", n, |builder, n| {
+        for _ in 0..1 << 16 {
+            let _: Var<_> = builder.uninit();
+        }
+        n
+    });
+}
@@ -0,0 +1,85 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::{AsmBuilder, AsmConfig},
+    ir::ByteArray,
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+use test_case::test_case;
+use tiny_keccak::{Hasher, Keccak};
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+type C = AsmConfig<F, EF>;
+
+#[test_case(&[]; "empty")]
+#[test_case(b"abc"; "abc")]
+#[test_case(&[0xab; 200]; "two blocks")]
+fn test_keccak256(input: &[u8]) {
+    let mut expected = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(input);
+    hasher.finalize(&mut expected);
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let bytes = builder.constant_bytes(input);
+    let digest = builder.keccak256(&bytes);
+    let expected = builder.constant_bytes(&expected);
+    builder.assert_eq::<ByteArray<C>>(digest, expected);
+    builder.halt();
+
+    execute_program(builder.compile_isa(), vec![]);
+}
+
+#[test_case(""; "empty")]
+#[test_case("abc"; "abc")]
+#[test_case("abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"; "two blocks")]
+fn test_sha256(input: &str) {
+    let expected = match input {
+        "" => "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "abc" => "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        _ => "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+    };
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let bytes = builder.constant_bytes(input.as_bytes());
+    let digest = builder.sha256(&bytes);
+    let expected = builder.constant_bytes(&hex::decode(expected).unwrap());
+    builder.assert_eq::<ByteArray<C>>(digest, expected);
+    builder.halt();
+
+    execute_program(builder.compile_isa(), vec![]);
+}
+
+#[test]
+fn test_keccak_f() {
+    let mut state = [0u64; 25];
+    tiny_keccak::keccakf(&mut state);
+    tiny_keccak::keccakf(&mut state);
+    let expected: Vec<u8> = state.iter().flat_map(|lane| lane.to_le_bytes()).collect();
+
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let bytes = builder.constant_bytes(&[0; 200]);
+    let bits = builder.bytes2bits(&bytes);
+    // The second call reuses the compiled permutation.
+    builder.keccak_f(&bits);
+    builder.keccak_f(&bits);
+    let digest = builder.bits2bytes(&bits);
+    let expected = builder.constant_bytes(&expected);
+    builder.assert_eq::<ByteArray<C>>(digest, expected);
+    builder.halt();
+
+    execute_program(builder.compile_isa(), vec![]);
+}
+
+#[test]
+#[should_panic]
+fn test_bytes_out_of_range() {
+    let mut builder = AsmBuilder::<F, EF>::default();
+    let bytes = builder.bytes(1);
+    builder.set(&bytes, 0, F::from_canonical_u32(256));
+    builder.sha256(&bytes);
+    builder.halt();
+
+    execute_program(builder.compile_isa(), vec![]);
+}
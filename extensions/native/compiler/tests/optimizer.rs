@@ -0,0 +1,78 @@
+use openvm_native_circuit::execute_program;
+use openvm_native_compiler::{
+    asm::AsmBuilder,
+    conversion::CompilerOptions,
+    ir::{Ext, Felt, Var},
+};
+use openvm_stark_backend::p3_field::{extension::BinomialExtensionField, AbstractField};
+use openvm_stark_sdk::p3_baby_bear::BabyBear;
+
+type F = BabyBear;
+type EF = BinomialExtensionField<BabyBear, 4>;
+
+fn build_program(expected: u32) -> AsmBuilder<F, EF> {
+    let mut builder = AsmBuilder::<F, EF>::default();
+
+    // Constants and copies that can be folded away.
+    let a: Var<_> = builder.eval(F::from_canonical_u32(3));
+    let b: Var<_> = builder.eval(a + F::from_canonical_u32(4));
+    let c: Var<_> = builder.eval(b * F::ONE);
+    let d: Var<_> = builder.eval(c - F::ZERO);
+    builder.assert_var_eq(d, F::from_canonical_u32(7));
+
+    // Common subexpressions within a block.
+    let x: Felt<_> = builder.eval(F::from_canonical_u32(5));
+    let y: Felt<_> = builder.eval(F::from_canonical_u32(6));
+    let xy: Felt<_> = builder.eval(x * y);
+    let yx: Felt<_> = builder.eval(y * x);
+    builder.assert_felt_eq(xy, yx);
+
+    // Loops and branches keep their semantics.
+    let sum: Var<_> = builder.eval(F::ZERO);
+    builder.range(0, 10).for_each(|i, builder| {
+        builder.if_eq(i, F::from_canonical_u32(5)).then_or_else(
+            |builder| builder.assign(&sum, sum + F::from_canonical_u32(100)),
+            |builder| builder.assign(&sum, sum + i),
+        );
+    });
+    builder.assert_var_eq(sum, F::from_canonical_u32(expected));
+
+    // Stores overwritten before being read.
+    let z: Ext<_, _> = builder.eval(F::ONE);
+    builder.assign(&z, z + F::TWO);
+    builder.assign(&z, z * z);
+    builder.assert_ext_eq(z, EF::from_canonical_u32(9));
+
+    let values = builder.array::<Felt<_>>(3);
+    builder.set(&values, 0, x);
+    builder.set(&values, 2, xy);
+    let value = builder.get(&values, 2);
+    builder.assert_felt_eq(value, F::from_canonical_u32(30));
+
+    builder.halt();
+    builder
+}
+
+#[test]
+fn test_optimizer() {
+    let program = build_program(140).compile_isa();
+    let optimized = build_program(140)
+        .compile_isa_with_options(CompilerOptions::default().with_optimizations());
+    assert!(
+        optimized.len() < program.len(),
+        "optimized program has {} instructions, unoptimized program has {}",
+        optimized.len(),
+        program.len()
+    );
+
+    execute_program(program, vec![]);
+    execute_program(optimized, vec![]);
+}
+
+#[test]
+#[should_panic]
+fn test_optimizer_keeps_failing_assertions() {
+    let program = build_program(139)
+        .compile_isa_with_options(CompilerOptions::default().with_optimizations());
+    execute_program(program, vec![]);
+}
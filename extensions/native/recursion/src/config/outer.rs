@@ -70,7 +70,7 @@ pub(crate) fn new_from_outer_vkv2(
         preprocessed_data: preprocessed_data.map(|data| {
             let commit: [Bn254Fr; DIGEST_WIDTH] = data.commit.into();
             VerifierSinglePreprocessedDataInProgram {
-                commit: DigestVal::N(commit.to_vec()),
+                commits: vec![DigestVal::N(commit.to_vec())],
             }
         }),
         width: params.width,
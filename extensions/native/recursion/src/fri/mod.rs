@@ -11,7 +11,7 @@ pub use two_adic_pcs::*;
 
 use self::types::{
     DimensionsVariable, FriChallengesVariable, FriConfigVariable, FriProofVariable,
-    FriQueryProofVariable,
+    FriQueryProofVariable, FriQueryVariable,
 };
 use crate::{
     challenger::ChallengerVariable,
@@ -50,15 +50,14 @@ pub fn verify_shape_and_sample_challenges<C: Config>(
 
     let num_query_proofs = proof.query_proofs.len().clone();
     builder
-        .if_ne(num_query_proofs, RVar::from(config.num_queries))
+        .if_ne(num_query_proofs, config.num_queries)
         .then(|builder| {
             builder.error();
         });
 
     challenger.check_witness(builder, config.proof_of_work_bits, proof.pow_witness);
 
-    let log_max_height =
-        builder.eval_expr(proof.commit_phase_commits.len() + RVar::from(config.log_blowup));
+    let log_max_height = builder.eval_expr(proof.commit_phase_commits.len() + config.log_blowup);
     let query_indices = builder.array(config.num_queries);
     builder.range(0, config.num_queries).for_each(|i, builder| {
         let index_bits = challenger.sample_bits(builder, log_max_height);
@@ -85,8 +84,7 @@ pub fn verify_challenges<C: Config>(
     C::F: TwoAdicField,
     C::EF: TwoAdicField,
 {
-    let log_max_height =
-        builder.eval_expr(proof.commit_phase_commits.len() + RVar::from(config.log_blowup));
+    let log_max_height = builder.eval_expr(proof.commit_phase_commits.len() + config.log_blowup);
     builder
         .range(0, challenges.query_indices.len())
         .for_each(|i, builder| {
@@ -94,16 +92,42 @@ pub fn verify_challenges<C: Config>(
             let query_proof = builder.get(&proof.query_proofs, i);
             let ro = builder.get(reduced_openings, i);
 
-            let folded_eval = verify_query(
-                builder,
-                config,
-                &proof.commit_phase_commits,
-                &index_bits,
-                &query_proof,
-                &challenges.betas,
-                &ro,
-                log_max_height,
-            );
+            let folded_eval = if builder.flags.static_only {
+                verify_query(
+                    builder,
+                    &config.generators,
+                    &proof.commit_phase_commits,
+                    &index_bits,
+                    &query_proof,
+                    &challenges.betas,
+                    &ro,
+                    log_max_height,
+                )
+            } else {
+                // Compile the query verification once, as a subroutine shared by every FRI
+                // verification in the program.
+                let query = FriQueryVariable {
+                    generators: config.generators.clone(),
+                    commit_phase_commits: proof.commit_phase_commits.clone(),
+                    index_bits,
+                    proof: query_proof,
+                    betas: challenges.betas.clone(),
+                    reduced_openings: ro,
+                    log_max_height: builder.eval(log_max_height),
+                };
+                builder.call("verify_query", query, |builder, query| {
+                    verify_query(
+                        builder,
+                        &query.generators,
+                        &query.commit_phase_commits,
+                        &query.index_bits,
+                        &query.proof,
+                        &query.betas,
+                        &query.reduced_openings,
+                        query.log_max_height.into(),
+                    )
+                })
+            };
 
             builder.assert_ext_eq(folded_eval, proof.final_poly);
         });
@@ -118,7 +142,7 @@ pub fn verify_challenges<C: Config>(
 #[allow(unused_variables)]
 pub fn verify_query<C: Config>(
     builder: &mut Builder<C>,
-    config: &FriConfigVariable<C>,
+    generators: &Array<C, Felt<C::F>>,
     commit_phase_commits: &Array<C, DigestVariable<C>>,
     index_bits: &Array<C, Var<C::N>>,
     proof: &FriQueryProofVariable<C>,
@@ -132,7 +156,7 @@ where
 {
     builder.cycle_tracker_start("verify-query");
     let folded_eval: Ext<C::F, C::EF> = builder.eval(C::F::ZERO);
-    let two_adic_generator_f = config.get_two_adic_generator(builder, log_max_height);
+    let two_adic_generator_f = builder.get(generators, log_max_height);
 
     let two_adic_gen_ext = two_adic_generator_f.to_operand().symbolic();
     let two_adic_generator_ef: Ext<_, _> = builder.eval(two_adic_gen_ext);
@@ -197,7 +221,7 @@ where
             );
             builder.cycle_tracker_end("verify-batch-ext");
 
-            let two_adic_generator_one = config.get_two_adic_generator(builder, Usize::from(1));
+            let two_adic_generator_one = builder.get(generators, Usize::from(1));
 
             let [xs_0, xs_1]: [Ext<_, _>; 2] =
                 cond_eval(builder, index_sibling_mod_2, x * two_adic_generator_one, x);
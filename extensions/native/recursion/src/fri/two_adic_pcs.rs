@@ -49,8 +49,7 @@ pub fn verify_two_adic_pcs<C: Config>(
     let fri_challenges = verify_shape_and_sample_challenges(builder, config, &proof, challenger);
     builder.cycle_tracker_end("stage-d-1-verify-shape-and-sample-challenges");
 
-    let log_global_max_height =
-        builder.eval_expr(proof.commit_phase_commits.len() + RVar::from(log_blowup));
+    let log_global_max_height = builder.eval_expr(proof.commit_phase_commits.len() + log_blowup);
 
     let reduced_openings: Array<_, Array<_, Ext<_, _>>> = builder.array(proof.query_proofs.len());
 
@@ -108,7 +107,7 @@ pub fn verify_two_adic_pcs<C: Config>(
                     let log_batch_max_index = to_perm_index(builder, RVar::zero());
                     let mat = builder.get(&mats, log_batch_max_index);
                     let domain = mat.domain;
-                    builder.eval(domain.log_n + RVar::from(log_blowup))
+                    builder.eval(domain.log_n + log_blowup)
                 };
 
                 let batch_dims: Array<C, DimensionsVariable<C>> = builder.array(mats.len());
@@ -120,7 +119,7 @@ pub fn verify_two_adic_pcs<C: Config>(
                     let mat = builder.get(&mats, mat_index.clone());
                     let domain = mat.domain;
                     let dim = DimensionsVariable::<C> {
-                        height: builder.eval(domain.size() * RVar::from(blowup)),
+                        height: builder.eval(domain.size() * blowup),
                     };
                     builder.set_value(&batch_dims, k, dim);
                     let opened_value = builder.get(&batch_opening.opened_values, mat_index);
@@ -155,8 +154,7 @@ pub fn verify_two_adic_pcs<C: Config>(
                         let mat_values = mat.values;
                         let domain = mat.domain;
                         let log2_domain_size = domain.log_n;
-                        let log_height =
-                            builder.eval_expr(log2_domain_size + RVar::from(log_blowup));
+                        let log_height = builder.eval_expr(log2_domain_size + log_blowup);
 
                         let cur_ro = builder.get(&ro, log_height);
                         let cur_alpha_pow = builder.get(&alpha_pow, log_height);
@@ -4,10 +4,15 @@ use crate::{digest::DigestVariable, fri::TwoAdicMultiplicativeCosetVariable};
 
 #[derive(Clone)]
 pub struct FriConfigVariable<C: Config> {
-    pub log_blowup: usize,
-    pub blowup: usize,
-    pub num_queries: usize,
+    /// Constant unless the config is read from the input stream, see
+    /// [witness_fri_config](crate::utils::witness_fri_config).
+    pub log_blowup: RVar<C::N>,
+    pub blowup: RVar<C::N>,
+    pub num_queries: RVar<C::N>,
     pub proof_of_work_bits: usize,
+    /// Index of the config in the allowed configs of the program if the config is read from the
+    /// input stream. Selects the preprocessed commitments of the verified AIRs.
+    pub index: Option<Var<C::N>>,
     pub generators: Array<C, Felt<C::F>>,
     pub subgroups: Array<C, TwoAdicMultiplicativeCosetVariable<C>>,
 }
@@ -50,6 +55,18 @@ pub struct FriCommitPhaseProofStepVariable<C: Config> {
     pub opening_proof: Array<C, DigestVariable<C>>,
 }
 
+/// The arguments of the FRI query verification subroutine.
+#[derive(DslVariable, Clone)]
+pub struct FriQueryVariable<C: Config> {
+    pub generators: Array<C, Felt<C::F>>,
+    pub commit_phase_commits: Array<C, DigestVariable<C>>,
+    pub index_bits: Array<C, Var<C::N>>,
+    pub proof: FriQueryProofVariable<C>,
+    pub betas: Array<C, Ext<C::F, C::EF>>,
+    pub reduced_openings: Array<C, Ext<C::F, C::EF>>,
+    pub log_max_height: Var<C::N>,
+}
+
 #[derive(DslVariable, Clone)]
 pub struct FriChallengesVariable<C: Config> {
     pub query_indices: Array<C, Array<C, Var<C::N>>>,
@@ -1,3 +1,4 @@
+pub mod ppot;
 pub mod utils;
 pub mod verifier;
 
@@ -6,11 +7,14 @@ pub mod testing_utils;
 mod tests;
 pub mod wrapper;
 
-use std::fmt::Debug;
+use std::{collections::BTreeMap, fmt::Debug};
 
 use itertools::Itertools;
 use openvm_native_compiler::{
-    constraints::halo2::compiler::{Halo2ConstraintCompiler, Halo2State},
+    constraints::halo2::{
+        compiler::{Halo2ConstraintCompiler, Halo2State},
+        stats::{DslOperationStats, Halo2Stats},
+    },
     ir::{Config, DslIr, TracedVec, Witness},
 };
 use openvm_stark_backend::p3_field::extension::BinomialExtensionField;
@@ -18,18 +22,21 @@ use openvm_stark_sdk::{p3_baby_bear::BabyBear, p3_bn254_fr::Bn254Fr};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use snark_verifier_sdk::{
     halo2::{gen_dummy_snark_from_vk, gen_snark_shplonk},
-    snark_verifier::halo2_base::{
-        gates::{
-            circuit::{builder::BaseCircuitBuilder, BaseCircuitParams, CircuitBuilderStage},
-            flex_gate::MultiPhaseThreadBreakPoints,
-        },
-        halo2_proofs::{
-            dev::MockProver,
-            halo2curves::bn256::{Bn256, Fr, G1Affine},
-            plonk::{keygen_pk2, ProvingKey},
-            poly::{commitment::Params, kzg::commitment::ParamsKZG},
-            SerdeFormat,
+    snark_verifier::{
+        halo2_base::{
+            gates::{
+                circuit::{builder::BaseCircuitBuilder, BaseCircuitParams, CircuitBuilderStage},
+                flex_gate::MultiPhaseThreadBreakPoints,
+            },
+            halo2_proofs::{
+                dev::MockProver,
+                halo2curves::bn256::{Bn256, Fr, G1Affine},
+                plonk::{keygen_pk2, keygen_vk, ProvingKey},
+                poly::{commitment::Params, kzg::commitment::ParamsKZG},
+                SerdeFormat,
+            },
         },
+        util::arithmetic::PrimeField as _,
     },
     CircuitExt, Snark, SHPLONK,
 };
@@ -38,6 +45,10 @@ use crate::halo2::utils::Halo2ParamsReader;
 
 pub type Halo2Params = ParamsKZG<Bn256>;
 
+/// Number of instances of the wrapper circuit for the KZG accumulator. They precede the public
+/// values of the snark verified by the wrapper.
+pub const NUM_ACCUMULATOR_INSTANCES: usize = 12;
+
 /// A prover that can generate proofs with the Halo2
 #[derive(Debug, Clone)]
 pub struct Halo2Prover;
@@ -48,6 +59,26 @@ pub struct EvmProof {
     pub proof: Vec<u8>,
 }
 
+impl EvmProof {
+    /// The public values of the snark verified by the wrapper, i.e. the instances after the
+    /// accumulator, each as 32 little-endian bytes.
+    pub fn public_values_le_bytes(&self) -> Vec<[u8; 32]> {
+        self.instances[0][NUM_ACCUMULATOR_INSTANCES..]
+            .iter()
+            .map(|x| x.to_repr())
+            .collect()
+    }
+
+    /// The KZG accumulator the instances start with, as 32-byte big-endian words. This is how the
+    /// EVM verifier reads the accumulator from the calldata.
+    pub fn accumulator_be_bytes(&self) -> Vec<u8> {
+        self.instances[0][..NUM_ACCUMULATOR_INSTANCES]
+            .iter()
+            .flat_map(|x| x.to_repr().into_iter().rev())
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DslOperations<C: Config> {
     pub operations: TracedVec<DslIr<C>>,
@@ -70,6 +101,16 @@ pub struct Halo2ProvingMetadata {
     pub num_pvs: Vec<usize>,
 }
 
+/// Statistics of a circuit built from DSL operations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Halo2CircuitStats {
+    /// Circuit parameters tuned for the cells used.
+    pub config_params: BaseCircuitParams,
+    pub total: Halo2Stats,
+    /// Cells used by each kind of DSL operation.
+    pub operations: BTreeMap<String, DslOperationStats>,
+}
+
 impl Halo2ProvingPinning {
     pub fn generate_dummy_snark(&self, reader: &impl Halo2ParamsReader) -> Snark {
         let k = self.metadata.config_params.k;
@@ -141,6 +182,59 @@ impl Halo2Prover {
         public_instances
     }
 
+    /// Builds the circuit of `dsl_operations` in mock mode and returns its statistics, without
+    /// keygen.
+    pub fn circuit_stats<
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BinomialExtensionField<BabyBear, 4>> + Debug,
+    >(
+        k: usize,
+        dsl_operations: DslOperations<C>,
+        witness: Witness<C>,
+    ) -> Halo2CircuitStats {
+        let mut state = Halo2State {
+            builder: Self::builder(CircuitBuilderStage::Mock, k),
+            ..Default::default()
+        };
+        state.load_witness(witness);
+        let operations = Halo2ConstraintCompiler::<C>::new(dsl_operations.num_public_values)
+            .constrain_halo2_with_stats(&mut state, dsl_operations.operations);
+        let mut builder = state.builder;
+        builder.calculate_params(Some(20));
+
+        let stats = builder.statistics();
+        Halo2CircuitStats {
+            config_params: builder.config_params.clone(),
+            total: Halo2Stats {
+                total_gate_cell: stats.gate.total_advice_per_phase.into_iter().sum(),
+                total_fixed: stats.gate.total_fixed,
+                total_lookup_cell: stats.total_lookup_advice_per_phase.into_iter().sum(),
+            },
+            operations,
+        }
+    }
+
+    /// Generates a dummy snark of the circuit of `dsl_operations` from its verifying key alone,
+    /// e.g. to select the `k` of its wrapper without generating the proving key.
+    pub fn dummy_snark<
+        C: Config<N = Bn254Fr, F = BabyBear, EF = BinomialExtensionField<BabyBear, 4>> + Debug,
+    >(
+        params: &Halo2Params,
+        dsl_operations: DslOperations<C>,
+        witness: Witness<C>,
+    ) -> Snark {
+        let k = params.k() as usize;
+        let builder = Self::builder(CircuitBuilderStage::Keygen, k);
+        let mut builder = Self::populate(builder, dsl_operations, witness, false);
+        builder.calculate_params(Some(20));
+        let vk = keygen_vk(params, &builder).unwrap();
+        let num_pvs = builder
+            .assigned_instances
+            .iter()
+            .map(|x| x.len())
+            .collect_vec();
+        gen_dummy_snark_from_vk::<SHPLONK>(params, &vk, num_pvs, None)
+    }
+
     /// Populates builder, tunes circuit, keygen
     pub fn keygen<
         C: Config<N = Bn254Fr, F = BabyBear, EF = BinomialExtensionField<BabyBear, 4>> + Debug,
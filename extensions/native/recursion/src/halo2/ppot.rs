@@ -0,0 +1,103 @@
+//! Import of KZG params from a challenge file of the Perpetual Powers of Tau ceremony.
+//!
+//! A challenge file of a ceremony of size `N = 2^power` consists of a 64-byte hash followed by
+//! `2N - 1` powers of tau in G1, `N` powers of tau in G2, `N` powers of alpha * tau in G1, `N`
+//! powers of beta * tau in G1 and beta in G2. Points are uncompressed with big-endian
+//! coordinates.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use snark_verifier_sdk::snark_verifier::halo2_base::halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::bn256::{Fq, Fq2, G1Affine, G2Affine},
+};
+
+use crate::halo2::{utils::kzg_params_from_parts, Halo2Params};
+
+const HASH_SIZE: usize = 64;
+const G1_SIZE: usize = 64;
+const G2_SIZE: usize = 128;
+
+/// Reads the params of size `2^k` from a challenge file.
+pub fn read_ppot_challenge_params(path: impl AsRef<Path>, k: usize) -> io::Result<Halo2Params> {
+    let mut file = File::open(path)?;
+    let num_powers = ppot_num_powers(file.metadata()?.len())?;
+    if 1 << k > num_powers {
+        return Err(invalid(format!(
+            "challenge file has {num_powers} powers, but 2^{k} are needed"
+        )));
+    }
+
+    file.seek(SeekFrom::Start(HASH_SIZE as u64))?;
+    let g = {
+        let mut reader = BufReader::new(&mut file);
+        (0..1 << k)
+            .map(|_| read_g1(&mut reader))
+            .collect::<io::Result<Vec<_>>>()?
+    };
+
+    let g2_offset = HASH_SIZE + (2 * num_powers - 1) * G1_SIZE;
+    file.seek(SeekFrom::Start(g2_offset as u64))?;
+    let g2 = read_g2(&mut file)?;
+    let s_g2 = read_g2(&mut file)?;
+
+    Ok(kzg_params_from_parts(k as u32, g, g2, s_g2))
+}
+
+/// Number of powers `N` of the ceremony of a challenge file of `len` bytes.
+fn ppot_num_powers(len: u64) -> io::Result<usize> {
+    // 64 + (2N - 1) * 64 + N * 128 + 2 * N * 64 + 128 = 384 * N + 128
+    let len = len as usize;
+    let num_powers = len.saturating_sub(HASH_SIZE + G2_SIZE - G1_SIZE) / (4 * G1_SIZE + G2_SIZE);
+    if !num_powers.is_power_of_two()
+        || len != HASH_SIZE + G2_SIZE - G1_SIZE + num_powers * (4 * G1_SIZE + G2_SIZE)
+    {
+        return Err(invalid(format!(
+            "{len} bytes is not the size of a challenge file"
+        )));
+    }
+    Ok(num_powers)
+}
+
+fn read_fq(reader: &mut impl Read) -> io::Result<Fq> {
+    let mut bytes = [0u8; 32];
+    reader.read_exact(&mut bytes)?;
+    bytes.reverse();
+    Option::from(Fq::from_bytes(&bytes)).ok_or_else(|| invalid("non-canonical coordinate".into()))
+}
+
+fn read_g1(reader: &mut impl Read) -> io::Result<G1Affine> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    Option::from(G1Affine::from_xy(x, y)).ok_or_else(|| invalid("G1 point not on curve".into()))
+}
+
+fn read_g2(reader: &mut impl Read) -> io::Result<G2Affine> {
+    let x_c1 = read_fq(reader)?;
+    let x_c0 = read_fq(reader)?;
+    let y_c1 = read_fq(reader)?;
+    let y_c0 = read_fq(reader)?;
+    let x = Fq2 { c0: x_c0, c1: x_c1 };
+    let y = Fq2 { c0: y_c0, c1: y_c1 };
+    Option::from(G2Affine::from_xy(x, y)).ok_or_else(|| invalid("G2 point not on curve".into()))
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ppot_num_powers() {
+        assert_eq!(ppot_num_powers(384 * 1024 + 128).unwrap(), 1024);
+        assert!(ppot_num_powers(384 * 1000 + 128).is_err());
+        assert!(ppot_num_powers(384 * 1024 + 64).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use openvm_native_compiler::ir::{Builder, ByteArray, Witness};
+
+use crate::{
+    config::outer::OuterConfig,
+    halo2::{DslOperations, Halo2Prover},
+};
+
+fn mock_digest(
+    hash: impl FnOnce(&mut Builder<OuterConfig>, &ByteArray<OuterConfig>) -> ByteArray<OuterConfig>,
+    input: &[u8],
+    expected: [u8; 32],
+) {
+    let mut builder = Builder::<OuterConfig>::default();
+    builder.flags.static_only = true;
+    let bytes = builder.constant_bytes(input);
+    let digest = hash(&mut builder, &bytes);
+    let expected = builder.constant_bytes(&expected);
+    builder.assert_eq::<ByteArray<_>>(digest, expected);
+
+    Halo2Prover::mock::<OuterConfig>(
+        18,
+        DslOperations {
+            operations: builder.operations,
+            num_public_values: 0,
+        },
+        Witness::default(),
+    );
+}
+
+#[test]
+fn test_sha256() {
+    let expected = [
+        0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae, 0x22,
+        0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61, 0xf2, 0x00,
+        0x15, 0xad,
+    ];
+    mock_digest(Builder::sha256, b"abc", expected);
+}
+
+#[test]
+#[ignore = "slow"]
+fn test_keccak256() {
+    let expected = [
+        0x4e, 0x03, 0x65, 0x7a, 0xea, 0x45, 0xa9, 0x4f, 0xc7, 0xd4, 0x7b, 0xa8, 0x26, 0xc8, 0xd6,
+        0x67, 0xc0, 0xd1, 0xe6, 0xe3, 0x3a, 0x64, 0xa0, 0x36, 0xec, 0x44, 0xf5, 0x8f, 0xa1, 0x2d,
+        0x6c, 0x45,
+    ];
+    mock_digest(Builder::keccak256, b"abc", expected);
+}
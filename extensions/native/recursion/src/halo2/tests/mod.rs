@@ -29,6 +29,7 @@ use crate::{
     utils::{reduce_32, split_32},
 };
 
+mod hash;
 mod multi_field32;
 mod outer_poseidon2;
 mod stark;
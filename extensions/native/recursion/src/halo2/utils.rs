@@ -1,7 +1,8 @@
 use std::{
     cmp::Reverse,
     collections::HashMap,
-    io::BufReader,
+    fs::File,
+    io::{self, BufReader, BufWriter},
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
@@ -17,7 +18,10 @@ use snark_verifier_sdk::{
     snark_verifier::{
         halo2_base::{
             halo2_proofs::{
-                halo2curves::bn256::{Bn256, G1Affine},
+                halo2curves::{
+                    bn256::{Bn256, G1Affine, G2Affine},
+                    pairing::Engine,
+                },
                 poly::{
                     commitment::{CommitmentScheme, Params},
                     kzg::commitment::{KZGCommitmentScheme, ParamsKZG},
@@ -40,6 +44,57 @@ pub(crate) fn gen_kzg_params(k: u32) -> Halo2Params {
     ParamsKZG::setup(k, &mut rng)
 }
 
+/// Generates the KZG params used by tests and by `RANDOM_SRS`.
+///
+/// INSECURE: the toxic waste is derived from a fixed seed, so anyone can forge proofs which
+/// verify against these params. Only use them to test the setup without the real params.
+pub fn gen_insecure_kzg_params(k: u32) -> Halo2Params {
+    gen_kzg_params(k)
+}
+
+/// Name of the file holding the params of size `2^k` in a params directory.
+pub fn kzg_params_file_name(k: usize) -> String {
+    format!("kzg_bn254_{k}.srs")
+}
+
+/// Writes `params` to their file in `params_dir`, where [CacheHalo2ParamsReader] reads them.
+pub fn write_kzg_params(params_dir: impl AsRef<Path>, params: &Halo2Params) -> io::Result<()> {
+    let path = params_dir
+        .as_ref()
+        .join(kzg_params_file_name(params.k() as usize));
+    let mut writer = BufWriter::new(File::create(path)?);
+    params.write(&mut writer)
+}
+
+/// Checks that `params` have size `2^k` and that their G1 and G2 powers are of the same secret.
+pub fn validate_kzg_params(params: &Halo2Params, k: usize) -> io::Result<()> {
+    let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+    if params.k() as usize != k || params.get_g().len() != 1 << k {
+        return Err(invalid(format!(
+            "expected params of size 2^{k}, got 2^{} with {} powers",
+            params.k(),
+            params.get_g().len()
+        )));
+    }
+    let g = params.get_g();
+    if Bn256::pairing(&g[1], &params.g2()) != Bn256::pairing(&g[0], &params.s_g2()) {
+        return Err(invalid(format!(
+            "G1 and G2 powers of the params for k = {k} don't match"
+        )));
+    }
+    Ok(())
+}
+
+/// Builds params from the powers of the secret in G1 and the secret in G2.
+pub(crate) fn kzg_params_from_parts(
+    k: u32,
+    g: Vec<G1Affine>,
+    g2: G2Affine,
+    s_g2: G2Affine,
+) -> Halo2Params {
+    FAKE_KZG_PARAMS.from_parts(k, g, None, g2, s_g2)
+}
+
 lazy_static! {
     // TODO: this should be dynamic. hard code for now.
     static ref SVK: G1Affine =
@@ -103,12 +158,8 @@ pub struct CacheHalo2ParamsReader {
 
 impl Halo2ParamsReader for CacheHalo2ParamsReader {
     fn read_params(&self, k: usize) -> Arc<Halo2Params> {
-        self.cached_params
-            .lock()
-            .unwrap()
-            .entry(k)
-            .or_insert_with(|| Arc::new(self.read_params_from_folder(k)))
-            .clone()
+        self.try_read_params(k)
+            .unwrap_or_else(|e| panic!("Failed to read params for k = {k}: {e}"))
     }
 }
 impl CacheHalo2ParamsReader {
@@ -124,12 +175,22 @@ impl CacheHalo2ParamsReader {
             cached_params: Default::default(),
         }
     }
-    fn read_params_from_folder(&self, k: usize) -> Halo2Params {
-        ParamsKZG::<Bn256>::read(&mut BufReader::new(
-            std::fs::File::open(self.params_dir.as_path().join(format!("kzg_bn254_{k}.srs")))
-                .expect("Params file does not exist"),
-        ))
-        .unwrap()
+    /// Reads and validates the params for `k`, see [validate_kzg_params].
+    pub fn try_read_params(&self, k: usize) -> io::Result<Arc<Halo2Params>> {
+        let mut cached_params = self.cached_params.lock().unwrap();
+        if let Some(params) = cached_params.get(&k) {
+            return Ok(params.clone());
+        }
+        let params = self.read_params_from_folder(k)?;
+        validate_kzg_params(&params, k)?;
+        let params = Arc::new(params);
+        cached_params.insert(k, params.clone());
+        Ok(params)
+    }
+    fn read_params_from_folder(&self, k: usize) -> io::Result<Halo2Params> {
+        ParamsKZG::<Bn256>::read(&mut BufReader::new(File::open(
+            self.params_dir.as_path().join(kzg_params_file_name(k)),
+        )?))
     }
 }
 
@@ -4,19 +4,22 @@ use serde::{Deserialize, Serialize};
 use snark_verifier_sdk::{
     evm::{evm_verify, gen_evm_proof_shplonk, gen_evm_verifier_shplonk},
     halo2::aggregation::{AggregationCircuit, AggregationConfigParams, VerifierUniversality},
-    snark_verifier::halo2_base::{
-        gates::circuit::{
-            CircuitBuilderStage,
-            CircuitBuilderStage::{Keygen, Prover},
+    snark_verifier::{
+        halo2_base::{
+            gates::circuit::{
+                CircuitBuilderStage,
+                CircuitBuilderStage::{Keygen, Prover},
+            },
+            halo2_proofs::{plonk::keygen_pk2, poly::commitment::Params},
         },
-        halo2_proofs::{plonk::keygen_pk2, poly::commitment::Params},
+        loader::evm::{compile_solidity as solc_compile, deploy_and_call},
     },
     CircuitExt, Snark, SHPLONK,
 };
 
 use crate::halo2::{
     utils::{Halo2ParamsReader, KZG_PARAMS_FOR_SVK},
-    EvmProof, Halo2Params, Halo2ProvingMetadata, Halo2ProvingPinning,
+    EvmProof, Halo2Params, Halo2ProvingMetadata, Halo2ProvingPinning, NUM_ACCUMULATOR_INSTANCES,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,6 +37,17 @@ impl From<EvmVerifier> for Vec<u8> {
     }
 }
 
+/// Compiles `solidity_code` with the `solc` in `PATH` and returns its deployment code.
+pub fn compile_solidity(solidity_code: &str) -> Vec<u8> {
+    solc_compile(solidity_code)
+}
+
+/// Deploys `deployment_code` to an in-memory EVM and calls the deployed contract with `calldata`.
+/// Returns the gas used by the call, or the reason why the deployment or the call failed.
+pub fn evm_deploy_and_call(deployment_code: Vec<u8>, calldata: Vec<u8>) -> Result<u64, String> {
+    deploy_and_call(deployment_code, calldata)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Halo2WrapperProvingKey {
     pub pinning: Halo2ProvingPinning,
@@ -127,8 +141,7 @@ impl Halo2WrapperProvingKey {
         );
         assert_eq!(
             self.pinning.metadata.num_pvs[0],
-            // 12 is the number of public values for the accumulator
-            snark_to_verify.instances[0].len() + 12
+            snark_to_verify.instances[0].len() + NUM_ACCUMULATOR_INSTANCES
         );
         generate_wrapper_circuit_object(Prover, k, snark_to_verify)
             .use_params(
@@ -142,7 +155,8 @@ impl Halo2WrapperProvingKey {
             .use_break_points(self.pinning.metadata.break_points.clone())
     }
 
-    pub(crate) fn select_k(dummy_snark: Snark) -> usize {
+    /// Selects the smallest `k` for which the wrapper of `dummy_snark` has a single advice column.
+    pub fn select_k(dummy_snark: Snark) -> usize {
         let mut k = 20;
         let mut first_run = true;
         loop {
@@ -27,12 +27,12 @@ use crate::{
     commit::{PcsVariable, PolynomialSpaceVariable},
     folder::RecursiveVerifierConstraintFolder,
     fri::{
-        types::{TwoAdicPcsMatsVariable, TwoAdicPcsRoundVariable},
+        types::{FriConfigVariable, TwoAdicPcsMatsVariable, TwoAdicPcsRoundVariable},
         TwoAdicFriPcsVariable, TwoAdicMultiplicativeCosetVariable,
     },
     hints::Hintable,
     types::{InnerConfig, MultiStarkVerificationAdvice, StarkVerificationAdvice},
-    utils::const_fri_config,
+    utils::{const_fri_config, witness_fri_config},
     vars::{
         AdjacentOpenedValuesVariable, AirProofDataVariable, CommitmentsVariable, StarkProofVariable,
     },
@@ -65,6 +65,35 @@ impl VerifierProgram<InnerConfig> {
         constants: MultiStarkVerificationAdvice<InnerConfig>,
         fri_params: &FriParameters,
         options: CompilerOptions,
+    ) -> Program<BabyBear> {
+        Self::build_impl(
+            constants,
+            |builder| const_fri_config(builder, fri_params),
+            options,
+        )
+    }
+
+    /// Create a new instance of the program for the [BabyBearPoseidon2] config which accepts
+    /// proofs generated with any of `allowed_fri_params`.
+    ///
+    /// The input stream is the proof followed by the index of its FRI parameters in
+    /// `allowed_fri_params`, see [witness_fri_config].
+    pub fn build_with_allowed_fri_params(
+        constants: MultiStarkVerificationAdvice<InnerConfig>,
+        allowed_fri_params: &[FriParameters],
+        options: CompilerOptions,
+    ) -> Program<BabyBear> {
+        Self::build_impl(
+            constants,
+            |builder| witness_fri_config(builder, allowed_fri_params),
+            options,
+        )
+    }
+
+    fn build_impl(
+        constants: MultiStarkVerificationAdvice<InnerConfig>,
+        fri_config: impl FnOnce(&mut Builder<InnerConfig>) -> FriConfigVariable<InnerConfig>,
+        options: CompilerOptions,
     ) -> Program<BabyBear> {
         let mut builder = Builder::<InnerConfig>::default();
 
@@ -76,7 +105,7 @@ impl VerifierProgram<InnerConfig> {
 
         builder.cycle_tracker_start("InitializePcsConst");
         let pcs = TwoAdicFriPcsVariable {
-            config: const_fri_config(&mut builder, fri_params),
+            config: fri_config(&mut builder),
         };
         builder.cycle_tracker_end("InitializePcsConst");
         StarkVerifier::verify::<DuplexChallengerVariable<_>>(
@@ -136,7 +165,7 @@ where
         C::EF: TwoAdicField,
     {
         let air_ids = proof.get_air_ids(builder);
-        let m_advice_var = get_advice_per_air(builder, m_advice, &air_ids);
+        let m_advice_var = get_advice_per_air(builder, m_advice, &air_ids, pcs.config.index);
         let StarkProofVariable::<C> {
             commitments,
             opening,
@@ -264,3 +264,80 @@ fn test_optional_air() {
         assert!(unwind_res.is_err());
     }
 }
+
+#[test]
+fn test_allowed_fri_params() {
+    use openvm_native_compiler::conversion::CompilerOptions;
+    use openvm_stark_backend::{engine::StarkEngine, prover::types::ProofInput};
+
+    use crate::types::InnerConfig;
+
+    let allowed_fri_params = [
+        FriParameters {
+            log_blowup: 1,
+            num_queries: 4,
+            proof_of_work_bits: 0,
+        },
+        FriParameters {
+            log_blowup: 2,
+            num_queries: 2,
+            proof_of_work_bits: 0,
+        },
+    ];
+    let fib_chip = FibonacciChip::new(0, 1, 32);
+    let vm_config = NativeConfig::aggregation(4, 7);
+
+    let proofs: Vec<_> = allowed_fri_params
+        .iter()
+        .map(|&fri_params| {
+            let engine = BabyBearPoseidon2Engine::new(fri_params);
+            let mut keygen_builder = engine.keygen_builder();
+            let fib_chip_id = keygen_builder.add_air(fib_chip.air());
+            let pk = keygen_builder.generate_pk();
+            let proof = engine.prover().prove(
+                &mut engine.new_challenger(),
+                &pk,
+                ProofInput {
+                    per_air: vec![fib_chip
+                        .clone()
+                        .generate_air_proof_input_with_id(fib_chip_id)],
+                },
+            );
+            (pk.get_vk(), proof)
+        })
+        .collect();
+
+    // The verifying key of the Fibonacci AIR doesn't depend on the FRI parameters.
+    let m_advice = new_from_inner_multi_vk(&proofs[0].0);
+    let program = VerifierProgram::build_with_allowed_fri_params(
+        m_advice,
+        &allowed_fri_params,
+        CompilerOptions::default(),
+    );
+    let input = |proof_index: usize, fri_params_index: usize| {
+        let mut input = proofs[proof_index].1.write();
+        input.extend(<usize as Hintable<InnerConfig>>::write(&fri_params_index));
+        input
+    };
+
+    for i in 0..allowed_fri_params.len() {
+        gen_vm_program_test_proof_input::<BabyBearPoseidon2Config, NativeConfig>(
+            program.clone(),
+            input(i, i),
+            vm_config.clone(),
+        );
+    }
+
+    // Negative: the proof doesn't match the claimed FRI parameters, or they aren't allowed.
+    disable_debug_builder();
+    for (proof_index, fri_params_index) in [(0, 1), (1, 2)] {
+        let unwind_res = catch_unwind(|| {
+            gen_vm_program_test_proof_input::<BabyBearPoseidon2Config, NativeConfig>(
+                program.clone(),
+                input(proof_index, fri_params_index),
+                vm_config.clone(),
+            );
+        });
+        assert!(unwind_res.is_err());
+    }
+}
@@ -53,7 +53,7 @@ where
     } = vk;
     StarkVerificationAdvice {
         preprocessed_data: preprocessed_data.map(|data| VerifierSinglePreprocessedDataInProgram {
-            commit: DigestVal::F(data.commit.clone().into().to_vec()),
+            commits: vec![DigestVal::F(data.commit.clone().into().to_vec())],
         }),
         width: params.width,
         quotient_degree,
@@ -86,6 +86,54 @@ where
     }
 }
 
+/// Create MultiStarkVerificationAdvice for an inner config from the verifying keys of the same
+/// AIRs keygened with different FRI configs. Only the preprocessed commitments may differ; they
+/// are kept in the order of `vks`, which must match the order of the allowed FRI configs of the
+/// verifier.
+pub fn new_from_inner_multi_vks<SC: StarkGenericConfig, C: Config<F = Val<SC>>>(
+    vks: &[MultiStarkVerifyingKey<SC>],
+) -> MultiStarkVerificationAdvice<C>
+where
+    Com<SC>: Into<[C::F; DIGEST_SIZE]>,
+{
+    let (first, rest) = vks
+        .split_first()
+        .expect("at least one verifying key is required");
+    let mut advice = new_from_inner_multi_vk(first);
+    for vk in rest {
+        let other = new_from_inner_multi_vk::<SC, C>(vk);
+        assert_eq!(
+            advice.num_challenges_to_sample, other.num_challenges_to_sample,
+            "verifying keys must have the same challenge phases"
+        );
+        assert_eq!(
+            advice.per_air.len(),
+            other.per_air.len(),
+            "verifying keys must have the same AIRs"
+        );
+        for (air, other_air) in advice.per_air.iter_mut().zip(other.per_air) {
+            assert!(
+                air.width.preprocessed == other_air.width.preprocessed
+                    && air.width.cached_mains == other_air.width.cached_mains
+                    && air.width.common_main == other_air.width.common_main
+                    && air.width.after_challenge == other_air.width.after_challenge
+                    && air.quotient_degree == other_air.quotient_degree
+                    && air.num_public_values == other_air.num_public_values
+                    && air.num_challenges_to_sample == other_air.num_challenges_to_sample
+                    && air.num_exposed_values_after_challenge
+                        == other_air.num_exposed_values_after_challenge,
+                "verifying keys must have the same AIRs"
+            );
+            match (air.preprocessed_data.as_mut(), other_air.preprocessed_data) {
+                (Some(data), Some(other_data)) => data.commits.extend(other_data.commits),
+                (None, None) => {}
+                _ => panic!("verifying keys must have the same preprocessed traces"),
+            }
+        }
+    }
+    advice
+}
+
 impl<C: Config> StarkVerificationAdvice<C> {
     pub fn log_quotient_degree(&self) -> usize {
         log2_strict_usize(self.quotient_degree)
@@ -93,7 +141,9 @@ impl<C: Config> StarkVerificationAdvice<C> {
 }
 
 pub struct VerifierSinglePreprocessedDataInProgram<C: Config> {
-    pub commit: DigestVal<C>,
+    /// One commitment per allowed FRI config of the verified proofs. The commitment of a
+    /// preprocessed trace depends on the log blowup.
+    pub commits: Vec<DigestVal<C>>,
 }
 
 pub struct VerifierInput<SC: StarkGenericConfig> {
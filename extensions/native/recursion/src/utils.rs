@@ -1,16 +1,103 @@
-use openvm_native_compiler::ir::{Builder, CanSelect, Config, Felt, MemVariable, Var};
+use openvm_native_compiler::ir::{Array, Builder, CanSelect, Config, Felt, MemVariable, RVar, Var};
 use openvm_stark_backend::{
     p3_commit::TwoAdicMultiplicativeCoset,
     p3_field::{AbstractField, TwoAdicField},
 };
 use openvm_stark_sdk::config::FriParameters;
 
-use crate::fri::{types::FriConfigVariable, TwoAdicMultiplicativeCosetVariable};
+use crate::{
+    fri::{types::FriConfigVariable, TwoAdicMultiplicativeCosetVariable},
+    hints::Hintable,
+};
 
 pub fn const_fri_config<C: Config>(
     builder: &mut Builder<C>,
     params: &FriParameters,
 ) -> FriConfigVariable<C> {
+    let (generators, subgroups) = two_adic_subgroups(builder);
+    FriConfigVariable {
+        log_blowup: RVar::from(params.log_blowup),
+        blowup: RVar::from(1 << params.log_blowup),
+        num_queries: RVar::from(params.num_queries),
+        proof_of_work_bits: params.proof_of_work_bits,
+        index: None,
+        subgroups,
+        generators,
+    }
+}
+
+/// Reads an index into `allowed` from the input stream and returns the FRI config with the log
+/// blowup and number of queries of `allowed[index]`. The program fails if the index is out of
+/// range.
+///
+/// `allowed` is compiled into the program, so the set of accepted configs is bound to the program
+/// commitment while the config of each proof is chosen by the prover. All configs must have the
+/// same proof of work bits. Only supported in dynamic mode.
+pub fn witness_fri_config<C: Config>(
+    builder: &mut Builder<C>,
+    allowed: &[FriParameters],
+) -> FriConfigVariable<C> {
+    let index = <usize as Hintable<C>>::read(builder);
+    select_fri_config(builder, allowed, index)
+}
+
+/// Returns the FRI config of `allowed[index]` for an index that is already part of the input,
+/// see [witness_fri_config].
+pub fn select_fri_config<C: Config>(
+    builder: &mut Builder<C>,
+    allowed: &[FriParameters],
+    index: Var<C::N>,
+) -> FriConfigVariable<C> {
+    assert!(
+        !builder.flags.static_only,
+        "witnessed FRI configs are not supported in static mode"
+    );
+    assert!(
+        !allowed.is_empty(),
+        "at least one FRI config must be allowed"
+    );
+    let proof_of_work_bits = allowed[0].proof_of_work_bits;
+    assert!(
+        allowed
+            .iter()
+            .all(|params| params.proof_of_work_bits == proof_of_work_bits),
+        "allowed FRI configs must have the same proof of work bits"
+    );
+
+    let log_blowup: Var<_> = builder.uninit();
+    let blowup: Var<_> = builder.uninit();
+    let num_queries: Var<_> = builder.uninit();
+    let found: Var<_> = builder.eval(C::N::ZERO);
+    for (i, params) in allowed.iter().enumerate() {
+        builder
+            .if_eq(index, C::N::from_canonical_usize(i))
+            .then(|builder| {
+                builder.assign(&log_blowup, C::N::from_canonical_usize(params.log_blowup));
+                builder.assign(&blowup, C::N::from_canonical_usize(1 << params.log_blowup));
+                builder.assign(&num_queries, C::N::from_canonical_usize(params.num_queries));
+                builder.assign(&found, C::N::ONE);
+            });
+    }
+    builder.assert_var_eq(found, C::N::ONE);
+
+    let (generators, subgroups) = two_adic_subgroups(builder);
+    FriConfigVariable {
+        log_blowup: log_blowup.into(),
+        blowup: blowup.into(),
+        num_queries: num_queries.into(),
+        proof_of_work_bits,
+        index: Some(index),
+        subgroups,
+        generators,
+    }
+}
+
+fn two_adic_subgroups<C: Config>(
+    builder: &mut Builder<C>,
+) -> (
+    Array<C, Felt<C::F>>,
+    Array<C, TwoAdicMultiplicativeCosetVariable<C>>,
+) {
     let two_adicity = C::F::TWO_ADICITY;
     let generators = builder.array(two_adicity + 1);
     let subgroups = builder.array(two_adicity + 1);
@@ -27,14 +114,7 @@ pub fn const_fri_config<C: Config>(
         // to `Usize::Var` because it calls `builder.eval`.
         builder.set_value(&subgroups, i, domain_value);
     }
-    FriConfigVariable {
-        log_blowup: params.log_blowup,
-        blowup: 1 << params.log_blowup,
-        num_queries: params.num_queries,
-        proof_of_work_bits: params.proof_of_work_bits,
-        subgroups,
-        generators,
-    }
+    (generators, subgroups)
 }
 
 /// Reference: https://github.com/Plonky3/Plonky3/blob/622375885320ac6bf3c338001760ed8f2230e3cb/field/src/helpers.rs#L136
@@ -3,7 +3,9 @@ use openvm_native_compiler::{
     ir::{Builder, Config},
     prelude::*,
 };
-use openvm_stark_backend::{keygen::types::TraceWidth, p3_util::log2_strict_usize};
+use openvm_stark_backend::{
+    keygen::types::TraceWidth, p3_field::AbstractField, p3_util::log2_strict_usize,
+};
 
 use crate::{
     types::{MultiStarkVerificationAdvice, StarkVerificationAdvice},
@@ -16,6 +18,7 @@ pub fn get_advice_per_air<C: Config>(
     builder: &mut Builder<C>,
     m_advice: &MultiStarkVerificationAdvice<C>,
     air_ids: &Array<C, Usize<C::N>>,
+    fri_config_index: Option<Var<C::N>>,
 ) -> MultiStarkVerificationAdviceVariable<C> {
     let num_challenges_to_sample_mask = m_advice
         .num_challenges_to_sample
@@ -29,8 +32,12 @@ pub fn get_advice_per_air<C: Config>(
         let curr_air_id = builder.get(air_ids, idx.clone());
         let air_id = Usize::from(air_id);
         builder.if_eq(air_id, curr_air_id).then(|builder| {
-            let advice_var =
-                constant_advice_and_update_mask(builder, advice, &num_challenges_to_sample_mask);
+            let advice_var = constant_advice_and_update_mask(
+                builder,
+                advice,
+                &num_challenges_to_sample_mask,
+                fri_config_index,
+            );
             builder.set_value(&advice_per_air, idx.clone(), advice_var);
             builder.inc(&idx);
         });
@@ -46,11 +53,25 @@ fn constant_advice_and_update_mask<C: Config>(
     builder: &mut Builder<C>,
     advice: &StarkVerificationAdvice<C>,
     num_challenges_to_sample_mask: &[Vec<Usize<C::N>>],
+    fri_config_index: Option<Var<C::N>>,
 ) -> StarkVerificationAdviceVariable<C> {
     let preprocessed_data = if let Some(preprocessed_data) = advice.preprocessed_data.as_ref() {
-        let commit = builder.constant(preprocessed_data.commit.clone());
+        let commits = &preprocessed_data.commits;
+        let commit = builder.constant(commits[0].clone());
         let arr = builder.array(1);
         builder.set_value(&arr, 0, commit);
+        if commits.len() > 1 {
+            let index = fri_config_index
+                .expect("preprocessed commitments of multiple FRI configs need a witnessed config");
+            for (i, commit) in commits.iter().enumerate().skip(1) {
+                builder
+                    .if_eq(index, C::N::from_canonical_usize(i))
+                    .then(|builder| {
+                        let commit = builder.constant(commit.clone());
+                        builder.set_value(&arr, 0, commit);
+                    });
+            }
+        }
         arr
     } else {
         builder.array(0)
@@ -80,3 +80,35 @@ fn test_fibonacci_program_halo2_verify() {
         standard_fri_params_with_100_bits_conjectured_security(3),
     );
 }
+
+#[test]
+fn test_fibonacci_program_verify_optimized() {
+    use openvm_native_compiler::conversion::CompilerOptions;
+    use openvm_native_recursion::testing_utils::{
+        inner::build_verification_program, recursive_stark_test,
+    };
+    use openvm_stark_sdk::{
+        config::baby_bear_poseidon2::{BabyBearPoseidon2Config, BabyBearPoseidon2Engine},
+        engine::StarkFriEngine,
+    };
+
+    let vparams = || {
+        <BabyBearPoseidon2Engine as StarkFriEngine<BabyBearPoseidon2Config>>::run_test_fast(
+            fibonacci_program_test_proof_input(0, 1, 32).per_air,
+        )
+        .unwrap()
+    };
+
+    let (program, _) = build_verification_program(vparams(), CompilerOptions::default());
+    let (optimized, _) =
+        build_verification_program(vparams(), CompilerOptions::default().with_optimizations());
+    assert!(optimized.len() < program.len());
+
+    recursive_stark_test(
+        vparams(),
+        CompilerOptions::default().with_optimizations(),
+        NativeConfig::aggregation(4, 7),
+        &BabyBearPoseidon2Engine::new(standard_fri_params_with_100_bits_conjectured_security(3)),
+    )
+    .unwrap();
+}
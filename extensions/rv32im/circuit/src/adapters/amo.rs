@@ -0,0 +1,382 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    cell::RefCell,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use openvm_circuit::{
+    arch::{
+        AdapterAirContext, AdapterRuntimeContext, BasicAdapterInterface, ExecutionBridge,
+        ExecutionBus, ExecutionState, MinimalInstruction, Result, VmAdapterAir, VmAdapterChip,
+        VmAdapterInterface,
+    },
+    system::{
+        memory::{
+            offline_checker::{MemoryBridge, MemoryReadAuxCols, MemoryWriteAuxCols},
+            MemoryAddress, MemoryAuxColsFactory, MemoryController, MemoryControllerRef,
+            MemoryReadRecord, MemoryWriteRecord,
+        },
+        program::ProgramBus,
+    },
+};
+use openvm_circuit_primitives::var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{
+    instruction::Instruction,
+    riscv::{RV32_MEMORY_AS, RV32_REGISTER_AS},
+};
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+};
+
+use super::{compose, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS};
+
+/// Handles atomic memory operations of the form OP a, b, c, d, e, f, g where
+/// [[b:4]_d:4]_e is read and overwritten with ([[b:4]_d:4]_e op [c:4]_d), and the value read
+/// is written to [a:4]_d.
+///
+/// Like the store path of the LoadStore adapter, the previous memory word is not read
+/// separately but taken from the write's auxiliary columns. On top of that, the previous word
+/// is also written back to `rd`. Operand f is 1 unless rd is x0, in which case the register
+/// write is skipped. Operand g is 1 if rd receives the previous memory word and 0 if it
+/// receives zero, as for a store-conditional that always succeeds.
+///
+/// The memory pointer must be 4-byte aligned.
+///
+/// The LoadStore adapter cannot be reused here: every row of it does exactly one register read,
+/// one read and one write, with the direction chosen by `is_load`. An atomic operation reads two
+/// registers, overwrites a memory word and writes `rd` in the same row, so it needs four memory
+/// accesses. Adding them to the LoadStore adapter would widen its trace and change the
+/// verifying key of every program using loads and stores.
+#[derive(Debug)]
+pub struct Rv32AmoAdapterChip<F: Field> {
+    pub air: Rv32AmoAdapterAir,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField32> Rv32AmoAdapterChip<F> {
+    pub fn new(
+        execution_bus: ExecutionBus,
+        program_bus: ProgramBus,
+        memory_controller: MemoryControllerRef<F>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+    ) -> Self {
+        let memory_controller = RefCell::borrow(&memory_controller);
+        let memory_bridge = memory_controller.memory_bridge();
+        Self {
+            air: Rv32AmoAdapterAir {
+                execution_bridge: ExecutionBridge::new(execution_bus, program_bus),
+                memory_bridge,
+                range_bus: range_checker_chip.bus(),
+                pointer_max_bits: memory_controller.mem_config().pointer_max_bits,
+            },
+            range_checker_chip,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32AmoReadRecord<F: Field> {
+    pub rs1: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    pub rs2: MemoryReadRecord<F, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Rv32AmoWriteRecord<F: Field> {
+    pub from_state: ExecutionState<u32>,
+    /// Write of the new value to RISC-V memory
+    pub mem: MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>,
+    /// Write to the destination register, None if rd is x0
+    pub rd: Option<MemoryWriteRecord<F, RV32_REGISTER_NUM_LIMBS>>,
+    pub rd_ptr: F,
+    pub returns_prev_data: bool,
+}
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct Rv32AmoAdapterCols<T> {
+    pub from_state: ExecutionState<T>,
+    pub rd_ptr: T,
+    pub rs1_ptr: T,
+    pub rs2_ptr: T,
+    pub rs1_data: [T; RV32_REGISTER_NUM_LIMBS],
+    pub reads_aux: [MemoryReadAuxCols<T, RV32_REGISTER_NUM_LIMBS>; 2],
+    /// prev_data holds the memory word before the operation
+    pub mem_write_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+    /// 1 if rd is not x0
+    pub needs_write: T,
+    /// 1 if rd receives the previous memory word, 0 if it receives zero
+    pub returns_prev_data: T,
+    pub rd_write_aux: MemoryWriteAuxCols<T, RV32_REGISTER_NUM_LIMBS>,
+}
+
+#[derive(Clone, Copy, Debug, derive_new::new)]
+pub struct Rv32AmoAdapterAir {
+    pub(super) execution_bridge: ExecutionBridge,
+    pub(super) memory_bridge: MemoryBridge,
+    pub range_bus: VariableRangeCheckerBus,
+    pointer_max_bits: usize,
+}
+
+impl<F: Field> BaseAir<F> for Rv32AmoAdapterAir {
+    fn width(&self) -> usize {
+        Rv32AmoAdapterCols::<F>::width()
+    }
+}
+
+impl<AB: InteractionBuilder> VmAdapterAir<AB> for Rv32AmoAdapterAir {
+    /// The core chip receives [rs2, prev_data] and returns the new memory word.
+    type Interface = BasicAdapterInterface<
+        AB::Expr,
+        MinimalInstruction<AB::Expr>,
+        2,
+        1,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local: &[AB::Var],
+        ctx: AdapterAirContext<AB::Expr, Self::Interface>,
+    ) {
+        let local: &Rv32AmoAdapterCols<_> = local.borrow();
+        let timestamp = local.from_state.timestamp;
+        let mut timestamp_delta: usize = 0;
+        let mut timestamp_pp = || {
+            timestamp_delta += 1;
+            timestamp + AB::F::from_canonical_usize(timestamp_delta - 1)
+        };
+        let is_valid = ctx.instruction.is_valid;
+
+        builder.assert_bool(local.needs_write);
+        builder.when(local.needs_write).assert_one(is_valid.clone());
+        builder.assert_bool(local.returns_prev_data);
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs1_ptr),
+                local.rs1_data,
+                timestamp_pp(),
+                &local.reads_aux[0],
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .read(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rs2_ptr),
+                ctx.reads[0].clone(),
+                timestamp_pp(),
+                &local.reads_aux[1],
+            )
+            .eval(builder, is_valid.clone());
+
+        // The memory pointer is rs1 itself. Constrain that it is 4-byte aligned and within
+        // pointer_max_bits by range checking its two 16-bit halves.
+        let limbs_01 =
+            local.rs1_data[0] + local.rs1_data[1] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        let limbs_23 =
+            local.rs1_data[2] + local.rs1_data[3] * AB::F::from_canonical_u32(1 << RV32_CELL_BITS);
+        self.range_bus
+            .range_check(
+                limbs_01.clone() * AB::F::from_canonical_u32(4).inverse(),
+                RV32_CELL_BITS * 2 - 2,
+            )
+            .eval(builder, is_valid.clone());
+        self.range_bus
+            .range_check(limbs_23.clone(), self.pointer_max_bits - RV32_CELL_BITS * 2)
+            .eval(builder, is_valid.clone());
+        let mem_ptr = limbs_01 + limbs_23 * AB::F::from_canonical_u32(1 << (RV32_CELL_BITS * 2));
+
+        // The core chip operates on the memory word that is being overwritten.
+        for (prev, read) in local
+            .mem_write_aux
+            .prev_data
+            .iter()
+            .zip(ctx.reads[1].iter())
+        {
+            builder
+                .when(is_valid.clone())
+                .assert_eq(*prev, read.clone());
+        }
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_MEMORY_AS), mem_ptr),
+                ctx.writes[0].clone(),
+                timestamp_pp(),
+                &local.mem_write_aux,
+            )
+            .eval(builder, is_valid.clone());
+
+        self.memory_bridge
+            .write(
+                MemoryAddress::new(AB::F::from_canonical_u32(RV32_REGISTER_AS), local.rd_ptr),
+                local
+                    .mem_write_aux
+                    .prev_data
+                    .map(|x| x * local.returns_prev_data),
+                timestamp_pp(),
+                &local.rd_write_aux,
+            )
+            .eval(builder, local.needs_write);
+
+        self.execution_bridge
+            .execute_and_increment_or_set_pc(
+                ctx.instruction.opcode,
+                [
+                    local.rd_ptr.into(),
+                    local.rs1_ptr.into(),
+                    local.rs2_ptr.into(),
+                    AB::Expr::from_canonical_u32(RV32_REGISTER_AS),
+                    AB::Expr::from_canonical_u32(RV32_MEMORY_AS),
+                    local.needs_write.into(),
+                    local.returns_prev_data.into(),
+                ],
+                local.from_state,
+                AB::F::from_canonical_usize(timestamp_delta),
+                (4, ctx.to_pc),
+            )
+            .eval(builder, is_valid);
+    }
+
+    fn get_from_pc(&self, local: &[AB::Var]) -> AB::Var {
+        let cols: &Rv32AmoAdapterCols<_> = local.borrow();
+        cols.from_state.pc
+    }
+}
+
+impl<F: PrimeField32> VmAdapterChip<F> for Rv32AmoAdapterChip<F> {
+    type ReadRecord = Rv32AmoReadRecord<F>;
+    type WriteRecord = Rv32AmoWriteRecord<F>;
+    type Air = Rv32AmoAdapterAir;
+    type Interface = BasicAdapterInterface<
+        F,
+        MinimalInstruction<F>,
+        2,
+        1,
+        RV32_REGISTER_NUM_LIMBS,
+        RV32_REGISTER_NUM_LIMBS,
+    >;
+
+    fn preprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+    ) -> Result<(
+        <Self::Interface as VmAdapterInterface<F>>::Reads,
+        Self::ReadRecord,
+    )> {
+        let Instruction { b, c, d, e, .. } = *instruction;
+
+        debug_assert_eq!(d.as_canonical_u32(), RV32_REGISTER_AS);
+        debug_assert_eq!(e.as_canonical_u32(), RV32_MEMORY_AS);
+        assert!(self.range_checker_chip.range_max_bits() >= 14);
+
+        let rs1 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, b);
+        let rs2 = memory.read::<RV32_REGISTER_NUM_LIMBS>(d, c);
+
+        let ptr_val = compose(rs1.data);
+        assert_eq!(
+            ptr_val % 4,
+            0,
+            "misaligned atomic access: ptr_val = {ptr_val}"
+        );
+        assert!(
+            ptr_val < (1 << self.air.pointer_max_bits),
+            "ptr_val: {ptr_val} >= 2 ** {}",
+            self.air.pointer_max_bits
+        );
+        self.range_checker_chip
+            .add_count((ptr_val & 0xffff) >> 2, RV32_CELL_BITS * 2 - 2);
+        self.range_checker_chip.add_count(
+            ptr_val >> (RV32_CELL_BITS * 2),
+            self.air.pointer_max_bits - RV32_CELL_BITS * 2,
+        );
+
+        // The previous memory word is constrained by the write in postprocess.
+        let prev_data =
+            memory.unsafe_read::<RV32_REGISTER_NUM_LIMBS>(e, F::from_canonical_u32(ptr_val));
+
+        Ok(([rs2.data, prev_data], Self::ReadRecord { rs1, rs2 }))
+    }
+
+    fn postprocess(
+        &mut self,
+        memory: &mut MemoryController<F>,
+        instruction: &Instruction<F>,
+        from_state: ExecutionState<u32>,
+        output: AdapterRuntimeContext<F, Self::Interface>,
+        read_record: &Self::ReadRecord,
+    ) -> Result<(ExecutionState<u32>, Self::WriteRecord)> {
+        let Instruction { a, d, e, f, g, .. } = *instruction;
+
+        let ptr = F::from_canonical_u32(compose(read_record.rs1.data));
+        let mem = memory.write(e, ptr, output.writes[0]);
+
+        let returns_prev_data = g == F::ONE;
+        let rd = if f != F::ZERO {
+            let rd_data = if returns_prev_data {
+                mem.prev_data
+            } else {
+                [F::ZERO; RV32_REGISTER_NUM_LIMBS]
+            };
+            Some(memory.write(d, a, rd_data))
+        } else {
+            memory.increment_timestamp();
+            None
+        };
+
+        Ok((
+            ExecutionState {
+                pc: output.to_pc.unwrap_or(from_state.pc + 4),
+                timestamp: memory.timestamp(),
+            },
+            Self::WriteRecord {
+                from_state,
+                mem,
+                rd,
+                rd_ptr: a,
+                returns_prev_data,
+            },
+        ))
+    }
+
+    fn generate_trace_row(
+        &self,
+        row_slice: &mut [F],
+        read_record: Self::ReadRecord,
+        write_record: Self::WriteRecord,
+        aux_cols_factory: &MemoryAuxColsFactory<F>,
+    ) {
+        let row_slice: &mut Rv32AmoAdapterCols<_> = row_slice.borrow_mut();
+        row_slice.from_state = write_record.from_state.map(F::from_canonical_u32);
+        row_slice.rd_ptr = write_record.rd_ptr;
+        row_slice.rs1_ptr = read_record.rs1.pointer;
+        row_slice.rs2_ptr = read_record.rs2.pointer;
+        row_slice.rs1_data = read_record.rs1.data;
+        row_slice.reads_aux = [
+            aux_cols_factory.make_read_aux_cols(read_record.rs1),
+            aux_cols_factory.make_read_aux_cols(read_record.rs2),
+        ];
+        row_slice.mem_write_aux = aux_cols_factory.make_write_aux_cols(write_record.mem);
+        row_slice.returns_prev_data = F::from_bool(write_record.returns_prev_data);
+        if let Some(rd) = write_record.rd {
+            row_slice.needs_write = F::ONE;
+            row_slice.rd_write_aux = aux_cols_factory.make_write_aux_cols(rd);
+        } else {
+            row_slice.needs_write = F::ZERO;
+            row_slice.rd_write_aux = MemoryWriteAuxCols::disabled();
+        }
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -4,6 +4,7 @@ use openvm_circuit::system::memory::{MemoryController, MemoryReadRecord};
 use openvm_stark_backend::p3_field::{AbstractField, PrimeField32};
 
 mod alu;
+mod amo;
 mod branch;
 mod hintstore;
 mod jalr;
@@ -12,6 +13,7 @@ mod mul;
 mod rdwrite;
 
 pub use alu::*;
+pub use amo::*;
 pub use branch::*;
 pub use hintstore::*;
 pub use jalr::*;
@@ -0,0 +1,160 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32AmoSwapOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct AmoSwapCoreCols<T, const NUM_LIMBS: usize> {
+    /// The value stored to memory
+    pub b: [T; NUM_LIMBS],
+    /// The memory word being overwritten
+    pub prev_data: [T; NUM_LIMBS],
+
+    pub opcode_amoswap_flag: T,
+    pub opcode_sc_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct AmoSwapCoreAir<const NUM_LIMBS: usize> {
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize> BaseAir<F> for AmoSwapCoreAir<NUM_LIMBS> {
+    fn width(&self) -> usize {
+        AmoSwapCoreCols::<F, NUM_LIMBS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize> BaseAirWithPublicValues<F> for AmoSwapCoreAir<NUM_LIMBS> {}
+
+impl<AB, I, const NUM_LIMBS: usize> VmCoreAir<AB, I> for AmoSwapCoreAir<NUM_LIMBS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &AmoSwapCoreCols<_, NUM_LIMBS> = local_core.borrow();
+        let flags = [cols.opcode_amoswap_flag, cols.opcode_sc_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        // Both operations store rs2 unchanged. They only differ in what the adapter writes to
+        // rd, which is determined by the instruction operands.
+        let expected_opcode = flags.iter().zip(Rv32AmoSwapOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.prev_data.map(Into::into)].into(),
+            writes: [cols.b.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AmoSwapCoreRecord<T, const NUM_LIMBS: usize> {
+    pub opcode: Rv32AmoSwapOpcode,
+    pub b: [T; NUM_LIMBS],
+    pub prev_data: [T; NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct AmoSwapCoreChip<const NUM_LIMBS: usize> {
+    pub air: AmoSwapCoreAir<NUM_LIMBS>,
+}
+
+impl<const NUM_LIMBS: usize> AmoSwapCoreChip<NUM_LIMBS> {
+    pub fn new(offset: usize) -> Self {
+        Self {
+            air: AmoSwapCoreAir { offset },
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize> VmCoreChip<F, I> for AmoSwapCoreChip<NUM_LIMBS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = AmoSwapCoreRecord<F, NUM_LIMBS>;
+    type Air = AmoSwapCoreAir<NUM_LIMBS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = Rv32AmoSwapOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let [b, prev_data]: [[F; NUM_LIMBS]; 2] = reads.into();
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [b].into(),
+        };
+        let record = Self::Record {
+            opcode: local_opcode,
+            b,
+            prev_data,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32AmoSwapOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut AmoSwapCoreCols<_, NUM_LIMBS> = row_slice.borrow_mut();
+        row_slice.b = record.b;
+        row_slice.prev_data = record.prev_data;
+        row_slice.opcode_amoswap_flag = F::from_bool(record.opcode == Rv32AmoSwapOpcode::AMOSWAP);
+        row_slice.opcode_sc_flag = F::from_bool(record.opcode == Rv32AmoSwapOpcode::SC);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
@@ -0,0 +1,30 @@
+use openvm_circuit::arch::VmChipWrapper;
+
+use super::{
+    adapters::{Rv32AmoAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    BaseAluCoreChip, MinMaxCoreChip,
+};
+
+mod core;
+pub use core::*;
+
+#[cfg(test)]
+mod tests;
+
+/// AMOADD.W, AMOXOR.W, AMOOR.W and AMOAND.W
+pub type Rv32AmoAluChip<F> = VmChipWrapper<
+    F,
+    Rv32AmoAdapterChip<F>,
+    BaseAluCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+
+/// AMOMIN.W, AMOMINU.W, AMOMAX.W and AMOMAXU.W
+pub type Rv32AmoMinMaxChip<F> = VmChipWrapper<
+    F,
+    Rv32AmoAdapterChip<F>,
+    MinMaxCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+
+/// AMOSWAP.W and SC.W
+pub type Rv32AmoSwapChip<F> =
+    VmChipWrapper<F, Rv32AmoAdapterChip<F>, AmoSwapCoreChip<RV32_REGISTER_NUM_LIMBS>>;
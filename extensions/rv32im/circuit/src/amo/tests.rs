@@ -0,0 +1,343 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{memory::gen_pointer, VmChipTestBuilder},
+        InstructionExecutor, VmAdapterChip, BITWISE_OP_LOOKUP_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, UsizeOpcode, VmOpcode};
+use openvm_rv32im_transpiler::{
+    BaseAluOpcode, MinMaxOpcode, Rv32AmoAluOpcode, Rv32AmoMinMaxOpcode, Rv32AmoSwapOpcode,
+};
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::{rngs::StdRng, Rng};
+
+use super::{AmoSwapCoreChip, AmoSwapCoreCols, Rv32AmoAluChip, Rv32AmoMinMaxChip, Rv32AmoSwapChip};
+use crate::{
+    adapters::{Rv32AmoAdapterChip, Rv32AmoAdapterCols, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    BaseAluCoreChip, MinMaxCoreChip,
+};
+
+type F = BabyBear;
+
+/// Writes an aligned pointer to rs1, a random word to memory and rs2, executes the atomic
+/// instruction and checks the resulting memory word and rd against `expected`.
+#[allow(clippy::too_many_arguments)]
+fn set_and_execute<E: InstructionExecutor<F>>(
+    tester: &mut VmChipTestBuilder<F>,
+    chip: &mut E,
+    rng: &mut StdRng,
+    opcode: VmOpcode,
+    write_rd: bool,
+    returns_prev_data: bool,
+    expected: impl Fn(u32, u32) -> u32,
+) {
+    let pointer_max_bits = tester
+        .memory_controller()
+        .borrow()
+        .mem_config()
+        .pointer_max_bits;
+    let ptr_val = rng.gen_range(0..(1u32 << (pointer_max_bits - 2))) << 2;
+
+    let rd = gen_pointer(rng, 4);
+    let mut rs1 = gen_pointer(rng, 4);
+    while rs1 == rd {
+        rs1 = gen_pointer(rng, 4);
+    }
+    let mut rs2 = gen_pointer(rng, 4);
+    while rs2 == rd || rs2 == rs1 {
+        rs2 = gen_pointer(rng, 4);
+    }
+
+    let prev_data: u32 = rng.gen();
+    let rs2_data: u32 = rng.gen();
+    tester.write(1, rs1, ptr_val.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(1, rs2, rs2_data.to_le_bytes().map(F::from_canonical_u8));
+    tester.write(
+        2,
+        ptr_val as usize,
+        prev_data.to_le_bytes().map(F::from_canonical_u8),
+    );
+    let rd_data = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(rng)
+        .map(F::from_canonical_u32);
+    tester.write(1, rd, rd_data);
+
+    tester.execute(
+        chip,
+        Instruction::from_usize(
+            opcode,
+            [
+                rd,
+                rs1,
+                rs2,
+                1,
+                2,
+                write_rd as usize,
+                returns_prev_data as usize,
+            ],
+        ),
+    );
+
+    assert_eq!(
+        expected(prev_data, rs2_data)
+            .to_le_bytes()
+            .map(F::from_canonical_u8),
+        tester.read::<4>(2, ptr_val as usize)
+    );
+    let expected_rd = if !write_rd {
+        rd_data
+    } else if returns_prev_data {
+        prev_data.to_le_bytes().map(F::from_canonical_u8)
+    } else {
+        [F::ZERO; RV32_REGISTER_NUM_LIMBS]
+    };
+    assert_eq!(expected_rd, tester.read::<4>(1, rd));
+}
+
+fn new_adapter(tester: &VmChipTestBuilder<F>) -> Rv32AmoAdapterChip<F> {
+    let range_checker_chip = tester.memory_controller().borrow().range_checker.clone();
+    Rv32AmoAdapterChip::new(
+        tester.execution_bus(),
+        tester.program_bus(),
+        tester.memory_controller(),
+        range_checker_chip,
+    )
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_amo_alu_rand_test(opcode: BaseAluOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32AmoAluChip::<F>::new(
+        new_adapter(&tester),
+        BaseAluCoreChip::new(bitwise_chip.clone(), Rv32AmoAluOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    let expected = |x: u32, y: u32| match opcode {
+        BaseAluOpcode::ADD => x.wrapping_add(y),
+        BaseAluOpcode::XOR => x ^ y,
+        BaseAluOpcode::OR => x | y,
+        BaseAluOpcode::AND => x & y,
+        BaseAluOpcode::SUB => unreachable!(),
+    };
+    for i in 0..num_ops {
+        set_and_execute(
+            &mut tester,
+            &mut chip,
+            &mut rng,
+            VmOpcode::with_default_offset(Rv32AmoAluOpcode(opcode)),
+            i % 4 != 0,
+            true,
+            expected,
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_amoadd_rand_test() {
+    run_rv32_amo_alu_rand_test(BaseAluOpcode::ADD, 100);
+}
+
+#[test]
+fn rv32_amoxor_rand_test() {
+    run_rv32_amo_alu_rand_test(BaseAluOpcode::XOR, 100);
+}
+
+#[test]
+fn rv32_amoor_rand_test() {
+    run_rv32_amo_alu_rand_test(BaseAluOpcode::OR, 100);
+}
+
+#[test]
+fn rv32_amoand_rand_test() {
+    run_rv32_amo_alu_rand_test(BaseAluOpcode::AND, 100);
+}
+
+fn run_rv32_amo_min_max_rand_test(opcode: MinMaxOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32AmoMinMaxChip::<F>::new(
+        new_adapter(&tester),
+        MinMaxCoreChip::new(bitwise_chip.clone(), Rv32AmoMinMaxOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    let expected = |x: u32, y: u32| match opcode {
+        MinMaxOpcode::MIN => (x as i32).min(y as i32) as u32,
+        MinMaxOpcode::MINU => x.min(y),
+        MinMaxOpcode::MAX => (x as i32).max(y as i32) as u32,
+        MinMaxOpcode::MAXU => x.max(y),
+    };
+    for i in 0..num_ops {
+        set_and_execute(
+            &mut tester,
+            &mut chip,
+            &mut rng,
+            VmOpcode::with_default_offset(Rv32AmoMinMaxOpcode(opcode)),
+            i % 4 != 0,
+            true,
+            expected,
+        );
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_amomin_rand_test() {
+    run_rv32_amo_min_max_rand_test(MinMaxOpcode::MIN, 100);
+}
+
+#[test]
+fn rv32_amominu_rand_test() {
+    run_rv32_amo_min_max_rand_test(MinMaxOpcode::MINU, 100);
+}
+
+#[test]
+fn rv32_amomax_rand_test() {
+    run_rv32_amo_min_max_rand_test(MinMaxOpcode::MAX, 100);
+}
+
+#[test]
+fn rv32_amomaxu_rand_test() {
+    run_rv32_amo_min_max_rand_test(MinMaxOpcode::MAXU, 100);
+}
+
+#[test]
+fn rv32_amoswap_sc_rand_test() {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32AmoSwapChip::<F>::new(
+        new_adapter(&tester),
+        AmoSwapCoreChip::new(Rv32AmoSwapOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    for i in 0..100 {
+        let (opcode, returns_prev_data) = if i % 2 == 0 {
+            (Rv32AmoSwapOpcode::AMOSWAP, true)
+        } else {
+            (Rv32AmoSwapOpcode::SC, false)
+        };
+        set_and_execute(
+            &mut tester,
+            &mut chip,
+            &mut rng,
+            VmOpcode::with_default_offset(opcode),
+            i % 3 != 0,
+            returns_prev_data,
+            |_, y| y,
+        );
+    }
+
+    let tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// part of the trace and check that the chip throws the expected error.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_amo_swap_negative_test(
+    modify: impl Fn(&mut AmoSwapCoreCols<F, RV32_REGISTER_NUM_LIMBS>, &mut Rv32AmoAdapterCols<F>),
+    expected_error: VerificationError,
+) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32AmoSwapChip::<F>::new(
+        new_adapter(&tester),
+        AmoSwapCoreChip::new(Rv32AmoSwapOpcode::default_offset()),
+        tester.memory_controller(),
+    );
+
+    set_and_execute(
+        &mut tester,
+        &mut chip,
+        &mut rng,
+        VmOpcode::with_default_offset(Rv32AmoSwapOpcode::AMOSWAP),
+        true,
+        true,
+        |_, y| y,
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let (adapter_row, core_row) = values.split_at_mut(adapter_width);
+        modify(core_row.borrow_mut(), adapter_row.borrow_mut());
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .finalize();
+    tester.simple_test_with_expected_error(expected_error);
+}
+
+#[test]
+fn rv32_amo_wrong_prev_data_negative_test() {
+    run_rv32_amo_swap_negative_test(
+        |core, _| core.prev_data[0] += F::ONE,
+        VerificationError::OodEvaluationMismatch,
+    );
+}
+
+#[test]
+fn rv32_amo_skipped_rd_write_negative_test() {
+    run_rv32_amo_swap_negative_test(
+        |_, adapter| adapter.needs_write = F::ZERO,
+        VerificationError::ChallengePhaseError,
+    );
+}
+
+#[test]
+fn rv32_amo_wrong_rd_value_negative_test() {
+    run_rv32_amo_swap_negative_test(
+        |_, adapter| adapter.returns_prev_data = F::ZERO,
+        VerificationError::ChallengePhaseError,
+    );
+}
@@ -0,0 +1,349 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::utils::not;
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::Rv32BitManipUnaryOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct BitManipUnaryCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    // Little-endian bit decomposition of each limb of b
+    pub b_bits: [[T; LIMB_BITS]; NUM_LIMBS],
+
+    pub opcode_clz_flag: T,
+    pub opcode_ctz_flag: T,
+    pub opcode_cpop_flag: T,
+    pub opcode_rev8_flag: T,
+    pub opcode_sext_b_flag: T,
+    pub opcode_sext_h_flag: T,
+    pub opcode_zext_h_flag: T,
+
+    // For CLZ (resp. CTZ), 1 at the most (resp. least) significant set bit of b. All zero if
+    // b = 0 or for any other opcode.
+    pub bit_marker: [[T; LIMB_BITS]; NUM_LIMBS],
+}
+
+/// Constrains the single-operand Zbb instructions by decomposing `b` into bits. Every output
+/// is a linear combination of the bits and limbs of `b`, so no lookups are needed.
+///
+/// The second operand is always the immediate zero and is not stored in the trace.
+#[derive(Copy, Clone, Debug)]
+pub struct BitManipUnaryCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for BitManipUnaryCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        BitManipUnaryCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for BitManipUnaryCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for BitManipUnaryCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &BitManipUnaryCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_clz_flag,
+            cols.opcode_ctz_flag,
+            cols.opcode_cpop_flag,
+            cols.opcode_rev8_flag,
+            cols.opcode_sext_b_flag,
+            cols.opcode_sext_h_flag,
+            cols.opcode_zext_h_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let num_bits = NUM_LIMBS * LIMB_BITS;
+        let bits: Vec<AB::Var> = cols.b_bits.iter().flatten().copied().collect();
+        let markers: Vec<AB::Var> = cols.bit_marker.iter().flatten().copied().collect();
+
+        // Constrain the bit decomposition of b.
+        for (limb, limb_bits) in b.iter().zip(cols.b_bits.iter()) {
+            let mut recomposed = AB::Expr::ZERO;
+            for (k, &bit) in limb_bits.iter().enumerate() {
+                builder.assert_bool(bit);
+                recomposed += bit * AB::Expr::from_canonical_u32(1 << k);
+            }
+            builder.assert_eq(*limb, recomposed);
+        }
+
+        // Markers may only be set at a set bit, and only for CLZ and CTZ.
+        let mut marker_sum = AB::Expr::ZERO;
+        for (&marker, &bit) in markers.iter().zip(bits.iter()) {
+            builder.assert_bool(marker);
+            builder.when(marker).assert_one(bit);
+            marker_sum += marker.into();
+        }
+        builder.assert_bool(marker_sum.clone());
+        builder
+            .when(not::<AB::Expr>(cols.opcode_clz_flag + cols.opcode_ctz_flag))
+            .assert_zero(marker_sum.clone());
+
+        // For CLZ every bit strictly above the marker must be zero, and for CTZ every bit
+        // strictly below it. If there is no marker, b must be zero.
+        let mut suffix_sum = AB::Expr::ZERO;
+        for (&marker, &bit) in markers.iter().zip(bits.iter()).rev() {
+            suffix_sum += marker.into();
+            builder
+                .when(cols.opcode_clz_flag)
+                .assert_zero(not::<AB::Expr>(suffix_sum.clone()) * bit);
+        }
+        let mut prefix_sum = AB::Expr::ZERO;
+        for (&marker, &bit) in markers.iter().zip(bits.iter()) {
+            prefix_sum += marker.into();
+            builder
+                .when(cols.opcode_ctz_flag)
+                .assert_zero(not::<AB::Expr>(prefix_sum.clone()) * bit);
+        }
+
+        let no_marker = AB::Expr::from_canonical_usize(num_bits) * not::<AB::Expr>(marker_sum);
+        let clz = markers
+            .iter()
+            .enumerate()
+            .fold(no_marker.clone(), |acc, (p, &marker)| {
+                acc + marker * AB::Expr::from_canonical_usize(num_bits - 1 - p)
+            });
+        let ctz = markers
+            .iter()
+            .enumerate()
+            .fold(no_marker, |acc, (p, &marker)| {
+                acc + marker * AB::Expr::from_canonical_usize(p)
+            });
+        let cpop = bits
+            .iter()
+            .fold(AB::Expr::ZERO, |acc, &bit| acc + bit.into());
+
+        // Constrain the output limb by limb.
+        let half = NUM_LIMBS / 2;
+        let limb_mask = AB::Expr::from_canonical_u32((1 << LIMB_BITS) - 1);
+        let byte_sign = cols.b_bits[0][LIMB_BITS - 1];
+        let half_sign = cols.b_bits[half - 1][LIMB_BITS - 1];
+        for j in 0..NUM_LIMBS {
+            let mut expected = cols.opcode_rev8_flag * b[NUM_LIMBS - 1 - j];
+            if j == 0 {
+                expected += cols.opcode_clz_flag * clz.clone()
+                    + cols.opcode_ctz_flag * ctz.clone()
+                    + cols.opcode_cpop_flag * cpop.clone()
+                    + cols.opcode_sext_b_flag * b[0];
+            } else {
+                expected += cols.opcode_sext_b_flag * byte_sign * limb_mask.clone();
+            }
+            if j < half {
+                expected += (cols.opcode_sext_h_flag + cols.opcode_zext_h_flag) * b[j];
+            } else {
+                expected += cols.opcode_sext_h_flag * half_sign * limb_mask.clone();
+            }
+            builder.assert_eq(a[j], expected);
+        }
+
+        let expected_opcode = flags
+            .iter()
+            .zip(Rv32BitManipUnaryOpcode::iter())
+            .fold(AB::Expr::ZERO, |acc, (flag, opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(opcode as u8)
+            })
+            + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), array::from_fn(|_| AB::Expr::ZERO)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitManipUnaryCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: Rv32BitManipUnaryOpcode,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    /// Flattened bit index of the marker, if any
+    pub marker_idx: Option<usize>,
+}
+
+#[derive(Debug)]
+pub struct BitManipUnaryCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: BitManipUnaryCoreAir<NUM_LIMBS, LIMB_BITS>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> BitManipUnaryCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(offset: usize) -> Self {
+        assert_eq!(NUM_LIMBS % 2, 0, "Number of limbs must be divisible by 2");
+        Self {
+            air: BitManipUnaryCoreAir { offset },
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for BitManipUnaryCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = BitManipUnaryCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = BitManipUnaryCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode =
+            Rv32BitManipUnaryOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let (a, marker_idx) = run_bitmanip_unary::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b);
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            marker_idx,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            Rv32BitManipUnaryOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut BitManipUnaryCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.b_bits = array::from_fn(|i| {
+            let limb = record.b[i].as_canonical_u32();
+            array::from_fn(|k| F::from_canonical_u32((limb >> k) & 1))
+        });
+        row_slice.bit_marker = array::from_fn(|i| {
+            array::from_fn(|k| F::from_bool(record.marker_idx == Some(i * LIMB_BITS + k)))
+        });
+        row_slice.opcode_clz_flag = F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::CLZ);
+        row_slice.opcode_ctz_flag = F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::CTZ);
+        row_slice.opcode_cpop_flag = F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::CPOP);
+        row_slice.opcode_rev8_flag = F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::REV8);
+        row_slice.opcode_sext_b_flag =
+            F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::SEXT_B);
+        row_slice.opcode_sext_h_flag =
+            F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::SEXT_H);
+        row_slice.opcode_zext_h_flag =
+            F::from_bool(record.opcode == Rv32BitManipUnaryOpcode::ZEXT_H);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, marker_idx), where marker_idx is the flattened index of the most (resp.
+// least) significant set bit for CLZ (resp. CTZ)
+pub(super) fn run_bitmanip_unary<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: Rv32BitManipUnaryOpcode,
+    x: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], Option<usize>) {
+    let num_bits = NUM_LIMBS * LIMB_BITS;
+    let limb_mask = (1 << LIMB_BITS) - 1;
+    let half = NUM_LIMBS / 2;
+    let bit = |p: usize| (x[p / LIMB_BITS] >> (p % LIMB_BITS)) & 1 == 1;
+    let sign_fill = |limb: u32| {
+        if limb >> (LIMB_BITS - 1) == 1 {
+            limb_mask
+        } else {
+            0
+        }
+    };
+
+    let mut result = [0u32; NUM_LIMBS];
+    let mut marker_idx = None;
+    match opcode {
+        Rv32BitManipUnaryOpcode::CLZ => {
+            marker_idx = (0..num_bits).rev().find(|&p| bit(p));
+            result[0] = marker_idx.map_or(num_bits, |p| num_bits - 1 - p) as u32;
+        }
+        Rv32BitManipUnaryOpcode::CTZ => {
+            marker_idx = (0..num_bits).find(|&p| bit(p));
+            result[0] = marker_idx.unwrap_or(num_bits) as u32;
+        }
+        Rv32BitManipUnaryOpcode::CPOP => {
+            result[0] = x.iter().map(|limb| limb.count_ones()).sum();
+        }
+        Rv32BitManipUnaryOpcode::REV8 => {
+            result = array::from_fn(|j| x[NUM_LIMBS - 1 - j]);
+        }
+        Rv32BitManipUnaryOpcode::SEXT_B => {
+            result = array::from_fn(|j| if j == 0 { x[0] } else { sign_fill(x[0]) });
+        }
+        Rv32BitManipUnaryOpcode::SEXT_H => {
+            result = array::from_fn(|j| {
+                if j < half {
+                    x[j]
+                } else {
+                    sign_fill(x[half - 1])
+                }
+            });
+        }
+        Rv32BitManipUnaryOpcode::ZEXT_H => {
+            result = array::from_fn(|j| if j < half { x[j] } else { 0 });
+        }
+    }
+    (result, marker_idx)
+}
@@ -0,0 +1,284 @@
+use std::{array, borrow::BorrowMut};
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper,
+    },
+    utils::generate_long_number,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::Rv32BitManipUnaryOpcode;
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_bitmanip_unary, BitManipUnaryCoreChip, Rv32BitManipUnaryChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    bitmanip_unary::BitManipUnaryCoreCols,
+    test_utils::rv32_rand_write_register_or_imm,
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_bitmanip_unary_rand_test(opcode: Rv32BitManipUnaryOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32BitManipUnaryChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        BitManipUnaryCoreChip::new(0),
+        tester.memory_controller(),
+    );
+
+    // Include special cases where b has no set bits, or only the lowest or highest bit set
+    let inputs = (0..num_ops)
+        .map(|_| generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng))
+        .chain([[0, 0, 0, 0], [1, 0, 0, 0], [0, 0, 0, 128]])
+        .collect::<Vec<_>>();
+
+    for b in inputs {
+        let (instruction, rd) = rv32_rand_write_register_or_imm(
+            &mut tester,
+            b,
+            [0; RV32_REGISTER_NUM_LIMBS],
+            Some(0),
+            opcode as usize,
+            &mut rng,
+        );
+        tester.execute(&mut chip, instruction);
+
+        let (a, _) = run_bitmanip_unary::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    let tester = tester.build().load(chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_clz_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::CLZ, 100);
+}
+
+#[test]
+fn rv32_ctz_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::CTZ, 100);
+}
+
+#[test]
+fn rv32_cpop_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::CPOP, 100);
+}
+
+#[test]
+fn rv32_rev8_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::REV8, 100);
+}
+
+#[test]
+fn rv32_sext_b_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::SEXT_B, 100);
+}
+
+#[test]
+fn rv32_sext_h_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::SEXT_H, 100);
+}
+
+#[test]
+fn rv32_zext_h_rand_test() {
+    run_rv32_bitmanip_unary_rand_test(Rv32BitManipUnaryOpcode::ZEXT_H, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the write part of the trace and check that the core chip throws the expected error.
+// A dummy adapter is used so memory interactions don't indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32BitManipUnaryTestChip<F> = VmChipWrapper<
+    F,
+    TestAdapterChip<F>,
+    BitManipUnaryCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+
+fn run_rv32_bitmanip_unary_negative_test(
+    opcode: Rv32BitManipUnaryOpcode,
+    a: [u32; RV32_REGISTER_NUM_LIMBS],
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    marker_idx: Option<Option<usize>>,
+) {
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32BitManipUnaryTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[
+                b.map(F::from_canonical_u32),
+                [F::ZERO; RV32_REGISTER_NUM_LIMBS],
+            ]
+            .concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        BitManipUnaryCoreChip::new(0),
+        tester.memory_controller(),
+    );
+
+    tester.execute(
+        &mut chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [0, 0, 0, 1, 0]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut BitManipUnaryCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a = a.map(F::from_canonical_u32);
+        if let Some(marker_idx) = marker_idx {
+            cols.bit_marker = array::from_fn(|i| {
+                array::from_fn(|k| F::from_bool(marker_idx == Some(i * RV32_CELL_BITS + k)))
+            });
+        }
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::OodEvaluationMismatch);
+}
+
+#[test]
+fn rv32_bitmanip_unary_wrong_negative_test() {
+    let b = [0x78, 0x56, 0x34, 0x12];
+    run_rv32_bitmanip_unary_negative_test(Rv32BitManipUnaryOpcode::CLZ, [4, 0, 0, 0], b, None);
+    run_rv32_bitmanip_unary_negative_test(Rv32BitManipUnaryOpcode::CTZ, [4, 0, 0, 0], b, None);
+    run_rv32_bitmanip_unary_negative_test(Rv32BitManipUnaryOpcode::CPOP, [12, 0, 0, 0], b, None);
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::REV8,
+        [0x78, 0x56, 0x34, 0x12],
+        b,
+        None,
+    );
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::SEXT_B,
+        [0x78, 0xff, 0xff, 0xff],
+        b,
+        None,
+    );
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::ZEXT_H,
+        [0x78, 0x56, 0xff, 0xff],
+        b,
+        None,
+    );
+}
+
+#[test]
+fn rv32_sext_h_wrong_sign_negative_test() {
+    let b = [0x00, 0x80, 0x00, 0x00];
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::SEXT_H,
+        [0x00, 0x80, 0x00, 0x00],
+        b,
+        None,
+    );
+}
+
+#[test]
+fn rv32_clz_wrong_marker_negative_test() {
+    // Marking a lower set bit instead of the most significant one
+    let b = [1, 0, 0, 1];
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::CLZ,
+        [31, 0, 0, 0],
+        b,
+        Some(Some(0)),
+    );
+}
+
+#[test]
+fn rv32_ctz_missing_marker_negative_test() {
+    // Claiming b = 0 when it is not
+    let b = [0, 0, 0, 128];
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::CTZ,
+        [32, 0, 0, 0],
+        b,
+        Some(None),
+    );
+}
+
+#[test]
+fn rv32_ctz_unset_marker_negative_test() {
+    // Marking a bit that is not set
+    let b = [0, 0, 0, 128];
+    run_rv32_bitmanip_unary_negative_test(
+        Rv32BitManipUnaryOpcode::CTZ,
+        [3, 0, 0, 0],
+        b,
+        Some(Some(3)),
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_bitmanip_unary_sanity_test() {
+    let mut rng = create_seeded_rng();
+    let inputs =
+        (0..100)
+            .map(|_| rng.gen::<u32>())
+            .chain([0, 1, 0x80, 0x8000, 0x8000_0000, u32::MAX]);
+    for x in inputs {
+        let x_limbs = x.to_le_bytes().map(u32::from);
+        for (opcode, expected) in [
+            (Rv32BitManipUnaryOpcode::CLZ, x.leading_zeros()),
+            (Rv32BitManipUnaryOpcode::CTZ, x.trailing_zeros()),
+            (Rv32BitManipUnaryOpcode::CPOP, x.count_ones()),
+            (Rv32BitManipUnaryOpcode::REV8, x.swap_bytes()),
+            (Rv32BitManipUnaryOpcode::SEXT_B, x as i8 as i32 as u32),
+            (Rv32BitManipUnaryOpcode::SEXT_H, x as i16 as i32 as u32),
+            (Rv32BitManipUnaryOpcode::ZEXT_H, x & 0xffff),
+        ] {
+            let (result, _) =
+                run_bitmanip_unary::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &x_limbs);
+            assert_eq!(expected.to_le_bytes().map(u32::from), result);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::BitwiseNotOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct BitwiseNotCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_andn_flag: T,
+    pub opcode_orn_flag: T,
+    pub opcode_xnor_flag: T,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct BitwiseNotCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for BitwiseNotCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        BitwiseNotCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for BitwiseNotCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for BitwiseNotCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &BitwiseNotCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_andn_flag,
+            cols.opcode_orn_flag,
+            cols.opcode_xnor_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // All three operations are the base bitwise operations applied to b and !c, where
+        // !c[i] = 2^LIMB_BITS - 1 - c[i]. Interaction with BitwiseOperationLookup constrains
+        // x ^ y for x = b[i] and y = !c[i], from which a[i] is uniquely determined:
+        //   - XNOR: a[i] = x ^ y
+        //   - ORN:  a[i] = x | y, so x ^ y = 2 * a[i] - x - y
+        //   - ANDN: a[i] = x & y, so x ^ y = x + y - 2 * a[i]
+        let mask = AB::Expr::from_canonical_u32((1 << LIMB_BITS) - 1);
+        for i in 0..NUM_LIMBS {
+            let x: AB::Expr = b[i].into();
+            let y = mask.clone() - c[i];
+            let x_xor_y = cols.opcode_xnor_flag * a[i]
+                + cols.opcode_orn_flag
+                    * ((AB::Expr::from_canonical_u32(2) * a[i]) - x.clone() - y.clone())
+                + cols.opcode_andn_flag
+                    * (x.clone() + y.clone() - (AB::Expr::from_canonical_u32(2) * a[i]));
+            self.bus
+                .send_xor(x, y, x_xor_y)
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags.iter().zip(BitwiseNotOpcode::iter()).fold(
+            AB::Expr::ZERO,
+            |acc, (flag, local_opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(local_opcode as u8)
+            },
+        ) + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitwiseNotCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: BitwiseNotOpcode,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct BitwiseNotCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: BitwiseNotCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> BitwiseNotCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: BitwiseNotCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for BitwiseNotCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = BitwiseNotCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = BitwiseNotCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = BitwiseNotOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let a = run_bitwise_not::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b, &c);
+
+        let output = AdapterRuntimeContext {
+            to_pc: None,
+            writes: [a.map(F::from_canonical_u32)].into(),
+        };
+
+        let mask = (1 << LIMB_BITS) - 1;
+        for (b_val, c_val) in b.iter().zip(c.iter()) {
+            self.bitwise_lookup_chip.request_xor(*b_val, mask - *c_val);
+        }
+
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!(
+            "{:?}",
+            BitwiseNotOpcode::from_usize(opcode - self.air.offset)
+        )
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut BitwiseNotCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.opcode_andn_flag = F::from_bool(record.opcode == BitwiseNotOpcode::ANDN);
+        row_slice.opcode_orn_flag = F::from_bool(record.opcode == BitwiseNotOpcode::ORN);
+        row_slice.opcode_xnor_flag = F::from_bool(record.opcode == BitwiseNotOpcode::XNOR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+pub(super) fn run_bitwise_not<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: BitwiseNotOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> [u32; NUM_LIMBS] {
+    let mask = (1 << LIMB_BITS) - 1;
+    array::from_fn(|i| {
+        let not_y = mask - y[i];
+        match opcode {
+            BitwiseNotOpcode::ANDN => x[i] & not_y,
+            BitwiseNotOpcode::ORN => x[i] | not_y,
+            BitwiseNotOpcode::XNOR => x[i] ^ not_y,
+        }
+    })
+}
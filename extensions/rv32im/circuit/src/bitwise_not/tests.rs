@@ -0,0 +1,219 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::BitwiseNotOpcode;
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_bitwise_not, BitwiseNotCoreChip, Rv32BitwiseNotChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    bitwise_not::BitwiseNotCoreCols,
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_bitwise_not_rand_test(opcode: BitwiseNotOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32BitwiseNotChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        BitwiseNotCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let a = run_bitwise_not::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c)
+            .map(F::from_canonical_u32);
+        assert_eq!(a, tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd))
+    }
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_andn_rand_test() {
+    run_rv32_bitwise_not_rand_test(BitwiseNotOpcode::ANDN, 100);
+}
+
+#[test]
+fn rv32_orn_rand_test() {
+    run_rv32_bitwise_not_rand_test(BitwiseNotOpcode::ORN, 100);
+}
+
+#[test]
+fn rv32_xnor_rand_test() {
+    run_rv32_bitwise_not_rand_test(BitwiseNotOpcode::XNOR, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the write part of the trace and check that the core chip throws the expected error.
+// A dummy adapter is used so memory interactions don't indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32BitwiseNotTestChip<F> = VmChipWrapper<
+    F,
+    TestAdapterChip<F>,
+    BitwiseNotCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>,
+>;
+
+fn run_rv32_bitwise_not_negative_test(
+    opcode: BitwiseNotOpcode,
+    a: [u32; RV32_REGISTER_NUM_LIMBS],
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    c: [u32; RV32_REGISTER_NUM_LIMBS],
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32BitwiseNotTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[b.map(F::from_canonical_u32), c.map(F::from_canonical_u32)].concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        BitwiseNotCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    tester.execute(
+        &mut chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [0, 0, 0, 1, 1]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut BitwiseNotCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a = a.map(F::from_canonical_u32);
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(VerificationError::ChallengePhaseError);
+}
+
+#[test]
+fn rv32_andn_wrong_negative_test() {
+    run_rv32_bitwise_not_negative_test(
+        BitwiseNotOpcode::ANDN,
+        [255, 0, 0, 0],
+        [255, 0, 0, 0],
+        [1, 0, 0, 0],
+    );
+}
+
+#[test]
+fn rv32_orn_wrong_negative_test() {
+    run_rv32_bitwise_not_negative_test(
+        BitwiseNotOpcode::ORN,
+        [0, 255, 255, 255],
+        [0, 0, 0, 0],
+        [0, 0, 0, 0],
+    );
+}
+
+#[test]
+fn rv32_xnor_wrong_negative_test() {
+    run_rv32_bitwise_not_negative_test(
+        BitwiseNotOpcode::XNOR,
+        [0, 0, 0, 0],
+        [1, 2, 3, 4],
+        [1, 2, 3, 4],
+    );
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_bitwise_not_sanity_test() {
+    let mut rng = create_seeded_rng();
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        let x_limbs = x.to_le_bytes().map(u32::from);
+        let y_limbs = y.to_le_bytes().map(u32::from);
+        for (opcode, expected) in [
+            (BitwiseNotOpcode::ANDN, x & !y),
+            (BitwiseNotOpcode::ORN, x | !y),
+            (BitwiseNotOpcode::XNOR, !(x ^ y)),
+        ] {
+            let result = run_bitwise_not::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(
+                opcode, &x_limbs, &y_limbs,
+            );
+            assert_eq!(expected.to_le_bytes().map(u32::from), result);
+        }
+    }
+}
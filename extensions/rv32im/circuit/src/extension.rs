@@ -16,9 +16,11 @@ use openvm_circuit_primitives::{
 use openvm_circuit_primitives_derive::{Chip, ChipUsageGetter};
 use openvm_instructions::{program::DEFAULT_PC_STEP, PhantomDiscriminant, UsizeOpcode, VmOpcode};
 use openvm_rv32im_transpiler::{
-    BaseAluOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode, LessThanOpcode,
-    MulHOpcode, MulOpcode, Rv32AuipcOpcode, Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode,
-    Rv32LoadStoreOpcode, Rv32Phantom, ShiftOpcode,
+    BaseAluOpcode, BitwiseNotOpcode, BranchEqualOpcode, BranchLessThanOpcode, DivRemOpcode,
+    LessThanOpcode, MinMaxOpcode, MulHOpcode, MulOpcode, RotateOpcode, Rv32AmoAluOpcode,
+    Rv32AmoMinMaxOpcode, Rv32AmoSwapOpcode, Rv32AuipcOpcode, Rv32BitManipUnaryOpcode,
+    Rv32HintStoreOpcode, Rv32JalLuiOpcode, Rv32JalrOpcode, Rv32LoadStoreOpcode, Rv32Phantom,
+    ShAddOpcode, ShiftOpcode,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use serde::{Deserialize, Serialize};
@@ -50,6 +52,38 @@ pub struct Rv32ImConfig {
     pub io: Rv32Io,
 }
 
+/// Config for a VM with base extension, IO extension, multiplication extension, and
+/// bit-manipulation extension
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Rv32ImZbbConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub mul: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub zbb: Rv32Zbb,
+}
+
+/// Config for a VM with base extension, IO extension, multiplication extension, and atomic
+/// extension
+#[derive(Clone, Debug, VmConfig, derive_new::new, Serialize, Deserialize)]
+pub struct Rv32ImAConfig {
+    #[system]
+    pub system: SystemConfig,
+    #[extension]
+    pub base: Rv32I,
+    #[extension]
+    pub mul: Rv32M,
+    #[extension]
+    pub io: Rv32Io,
+    #[extension]
+    pub rv32a: Rv32A,
+}
+
 impl Default for Rv32IConfig {
     fn default() -> Self {
         let system = SystemConfig::default().with_continuations();
@@ -73,6 +107,32 @@ impl Default for Rv32ImConfig {
     }
 }
 
+impl Default for Rv32ImZbbConfig {
+    fn default() -> Self {
+        let inner = Rv32ImConfig::default();
+        Self {
+            system: inner.system,
+            base: inner.base,
+            mul: inner.mul,
+            io: inner.io,
+            zbb: Default::default(),
+        }
+    }
+}
+
+impl Default for Rv32ImAConfig {
+    fn default() -> Self {
+        let inner = Rv32ImConfig::default();
+        Self {
+            system: inner.system,
+            base: inner.base,
+            mul: inner.mul,
+            io: inner.io,
+            rv32a: Default::default(),
+        }
+    }
+}
+
 impl Rv32IConfig {
     pub fn with_public_values(public_values: usize) -> Self {
         let system = SystemConfig::default()
@@ -145,6 +205,14 @@ impl Default for Rv32M {
     }
 }
 
+/// RISC-V Bit-Manipulation Extension (Zbb and Zba subsets)
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32Zbb;
+
+/// RISC-V 32-bit Atomic Extension (RV32A) Extension, word-sized operations only
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Rv32A;
+
 fn default_range_tuple_checker_sizes() -> [u32; 2] {
     [1 << 8, 8 * (1 << 8)]
 }
@@ -175,6 +243,24 @@ pub enum Rv32MExecutor<F: PrimeField32> {
     DivRem(Rv32DivRemChip<F>),
 }
 
+/// RISC-V Bit-Manipulation Extension (Zbb and Zba subsets) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32ZbbExecutor<F: PrimeField32> {
+    BitwiseNot(Rv32BitwiseNotChip<F>),
+    MinMax(Rv32MinMaxChip<F>),
+    Rotate(Rv32RotateChip<F>),
+    ShAdd(Rv32ShAddChip<F>),
+    BitManipUnary(Rv32BitManipUnaryChip<F>),
+}
+
+/// RISC-V 32-bit Atomic Extension (RV32A) Instruction Executors
+#[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
+pub enum Rv32AExecutor<F: PrimeField32> {
+    AmoAlu(Rv32AmoAluChip<F>),
+    AmoMinMax(Rv32AmoMinMaxChip<F>),
+    AmoSwap(Rv32AmoSwapChip<F>),
+}
+
 /// RISC-V 32-bit Io Instruction Executors
 #[derive(ChipUsageGetter, Chip, InstructionExecutor, From, AnyEnum)]
 pub enum Rv32IoExecutor<F: PrimeField32> {
@@ -197,6 +283,20 @@ pub enum Rv32MPeriphery<F: PrimeField32> {
     Phantom(PhantomChip<F>),
 }
 
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32ZbbPeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
+#[derive(From, ChipUsageGetter, Chip, AnyEnum)]
+pub enum Rv32APeriphery<F: PrimeField32> {
+    BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
+    // We put this only to get the <F> generic to work
+    Phantom(PhantomChip<F>),
+}
+
 #[derive(From, ChipUsageGetter, Chip, AnyEnum)]
 pub enum Rv32IoPeriphery<F: PrimeField32> {
     BitwiseOperationLookup(Arc<BitwiseOperationLookupChip<8>>),
@@ -461,6 +561,170 @@ impl<F: PrimeField32> VmExtension<F> for Rv32M {
     }
 }
 
+impl<F: PrimeField32> VmExtension<F> for Rv32Zbb {
+    type Executor = Rv32ZbbExecutor<F>;
+    type Periphery = Rv32ZbbPeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Rv32ZbbExecutor<F>, Rv32ZbbPeriphery<F>>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let bitwise_not_chip = Rv32BitwiseNotChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            BitwiseNotCoreChip::new(bitwise_lu_chip.clone(), BitwiseNotOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            bitwise_not_chip,
+            BitwiseNotOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let min_max_chip = Rv32MinMaxChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            MinMaxCoreChip::new(bitwise_lu_chip.clone(), MinMaxOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            min_max_chip,
+            MinMaxOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let rotate_chip = Rv32RotateChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            RotateCoreChip::new(
+                bitwise_lu_chip.clone(),
+                range_checker.clone(),
+                RotateOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            rotate_chip,
+            RotateOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let sh_add_chip = Rv32ShAddChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            ShAddCoreChip::new(bitwise_lu_chip.clone(), ShAddOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            sh_add_chip,
+            ShAddOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let bitmanip_unary_chip = Rv32BitManipUnaryChip::new(
+            Rv32BaseAluAdapterChip::new(execution_bus, program_bus, memory_controller.clone()),
+            BitManipUnaryCoreChip::new(Rv32BitManipUnaryOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            bitmanip_unary_chip,
+            Rv32BitManipUnaryOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
+impl<F: PrimeField32> VmExtension<F> for Rv32A {
+    type Executor = Rv32AExecutor<F>;
+    type Periphery = Rv32APeriphery<F>;
+
+    fn build(
+        &self,
+        builder: &mut VmInventoryBuilder<F>,
+    ) -> Result<VmInventory<Rv32AExecutor<F>, Rv32APeriphery<F>>, VmInventoryError> {
+        let mut inventory = VmInventory::new();
+        let SystemPort {
+            execution_bus,
+            program_bus,
+            memory_controller,
+        } = builder.system_port();
+        let range_checker = builder.system_base().range_checker_chip.clone();
+        let bitwise_lu_chip = if let Some(chip) = builder
+            .find_chip::<Arc<BitwiseOperationLookupChip<8>>>()
+            .first()
+        {
+            Arc::clone(chip)
+        } else {
+            let bitwise_lu_bus = BitwiseOperationLookupBus::new(builder.new_bus_idx());
+            let chip = Arc::new(BitwiseOperationLookupChip::new(bitwise_lu_bus));
+            inventory.add_periphery_chip(chip.clone());
+            chip
+        };
+
+        let amo_alu_chip = Rv32AmoAluChip::new(
+            Rv32AmoAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+            ),
+            BaseAluCoreChip::new(bitwise_lu_chip.clone(), Rv32AmoAluOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            amo_alu_chip,
+            Rv32AmoAluOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let amo_min_max_chip = Rv32AmoMinMaxChip::new(
+            Rv32AmoAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+            ),
+            MinMaxCoreChip::new(
+                bitwise_lu_chip.clone(),
+                Rv32AmoMinMaxOpcode::default_offset(),
+            ),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            amo_min_max_chip,
+            Rv32AmoMinMaxOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        let amo_swap_chip = Rv32AmoSwapChip::new(
+            Rv32AmoAdapterChip::new(
+                execution_bus,
+                program_bus,
+                memory_controller.clone(),
+                range_checker.clone(),
+            ),
+            AmoSwapCoreChip::new(Rv32AmoSwapOpcode::default_offset()),
+            memory_controller.clone(),
+        );
+        inventory.add_executor(
+            amo_swap_chip,
+            Rv32AmoSwapOpcode::iter().map(VmOpcode::with_default_offset),
+        )?;
+
+        Ok(inventory)
+    }
+}
+
 impl<F: PrimeField32> VmExtension<F> for Rv32Io {
     type Executor = Rv32IoExecutor<F>;
     type Periphery = Rv32IoPeriphery<F>;
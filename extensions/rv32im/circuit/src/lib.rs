@@ -1,7 +1,10 @@
 pub mod adapters;
 
+mod amo;
 mod auipc;
 mod base_alu;
+mod bitmanip_unary;
+mod bitwise_not;
 mod branch_eq;
 mod branch_lt;
 mod divrem;
@@ -11,12 +14,18 @@ mod jalr;
 mod less_than;
 mod load_sign_extend;
 mod loadstore;
+mod min_max;
 mod mul;
 mod mulh;
+mod rotate;
+mod sh_add;
 mod shift;
 
+pub use amo::*;
 pub use auipc::*;
 pub use base_alu::*;
+pub use bitmanip_unary::*;
+pub use bitwise_not::*;
 pub use branch_eq::*;
 pub use branch_lt::*;
 pub use divrem::*;
@@ -26,8 +35,11 @@ pub use jalr::*;
 pub use less_than::*;
 pub use load_sign_extend::*;
 pub use loadstore::*;
+pub use min_max::*;
 pub use mul::*;
 pub use mulh::*;
+pub use rotate::*;
+pub use sh_add::*;
 pub use shift::*;
 
 mod extension;
@@ -0,0 +1,252 @@
+use std::{borrow::BorrowMut, sync::Arc};
+
+use openvm_circuit::{
+    arch::{
+        testing::{TestAdapterChip, VmChipTestBuilder},
+        ExecutionBridge, VmAdapterChip, VmChipWrapper, BITWISE_OP_LOOKUP_BUS,
+    },
+    utils::generate_long_number,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_instructions::{instruction::Instruction, VmOpcode};
+use openvm_rv32im_transpiler::MinMaxOpcode;
+use openvm_stark_backend::{
+    p3_air::BaseAir,
+    p3_field::AbstractField,
+    p3_matrix::{
+        dense::{DenseMatrix, RowMajorMatrix},
+        Matrix,
+    },
+    utils::disable_debug_builder,
+    verifier::VerificationError,
+    ChipUsageGetter,
+};
+use openvm_stark_sdk::{p3_baby_bear::BabyBear, utils::create_seeded_rng};
+use rand::Rng;
+
+use super::{core::run_min_max, MinMaxCoreChip, Rv32MinMaxChip};
+use crate::{
+    adapters::{Rv32BaseAluAdapterChip, RV32_CELL_BITS, RV32_REGISTER_NUM_LIMBS},
+    min_max::MinMaxCoreCols,
+    test_utils::{generate_rv32_is_type_immediate, rv32_rand_write_register_or_imm},
+};
+
+type F = BabyBear;
+
+//////////////////////////////////////////////////////////////////////////////////////
+// POSITIVE TESTS
+//
+// Randomly generate computations and execute, ensuring that the generated trace
+// passes all constraints.
+//////////////////////////////////////////////////////////////////////////////////////
+
+fn run_rv32_min_max_rand_test(opcode: MinMaxOpcode, num_ops: usize) {
+    let mut rng = create_seeded_rng();
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester = VmChipTestBuilder::default();
+    let mut chip = Rv32MinMaxChip::<F>::new(
+        Rv32BaseAluAdapterChip::new(
+            tester.execution_bus(),
+            tester.program_bus(),
+            tester.memory_controller(),
+        ),
+        MinMaxCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    for _ in 0..num_ops {
+        let b = generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng);
+        let (c_imm, c) = if rng.gen_bool(0.5) {
+            (
+                None,
+                generate_long_number::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(&mut rng),
+            )
+        } else {
+            let (imm, c) = generate_rv32_is_type_immediate(&mut rng);
+            (Some(imm), c)
+        };
+
+        let (instruction, rd) =
+            rv32_rand_write_register_or_imm(&mut tester, b, c, c_imm, opcode as usize, &mut rng);
+        tester.execute(&mut chip, instruction);
+
+        let (a, _, _, _, _) =
+            run_min_max::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &b, &c);
+        assert_eq!(
+            a.map(F::from_canonical_u32),
+            tester.read::<RV32_REGISTER_NUM_LIMBS>(1, rd)
+        )
+    }
+
+    // Test special case where b = c
+    let b = [101, 128, 202, 255];
+    let (instruction, _) =
+        rv32_rand_write_register_or_imm(&mut tester, b, b, None, opcode as usize, &mut rng);
+    tester.execute(&mut chip, instruction);
+
+    let b = [36, 0, 0, 0];
+    let (instruction, _) =
+        rv32_rand_write_register_or_imm(&mut tester, b, b, Some(36), opcode as usize, &mut rng);
+    tester.execute(&mut chip, instruction);
+
+    let tester = tester.build().load(chip).load(bitwise_chip).finalize();
+    tester.simple_test().expect("Verification failed");
+}
+
+#[test]
+fn rv32_min_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MIN, 100);
+}
+
+#[test]
+fn rv32_minu_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MINU, 100);
+}
+
+#[test]
+fn rv32_max_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MAX, 100);
+}
+
+#[test]
+fn rv32_maxu_rand_test() {
+    run_rv32_min_max_rand_test(MinMaxOpcode::MAXU, 100);
+}
+
+//////////////////////////////////////////////////////////////////////////////////////
+// NEGATIVE TESTS
+//
+// Given a fake trace of a single operation, setup a chip and run the test. We replace
+// the write part of the trace and check that the core chip throws the expected error.
+// A dummy adapter is used so memory interactions don't indirectly cause false passes.
+//////////////////////////////////////////////////////////////////////////////////////
+
+type Rv32MinMaxTestChip<F> =
+    VmChipWrapper<F, TestAdapterChip<F>, MinMaxCoreChip<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>>;
+
+fn run_rv32_min_max_negative_test(
+    opcode: MinMaxOpcode,
+    a: [u32; RV32_REGISTER_NUM_LIMBS],
+    b: [u32; RV32_REGISTER_NUM_LIMBS],
+    c: [u32; RV32_REGISTER_NUM_LIMBS],
+    cmp_result: Option<bool>,
+    interaction_error: bool,
+) {
+    let bitwise_bus = BitwiseOperationLookupBus::new(BITWISE_OP_LOOKUP_BUS);
+    let bitwise_chip = Arc::new(BitwiseOperationLookupChip::<RV32_CELL_BITS>::new(
+        bitwise_bus,
+    ));
+
+    let mut tester: VmChipTestBuilder<BabyBear> = VmChipTestBuilder::default();
+    let mut chip = Rv32MinMaxTestChip::<F>::new(
+        TestAdapterChip::new(
+            vec![[b.map(F::from_canonical_u32), c.map(F::from_canonical_u32)].concat()],
+            vec![None],
+            ExecutionBridge::new(tester.execution_bus(), tester.program_bus()),
+        ),
+        MinMaxCoreChip::new(bitwise_chip.clone(), 0),
+        tester.memory_controller(),
+    );
+
+    tester.execute(
+        &mut chip,
+        Instruction::from_usize(VmOpcode::from_usize(opcode as usize), [0, 0, 0, 1, 1]),
+    );
+
+    let trace_width = chip.trace_width();
+    let adapter_width = BaseAir::<F>::width(chip.adapter.air());
+
+    let modify_trace = |trace: &mut DenseMatrix<BabyBear>| {
+        let mut values = trace.row_slice(0).to_vec();
+        let cols: &mut MinMaxCoreCols<F, RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS> =
+            values.split_at_mut(adapter_width).1.borrow_mut();
+        cols.a = a.map(F::from_canonical_u32);
+        if let Some(cmp_result) = cmp_result {
+            // Keep diff_val consistent with the pranked comparison so that only the range
+            // check on diff_val can catch it
+            let diff_idx = cols.diff_marker.iter().position(|x| *x == F::ONE).unwrap();
+            let diff = if cmp_result {
+                F::from_canonical_u32(c[diff_idx]) - F::from_canonical_u32(b[diff_idx])
+            } else {
+                F::from_canonical_u32(b[diff_idx]) - F::from_canonical_u32(c[diff_idx])
+            };
+            cols.cmp_result = F::from_bool(cmp_result);
+            cols.diff_val = diff;
+        }
+        *trace = RowMajorMatrix::new(values, trace_width);
+    };
+
+    disable_debug_builder();
+    let tester = tester
+        .build()
+        .load_and_prank_trace(chip, modify_trace)
+        .load(bitwise_chip)
+        .finalize();
+    tester.simple_test_with_expected_error(if interaction_error {
+        VerificationError::ChallengePhaseError
+    } else {
+        VerificationError::OodEvaluationMismatch
+    });
+}
+
+#[test]
+fn rv32_min_max_wrong_negative_test() {
+    let a = [1, 0, 0, 0];
+    let b = [1, 0, 0, 0];
+    let c = [2, 0, 0, 0];
+    run_rv32_min_max_negative_test(MinMaxOpcode::MAX, a, b, c, None, false);
+    run_rv32_min_max_negative_test(MinMaxOpcode::MAXU, a, b, c, None, false);
+    let a = [2, 0, 0, 0];
+    run_rv32_min_max_negative_test(MinMaxOpcode::MIN, a, b, c, None, false);
+    run_rv32_min_max_negative_test(MinMaxOpcode::MINU, a, b, c, None, false);
+}
+
+#[test]
+fn rv32_min_signedness_negative_test() {
+    // -1 < 1 as signed integers, so MIN must select b
+    let a = [1, 0, 0, 0];
+    let b = [255, 255, 255, 255];
+    let c = [1, 0, 0, 0];
+    run_rv32_min_max_negative_test(MinMaxOpcode::MIN, a, b, c, None, false);
+}
+
+#[test]
+fn rv32_min_max_wrong_cmp_negative_test() {
+    let b = [1, 0, 0, 0];
+    let c = [2, 0, 0, 0];
+    run_rv32_min_max_negative_test(MinMaxOpcode::MINU, c, b, c, Some(false), true);
+    run_rv32_min_max_negative_test(MinMaxOpcode::MAXU, b, b, c, Some(false), true);
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/// SANITY TESTS
+///
+/// Ensure that solve functions produce the correct results.
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn run_min_max_sanity_test() {
+    let mut rng = create_seeded_rng();
+    for _ in 0..100 {
+        let x: u32 = rng.gen();
+        let y: u32 = rng.gen();
+        let x_limbs = x.to_le_bytes().map(u32::from);
+        let y_limbs = y.to_le_bytes().map(u32::from);
+        for (opcode, expected) in [
+            (MinMaxOpcode::MIN, (x as i32).min(y as i32) as u32),
+            (MinMaxOpcode::MINU, x.min(y)),
+            (MinMaxOpcode::MAX, (x as i32).max(y as i32) as u32),
+            (MinMaxOpcode::MAXU, x.max(y)),
+        ] {
+            let (result, _, _, _, _) =
+                run_min_max::<RV32_REGISTER_NUM_LIMBS, RV32_CELL_BITS>(opcode, &x_limbs, &y_limbs);
+            assert_eq!(expected.to_le_bytes().map(u32::from), result);
+        }
+    }
+}
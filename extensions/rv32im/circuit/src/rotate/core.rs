@@ -0,0 +1,345 @@
+use std::{
+    array,
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::{
+    bitwise_op_lookup::{BitwiseOperationLookupBus, BitwiseOperationLookupChip},
+    var_range::{VariableRangeCheckerBus, VariableRangeCheckerChip},
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::RotateOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow, Clone, Copy, Debug)]
+pub struct RotateCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_rol_flag: T,
+    pub opcode_ror_flag: T,
+
+    // bit_multiplier = 2^bit_shift
+    pub bit_multiplier_left: T,
+    pub bit_multiplier_right: T,
+
+    // Boolean columns that are 1 exactly at the index of the bit/limb shift amount
+    pub bit_shift_marker: [T; LIMB_BITS],
+    pub limb_shift_marker: [T; NUM_LIMBS],
+
+    // Part of each b[i] that gets bit rotated into the neighbouring limb
+    pub bit_shift_carry: [T; NUM_LIMBS],
+}
+
+/// Constrains rotations by splitting the rotation amount into a bit rotation and a limb
+/// rotation, in the same way [ShiftCoreAir](crate::ShiftCoreAir) does for shifts. The only
+/// difference is that carries out of the most (resp. least) significant limb wrap around
+/// instead of being discarded.
+#[derive(Copy, Clone, Debug)]
+pub struct RotateCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bitwise_lookup_bus: BitwiseOperationLookupBus,
+    pub range_bus: VariableRangeCheckerBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        RotateCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for RotateCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &RotateCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [cols.opcode_rol_flag, cols.opcode_ror_flag];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+
+        // Constrain that bit_shift, bit_multiplier are correct, i.e. that bit_multiplier =
+        // 1 << bit_shift. Because the sum of all bit_shift_marker[i] is constrained to be
+        // 1, bit_shift is guaranteed to be in range.
+        let mut bit_marker_sum = AB::Expr::ZERO;
+        let mut bit_shift = AB::Expr::ZERO;
+
+        for i in 0..LIMB_BITS {
+            builder.assert_bool(cols.bit_shift_marker[i]);
+            bit_marker_sum += cols.bit_shift_marker[i].into();
+            bit_shift += AB::Expr::from_canonical_usize(i) * cols.bit_shift_marker[i];
+
+            let mut when_bit_shift = builder.when(cols.bit_shift_marker[i]);
+            when_bit_shift.assert_eq(
+                cols.bit_multiplier_left,
+                AB::Expr::from_canonical_usize(1 << i) * cols.opcode_rol_flag,
+            );
+            when_bit_shift.assert_eq(
+                cols.bit_multiplier_right,
+                AB::Expr::from_canonical_usize(1 << i) * cols.opcode_ror_flag,
+            );
+        }
+        builder.when(is_valid.clone()).assert_one(bit_marker_sum);
+
+        // Check that a[j] = b rotated by c on both the bit and limb level. All limb indices
+        // are taken modulo NUM_LIMBS.
+        let mut limb_marker_sum = AB::Expr::ZERO;
+        let mut limb_shift = AB::Expr::ZERO;
+        for i in 0..NUM_LIMBS {
+            builder.assert_bool(cols.limb_shift_marker[i]);
+            limb_marker_sum += cols.limb_shift_marker[i].into();
+            limb_shift += AB::Expr::from_canonical_usize(i) * cols.limb_shift_marker[i];
+
+            let mut when_limb_shift = builder.when(cols.limb_shift_marker[i]);
+
+            for j in 0..NUM_LIMBS {
+                // ROL constraints
+                let src = (j + NUM_LIMBS - i) % NUM_LIMBS;
+                let prev = (src + NUM_LIMBS - 1) % NUM_LIMBS;
+                let expected_a_left = cols.bit_shift_carry[prev] * cols.opcode_rol_flag
+                    + b[src] * cols.bit_multiplier_left
+                    - AB::Expr::from_canonical_usize(1 << LIMB_BITS)
+                        * cols.bit_shift_carry[src]
+                        * cols.opcode_rol_flag;
+                when_limb_shift.assert_eq(a[j] * cols.opcode_rol_flag, expected_a_left);
+
+                // ROR constraints
+                let src = (j + i) % NUM_LIMBS;
+                let next = (src + 1) % NUM_LIMBS;
+                let expected_a_right = cols.bit_shift_carry[next]
+                    * cols.opcode_ror_flag
+                    * AB::F::from_canonical_usize(1 << LIMB_BITS)
+                    + cols.opcode_ror_flag * (b[src] - cols.bit_shift_carry[src]);
+                when_limb_shift.assert_eq(a[j] * cols.bit_multiplier_right, expected_a_right);
+            }
+        }
+        builder.when(is_valid.clone()).assert_one(limb_marker_sum);
+
+        // Check that bit_shift and limb_shift are correct.
+        let num_bits = AB::F::from_canonical_usize(NUM_LIMBS * LIMB_BITS);
+        self.range_bus
+            .range_check(
+                (c[0] - limb_shift * AB::F::from_canonical_usize(LIMB_BITS) - bit_shift.clone())
+                    * num_bits.inverse(),
+                LIMB_BITS - ((NUM_LIMBS * LIMB_BITS) as u32).ilog2() as usize,
+            )
+            .eval(builder, is_valid.clone());
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bitwise_lookup_bus
+                .send_range(a[i * 2], a[i * 2 + 1])
+                .eval(builder, is_valid.clone());
+        }
+
+        for carry in cols.bit_shift_carry {
+            self.range_bus
+                .send(carry, bit_shift.clone())
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags
+            .iter()
+            .zip(RotateOpcode::iter())
+            .fold(AB::Expr::ZERO, |acc, (flag, opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(opcode as u8)
+            })
+            + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RotateCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: RotateOpcode,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub bit_shift_carry: [T; NUM_LIMBS],
+    pub bit_shift: usize,
+    pub limb_shift: usize,
+}
+
+#[derive(Debug)]
+pub struct RotateCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: RotateCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+    pub range_checker_chip: Arc<VariableRangeCheckerChip>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> RotateCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        range_checker_chip: Arc<VariableRangeCheckerChip>,
+        offset: usize,
+    ) -> Self {
+        assert_eq!(NUM_LIMBS % 2, 0, "Number of limbs must be divisible by 2");
+        Self {
+            air: RotateCoreAir {
+                bitwise_lookup_bus: bitwise_lookup_chip.bus(),
+                range_bus: range_checker_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+            range_checker_chip,
+        }
+    }
+}
+
+impl<F: PrimeField32, I: VmAdapterInterface<F>, const NUM_LIMBS: usize, const LIMB_BITS: usize>
+    VmCoreChip<F, I> for RotateCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = RotateCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = RotateCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let rotate_opcode = RotateOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, limb_shift, bit_shift) = run_rotate::<NUM_LIMBS, LIMB_BITS>(rotate_opcode, &b, &c);
+
+        let bit_shift_carry = array::from_fn(|i| match rotate_opcode {
+            RotateOpcode::ROL => b[i] >> (LIMB_BITS - bit_shift),
+            RotateOpcode::ROR => b[i] % (1 << bit_shift),
+        });
+
+        let num_bits_log = (NUM_LIMBS * LIMB_BITS).ilog2();
+        self.range_checker_chip.add_count(
+            (((c[0] as usize) - bit_shift - limb_shift * LIMB_BITS) >> num_bits_log) as u32,
+            LIMB_BITS - num_bits_log as usize,
+        );
+
+        for i in 0..(NUM_LIMBS / 2) {
+            self.bitwise_lookup_chip
+                .request_range(a[i * 2], a[i * 2 + 1]);
+        }
+        for carry_val in bit_shift_carry {
+            self.range_checker_chip.add_count(carry_val, bit_shift);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = RotateCoreRecord {
+            opcode: rotate_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            bit_shift_carry: bit_shift_carry.map(F::from_canonical_u32),
+            bit_shift,
+            limb_shift,
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", RotateOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut RotateCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.bit_multiplier_left = match record.opcode {
+            RotateOpcode::ROL => F::from_canonical_usize(1 << record.bit_shift),
+            RotateOpcode::ROR => F::ZERO,
+        };
+        row_slice.bit_multiplier_right = match record.opcode {
+            RotateOpcode::ROL => F::ZERO,
+            RotateOpcode::ROR => F::from_canonical_usize(1 << record.bit_shift),
+        };
+        row_slice.bit_shift_marker = array::from_fn(|i| F::from_bool(i == record.bit_shift));
+        row_slice.limb_shift_marker = array::from_fn(|i| F::from_bool(i == record.limb_shift));
+        row_slice.bit_shift_carry = record.bit_shift_carry;
+        row_slice.opcode_rol_flag = F::from_bool(record.opcode == RotateOpcode::ROL);
+        row_slice.opcode_ror_flag = F::from_bool(record.opcode == RotateOpcode::ROR);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, limb_shift, bit_shift)
+pub(super) fn run_rotate<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: RotateOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], usize, usize) {
+    let shift = (y[0] as usize) % (NUM_LIMBS * LIMB_BITS);
+    let (limb_shift, bit_shift) = (shift / LIMB_BITS, shift % LIMB_BITS);
+    let mask = (1 << LIMB_BITS) - 1;
+
+    let result = array::from_fn(|j| match opcode {
+        RotateOpcode::ROL => {
+            let src = (j + NUM_LIMBS - limb_shift) % NUM_LIMBS;
+            let prev = (src + NUM_LIMBS - 1) % NUM_LIMBS;
+            ((x[src] << bit_shift) & mask) | (x[prev] >> (LIMB_BITS - bit_shift))
+        }
+        RotateOpcode::ROR => {
+            let src = (j + limb_shift) % NUM_LIMBS;
+            let next = (src + 1) % NUM_LIMBS;
+            (x[src] >> bit_shift) | ((x[next] << (LIMB_BITS - bit_shift)) & mask)
+        }
+    });
+    (result, limb_shift, bit_shift)
+}
@@ -0,0 +1,242 @@
+use std::{
+    borrow::{Borrow, BorrowMut},
+    sync::Arc,
+};
+
+use openvm_circuit::arch::{
+    AdapterAirContext, AdapterRuntimeContext, MinimalInstruction, Result, VmAdapterInterface,
+    VmCoreAir, VmCoreChip,
+};
+use openvm_circuit_primitives::bitwise_op_lookup::{
+    BitwiseOperationLookupBus, BitwiseOperationLookupChip,
+};
+use openvm_circuit_primitives_derive::AlignedBorrow;
+use openvm_instructions::{instruction::Instruction, UsizeOpcode};
+use openvm_rv32im_transpiler::ShAddOpcode;
+use openvm_stark_backend::{
+    interaction::InteractionBuilder,
+    p3_air::{AirBuilder, BaseAir},
+    p3_field::{AbstractField, Field, PrimeField32},
+    rap::BaseAirWithPublicValues,
+};
+use strum::IntoEnumIterator;
+
+#[repr(C)]
+#[derive(AlignedBorrow)]
+pub struct ShAddCoreCols<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+
+    pub opcode_sh1add_flag: T,
+    pub opcode_sh2add_flag: T,
+    pub opcode_sh3add_flag: T,
+
+    // carry[i] = (b[i] * 2^shift + c[i] + carry[i - 1] - a[i]) / 2^LIMB_BITS
+    pub carry: [T; NUM_LIMBS],
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ShAddCoreAir<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub bus: BitwiseOperationLookupBus,
+    offset: usize,
+}
+
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAir<F>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+    fn width(&self) -> usize {
+        ShAddCoreCols::<F, NUM_LIMBS, LIMB_BITS>::width()
+    }
+}
+impl<F: Field, const NUM_LIMBS: usize, const LIMB_BITS: usize> BaseAirWithPublicValues<F>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+{
+}
+
+impl<AB, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreAir<AB, I>
+    for ShAddCoreAir<NUM_LIMBS, LIMB_BITS>
+where
+    AB: InteractionBuilder,
+    I: VmAdapterInterface<AB::Expr>,
+    I::Reads: From<[[AB::Expr; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[AB::Expr; NUM_LIMBS]; 1]>,
+    I::ProcessedInstruction: From<MinimalInstruction<AB::Expr>>,
+{
+    fn eval(
+        &self,
+        builder: &mut AB,
+        local_core: &[AB::Var],
+        _from_pc: AB::Var,
+    ) -> AdapterAirContext<AB::Expr, I> {
+        let cols: &ShAddCoreCols<_, NUM_LIMBS, LIMB_BITS> = local_core.borrow();
+        let flags = [
+            cols.opcode_sh1add_flag,
+            cols.opcode_sh2add_flag,
+            cols.opcode_sh3add_flag,
+        ];
+
+        let is_valid = flags.iter().fold(AB::Expr::ZERO, |acc, &flag| {
+            builder.assert_bool(flag);
+            acc + flag.into()
+        });
+        builder.assert_bool(is_valid.clone());
+
+        let a = &cols.a;
+        let b = &cols.b;
+        let c = &cols.c;
+        let multiplier = flags
+            .iter()
+            .enumerate()
+            .fold(AB::Expr::ZERO, |acc, (i, &flag)| {
+                acc + AB::Expr::from_canonical_u32(2 << i) * flag
+            });
+
+        // Each limb satisfies b[i] * 2^shift + c[i] + carry[i - 1] = a[i] + 2^LIMB_BITS * carry[i].
+        // Both a[i] and carry[i] are range checked to LIMB_BITS bits below. Since the left hand
+        // side is less than 2^(LIMB_BITS + 4), there is no overflow and this uniquely determines
+        // a[i] and carry[i]. The final carry is discarded.
+        for i in 0..NUM_LIMBS {
+            let carry_in = if i > 0 {
+                cols.carry[i - 1].into()
+            } else {
+                AB::Expr::ZERO
+            };
+            builder.assert_eq(
+                b[i] * multiplier.clone() + c[i] + carry_in,
+                a[i] + AB::Expr::from_canonical_u32(1 << LIMB_BITS) * cols.carry[i],
+            );
+            self.bus
+                .send_range(a[i], cols.carry[i])
+                .eval(builder, is_valid.clone());
+        }
+
+        let expected_opcode = flags
+            .iter()
+            .zip(ShAddOpcode::iter())
+            .fold(AB::Expr::ZERO, |acc, (flag, opcode)| {
+                acc + (*flag).into() * AB::Expr::from_canonical_u8(opcode as u8)
+            })
+            + AB::Expr::from_canonical_usize(self.offset);
+
+        AdapterAirContext {
+            to_pc: None,
+            reads: [cols.b.map(Into::into), cols.c.map(Into::into)].into(),
+            writes: [cols.a.map(Into::into)].into(),
+            instruction: MinimalInstruction {
+                is_valid,
+                opcode: expected_opcode,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShAddCoreRecord<T, const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub opcode: ShAddOpcode,
+    pub a: [T; NUM_LIMBS],
+    pub b: [T; NUM_LIMBS],
+    pub c: [T; NUM_LIMBS],
+    pub carry: [T; NUM_LIMBS],
+}
+
+#[derive(Debug)]
+pub struct ShAddCoreChip<const NUM_LIMBS: usize, const LIMB_BITS: usize> {
+    pub air: ShAddCoreAir<NUM_LIMBS, LIMB_BITS>,
+    pub bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+}
+
+impl<const NUM_LIMBS: usize, const LIMB_BITS: usize> ShAddCoreChip<NUM_LIMBS, LIMB_BITS> {
+    pub fn new(
+        bitwise_lookup_chip: Arc<BitwiseOperationLookupChip<LIMB_BITS>>,
+        offset: usize,
+    ) -> Self {
+        Self {
+            air: ShAddCoreAir {
+                bus: bitwise_lookup_chip.bus(),
+                offset,
+            },
+            bitwise_lookup_chip,
+        }
+    }
+}
+
+impl<F, I, const NUM_LIMBS: usize, const LIMB_BITS: usize> VmCoreChip<F, I>
+    for ShAddCoreChip<NUM_LIMBS, LIMB_BITS>
+where
+    F: PrimeField32,
+    I: VmAdapterInterface<F>,
+    I::Reads: Into<[[F; NUM_LIMBS]; 2]>,
+    I::Writes: From<[[F; NUM_LIMBS]; 1]>,
+{
+    type Record = ShAddCoreRecord<F, NUM_LIMBS, LIMB_BITS>;
+    type Air = ShAddCoreAir<NUM_LIMBS, LIMB_BITS>;
+
+    #[allow(clippy::type_complexity)]
+    fn execute_instruction(
+        &self,
+        instruction: &Instruction<F>,
+        _from_pc: u32,
+        reads: I::Reads,
+    ) -> Result<(AdapterRuntimeContext<F, I>, Self::Record)> {
+        let Instruction { opcode, .. } = instruction;
+        let local_opcode = ShAddOpcode::from_usize(opcode.local_opcode_idx(self.air.offset));
+
+        let data: [[F; NUM_LIMBS]; 2] = reads.into();
+        let b = data[0].map(|x| x.as_canonical_u32());
+        let c = data[1].map(|y| y.as_canonical_u32());
+        let (a, carry) = run_sh_add::<NUM_LIMBS, LIMB_BITS>(local_opcode, &b, &c);
+
+        for (a_val, carry_val) in a.iter().zip(carry.iter()) {
+            self.bitwise_lookup_chip.request_range(*a_val, *carry_val);
+        }
+
+        let output = AdapterRuntimeContext::without_pc([a.map(F::from_canonical_u32)]);
+        let record = Self::Record {
+            opcode: local_opcode,
+            a: a.map(F::from_canonical_u32),
+            b: data[0],
+            c: data[1],
+            carry: carry.map(F::from_canonical_u32),
+        };
+
+        Ok((output, record))
+    }
+
+    fn get_opcode_name(&self, opcode: usize) -> String {
+        format!("{:?}", ShAddOpcode::from_usize(opcode - self.air.offset))
+    }
+
+    fn generate_trace_row(&self, row_slice: &mut [F], record: Self::Record) {
+        let row_slice: &mut ShAddCoreCols<_, NUM_LIMBS, LIMB_BITS> = row_slice.borrow_mut();
+        row_slice.a = record.a;
+        row_slice.b = record.b;
+        row_slice.c = record.c;
+        row_slice.carry = record.carry;
+        row_slice.opcode_sh1add_flag = F::from_bool(record.opcode == ShAddOpcode::SH1ADD);
+        row_slice.opcode_sh2add_flag = F::from_bool(record.opcode == ShAddOpcode::SH2ADD);
+        row_slice.opcode_sh3add_flag = F::from_bool(record.opcode == ShAddOpcode::SH3ADD);
+    }
+
+    fn air(&self) -> &Self::Air {
+        &self.air
+    }
+}
+
+// Returns (result, carry)
+pub(super) fn run_sh_add<const NUM_LIMBS: usize, const LIMB_BITS: usize>(
+    opcode: ShAddOpcode,
+    x: &[u32; NUM_LIMBS],
+    y: &[u32; NUM_LIMBS],
+) -> ([u32; NUM_LIMBS], [u32; NUM_LIMBS]) {
+    let shift = opcode as usize + 1;
+    let mut z = [0u32; NUM_LIMBS];
+    let mut carry = [0u32; NUM_LIMBS];
+    for i in 0..NUM_LIMBS {
+        let sum = (x[i] << shift) + y[i] + if i > 0 { carry[i - 1] } else { 0 };
+        z[i] = sum & ((1 << LIMB_BITS) - 1);
+        carry[i] = sum >> LIMB_BITS;
+    }
+    (z, carry)
+}
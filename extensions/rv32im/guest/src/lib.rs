@@ -12,6 +12,20 @@ pub const SYSTEM_OPCODE: u8 = 0x0b;
 pub const CSR_OPCODE: u8 = 0b1110011;
 pub const RV32_ALU_OPCODE: u8 = 0b0110011;
 pub const RV32M_FUNCT7: u8 = 0x01;
+pub const RV32_ALU_IMM_OPCODE: u8 = 0b0010011;
+
+/// funct7 shared by ANDN, ORN, XNOR (and SUB, SRA from the base ISA)
+pub const ZBB_NEGATE_FUNCT7: u8 = 0b0100000;
+pub const ZBB_MIN_MAX_FUNCT7: u8 = 0b0000101;
+/// funct7 shared by ROL, ROR, RORI and the CLZ/CTZ/CPOP/SEXT immediates
+pub const ZBB_ROTATE_FUNCT7: u8 = 0b0110000;
+pub const ZBB_ZEXT_H_FUNCT7: u8 = 0b0000100;
+pub const ZBA_SH_ADD_FUNCT7: u8 = 0b0010000;
+/// Full 12-bit immediate of REV8 on RV32
+pub const ZBB_REV8_IMM: u32 = 0x698;
+
+pub const RV32_AMO_OPCODE: u8 = 0b0101111;
+pub const AMO_W_FUNCT3: u8 = 0b010;
 
 pub const TERMINATE_FUNCT3: u8 = 0b000;
 pub const HINT_STORE_W_FUNCT3: u8 = 0b001;
@@ -19,6 +33,24 @@ pub const REVEAL_FUNCT3: u8 = 0b010;
 pub const PHANTOM_FUNCT3: u8 = 0b011;
 pub const CSRRW_FUNCT3: u8 = 0b001;
 
+/// funct5 (the top 5 bits of funct7) of RV32A instructions. The remaining two bits of funct7
+/// are the aq/rl ordering flags.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
+#[repr(u8)]
+pub enum AmoFunct5 {
+    AmoAdd = 0b00000,
+    AmoSwap = 0b00001,
+    Lr = 0b00010,
+    Sc = 0b00011,
+    AmoXor = 0b00100,
+    AmoOr = 0b01000,
+    AmoAnd = 0b01100,
+    AmoMin = 0b10000,
+    AmoMax = 0b10100,
+    AmoMinu = 0b11000,
+    AmoMaxu = 0b11100,
+}
+
 /// imm options for system phantom instructions
 #[derive(Debug, Copy, Clone, PartialEq, Eq, FromRepr)]
 #[repr(u16)]
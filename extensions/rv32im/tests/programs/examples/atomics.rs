@@ -0,0 +1,66 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::{
+    hint::black_box,
+    sync::atomic::{AtomicI32, AtomicU32, Ordering},
+};
+
+openvm::entry!(main);
+
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+static SIGNED: AtomicI32 = AtomicI32::new(0);
+
+pub fn main() {
+    let x: u32 = black_box(0x12345678);
+
+    // AMOSWAP.W, AMOADD.W
+    if COUNTER.swap(x, Ordering::SeqCst) != 0 {
+        openvm::process::panic();
+    }
+    if COUNTER.fetch_add(black_box(0x11111111), Ordering::SeqCst) != x {
+        openvm::process::panic();
+    }
+
+    // AMOXOR.W, AMOOR.W, AMOAND.W
+    if COUNTER.fetch_xor(black_box(0xffff0000), Ordering::SeqCst) != 0x23456789 {
+        openvm::process::panic();
+    }
+    if COUNTER.fetch_or(black_box(0x0000000f), Ordering::SeqCst) != 0xdcba6789 {
+        openvm::process::panic();
+    }
+    if COUNTER.fetch_and(black_box(0x0ffffff0), Ordering::SeqCst) != 0xdcba678f {
+        openvm::process::panic();
+    }
+
+    // AMOMINU.W, AMOMAXU.W
+    if COUNTER.fetch_min(black_box(0x80000000), Ordering::SeqCst) != 0x0cba6780 {
+        openvm::process::panic();
+    }
+    if COUNTER.fetch_max(black_box(0x80000000), Ordering::SeqCst) != 0x0cba6780 {
+        openvm::process::panic();
+    }
+    if COUNTER.load(Ordering::SeqCst) != 0x80000000 {
+        openvm::process::panic();
+    }
+
+    // AMOMIN.W, AMOMAX.W
+    SIGNED.store(black_box(-5), Ordering::SeqCst);
+    if SIGNED.fetch_max(black_box(3), Ordering::SeqCst) != -5 {
+        openvm::process::panic();
+    }
+    if SIGNED.fetch_min(black_box(-7), Ordering::SeqCst) != 3 {
+        openvm::process::panic();
+    }
+    if SIGNED.load(Ordering::SeqCst) != -7 {
+        openvm::process::panic();
+    }
+
+    // LR.W / SC.W loop
+    if SIGNED.compare_exchange(-7, 42, Ordering::SeqCst, Ordering::SeqCst) != Ok(-7) {
+        openvm::process::panic();
+    }
+    if SIGNED.compare_exchange(-7, 0, Ordering::SeqCst, Ordering::SeqCst) != Err(42) {
+        openvm::process::panic();
+    }
+}
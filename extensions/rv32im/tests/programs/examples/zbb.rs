@@ -0,0 +1,68 @@
+#![cfg_attr(not(feature = "std"), no_main)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::hint::black_box;
+
+openvm::entry!(main);
+
+pub fn main() {
+    let x: u32 = black_box(0x12345678);
+    let y: u32 = black_box(0x80000ff0);
+
+    // ROL, ROR, RORI
+    if x.rotate_left(black_box(12)) != 0x45678123 {
+        openvm::process::panic();
+    }
+    if x.rotate_right(black_box(4)) != 0x81234567 {
+        openvm::process::panic();
+    }
+    if x.rotate_right(20) != 0x45678123 {
+        openvm::process::panic();
+    }
+
+    // ANDN, ORN, XNOR
+    if x & !y != 0x12345008 {
+        openvm::process::panic();
+    }
+    if x | !y != 0x7ffff67f {
+        openvm::process::panic();
+    }
+    if !(x ^ y) != 0x6dcba677 {
+        openvm::process::panic();
+    }
+
+    // CLZ, CTZ, CPOP
+    if x.leading_zeros() != 3 || y.leading_zeros() != 0 || black_box(0u32).leading_zeros() != 32 {
+        openvm::process::panic();
+    }
+    if x.trailing_zeros() != 3 || y.trailing_zeros() != 4 {
+        openvm::process::panic();
+    }
+    if x.count_ones() != 13 || y.count_ones() != 9 {
+        openvm::process::panic();
+    }
+
+    // REV8, SEXT.B, SEXT.H, ZEXT.H
+    if x.swap_bytes() != 0x78563412 {
+        openvm::process::panic();
+    }
+    if y as i8 as i32 != -16 || x as i16 as i32 != 0x5678 || y as i16 as i32 != 0xff0 {
+        openvm::process::panic();
+    }
+    if y as u16 as u32 != 0x0ff0 {
+        openvm::process::panic();
+    }
+
+    // MIN, MINU, MAX, MAXU
+    if (x as i32).min(y as i32) != y as i32 || x.min(y) != x {
+        openvm::process::panic();
+    }
+    if (x as i32).max(y as i32) != x as i32 || x.max(y) != y {
+        openvm::process::panic();
+    }
+
+    // SH1ADD, SH2ADD, SH3ADD
+    if (x << 1).wrapping_add(y) != 0xa468bce0 || (x << 3).wrapping_add(y) != 0x11a2c3b0 {
+        openvm::process::panic();
+    }
+}
@@ -1,20 +1,22 @@
 #[cfg(test)]
 mod tests {
     use eyre::Result;
+    use openvm_build::GuestOptions;
     use openvm_circuit::{
         arch::{hasher::poseidon2::vm_poseidon2_hasher, VmExecutor},
         system::memory::tree::public_values::UserPublicValuesProof,
         utils::{air_test, air_test_with_min_segments},
     };
     use openvm_instructions::exe::VmExe;
-    use openvm_rv32im_circuit::{Rv32IConfig, Rv32ImConfig};
+    use openvm_rv32im_circuit::{Rv32IConfig, Rv32ImAConfig, Rv32ImConfig, Rv32ImZbbConfig};
     use openvm_rv32im_transpiler::{
-        Rv32ITranspilerExtension, Rv32IoTranspilerExtension, Rv32MTranspilerExtension,
+        Rv32ATranspilerExtension, Rv32ITranspilerExtension, Rv32IoTranspilerExtension,
+        Rv32MTranspilerExtension, Rv32ZbbTranspilerExtension,
     };
     use openvm_stark_sdk::{openvm_stark_backend::p3_field::AbstractField, p3_baby_bear::BabyBear};
     use openvm_toolchain_tests::{
         build_example_program_at_path, build_example_program_at_path_with_features,
-        get_programs_dir,
+        build_example_program_at_path_with_options, get_programs_dir,
     };
     use openvm_transpiler::{
         elf::ELF_DEFAULT_MAX_NUM_PUBLIC_VALUES, transpiler::Transpiler, FromElf,
@@ -184,4 +186,44 @@ mod tests {
         air_test(config, exe);
         Ok(())
     }
+
+    #[test]
+    fn test_zbb() -> Result<()> {
+        let elf = build_example_program_at_path_with_options(
+            get_programs_dir!(),
+            "zbb",
+            GuestOptions::default().with_target_features(["+zbb", "+zba"]),
+        )?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Rv32ZbbTranspilerExtension),
+        )?;
+        let config = Rv32ImZbbConfig::default();
+        air_test(config, exe);
+        Ok(())
+    }
+
+    #[test]
+    fn test_atomics() -> Result<()> {
+        let elf = build_example_program_at_path_with_options(
+            get_programs_dir!(),
+            "atomics",
+            GuestOptions::default().with_target_features(["+a"]),
+        )?;
+        let exe = VmExe::from_elf(
+            elf,
+            Transpiler::<F>::default()
+                .with_extension(Rv32ITranspilerExtension)
+                .with_extension(Rv32MTranspilerExtension)
+                .with_extension(Rv32IoTranspilerExtension)
+                .with_extension(Rv32ATranspilerExtension),
+        )?;
+        let config = Rv32ImAConfig::default();
+        air_test(config, exe);
+        Ok(())
+    }
 }
@@ -9,7 +9,7 @@
 
 use openvm_instructions::UsizeOpcode;
 use openvm_instructions_derive::UsizeOpcode;
-use strum::{EnumCount, EnumIter, FromRepr};
+use strum::{EnumCount, EnumIter, FromRepr, IntoEnumIterator};
 
 #[derive(
     Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
@@ -154,6 +154,119 @@ pub enum DivRemOpcode {
     REMU,
 }
 
+// =================================================================================================
+// Zbb/Zba bit-manipulation opcodes.
+// =================================================================================================
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x260]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum BitwiseNotOpcode {
+    ANDN,
+    ORN,
+    XNOR,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x265]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum MinMaxOpcode {
+    MIN,
+    MINU,
+    MAX,
+    MAXU,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x26a]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum RotateOpcode {
+    ROL,
+    ROR,
+}
+
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x270]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum ShAddOpcode {
+    SH1ADD,
+    SH2ADD,
+    SH3ADD,
+}
+
+/// Single-operand Zbb instructions. The result is written to `rd` and `rs2` is always the
+/// immediate zero.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x275]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32BitManipUnaryOpcode {
+    CLZ,
+    CTZ,
+    CPOP,
+    REV8,
+    SEXT_B,
+    SEXT_H,
+    ZEXT_H,
+}
+
+// =================================================================================================
+// RV32A atomic memory opcodes.
+// The atomic read-modify-write operations reuse the ALU and min/max core opcodes at a separate
+// offset, since the operation is the same and only the adapter differs.
+// =================================================================================================
+
+/// AMOADD, AMOXOR, AMOOR and AMOAND. SUB has no atomic counterpart and is never emitted.
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x280]
+pub struct Rv32AmoAluOpcode(pub BaseAluOpcode);
+
+impl Rv32AmoAluOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        BaseAluOpcode::iter()
+            .filter(|&opcode| opcode != BaseAluOpcode::SUB)
+            .map(Self)
+    }
+}
+
+/// AMOMIN, AMOMINU, AMOMAX and AMOMAXU.
+#[derive(Copy, Clone, Debug, UsizeOpcode)]
+#[opcode_offset = 0x285]
+pub struct Rv32AmoMinMaxOpcode(pub MinMaxOpcode);
+
+impl Rv32AmoMinMaxOpcode {
+    pub fn iter() -> impl Iterator<Item = Self> {
+        MinMaxOpcode::iter().map(Self)
+    }
+}
+
+/// Atomic operations that store rs2 unchanged. SC always succeeds, so it only differs from
+/// AMOSWAP in writing zero to rd.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, EnumCount, EnumIter, FromRepr, UsizeOpcode,
+)]
+#[opcode_offset = 0x28a]
+#[repr(usize)]
+#[allow(non_camel_case_types)]
+pub enum Rv32AmoSwapOpcode {
+    AMOSWAP,
+    SC,
+}
+
 // =================================================================================================
 // Rv32HintStore Instruction
 // =================================================================================================
@@ -2,20 +2,22 @@ use std::marker::PhantomData;
 
 use openvm_instructions::{
     instruction::Instruction, riscv::RV32_REGISTER_NUM_LIMBS, PhantomDiscriminant, SystemOpcode,
-    VmOpcode,
+    UsizeOpcode, VmOpcode,
 };
 use openvm_rv32im_guest::{
-    PhantomImm, CSRRW_FUNCT3, CSR_OPCODE, HINT_STORE_W_FUNCT3, PHANTOM_FUNCT3, REVEAL_FUNCT3,
-    RV32M_FUNCT7, RV32_ALU_OPCODE, SYSTEM_OPCODE, TERMINATE_FUNCT3,
+    AmoFunct5, PhantomImm, AMO_W_FUNCT3, CSRRW_FUNCT3, CSR_OPCODE, HINT_STORE_W_FUNCT3,
+    PHANTOM_FUNCT3, REVEAL_FUNCT3, RV32M_FUNCT7, RV32_ALU_IMM_OPCODE, RV32_ALU_OPCODE,
+    RV32_AMO_OPCODE, SYSTEM_OPCODE, TERMINATE_FUNCT3, ZBA_SH_ADD_FUNCT7, ZBB_MIN_MAX_FUNCT7,
+    ZBB_NEGATE_FUNCT7, ZBB_REV8_IMM, ZBB_ROTATE_FUNCT7, ZBB_ZEXT_H_FUNCT7,
 };
 use openvm_stark_backend::p3_field::PrimeField32;
 use openvm_transpiler::{
-    util::{nop, unimp},
+    util::{from_i_type_shamt, from_r_type, nop, unimp},
     TranspilerExtension,
 };
 use rrs::InstructionTranspiler;
 use rrs_lib::{
-    instruction_formats::{IType, RType},
+    instruction_formats::{IType, ITypeShamt, RType},
     process_instruction,
 };
 
@@ -32,6 +34,17 @@ pub struct Rv32MTranspilerExtension;
 #[derive(Default)]
 pub struct Rv32IoTranspilerExtension;
 
+/// Transpiler extension for the Zbb and Zba bit-manipulation instructions.
+#[derive(Default)]
+pub struct Rv32ZbbTranspilerExtension;
+
+/// Transpiler extension for the word-sized RV32A atomic instructions.
+///
+/// The VM executes a single hart, so the aq/rl ordering bits are ignored, LR.W is a plain
+/// word load and SC.W always succeeds.
+#[derive(Default)]
+pub struct Rv32ATranspilerExtension;
+
 impl<F: PrimeField32> TranspilerExtension<F> for Rv32ITranspilerExtension {
     fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
         let mut transpiler = InstructionTranspiler::<F>(PhantomData);
@@ -178,3 +191,175 @@ impl<F: PrimeField32> TranspilerExtension<F> for Rv32IoTranspilerExtension {
         instruction.map(|instruction| (instruction, 1))
     }
 }
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ZbbTranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        let funct3 = dec_insn.funct3 as u8;
+        let funct7 = dec_insn.funct7 as u8;
+
+        let instruction = match opcode {
+            RV32_ALU_OPCODE => {
+                let local_opcode = match (funct7, funct3) {
+                    (ZBB_NEGATE_FUNCT7, 0b111) => BitwiseNotOpcode::ANDN.with_default_offset(),
+                    (ZBB_NEGATE_FUNCT7, 0b110) => BitwiseNotOpcode::ORN.with_default_offset(),
+                    (ZBB_NEGATE_FUNCT7, 0b100) => BitwiseNotOpcode::XNOR.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b100) => MinMaxOpcode::MIN.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b101) => MinMaxOpcode::MINU.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b110) => MinMaxOpcode::MAX.with_default_offset(),
+                    (ZBB_MIN_MAX_FUNCT7, 0b111) => MinMaxOpcode::MAXU.with_default_offset(),
+                    (ZBB_ROTATE_FUNCT7, 0b001) => RotateOpcode::ROL.with_default_offset(),
+                    (ZBB_ROTATE_FUNCT7, 0b101) => RotateOpcode::ROR.with_default_offset(),
+                    (ZBA_SH_ADD_FUNCT7, 0b010) => ShAddOpcode::SH1ADD.with_default_offset(),
+                    (ZBA_SH_ADD_FUNCT7, 0b100) => ShAddOpcode::SH2ADD.with_default_offset(),
+                    (ZBA_SH_ADD_FUNCT7, 0b110) => ShAddOpcode::SH3ADD.with_default_offset(),
+                    // ZEXT.H is the RV32 encoding of PACK with rs2 = x0
+                    (ZBB_ZEXT_H_FUNCT7, 0b100) if dec_insn.rs2 == 0 => {
+                        return Some((
+                            from_unary(Rv32BitManipUnaryOpcode::ZEXT_H, dec_insn.rd, dec_insn.rs1),
+                            1,
+                        ));
+                    }
+                    _ => return None,
+                };
+                from_r_type(local_opcode, 1, &dec_insn)
+            }
+            RV32_ALU_IMM_OPCODE => {
+                let dec_insn = IType::new(instruction_u32);
+                let imm = (dec_insn.imm as u32) & 0xfff;
+                match (funct3, funct7) {
+                    (0b001, ZBB_ROTATE_FUNCT7) => {
+                        // The rs2 field selects the unary operation
+                        let unary_opcode = match imm & 0x1f {
+                            0b00000 => Rv32BitManipUnaryOpcode::CLZ,
+                            0b00001 => Rv32BitManipUnaryOpcode::CTZ,
+                            0b00010 => Rv32BitManipUnaryOpcode::CPOP,
+                            0b00100 => Rv32BitManipUnaryOpcode::SEXT_B,
+                            0b00101 => Rv32BitManipUnaryOpcode::SEXT_H,
+                            _ => return None,
+                        };
+                        from_unary(unary_opcode, dec_insn.rd, dec_insn.rs1)
+                    }
+                    (0b101, ZBB_ROTATE_FUNCT7) => from_i_type_shamt(
+                        RotateOpcode::ROR.with_default_offset(),
+                        &ITypeShamt::new(instruction_u32),
+                    ),
+                    (0b101, _) if imm == ZBB_REV8_IMM => {
+                        from_unary(Rv32BitManipUnaryOpcode::REV8, dec_insn.rd, dec_insn.rs1)
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+
+        Some((instruction, 1))
+    }
+}
+
+impl<F: PrimeField32> TranspilerExtension<F> for Rv32ATranspilerExtension {
+    fn process_custom(&self, instruction_stream: &[u32]) -> Option<(Instruction<F>, usize)> {
+        if instruction_stream.is_empty() {
+            return None;
+        }
+        let instruction_u32 = instruction_stream[0];
+
+        let opcode = (instruction_u32 & 0x7f) as u8;
+        let dec_insn = RType::new(instruction_u32);
+        if opcode != RV32_AMO_OPCODE || dec_insn.funct3 as u8 != AMO_W_FUNCT3 {
+            return None;
+        }
+        let funct5 = AmoFunct5::from_repr((dec_insn.funct7 >> 2) as u8)?;
+
+        let global_opcode = match funct5 {
+            AmoFunct5::Lr => {
+                if dec_insn.rs2 != 0 {
+                    return None;
+                }
+                if dec_insn.rd == 0 {
+                    return Some((nop(), 1));
+                }
+                return Some((
+                    Instruction::from_usize(
+                        VmOpcode::with_default_offset(Rv32LoadStoreOpcode::LOADW),
+                        [
+                            RV32_REGISTER_NUM_LIMBS * dec_insn.rd,
+                            RV32_REGISTER_NUM_LIMBS * dec_insn.rs1,
+                            0,
+                            1,
+                            2,
+                        ],
+                    ),
+                    1,
+                ));
+            }
+            AmoFunct5::Sc => VmOpcode::with_default_offset(Rv32AmoSwapOpcode::SC),
+            AmoFunct5::AmoSwap => VmOpcode::with_default_offset(Rv32AmoSwapOpcode::AMOSWAP),
+            AmoFunct5::AmoAdd => {
+                VmOpcode::with_default_offset(Rv32AmoAluOpcode(BaseAluOpcode::ADD))
+            }
+            AmoFunct5::AmoXor => {
+                VmOpcode::with_default_offset(Rv32AmoAluOpcode(BaseAluOpcode::XOR))
+            }
+            AmoFunct5::AmoOr => VmOpcode::with_default_offset(Rv32AmoAluOpcode(BaseAluOpcode::OR)),
+            AmoFunct5::AmoAnd => {
+                VmOpcode::with_default_offset(Rv32AmoAluOpcode(BaseAluOpcode::AND))
+            }
+            AmoFunct5::AmoMin => {
+                VmOpcode::with_default_offset(Rv32AmoMinMaxOpcode(MinMaxOpcode::MIN))
+            }
+            AmoFunct5::AmoMax => {
+                VmOpcode::with_default_offset(Rv32AmoMinMaxOpcode(MinMaxOpcode::MAX))
+            }
+            AmoFunct5::AmoMinu => {
+                VmOpcode::with_default_offset(Rv32AmoMinMaxOpcode(MinMaxOpcode::MINU))
+            }
+            AmoFunct5::AmoMaxu => {
+                VmOpcode::with_default_offset(Rv32AmoMinMaxOpcode(MinMaxOpcode::MAXU))
+            }
+        };
+        // SC.W writes 0 (success) to rd, every other AMO writes the original memory word.
+        let returns_prev_data = funct5 != AmoFunct5::Sc;
+
+        Some((
+            Instruction::from_usize(
+                global_opcode,
+                [
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rd,
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rs1,
+                    RV32_REGISTER_NUM_LIMBS * dec_insn.rs2,
+                    1,
+                    2,
+                    (dec_insn.rd != 0) as usize,
+                    returns_prev_data as usize,
+                ],
+            ),
+            1,
+        ))
+    }
+}
+
+/// Unary instructions are handled by an ALU adapter with `rs2` set to the immediate zero.
+fn from_unary<F: PrimeField32>(
+    opcode: Rv32BitManipUnaryOpcode,
+    rd: usize,
+    rs1: usize,
+) -> Instruction<F> {
+    if rd == 0 {
+        return nop();
+    }
+    Instruction::from_isize(
+        VmOpcode::with_default_offset(opcode),
+        (RV32_REGISTER_NUM_LIMBS * rd) as isize,
+        (RV32_REGISTER_NUM_LIMBS * rs1) as isize,
+        0,
+        1,
+        0,
+    )
+}